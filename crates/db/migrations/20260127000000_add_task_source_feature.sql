@@ -0,0 +1,5 @@
+-- Link AI-generated tasks back to the requirements feature that produced them
+-- Enables regenerating tasks for a single feature without re-running the whole analysis
+ALTER TABLE tasks ADD COLUMN source_feature TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_source_feature ON tasks(project_id, source_feature);
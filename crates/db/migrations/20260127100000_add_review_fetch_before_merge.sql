@@ -0,0 +1,3 @@
+-- Optionally fetch the target branch from the remote before auto-merging
+-- so divergence detection reflects upstream state rather than a stale local branch
+ALTER TABLE project_review_settings ADD COLUMN fetch_before_merge INTEGER NOT NULL DEFAULT 0;
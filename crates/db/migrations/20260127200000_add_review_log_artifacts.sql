@@ -0,0 +1,4 @@
+-- Store full test output on disk and keep only a preview in review_automation_logs.output
+ALTER TABLE review_automation_logs ADD COLUMN artifact_path TEXT;
+
+ALTER TABLE project_review_settings ADD COLUMN store_test_artifacts INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Opt-in digest mode for agent activity notifications
+-- NULL keeps per-event notifications; a value batches events into one summary per window
+ALTER TABLE project_agent_settings ADD COLUMN digest_window_minutes INTEGER;
@@ -0,0 +1,2 @@
+-- Maximum number of layers the agent may work on concurrently (previously hardcoded to 3)
+ALTER TABLE project_agent_settings ADD COLUMN max_concurrent_layers INTEGER NOT NULL DEFAULT 3;
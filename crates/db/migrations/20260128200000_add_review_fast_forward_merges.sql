@@ -0,0 +1,3 @@
+-- Allow auto-merge to fast-forward the target branch when the workspace branch is strictly ahead,
+-- instead of always creating a squash commit
+ALTER TABLE project_review_settings ADD COLUMN fast_forward_merges INTEGER NOT NULL DEFAULT 0;
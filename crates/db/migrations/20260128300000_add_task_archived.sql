@@ -0,0 +1,2 @@
+-- Archived tasks are hidden from the board by default but stay queryable
+ALTER TABLE tasks ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- How auto-started attempts pick the agent's working directory:
+-- 'repo_name' (single repo: repo dir, multi repo: workspace root), 'workspace_root', or 'explicit'
+ALTER TABLE project_agent_settings ADD COLUMN working_dir_strategy TEXT NOT NULL DEFAULT 'repo_name'
+    CHECK (working_dir_strategy IN ('repo_name', 'workspace_root', 'explicit'));
+ALTER TABLE project_agent_settings ADD COLUMN working_dir_path TEXT;
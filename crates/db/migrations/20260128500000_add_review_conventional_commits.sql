@@ -0,0 +1,2 @@
+-- Format auto-merge commit messages as Conventional Commits (e.g. "feat(backend): ...")
+ALTER TABLE project_review_settings ADD COLUMN conventional_commits INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- When disabled, Todo Fullstack tasks are selected like any other task instead of being split by layer
+ALTER TABLE project_agent_settings ADD COLUMN auto_breakdown_fullstack INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,2 @@
+-- Selected tasks scored below this complexity are moved to InProgress but not auto-started
+ALTER TABLE project_agent_settings ADD COLUMN min_complexity_for_auto_attempt INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Sign auto-merge commits with the repository's configured signing key
+ALTER TABLE project_review_settings ADD COLUMN sign_commits INTEGER NOT NULL DEFAULT 0;
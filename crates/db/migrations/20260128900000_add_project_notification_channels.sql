@@ -0,0 +1,12 @@
+-- Per-project notification routing; projects without rows use the global desktop notifications
+CREATE TABLE IF NOT EXISTS project_notification_channels (
+    id TEXT PRIMARY KEY NOT NULL,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    channel_type TEXT NOT NULL, -- 'desktop', 'webhook', 'slack'
+    config TEXT NOT NULL DEFAULT '{}', -- JSON, e.g. {"url": "..."} for webhooks
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_notification_channels_project_id ON project_notification_channels(project_id);
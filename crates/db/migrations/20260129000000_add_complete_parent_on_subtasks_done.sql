@@ -0,0 +1,2 @@
+-- Opt-in: mark a broken-down parent task Done once all of its subtasks are finished
+ALTER TABLE project_review_settings ADD COLUMN complete_parent_on_subtasks_done INTEGER NOT NULL DEFAULT 0;
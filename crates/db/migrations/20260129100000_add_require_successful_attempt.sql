@@ -0,0 +1,2 @@
+-- Only treat in-review tasks as ready once a coding agent run completed successfully
+ALTER TABLE project_review_settings ADD COLUMN require_successful_attempt INTEGER NOT NULL DEFAULT 0;
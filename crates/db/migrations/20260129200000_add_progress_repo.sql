@@ -0,0 +1,2 @@
+-- Repo whose .progress file receives a task's post-task notes on auto-merge
+ALTER TABLE project_review_settings ADD COLUMN progress_repo TEXT;
@@ -0,0 +1,2 @@
+-- When disabled, an active Integration task no longer blocks selection of other tasks
+ALTER TABLE project_agent_settings ADD COLUMN integration_blocks_all INTEGER NOT NULL DEFAULT 1;
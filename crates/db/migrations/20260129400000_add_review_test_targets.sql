@@ -0,0 +1,2 @@
+-- Rules mapping changed-file globs to targeted test commands, one 'glob => command' per line
+ALTER TABLE project_review_settings ADD COLUMN test_targets TEXT;
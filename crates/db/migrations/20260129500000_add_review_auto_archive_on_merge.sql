@@ -0,0 +1,2 @@
+-- Archive tasks once review automation has merged them
+ALTER TABLE project_review_settings ADD COLUMN auto_archive_on_merge INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+-- Prompt template versions pinned per project; templates without a row use their latest version
+CREATE TABLE IF NOT EXISTS project_prompt_versions (
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    template_name TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, template_name)
+);
+
+-- Label of the prompt template version behind an agent decision, e.g. 'task_selection@v1'
+ALTER TABLE agent_activity_logs ADD COLUMN prompt_version TEXT;
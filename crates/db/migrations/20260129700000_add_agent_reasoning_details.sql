@@ -0,0 +1,2 @@
+-- Structured explanation of an AI task selection (JSON SelectionReasoning); NULL for plain-text reasoning
+ALTER TABLE agent_activity_logs ADD COLUMN reasoning_details TEXT;
@@ -0,0 +1,11 @@
+-- Allow 'testing' as a task_type. SQLite cannot alter a CHECK constraint, so the
+-- column is recreated with the widened constraint and the data copied across.
+
+ALTER TABLE tasks ADD COLUMN task_type_new TEXT DEFAULT 'implementation'
+    CHECK (task_type_new IS NULL OR task_type_new IN ('architecture', 'mock', 'implementation', 'testing', 'integration'));
+
+UPDATE tasks SET task_type_new = task_type;
+
+ALTER TABLE tasks DROP COLUMN task_type;
+
+ALTER TABLE tasks RENAME COLUMN task_type_new TO task_type;
@@ -0,0 +1,2 @@
+-- Include a task's recent human comments in task selection and auto-attempt prompts
+ALTER TABLE project_agent_settings ADD COLUMN include_recent_comments INTEGER NOT NULL DEFAULT 0;
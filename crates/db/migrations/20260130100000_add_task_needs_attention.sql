@@ -0,0 +1,2 @@
+-- Why automation gave up on a task (e.g. conflict breakdown failed); NULL when no human is needed
+ALTER TABLE tasks ADD COLUMN needs_attention_reason TEXT;
@@ -0,0 +1,2 @@
+-- Project description, tech stack and conventions injected into requirements/task-generation prompts
+ALTER TABLE projects ADD COLUMN context TEXT;
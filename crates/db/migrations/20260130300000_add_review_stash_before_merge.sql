@@ -0,0 +1,4 @@
+-- Stash uncommitted worktree changes before auto-merging and restore them afterwards
+ALTER TABLE project_review_settings ADD COLUMN stash_before_merge INTEGER NOT NULL DEFAULT 0;
+-- When set, stashed changes are discarded after a successful merge since the merge supersedes them
+ALTER TABLE project_review_settings ADD COLUMN drop_stash_after_merge INTEGER NOT NULL DEFAULT 0;
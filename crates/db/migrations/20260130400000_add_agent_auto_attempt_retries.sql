@@ -0,0 +1,2 @@
+-- How many times to retry starting an auto-attempt before giving up on it
+ALTER TABLE project_agent_settings ADD COLUMN auto_attempt_retries INTEGER NOT NULL DEFAULT 2;
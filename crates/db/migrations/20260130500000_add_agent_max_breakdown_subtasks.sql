@@ -0,0 +1,2 @@
+-- Hard cap on how many subtasks an automatic breakdown may create
+ALTER TABLE project_agent_settings ADD COLUMN max_breakdown_subtasks INTEGER NOT NULL DEFAULT 5;
@@ -0,0 +1,2 @@
+-- Commits the latest attempt added over its target branch, recorded when the task enters review
+ALTER TABLE tasks ADD COLUMN commit_count INTEGER;
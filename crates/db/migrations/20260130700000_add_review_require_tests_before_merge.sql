@@ -0,0 +1,2 @@
+-- Block auto-merge unless tests ran and passed
+ALTER TABLE project_review_settings ADD COLUMN require_tests_before_merge INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Link generated tasks to the requirements record they came from
+ALTER TABLE tasks ADD COLUMN requirements_id BLOB DEFAULT NULL REFERENCES project_requirements(id) ON DELETE SET NULL;
@@ -0,0 +1,2 @@
+-- Descriptions longer than this many characters are truncated in selection and complexity prompts
+ALTER TABLE project_agent_settings ADD COLUMN max_prompt_description_chars INTEGER NOT NULL DEFAULT 4000;
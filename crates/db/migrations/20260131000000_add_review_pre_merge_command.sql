@@ -0,0 +1,2 @@
+-- Project-specific shell command that must succeed in the workspace before auto-merge
+ALTER TABLE project_review_settings ADD COLUMN pre_merge_command TEXT;
@@ -0,0 +1,2 @@
+-- Org-specific guidance prepended to the system prompt of every AI call made for the project
+ALTER TABLE projects ADD COLUMN system_prompt_prefix TEXT;
@@ -0,0 +1,2 @@
+-- Cherry-pick branches with exactly one commit onto the target instead of squash merging
+ALTER TABLE project_review_settings ADD COLUMN cherry_pick_single_commits INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,70 @@
+-- Add a 'blocked' task status with a reason. SQLite can't alter a CHECK constraint, so the
+-- tasks table is rebuilt with the new status allowed and a blocked_reason column.
+
+-- Rebuilding needs FK disabled to avoid cascade deletes during DROP TABLE
+-- sqlx workaround: end auto-transaction to allow PRAGMA to take effect
+-- https://github.com/launchbadge/sqlx/issues/2085#issuecomment-1499859906
+COMMIT;
+
+PRAGMA foreign_keys = OFF;
+
+BEGIN TRANSACTION;
+
+CREATE TABLE tasks_new (
+    id                     BLOB PRIMARY KEY,
+    project_id             BLOB NOT NULL,
+    title                  TEXT NOT NULL,
+    description            TEXT,
+    status                 TEXT NOT NULL DEFAULT 'todo'
+                              CHECK (status IN ('todo','inprogress','done','cancelled','inreview','blocked')),
+    created_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    parent_workspace_id    BLOB REFERENCES workspaces(id),
+    source                 TEXT DEFAULT 'manual'
+                              CHECK (source IN ('manual', 'ai_generated')),
+    layer                  TEXT
+                              CHECK (layer IS NULL OR layer IN ('data', 'backend', 'frontend', 'fullstack', 'devops', 'testing')),
+    sequence               INTEGER,
+    testing_criteria       TEXT,
+    stage_started_at       TEXT DEFAULT NULL,
+    complexity_score       INTEGER DEFAULT NULL,
+    parent_task_id         BLOB DEFAULT NULL REFERENCES tasks(id) ON DELETE SET NULL,
+    prevent_breakdown      INTEGER NOT NULL DEFAULT 0,
+    post_task_actions      TEXT,
+    source_feature         TEXT,
+    archived               INTEGER NOT NULL DEFAULT 0,
+    task_type              TEXT DEFAULT 'implementation'
+                              CHECK (task_type IS NULL OR task_type IN ('architecture', 'mock', 'implementation', 'testing', 'integration')),
+    needs_attention_reason TEXT,
+    commit_count           INTEGER,
+    requirements_id        BLOB DEFAULT NULL REFERENCES project_requirements(id) ON DELETE SET NULL,
+    blocked_reason         TEXT, -- Why the task is blocked; set only while status is 'blocked'
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+INSERT INTO tasks_new (id, project_id, title, description, status, created_at, updated_at, parent_workspace_id, source, layer, sequence, testing_criteria, stage_started_at, complexity_score, parent_task_id, prevent_breakdown, post_task_actions, source_feature, archived, task_type, needs_attention_reason, commit_count, requirements_id)
+SELECT id, project_id, title, description, status, created_at, updated_at, parent_workspace_id, source, layer, sequence, testing_criteria, stage_started_at, complexity_score, parent_task_id, prevent_breakdown, post_task_actions, source_feature, archived, task_type, needs_attention_reason, commit_count, requirements_id
+FROM tasks;
+
+DROP TABLE tasks;
+ALTER TABLE tasks_new RENAME TO tasks;
+
+-- Recreate tasks indexes
+CREATE INDEX idx_tasks_project_created_at ON tasks (project_id, created_at DESC);
+CREATE INDEX idx_tasks_parent_workspace_id ON tasks(parent_workspace_id);
+CREATE INDEX idx_tasks_sequence ON tasks(project_id, sequence) WHERE sequence IS NOT NULL;
+CREATE INDEX idx_tasks_stage_timeout ON tasks(status, stage_started_at)
+  WHERE status IN ('inprogress', 'inreview') AND stage_started_at IS NOT NULL;
+CREATE INDEX idx_tasks_parent_task_id ON tasks(parent_task_id)
+  WHERE parent_task_id IS NOT NULL;
+CREATE INDEX idx_tasks_source_feature ON tasks(project_id, source_feature);
+
+-- Verify foreign key constraints before committing
+PRAGMA foreign_key_check;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
+
+-- sqlx workaround: start empty transaction for sqlx to close gracefully
+BEGIN TRANSACTION;
@@ -0,0 +1,4 @@
+-- Git hosting provider used for the project's pull/merge requests, overriding detection from
+-- the remote URL (needed for self-managed instances on custom hostnames)
+ALTER TABLE projects ADD COLUMN git_host_provider TEXT
+    CHECK (git_host_provider IS NULL OR git_host_provider IN ('github', 'gitlab', 'azure_devops'));
@@ -0,0 +1,2 @@
+-- Block auto-merge until an AI check finds the task's testing_criteria met by its changes and test output
+ALTER TABLE project_review_settings ADD COLUMN enforce_testing_criteria INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Local time window ('HH:MM', end exclusive) during which the agent doesn't select tasks and
+-- non-critical notifications are suppressed; NULL start/end means no quiet hours
+ALTER TABLE project_agent_settings ADD COLUMN quiet_hours_start TEXT;
+ALTER TABLE project_agent_settings ADD COLUMN quiet_hours_end TEXT;
+ALTER TABLE project_agent_settings ADD COLUMN quiet_hours_utc_offset_minutes INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+-- Earlier versions of a project's requirements, saved each time they are edited
+CREATE TABLE IF NOT EXISTS project_requirements_versions (
+    id BLOB PRIMARY KEY NOT NULL,
+    requirements_id BLOB NOT NULL REFERENCES project_requirements(id) ON DELETE CASCADE,
+    raw_requirements TEXT NOT NULL,
+    prd_content TEXT,
+    analysis_result TEXT,  -- JSON: features extracted from this version
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_project_requirements_versions_requirements_id
+    ON project_requirements_versions(requirements_id, created_at);
@@ -0,0 +1,2 @@
+-- Cap on InProgress/InReview tasks per project, across all layers (0 = no cap)
+ALTER TABLE project_agent_settings ADD COLUMN max_in_progress_tasks INTEGER NOT NULL DEFAULT 0;
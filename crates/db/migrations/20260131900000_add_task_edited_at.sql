@@ -0,0 +1,3 @@
+-- When a user last changed a task's title or description; NULL for tasks never edited, so
+-- AI-generated tasks nobody has groomed yet can be counted
+ALTER TABLE tasks ADD COLUMN edited_at DATETIME;
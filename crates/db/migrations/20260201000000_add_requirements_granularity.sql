@@ -0,0 +1,2 @@
+-- How finely feature extraction decomposes the requirements: coarse, normal or fine
+ALTER TABLE project_requirements ADD COLUMN granularity TEXT NOT NULL DEFAULT 'normal';
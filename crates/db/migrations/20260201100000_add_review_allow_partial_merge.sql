@@ -0,0 +1,2 @@
+-- When a task touches several repos, merge the conflict-free ones and leave the rest (0: all-or-nothing)
+ALTER TABLE project_review_settings ADD COLUMN allow_partial_merge INTEGER NOT NULL DEFAULT 0;
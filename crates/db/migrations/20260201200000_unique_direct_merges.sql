@@ -0,0 +1,17 @@
+-- One direct merge row per workspace, repo and target branch, so a retried merge can't be
+-- recorded twice. Keep the newest of any existing duplicates.
+DELETE FROM merges
+WHERE merge_type = 'direct'
+  AND EXISTS (
+    SELECT 1 FROM merges AS newer
+    WHERE newer.merge_type = 'direct'
+      AND newer.workspace_id = merges.workspace_id
+      AND newer.repo_id = merges.repo_id
+      AND newer.target_branch_name = merges.target_branch_name
+      AND (newer.created_at > merges.created_at
+           OR (newer.created_at = merges.created_at AND newer.rowid > merges.rowid))
+  );
+
+CREATE UNIQUE INDEX idx_merges_direct_unique
+ON merges (workspace_id, repo_id, target_branch_name)
+WHERE merge_type = 'direct';
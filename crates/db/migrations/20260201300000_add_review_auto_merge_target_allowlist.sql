@@ -0,0 +1,2 @@
+-- Branches auto-merge may target, as names or globs separated by commas or newlines (NULL: any branch)
+ALTER TABLE project_review_settings ADD COLUMN auto_merge_target_allowlist TEXT;
@@ -0,0 +1,9 @@
+-- The latest complexity analysis of each task, including the suggested breakdown
+CREATE TABLE IF NOT EXISTS task_complexity_analyses (
+    task_id BLOB PRIMARY KEY NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    complexity_score INTEGER NOT NULL,
+    can_be_broken_down INTEGER NOT NULL,
+    reasoning TEXT NOT NULL,
+    subtasks TEXT,  -- JSON: suggested subtasks
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,2 @@
+-- Per-task-type validation commands for review automation
+ALTER TABLE project_review_settings ADD COLUMN test_profiles TEXT;
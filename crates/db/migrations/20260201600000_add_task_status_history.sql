@@ -0,0 +1,13 @@
+-- Every task status change, with who or what made it and why
+CREATE TABLE IF NOT EXISTS task_status_history (
+    id BLOB PRIMARY KEY NOT NULL,
+    task_id BLOB NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    actor TEXT,   -- 'user' or the service that made the change
+    reason TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_task_status_history_task_id
+    ON task_status_history(task_id, created_at);
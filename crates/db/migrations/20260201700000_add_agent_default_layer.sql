@@ -0,0 +1,2 @@
+-- Layer agent selection assumes for tasks without one
+ALTER TABLE project_agent_settings ADD COLUMN default_layer TEXT;
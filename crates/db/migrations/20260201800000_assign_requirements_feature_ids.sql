@@ -0,0 +1,25 @@
+-- Features analyzed before they had ids deserialize with the nil id, so they can't be
+-- regenerated individually. Give each of them a random id.
+UPDATE project_requirements
+SET analysis_result = json_set(
+    analysis_result,
+    '$.features',
+    (
+        SELECT json_group_array(json(
+            CASE
+                WHEN coalesce(json_extract(feature.value, '$.id'), '00000000-0000-0000-0000-000000000000')
+                     = '00000000-0000-0000-0000-000000000000'
+                THEN json_set(feature.value, '$.id', lower(hex(randomblob(16))))
+                ELSE feature.value
+            END
+        ))
+        FROM json_each(project_requirements.analysis_result, '$.features') AS feature
+    )
+)
+WHERE analysis_result IS NOT NULL
+  AND EXISTS (
+      SELECT 1
+      FROM json_each(project_requirements.analysis_result, '$.features') AS feature
+      WHERE coalesce(json_extract(feature.value, '$.id'), '00000000-0000-0000-0000-000000000000')
+            = '00000000-0000-0000-0000-000000000000'
+  );
@@ -0,0 +1,3 @@
+-- Target branch head before a direct merge, so reverting a fast-forward can undo every commit it
+-- brought in rather than only the new head. NULL for merges recorded before this column.
+ALTER TABLE merges ADD COLUMN base_commit TEXT;
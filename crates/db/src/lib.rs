@@ -1,7 +1,7 @@
 use std::{str::FromStr, sync::Arc};
 
 use sqlx::{
-    Error, Pool, Sqlite, SqlitePool,
+    Error, Pool, Sqlite,
     migrate::MigrateError,
     sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
 };
@@ -9,6 +9,10 @@ use utils::assets::asset_dir;
 
 pub mod models;
 
+/// Connections in the pool, enough for the background services to work on several projects at
+/// once while still serving API requests
+const MAX_CONNECTIONS: u32 = 32;
+
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     use std::collections::HashSet;
 
@@ -81,7 +85,10 @@ impl DBService {
         let options = SqliteConnectOptions::from_str(&database_url)?
             .create_if_missing(true)
             .journal_mode(SqliteJournalMode::Delete);
-        let pool = SqlitePool::connect_with(options).await?;
+        let pool = SqlitePoolOptions::new()
+            .max_connections(MAX_CONNECTIONS)
+            .connect_with(options)
+            .await?;
         run_migrations(&pool).await?;
         Ok(DBService { pool })
     }
@@ -120,6 +127,7 @@ impl DBService {
 
         let pool = if let Some(hook) = after_connect {
             SqlitePoolOptions::new()
+                .max_connections(MAX_CONNECTIONS)
                 .after_connect(move |conn, _meta| {
                     let hook = hook.clone();
                     Box::pin(async move {
@@ -130,7 +138,10 @@ impl DBService {
                 .connect_with(options)
                 .await?
         } else {
-            SqlitePool::connect_with(options).await?
+            SqlitePoolOptions::new()
+                .max_connections(MAX_CONNECTIONS)
+                .connect_with(options)
+                .await?
         };
 
         run_migrations(&pool).await?;
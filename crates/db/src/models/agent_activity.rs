@@ -1,10 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use serde_with::rust::double_option;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
 use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::task::TaskLayer;
+
 /// Action taken by the agent
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display)]
 #[sqlx(type_name = "agent_action", rename_all = "lowercase")]
@@ -16,6 +19,21 @@ pub enum AgentAction {
     Error,
     Replaced, // Task was broken into subtasks
     Timeout,  // Task was cancelled due to timeout
+    ParentCompleted, // All subtasks finished, so the parent task was completed
+}
+
+/// How auto-started attempts choose the agent's working directory
+#[derive(
+    Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
+)]
+#[sqlx(type_name = "working_dir_strategy", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WorkingDirStrategy {
+    #[default]
+    RepoName, // Repo directory for single-repo projects, workspace root otherwise
+    WorkspaceRoot,
+    Explicit, // Path relative to the workspace root, from working_dir_path
 }
 
 /// Agent activity settings for a project
@@ -26,10 +44,42 @@ pub struct ProjectAgentSettings {
     pub enabled: bool,
     pub interval_seconds: i32,
     pub max_breakdown_depth: i32, // Recursive breakdown depth (default: 1)
+    pub digest_window_minutes: Option<i32>, // Batch notifications per window (None: notify per event)
+    pub max_concurrent_layers: i32, // Layers that may have active tasks at once (default: 3)
+    pub working_dir_strategy: WorkingDirStrategy,
+    pub working_dir_path: Option<String>, // Used when working_dir_strategy is Explicit
+    pub auto_breakdown_fullstack: bool, // Split Todo Fullstack tasks into layer subtasks (default: true)
+    pub min_complexity_for_auto_attempt: i32, // Minimum complexity score to auto-start an attempt (default: 0)
+    pub max_in_progress_tasks: i32, // Max InProgress/InReview tasks across all layers (default: 0 = no cap)
+    pub max_prompt_description_chars: i32, // Longer task descriptions are truncated in AI prompts (default: 4000)
+    pub max_breakdown_subtasks: i32, // Breakdowns suggesting more subtasks are rejected (default: 5)
+    pub auto_attempt_retries: i32, // Extra tries when starting an auto-attempt fails (default: 2)
+    pub include_recent_comments: bool, // Include recent human comments in selection and auto-attempt prompts (default: false)
+    pub integration_blocks_all: bool, // An active Integration task blocks all new selection (default: true)
+    pub quiet_hours_start: Option<String>, // 'HH:MM' local time quiet hours begin (None: no quiet hours)
+    pub quiet_hours_end: Option<String>, // 'HH:MM' local time quiet hours end, exclusive
+    pub quiet_hours_utc_offset_minutes: i32, // Offset of the quiet hours' local time from UTC (default: 0)
+    pub default_layer: Option<TaskLayer>, // Layer selection assumes for tasks without one (None: they only run alone)
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// How the factors behind an AI task selection played out
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct SelectionFactors {
+    pub priority: Option<String>,
+    pub unblocking: Option<String>,
+    pub layer_readiness: Option<String>,
+}
+
+/// Structured explanation of why the AI chose a task over the runner-up
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct SelectionReasoning {
+    pub summary: String,
+    pub runner_up_task_id: Option<Uuid>,
+    pub factors: SelectionFactors,
+}
+
 /// Log entry for agent activity
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct AgentActivityLog {
@@ -38,6 +88,9 @@ pub struct AgentActivityLog {
     pub task_id: Option<Uuid>,
     pub action: AgentAction,
     pub reasoning: Option<String>,
+    #[ts(type = "SelectionReasoning | null")]
+    pub reasoning_details: Option<Json<SelectionReasoning>>, // Set for structured AI selections
+    pub prompt_version: Option<String>, // e.g. 'task_selection@v1' for AI selections
     pub created_at: DateTime<Utc>,
 }
 
@@ -46,11 +99,59 @@ pub struct AgentActivityLog {
 pub struct AgentActivityStatus {
     pub enabled: bool,
     pub interval_seconds: i32,
+    pub digest_window_minutes: Option<i32>,
     pub last_run: Option<DateTime<Utc>>,
     pub last_selected_task_id: Option<Uuid>,
     pub last_reasoning: Option<String>,
 }
 
+/// Request body for updating agent activity settings
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateAgentActivitySettings {
+    pub interval_seconds: i32,
+    pub max_concurrent_layers: i32,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub digest_window_minutes: Option<Option<i32>>, // Absent keeps the window; null turns digests off
+    pub working_dir_strategy: Option<WorkingDirStrategy>, // None keeps the current strategy
+    pub working_dir_path: Option<String>,
+    pub auto_breakdown_fullstack: Option<bool>, // None keeps the current setting
+    pub min_complexity_for_auto_attempt: Option<i32>, // None keeps the current threshold
+    pub max_in_progress_tasks: Option<i32>, // None keeps the current cap
+    pub max_prompt_description_chars: Option<i32>, // None keeps the current budget
+    pub max_breakdown_subtasks: Option<i32>, // None keeps the current cap
+    pub auto_attempt_retries: Option<i32>, // None keeps the current retry count
+    pub include_recent_comments: Option<bool>, // None keeps the current setting
+    pub integration_blocks_all: Option<bool>, // None keeps the current setting
+    pub quiet_hours_start: Option<String>, // Set with quiet_hours_end; empty strings clear quiet hours
+    pub quiet_hours_end: Option<String>,
+    pub quiet_hours_utc_offset_minutes: Option<i32>, // None keeps the current offset
+    pub default_layer: Option<String>, // Layer name; an empty string clears the default
+}
+
+/// Validated settings changes for `ProjectAgentSettings::update`; `None` keeps the current value
+#[derive(Debug, Clone)]
+pub struct AgentSettingsUpdate {
+    pub interval_seconds: i32,
+    pub max_concurrent_layers: i32,
+    pub digest_window_minutes: Option<Option<i32>>,
+    pub working_dir: Option<(WorkingDirStrategy, Option<String>)>, // Strategy and its path
+    pub auto_breakdown_fullstack: Option<bool>,
+    pub min_complexity_for_auto_attempt: Option<i32>,
+    pub max_in_progress_tasks: Option<i32>,
+    pub max_prompt_description_chars: Option<i32>,
+    pub max_breakdown_subtasks: Option<i32>,
+    pub auto_attempt_retries: Option<i32>,
+    pub include_recent_comments: Option<bool>,
+    pub integration_blocks_all: Option<bool>,
+    pub quiet_hours: Option<(Option<String>, Option<String>, i32)>, // Start, end and UTC offset
+    pub default_layer: Option<Option<TaskLayer>>,
+}
+
 /// Response for agent trigger action
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct AgentTriggerResponse {
@@ -72,6 +173,22 @@ impl ProjectAgentSettings {
                 enabled as "enabled!: bool",
                 interval_seconds as "interval_seconds!: i32",
                 max_breakdown_depth as "max_breakdown_depth!: i32",
+                digest_window_minutes as "digest_window_minutes: i32",
+                max_concurrent_layers as "max_concurrent_layers!: i32",
+                working_dir_strategy as "working_dir_strategy!: WorkingDirStrategy",
+                working_dir_path,
+                auto_breakdown_fullstack as "auto_breakdown_fullstack!: bool",
+                min_complexity_for_auto_attempt as "min_complexity_for_auto_attempt!: i32",
+                max_in_progress_tasks as "max_in_progress_tasks!: i32",
+                max_prompt_description_chars as "max_prompt_description_chars!: i32",
+                max_breakdown_subtasks as "max_breakdown_subtasks!: i32",
+                auto_attempt_retries as "auto_attempt_retries!: i32",
+                include_recent_comments as "include_recent_comments!: bool",
+                integration_blocks_all as "integration_blocks_all!: bool",
+                quiet_hours_start,
+                quiet_hours_end,
+                quiet_hours_utc_offset_minutes as "quiet_hours_utc_offset_minutes!: i32",
+                default_layer as "default_layer: TaskLayer",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
             FROM project_agent_settings
@@ -87,15 +204,17 @@ impl ProjectAgentSettings {
         project_id: Uuid,
         enabled: bool,
         interval_seconds: i32,
+        max_concurrent_layers: i32,
     ) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
         sqlx::query_as!(
             ProjectAgentSettings,
-            r#"INSERT INTO project_agent_settings (id, project_id, enabled, interval_seconds)
-            VALUES ($1, $2, $3, $4)
+            r#"INSERT INTO project_agent_settings (id, project_id, enabled, interval_seconds, max_concurrent_layers)
+            VALUES ($1, $2, $3, $4, $5)
             ON CONFLICT(project_id) DO UPDATE SET
                 enabled = excluded.enabled,
                 interval_seconds = excluded.interval_seconds,
+                max_concurrent_layers = excluded.max_concurrent_layers,
                 updated_at = CURRENT_TIMESTAMP
             RETURNING
                 id as "id!: Uuid",
@@ -103,12 +222,29 @@ impl ProjectAgentSettings {
                 enabled as "enabled!: bool",
                 interval_seconds as "interval_seconds!: i32",
                 max_breakdown_depth as "max_breakdown_depth!: i32",
+                digest_window_minutes as "digest_window_minutes: i32",
+                max_concurrent_layers as "max_concurrent_layers!: i32",
+                working_dir_strategy as "working_dir_strategy!: WorkingDirStrategy",
+                working_dir_path,
+                auto_breakdown_fullstack as "auto_breakdown_fullstack!: bool",
+                min_complexity_for_auto_attempt as "min_complexity_for_auto_attempt!: i32",
+                max_in_progress_tasks as "max_in_progress_tasks!: i32",
+                max_prompt_description_chars as "max_prompt_description_chars!: i32",
+                max_breakdown_subtasks as "max_breakdown_subtasks!: i32",
+                auto_attempt_retries as "auto_attempt_retries!: i32",
+                include_recent_comments as "include_recent_comments!: bool",
+                integration_blocks_all as "integration_blocks_all!: bool",
+                quiet_hours_start,
+                quiet_hours_end,
+                quiet_hours_utc_offset_minutes as "quiet_hours_utc_offset_minutes!: i32",
+                default_layer as "default_layer: TaskLayer",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             project_id,
             enabled,
-            interval_seconds
+            interval_seconds,
+            max_concurrent_layers
         )
         .fetch_one(pool)
         .await
@@ -119,8 +255,125 @@ impl ProjectAgentSettings {
         project_id: Uuid,
         enabled: bool,
     ) -> Result<Self, sqlx::Error> {
-        // Default interval is 60 seconds
-        Self::create_or_update(pool, project_id, enabled, 60).await
+        // Keep existing tuning; defaults are a 60 second interval and 3 concurrent layers
+        let existing = Self::find_by_project_id(pool, project_id).await?;
+        let interval_seconds = existing.as_ref().map_or(60, |s| s.interval_seconds);
+        let max_concurrent_layers = existing.as_ref().map_or(3, |s| s.max_concurrent_layers);
+        Self::create_or_update(pool, project_id, enabled, interval_seconds, max_concurrent_layers)
+            .await
+    }
+
+    /// Apply a validated settings update in one transaction, creating the project's settings
+    /// (disabled) if needed
+    pub async fn update(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        update: &AgentSettingsUpdate,
+    ) -> Result<Self, sqlx::Error> {
+        let digest_window_set = update.digest_window_minutes.is_some();
+        let digest_window_minutes = update.digest_window_minutes.flatten();
+        let working_dir_strategy = update
+            .working_dir
+            .as_ref()
+            .map(|(strategy, _)| strategy.clone());
+        let working_dir_path = update
+            .working_dir
+            .as_ref()
+            .and_then(|(_, path)| path.as_deref());
+        let quiet_hours_set = update.quiet_hours.is_some();
+        let (quiet_hours_start, quiet_hours_end, quiet_hours_utc_offset_minutes) =
+            match &update.quiet_hours {
+                Some((start, end, offset)) => (start.as_deref(), end.as_deref(), Some(*offset)),
+                None => (None, None, None),
+            };
+        let default_layer_set = update.default_layer.is_some();
+        let default_layer = update.default_layer.clone().flatten();
+
+        let mut tx = pool.begin().await?;
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO project_agent_settings (id, project_id, interval_seconds, max_concurrent_layers)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT(project_id) DO NOTHING"#,
+            id,
+            project_id,
+            update.interval_seconds,
+            update.max_concurrent_layers
+        )
+        .execute(&mut *tx)
+        .await?;
+        let settings = sqlx::query_as!(
+            ProjectAgentSettings,
+            r#"UPDATE project_agent_settings
+            SET interval_seconds = $2,
+                max_concurrent_layers = $3,
+                digest_window_minutes = CASE WHEN $4 THEN $5 ELSE digest_window_minutes END,
+                working_dir_strategy = COALESCE($6, working_dir_strategy),
+                working_dir_path = CASE WHEN $6 IS NULL THEN working_dir_path ELSE $7 END,
+                auto_breakdown_fullstack = COALESCE($8, auto_breakdown_fullstack),
+                min_complexity_for_auto_attempt = COALESCE($9, min_complexity_for_auto_attempt),
+                max_in_progress_tasks = COALESCE($10, max_in_progress_tasks),
+                max_prompt_description_chars = COALESCE($11, max_prompt_description_chars),
+                max_breakdown_subtasks = COALESCE($12, max_breakdown_subtasks),
+                auto_attempt_retries = COALESCE($13, auto_attempt_retries),
+                include_recent_comments = COALESCE($14, include_recent_comments),
+                integration_blocks_all = COALESCE($15, integration_blocks_all),
+                quiet_hours_start = CASE WHEN $16 THEN $17 ELSE quiet_hours_start END,
+                quiet_hours_end = CASE WHEN $16 THEN $18 ELSE quiet_hours_end END,
+                quiet_hours_utc_offset_minutes = COALESCE($19, quiet_hours_utc_offset_minutes),
+                default_layer = CASE WHEN $20 THEN $21 ELSE default_layer END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE project_id = $1
+            RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                interval_seconds as "interval_seconds!: i32",
+                max_breakdown_depth as "max_breakdown_depth!: i32",
+                digest_window_minutes as "digest_window_minutes: i32",
+                max_concurrent_layers as "max_concurrent_layers!: i32",
+                working_dir_strategy as "working_dir_strategy!: WorkingDirStrategy",
+                working_dir_path,
+                auto_breakdown_fullstack as "auto_breakdown_fullstack!: bool",
+                min_complexity_for_auto_attempt as "min_complexity_for_auto_attempt!: i32",
+                max_in_progress_tasks as "max_in_progress_tasks!: i32",
+                max_prompt_description_chars as "max_prompt_description_chars!: i32",
+                max_breakdown_subtasks as "max_breakdown_subtasks!: i32",
+                auto_attempt_retries as "auto_attempt_retries!: i32",
+                include_recent_comments as "include_recent_comments!: bool",
+                integration_blocks_all as "integration_blocks_all!: bool",
+                quiet_hours_start,
+                quiet_hours_end,
+                quiet_hours_utc_offset_minutes as "quiet_hours_utc_offset_minutes!: i32",
+                default_layer as "default_layer: TaskLayer",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            update.interval_seconds,
+            update.max_concurrent_layers,
+            digest_window_set,
+            digest_window_minutes,
+            working_dir_strategy,
+            working_dir_path,
+            update.auto_breakdown_fullstack,
+            update.min_complexity_for_auto_attempt,
+            update.max_in_progress_tasks,
+            update.max_prompt_description_chars,
+            update.max_breakdown_subtasks,
+            update.auto_attempt_retries,
+            update.include_recent_comments,
+            update.integration_blocks_all,
+            quiet_hours_set,
+            quiet_hours_start,
+            quiet_hours_end,
+            quiet_hours_utc_offset_minutes,
+            default_layer_set,
+            default_layer
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(settings)
     }
 
     pub async fn find_all_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
@@ -132,6 +385,22 @@ impl ProjectAgentSettings {
                 enabled as "enabled!: bool",
                 interval_seconds as "interval_seconds!: i32",
                 max_breakdown_depth as "max_breakdown_depth!: i32",
+                digest_window_minutes as "digest_window_minutes: i32",
+                max_concurrent_layers as "max_concurrent_layers!: i32",
+                working_dir_strategy as "working_dir_strategy!: WorkingDirStrategy",
+                working_dir_path,
+                auto_breakdown_fullstack as "auto_breakdown_fullstack!: bool",
+                min_complexity_for_auto_attempt as "min_complexity_for_auto_attempt!: i32",
+                max_in_progress_tasks as "max_in_progress_tasks!: i32",
+                max_prompt_description_chars as "max_prompt_description_chars!: i32",
+                max_breakdown_subtasks as "max_breakdown_subtasks!: i32",
+                auto_attempt_retries as "auto_attempt_retries!: i32",
+                include_recent_comments as "include_recent_comments!: bool",
+                integration_blocks_all as "integration_blocks_all!: bool",
+                quiet_hours_start,
+                quiet_hours_end,
+                quiet_hours_utc_offset_minutes as "quiet_hours_utc_offset_minutes!: i32",
+                default_layer as "default_layer: TaskLayer",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
             FROM project_agent_settings
@@ -149,24 +418,43 @@ impl AgentActivityLog {
         task_id: Option<Uuid>,
         action: AgentAction,
         reasoning: Option<String>,
+    ) -> Result<Self, sqlx::Error> {
+        Self::create_with_details(pool, project_id, task_id, action, reasoning, None, None).await
+    }
+
+    /// Create a log entry for an AI decision, recording its structured reasoning and the prompt
+    /// template version that produced it
+    pub async fn create_with_details(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        task_id: Option<Uuid>,
+        action: AgentAction,
+        reasoning: Option<String>,
+        reasoning_details: Option<SelectionReasoning>,
+        prompt_version: Option<String>,
     ) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
+        let reasoning_details = reasoning_details.map(Json);
         sqlx::query_as!(
             AgentActivityLog,
-            r#"INSERT INTO agent_activity_logs (id, project_id, task_id, action, reasoning)
-            VALUES ($1, $2, $3, $4, $5)
+            r#"INSERT INTO agent_activity_logs (id, project_id, task_id, action, reasoning, reasoning_details, prompt_version)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING
                 id as "id!: Uuid",
                 project_id as "project_id!: Uuid",
                 task_id as "task_id: Uuid",
                 action as "action!: AgentAction",
                 reasoning,
+                reasoning_details as "reasoning_details: Json<SelectionReasoning>",
+                prompt_version,
                 created_at as "created_at!: DateTime<Utc>""#,
             id,
             project_id,
             task_id,
             action,
-            reasoning
+            reasoning,
+            reasoning_details,
+            prompt_version
         )
         .fetch_one(pool)
         .await
@@ -184,6 +472,8 @@ impl AgentActivityLog {
                 task_id as "task_id: Uuid",
                 action as "action!: AgentAction",
                 reasoning,
+                reasoning_details as "reasoning_details: Json<SelectionReasoning>",
+                prompt_version,
                 created_at as "created_at!: DateTime<Utc>"
             FROM agent_activity_logs
             WHERE project_id = $1
@@ -208,6 +498,8 @@ impl AgentActivityLog {
                 task_id as "task_id: Uuid",
                 action as "action!: AgentAction",
                 reasoning,
+                reasoning_details as "reasoning_details: Json<SelectionReasoning>",
+                prompt_version,
                 created_at as "created_at!: DateTime<Utc>"
             FROM agent_activity_logs
             WHERE project_id = $1
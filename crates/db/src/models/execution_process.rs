@@ -323,6 +323,21 @@ impl ExecutionProcess {
         Ok(count > 0)
     }
 
+    /// Count workspaces, across all projects, with running processes other than dev servers
+    pub async fn count_workspaces_with_running_processes(
+        pool: &SqlitePool,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(DISTINCT s.workspace_id) as "count!: i64"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE ep.status = 'running'
+                 AND ep.run_reason != 'devserver'"#
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     /// Find running dev servers for a specific workspace (across all sessions)
     pub async fn find_running_dev_servers_by_workspace(
         pool: &SqlitePool,
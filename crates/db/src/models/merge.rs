@@ -29,6 +29,7 @@ pub struct DirectMerge {
     pub workspace_id: Uuid,
     pub repo_id: Uuid,
     pub merge_commit: String,
+    pub base_commit: Option<String>, // Target head before the merge; None for older records
     pub target_branch_name: String,
     pub created_at: DateTime<Utc>,
 }
@@ -67,6 +68,7 @@ struct MergeRow {
     repo_id: Uuid,
     merge_type: MergeType,
     merge_commit: Option<String>,
+    base_commit: Option<String>,
     target_branch_name: String,
     pr_number: Option<i64>,
     pr_url: Option<String>,
@@ -84,13 +86,17 @@ impl Merge {
         }
     }
 
-    /// Create a direct merge record
+    /// Record a direct merge. A workspace has at most one direct merge per repo and target
+    /// branch: recording another (a retry, or a pinned workspace merged again) replaces the
+    /// commit on the existing row instead of adding a duplicate. `base_commit` is the target
+    /// head before the merge, when known.
     pub async fn create_direct(
         pool: &SqlitePool,
         workspace_id: Uuid,
         repo_id: Uuid,
         target_branch_name: &str,
         merge_commit: &str,
+        base_commit: Option<&str>,
     ) -> Result<DirectMerge, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -98,14 +104,20 @@ impl Merge {
         sqlx::query_as!(
             MergeRow,
             r#"INSERT INTO merges (
-                id, workspace_id, repo_id, merge_type, merge_commit, created_at, target_branch_name
-            ) VALUES ($1, $2, $3, 'direct', $4, $5, $6)
+                id, workspace_id, repo_id, merge_type, merge_commit, base_commit, created_at, target_branch_name
+            ) VALUES ($1, $2, $3, 'direct', $4, $5, $6, $7)
+            ON CONFLICT (workspace_id, repo_id, target_branch_name) WHERE merge_type = 'direct'
+            DO UPDATE SET
+                merge_commit = excluded.merge_commit,
+                base_commit = excluded.base_commit,
+                created_at = excluded.created_at
             RETURNING
                 id as "id!: Uuid",
                 workspace_id as "workspace_id!: Uuid",
                 repo_id as "repo_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                base_commit,
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -118,6 +130,7 @@ impl Merge {
             workspace_id,
             repo_id,
             merge_commit,
+            base_commit,
             now,
             target_branch_name
         )
@@ -148,6 +161,7 @@ impl Merge {
                 repo_id as "repo_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                base_commit,
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -179,6 +193,7 @@ impl Merge {
                 repo_id as "repo_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                base_commit,
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -239,6 +254,7 @@ impl Merge {
                 repo_id as "repo_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                base_commit,
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -258,6 +274,44 @@ impl Merge {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    /// Find the direct merge recorded for a workspace's repo into `target_branch_name`, if any
+    pub async fn find_direct(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        target_branch_name: &str,
+    ) -> Result<Option<DirectMerge>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            MergeRow,
+            r#"SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                merge_type as "merge_type!: MergeType",
+                merge_commit,
+                base_commit,
+                pr_number,
+                pr_url,
+                pr_status as "pr_status?: MergeStatus",
+                pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                pr_merge_commit_sha,
+                target_branch_name as "target_branch_name!: String",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM merges
+            WHERE workspace_id = $1
+              AND repo_id = $2
+              AND target_branch_name = $3
+              AND merge_type = 'direct'"#,
+            workspace_id,
+            repo_id,
+            target_branch_name
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
     /// Find all merges for a workspace and specific repo
     pub async fn find_by_workspace_and_repo_id(
         pool: &SqlitePool,
@@ -272,6 +326,7 @@ impl Merge {
                 repo_id as "repo_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                base_commit,
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -291,6 +346,39 @@ impl Merge {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    /// Find all merges recorded for any workspace of a task, newest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            MergeRow,
+            r#"SELECT
+                m.id as "id!: Uuid",
+                m.workspace_id as "workspace_id!: Uuid",
+                m.repo_id as "repo_id!: Uuid",
+                m.merge_type as "merge_type!: MergeType",
+                m.merge_commit,
+                m.base_commit,
+                m.pr_number,
+                m.pr_url,
+                m.pr_status as "pr_status?: MergeStatus",
+                m.pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                m.pr_merge_commit_sha,
+                m.target_branch_name as "target_branch_name!: String",
+                m.created_at as "created_at!: DateTime<Utc>"
+            FROM merges m
+            JOIN workspaces w ON m.workspace_id = w.id
+            WHERE w.task_id = $1
+            ORDER BY m.created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     /// Get the latest PR status for each workspace (for workspace summaries)
     /// Returns a map of workspace_id -> MergeStatus for workspaces that have PRs
     pub async fn get_latest_pr_status_for_workspaces(
@@ -341,6 +429,7 @@ impl From<MergeRow> for DirectMerge {
             merge_commit: row
                 .merge_commit
                 .expect("direct merge must have merge_commit"),
+            base_commit: row.base_commit,
             target_branch_name: row.target_branch_name,
             created_at: row.created_at,
         }
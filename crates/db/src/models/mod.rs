@@ -6,6 +6,8 @@ pub mod execution_process_repo_state;
 pub mod image;
 pub mod merge;
 pub mod project;
+pub mod project_notification_channel;
+pub mod project_prompt_version;
 pub mod project_repo;
 pub mod project_requirements;
 pub mod repo;
@@ -14,5 +16,8 @@ pub mod scratch;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_comment;
+pub mod task_complexity_analysis;
+pub mod task_status_history;
 pub mod workspace;
 pub mod workspace_repo;
@@ -23,6 +23,13 @@ pub struct Project {
     pub name: String,
     pub default_agent_working_dir: Option<String>,
     pub remote_project_id: Option<Uuid>,
+    /// Description, tech stack and conventions given to the AI when generating work
+    pub context: Option<String>,
+    /// Org-specific guidance prepended to the system prompt of every AI call for the project
+    pub system_prompt_prefix: Option<String>,
+    /// Provider for pull/merge requests ("github", "gitlab" or "azure_devops"); None detects it
+    /// from the remote URL
+    pub git_host_provider: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -38,6 +45,9 @@ pub struct CreateProject {
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
+    pub context: Option<String>, // Empty string clears the context
+    pub system_prompt_prefix: Option<String>, // Empty string clears the prefix
+    pub git_host_provider: Option<String>, // Empty string clears the provider
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -71,6 +81,9 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      context,
+                      system_prompt_prefix,
+                      git_host_provider,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -88,6 +101,9 @@ impl Project {
             SELECT p.id as "id!: Uuid", p.name,
                    p.default_agent_working_dir,
                    p.remote_project_id as "remote_project_id: Uuid",
+                   p.context,
+                   p.system_prompt_prefix,
+                   p.git_host_provider,
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -111,6 +127,9 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      context,
+                      system_prompt_prefix,
+                      git_host_provider,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -128,6 +147,9 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      context,
+                      system_prompt_prefix,
+                      git_host_provider,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -148,6 +170,9 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      context,
+                      system_prompt_prefix,
+                      git_host_provider,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -176,6 +201,9 @@ impl Project {
                           name,
                           default_agent_working_dir,
                           remote_project_id as "remote_project_id: Uuid",
+                          context,
+                          system_prompt_prefix,
+                          git_host_provider,
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
@@ -195,20 +223,41 @@ impl Project {
             .ok_or(sqlx::Error::RowNotFound)?;
 
         let name = payload.name.clone().unwrap_or(existing.name);
+        let context = match payload.context.as_deref().map(str::trim) {
+            Some("") => None,
+            Some(context) => Some(context.to_string()),
+            None => existing.context,
+        };
+        let system_prompt_prefix = match payload.system_prompt_prefix.as_deref().map(str::trim) {
+            Some("") => None,
+            Some(prefix) => Some(prefix.to_string()),
+            None => existing.system_prompt_prefix,
+        };
+        let git_host_provider = match payload.git_host_provider.as_deref().map(str::trim) {
+            Some("") => None,
+            Some(provider) => Some(provider.to_string()),
+            None => existing.git_host_provider,
+        };
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2
+               SET name = $2, context = $3, system_prompt_prefix = $4, git_host_provider = $5
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          default_agent_working_dir,
                          remote_project_id as "remote_project_id: Uuid",
+                         context,
+                         system_prompt_prefix,
+                         git_host_provider,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
+            context,
+            system_prompt_prefix,
+            git_host_provider,
         )
         .fetch_one(pool)
         .await
@@ -0,0 +1,162 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Where a project's notifications are delivered
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display,
+)]
+#[sqlx(type_name = "notification_channel_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationChannelType {
+    Desktop, // Sound and OS push notifications, per the global notification config
+    Webhook, // config: {"url": "..."}
+    Slack,   // config: {"webhook_url": "..."}
+}
+
+/// A notification channel configured for a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectNotificationChannel {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub channel_type: NotificationChannelType,
+    #[ts(type = "JsonValue")]
+    pub config: Json<Value>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body for adding a notification channel to a project
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateProjectNotificationChannel {
+    pub channel_type: NotificationChannelType,
+    #[ts(type = "JsonValue | null")]
+    pub config: Option<Value>,
+    pub enabled: Option<bool>,
+}
+
+/// Request body for updating a notification channel; None keeps the current value
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateProjectNotificationChannel {
+    #[ts(type = "JsonValue | null")]
+    pub config: Option<Value>,
+    pub enabled: Option<bool>,
+}
+
+impl ProjectNotificationChannel {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectNotificationChannel,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                channel_type as "channel_type!: NotificationChannelType",
+                config as "config!: Json<Value>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM project_notification_channels
+            WHERE project_id = $1
+            ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectNotificationChannel,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                channel_type as "channel_type!: NotificationChannelType",
+                config as "config!: Json<Value>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM project_notification_channels
+            WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        channel_type: NotificationChannelType,
+        config: &Value,
+        enabled: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let config = Json(config);
+        sqlx::query_as!(
+            ProjectNotificationChannel,
+            r#"INSERT INTO project_notification_channels (id, project_id, channel_type, config, enabled)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                channel_type as "channel_type!: NotificationChannelType",
+                config as "config!: Json<Value>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            channel_type,
+            config,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        config: &Value,
+        enabled: bool,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let config = Json(config);
+        sqlx::query_as!(
+            ProjectNotificationChannel,
+            r#"UPDATE project_notification_channels
+            SET config = $2,
+                enabled = $3,
+                updated_at = datetime('now', 'subsec')
+            WHERE id = $1
+            RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                channel_type as "channel_type!: NotificationChannelType",
+                config as "config!: Json<Value>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            config,
+            enabled
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_notification_channels WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
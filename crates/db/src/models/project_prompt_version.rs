@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A prompt template version pinned for a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectPromptVersion {
+    pub project_id: Uuid,
+    pub template_name: String,
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body for pinning a prompt template version; None follows the latest version
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SetProjectPromptVersion {
+    pub version: Option<i64>,
+}
+
+impl ProjectPromptVersion {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPromptVersion,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                template_name,
+                version as "version!: i64",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM project_prompt_versions
+            WHERE project_id = $1
+            ORDER BY template_name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The version pinned for a template, if any
+    pub async fn find_version(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        template_name: &str,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT version as "version!: i64"
+            FROM project_prompt_versions
+            WHERE project_id = $1 AND template_name = $2"#,
+            project_id,
+            template_name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Pin a template version for a project, replacing any existing pin
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        template_name: &str,
+        version: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPromptVersion,
+            r#"INSERT INTO project_prompt_versions (project_id, template_name, version)
+            VALUES ($1, $2, $3)
+            ON CONFLICT(project_id, template_name) DO UPDATE SET
+                version = excluded.version,
+                updated_at = datetime('now', 'subsec')
+            RETURNING
+                project_id as "project_id!: Uuid",
+                template_name,
+                version as "version!: i64",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            template_name,
+            version
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Unpin a template so the project follows its latest version
+    pub async fn delete(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        template_name: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_prompt_versions WHERE project_id = $1 AND template_name = $2",
+            project_id,
+            template_name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
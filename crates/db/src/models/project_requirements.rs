@@ -19,9 +19,34 @@ pub enum GenerationStatus {
     Failed,
 }
 
+impl GenerationStatus {
+    /// Whether an analysis run owns the record. `Pending` counts because the record sits in it
+    /// between creation and the background run picking it up.
+    pub fn is_in_progress(&self) -> bool {
+        matches!(self, Self::Pending | Self::Analyzing | Self::Generating)
+    }
+}
+
+/// How finely feature extraction decomposes requirements, to keep plan size predictable
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
+)]
+#[sqlx(type_name = "feature_granularity", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum FeatureGranularity {
+    Coarse,
+    #[default]
+    Normal,
+    Fine,
+}
+
 /// A feature extracted from requirements analysis
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ExtractedFeature {
+    /// Stable id used to link generated tasks back to this feature
+    #[serde(default)]
+    pub id: Uuid,
     pub name: String,
     pub description: String,
     pub layer: Option<String>,
@@ -33,6 +58,16 @@ pub struct ExtractedFeature {
 pub struct AnalysisResult {
     pub features: Vec<ExtractedFeature>,
     pub summary: Option<String>,
+    /// Feature extraction prompt that produced this result, e.g. 'feature_extraction@v1'
+    #[serde(default)]
+    pub prompt_version: Option<String>,
+}
+
+impl AnalysisResult {
+    /// Find a feature by its stable id
+    pub fn find_feature(&self, feature_id: Uuid) -> Option<&ExtractedFeature> {
+        self.features.iter().find(|f| f.id == feature_id)
+    }
 }
 
 /// Project requirements with generation state
@@ -45,6 +80,7 @@ pub struct ProjectRequirements {
     pub analysis_result: Option<String>, // JSON-serialized AnalysisResult
     pub generation_status: GenerationStatus,
     pub error_message: Option<String>,
+    pub granularity: FeatureGranularity,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -58,11 +94,23 @@ impl ProjectRequirements {
     }
 }
 
-/// Request body for creating requirements
+/// Request body for creating or editing requirements
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct CreateProjectRequirements {
     pub raw_requirements: String,
     pub prd_content: Option<String>,
+    pub granularity: Option<FeatureGranularity>, // None: normal when creating, unchanged when editing
+}
+
+/// An earlier version of a project's requirements, saved when they were edited
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectRequirementsVersion {
+    pub id: Uuid,
+    pub requirements_id: Uuid,
+    pub raw_requirements: String,
+    pub prd_content: Option<String>,
+    pub analysis_result: Option<String>, // JSON-serialized AnalysisResult of this version
+    pub created_at: DateTime<Utc>,
 }
 
 /// Response for requirements status
@@ -74,6 +122,9 @@ pub struct ProjectRequirementsStatus {
     pub analysis_result: Option<AnalysisResult>,
     pub tasks_generated: Option<i32>,
     pub error_message: Option<String>,
+    pub raw_requirements: Option<String>, // Only set when the submitted text is requested
+    pub prd_content: Option<String>, // Only set when the submitted text is requested
+    pub granularity: FeatureGranularity,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -85,11 +136,13 @@ impl ProjectRequirements {
         project_id: Uuid,
         data: &CreateProjectRequirements,
     ) -> Result<Self, sqlx::Error> {
+        let granularity = data.granularity.unwrap_or_default();
         sqlx::query_as!(
             ProjectRequirements,
             r#"
-            INSERT INTO project_requirements (id, project_id, raw_requirements, prd_content)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO project_requirements
+                (id, project_id, raw_requirements, prd_content, granularity)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING
                 id              as "id!: Uuid",
                 project_id      as "project_id!: Uuid",
@@ -97,6 +150,7 @@ impl ProjectRequirements {
                 prd_content,
                 analysis_result,
                 generation_status as "generation_status!: GenerationStatus",
+                granularity     as "granularity!: FeatureGranularity",
                 error_message,
                 created_at      as "created_at!: DateTime<Utc>",
                 updated_at      as "updated_at!: DateTime<Utc>"
@@ -105,11 +159,55 @@ impl ProjectRequirements {
             project_id,
             data.raw_requirements,
             data.prd_content,
+            granularity,
         )
         .fetch_one(pool)
         .await
     }
 
+    /// Create a requirements record unless the project already has one being analyzed.
+    /// The check and insert are a single statement so concurrent callers cannot both win.
+    /// Returns `None` when an analysis is already in progress.
+    pub async fn create_unless_in_progress(
+        pool: &SqlitePool,
+        id: Uuid,
+        project_id: Uuid,
+        data: &CreateProjectRequirements,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let granularity = data.granularity.unwrap_or_default();
+        sqlx::query_as!(
+            ProjectRequirements,
+            r#"
+            INSERT INTO project_requirements
+                (id, project_id, raw_requirements, prd_content, granularity)
+            SELECT $1, $2, $3, $4, $5
+            WHERE NOT EXISTS (
+                SELECT 1 FROM project_requirements
+                WHERE project_id = $2
+                  AND generation_status IN ('pending', 'analyzing', 'generating')
+            )
+            RETURNING
+                id              as "id!: Uuid",
+                project_id      as "project_id!: Uuid",
+                raw_requirements,
+                prd_content,
+                analysis_result,
+                generation_status as "generation_status!: GenerationStatus",
+                granularity     as "granularity!: FeatureGranularity",
+                error_message,
+                created_at      as "created_at!: DateTime<Utc>",
+                updated_at      as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            project_id,
+            data.raw_requirements,
+            data.prd_content,
+            granularity,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             ProjectRequirements,
@@ -121,6 +219,7 @@ impl ProjectRequirements {
                 prd_content,
                 analysis_result,
                 generation_status as "generation_status!: GenerationStatus",
+                granularity     as "granularity!: FeatureGranularity",
                 error_message,
                 created_at      as "created_at!: DateTime<Utc>",
                 updated_at      as "updated_at!: DateTime<Utc>"
@@ -147,6 +246,7 @@ impl ProjectRequirements {
                 prd_content,
                 analysis_result,
                 generation_status as "generation_status!: GenerationStatus",
+                granularity     as "granularity!: FeatureGranularity",
                 error_message,
                 created_at      as "created_at!: DateTime<Utc>",
                 updated_at      as "updated_at!: DateTime<Utc>"
@@ -184,6 +284,24 @@ impl ProjectRequirements {
         Ok(())
     }
 
+    /// Fail every record an analysis run still owns. Runs are background tasks that don't
+    /// survive a restart, so at startup these records would otherwise stay in progress and
+    /// block new analyses for their project.
+    pub async fn fail_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE project_requirements
+            SET generation_status = 'failed',
+                error_message = 'Analysis was interrupted by a restart',
+                updated_at = datetime('now', 'subsec')
+            WHERE generation_status IN ('pending', 'analyzing', 'generating')
+            "#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn update_analysis_result(
         pool: &SqlitePool,
         id: Uuid,
@@ -206,6 +324,68 @@ impl ProjectRequirements {
         Ok(())
     }
 
+    /// Replace the requirements text, saving the current text and analysis as a version and
+    /// resetting the record to pending. Returns `None` while an analysis is in progress.
+    pub async fn update_content_unless_in_progress(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateProjectRequirements,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let version_id = Uuid::new_v4();
+        let archived = sqlx::query!(
+            r#"
+            INSERT INTO project_requirements_versions
+                (id, requirements_id, raw_requirements, prd_content, analysis_result)
+            SELECT $1, id, raw_requirements, prd_content, analysis_result
+            FROM project_requirements
+            WHERE id = $2
+              AND generation_status NOT IN ('pending', 'analyzing', 'generating')
+            "#,
+            version_id,
+            id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if archived == 0 {
+            return Ok(None);
+        }
+
+        let requirements = sqlx::query_as!(
+            ProjectRequirements,
+            r#"
+            UPDATE project_requirements
+            SET raw_requirements = $2,
+                prd_content = $3,
+                granularity = COALESCE($4, granularity),
+                generation_status = 'pending',
+                error_message = NULL,
+                updated_at = datetime('now', 'subsec')
+            WHERE id = $1
+            RETURNING
+                id              as "id!: Uuid",
+                project_id      as "project_id!: Uuid",
+                raw_requirements,
+                prd_content,
+                analysis_result,
+                generation_status as "generation_status!: GenerationStatus",
+                granularity     as "granularity!: FeatureGranularity",
+                error_message,
+                created_at      as "created_at!: DateTime<Utc>",
+                updated_at      as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            data.raw_requirements,
+            data.prd_content,
+            data.granularity,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some(requirements))
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM project_requirements WHERE id = $1", id)
             .execute(pool)
@@ -226,3 +406,30 @@ impl ProjectRequirements {
         Ok(result.rows_affected())
     }
 }
+
+impl ProjectRequirementsVersion {
+    /// Earlier versions of a requirements record, newest first
+    pub async fn find_by_requirements_id(
+        pool: &SqlitePool,
+        requirements_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectRequirementsVersion,
+            r#"
+            SELECT
+                id              as "id!: Uuid",
+                requirements_id as "requirements_id!: Uuid",
+                raw_requirements,
+                prd_content,
+                analysis_result,
+                created_at      as "created_at!: DateTime<Utc>"
+            FROM project_requirements_versions
+            WHERE requirements_id = $1
+            ORDER BY created_at DESC
+            "#,
+            requirements_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
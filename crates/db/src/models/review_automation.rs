@@ -17,6 +17,12 @@ pub enum ReviewAction {
     MergeConflict,
     Skipped,
     Error,
+    NoChanges,        // Branch has no commits ahead of its target; nothing to merge
+    ConflictResolved, // Conflicts were resolved manually; earlier conflicts no longer count
+    CriteriaMet,      // The task's testing_criteria were judged met by its changes and tests
+    CriteriaUnmet,    // The task's testing_criteria were judged unmet; merge was blocked
+    MergeReverted,    // A merged task was reverted on its target branch and sent back to Todo
+    PartiallyMerged,  // Some of a task's repos merged while others conflicted; lists what remains
 }
 
 /// Review automation settings for a project
@@ -27,6 +33,25 @@ pub struct ProjectReviewSettings {
     pub enabled: bool,
     pub auto_merge_enabled: bool,
     pub run_tests_enabled: bool,
+    pub fetch_before_merge: bool,
+    pub store_test_artifacts: bool,
+    pub fast_forward_merges: bool,
+    pub conventional_commits: bool,
+    pub sign_commits: bool,
+    pub complete_parent_on_subtasks_done: bool,
+    pub require_successful_attempt: bool,
+    pub auto_archive_on_merge: bool,
+    pub stash_before_merge: bool,
+    pub drop_stash_after_merge: bool,
+    pub require_tests_before_merge: bool,
+    pub cherry_pick_single_commits: bool,
+    pub enforce_testing_criteria: bool,
+    pub allow_partial_merge: bool,
+    pub progress_repo: Option<String>, // Repo name for .progress updates (None: first repo)
+    pub test_targets: Option<String>, // Lines of 'glob => command' (None: always run the full suite)
+    pub pre_merge_command: Option<String>, // Shell command that must exit 0 before auto-merge (None: no gate)
+    pub auto_merge_target_allowlist: Option<String>, // Branch names or globs auto-merge may target (None: any branch)
+    pub test_profiles: Option<String>, // Lines of 'task type => command' (None: every type runs the usual tests)
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -40,6 +65,7 @@ pub struct ReviewAutomationLog {
     pub action: ReviewAction,
     pub output: Option<String>,
     pub error_message: Option<String>,
+    pub artifact_path: Option<String>, // Full output on disk when output holds only a preview
     pub created_at: DateTime<Utc>,
 }
 
@@ -49,6 +75,25 @@ pub struct ReviewAutomationStatus {
     pub enabled: bool,
     pub auto_merge_enabled: bool,
     pub run_tests_enabled: bool,
+    pub fetch_before_merge: bool,
+    pub store_test_artifacts: bool,
+    pub fast_forward_merges: bool,
+    pub conventional_commits: bool,
+    pub sign_commits: bool,
+    pub complete_parent_on_subtasks_done: bool,
+    pub require_successful_attempt: bool,
+    pub auto_archive_on_merge: bool,
+    pub stash_before_merge: bool,
+    pub drop_stash_after_merge: bool,
+    pub require_tests_before_merge: bool,
+    pub cherry_pick_single_commits: bool,
+    pub enforce_testing_criteria: bool,
+    pub allow_partial_merge: bool,
+    pub progress_repo: Option<String>,
+    pub test_targets: Option<String>,
+    pub pre_merge_command: Option<String>,
+    pub auto_merge_target_allowlist: Option<String>,
+    pub test_profiles: Option<String>,
     pub last_action: Option<ReviewAction>,
     pub last_task_id: Option<Uuid>,
 }
@@ -59,6 +104,61 @@ pub struct ReviewAutomationSettingsResponse {
     pub enabled: bool,
     pub auto_merge_enabled: bool,
     pub run_tests_enabled: bool,
+    pub fetch_before_merge: bool,
+    pub store_test_artifacts: bool,
+    pub fast_forward_merges: bool,
+    pub conventional_commits: bool,
+    pub sign_commits: bool,
+    pub complete_parent_on_subtasks_done: bool,
+    pub require_successful_attempt: bool,
+    pub auto_archive_on_merge: bool,
+    pub stash_before_merge: bool,
+    pub drop_stash_after_merge: bool,
+    pub require_tests_before_merge: bool,
+    pub cherry_pick_single_commits: bool,
+    pub enforce_testing_criteria: bool,
+    pub allow_partial_merge: bool,
+    pub progress_repo: Option<String>,
+    pub test_targets: Option<String>,
+    pub pre_merge_command: Option<String>,
+    pub auto_merge_target_allowlist: Option<String>,
+    pub test_profiles: Option<String>,
+}
+
+/// Request body for updating review automation settings
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateReviewAutomationSettings {
+    pub enabled: bool,
+    pub auto_merge_enabled: bool,
+    pub run_tests_enabled: bool,
+    pub fetch_before_merge: Option<bool>,
+    pub store_test_artifacts: Option<bool>,
+    pub fast_forward_merges: Option<bool>,
+    pub conventional_commits: Option<bool>,
+    pub sign_commits: Option<bool>,
+    pub complete_parent_on_subtasks_done: Option<bool>,
+    pub require_successful_attempt: Option<bool>,
+    pub auto_archive_on_merge: Option<bool>,
+    pub stash_before_merge: Option<bool>,
+    pub drop_stash_after_merge: Option<bool>,
+    pub require_tests_before_merge: Option<bool>,
+    pub cherry_pick_single_commits: Option<bool>,
+    pub enforce_testing_criteria: Option<bool>,
+    pub allow_partial_merge: Option<bool>,
+    pub progress_repo: Option<String>, // Empty string clears the setting
+    pub test_targets: Option<String>, // Empty string clears the setting
+    pub pre_merge_command: Option<String>, // Empty string clears the setting
+    pub auto_merge_target_allowlist: Option<String>, // Empty string clears the setting
+    pub test_profiles: Option<String>, // Empty string clears the setting
+}
+
+/// Response for settings updates, with warnings about risky combinations
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReviewAutomationSettingsUpdateResponse {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub settings: ReviewAutomationSettingsResponse,
+    pub warnings: Vec<String>,
 }
 
 impl From<ProjectReviewSettings> for ReviewAutomationSettingsResponse {
@@ -67,6 +167,25 @@ impl From<ProjectReviewSettings> for ReviewAutomationSettingsResponse {
             enabled: settings.enabled,
             auto_merge_enabled: settings.auto_merge_enabled,
             run_tests_enabled: settings.run_tests_enabled,
+            fetch_before_merge: settings.fetch_before_merge,
+            store_test_artifacts: settings.store_test_artifacts,
+            fast_forward_merges: settings.fast_forward_merges,
+            conventional_commits: settings.conventional_commits,
+            sign_commits: settings.sign_commits,
+            complete_parent_on_subtasks_done: settings.complete_parent_on_subtasks_done,
+            require_successful_attempt: settings.require_successful_attempt,
+            auto_archive_on_merge: settings.auto_archive_on_merge,
+            stash_before_merge: settings.stash_before_merge,
+            drop_stash_after_merge: settings.drop_stash_after_merge,
+            require_tests_before_merge: settings.require_tests_before_merge,
+            cherry_pick_single_commits: settings.cherry_pick_single_commits,
+            enforce_testing_criteria: settings.enforce_testing_criteria,
+            allow_partial_merge: settings.allow_partial_merge,
+            progress_repo: settings.progress_repo,
+            test_targets: settings.test_targets,
+            pre_merge_command: settings.pre_merge_command,
+            auto_merge_target_allowlist: settings.auto_merge_target_allowlist,
+            test_profiles: settings.test_profiles,
         }
     }
 }
@@ -84,6 +203,25 @@ impl ProjectReviewSettings {
                 enabled as "enabled!: bool",
                 auto_merge_enabled as "auto_merge_enabled!: bool",
                 run_tests_enabled as "run_tests_enabled!: bool",
+                fetch_before_merge as "fetch_before_merge!: bool",
+                store_test_artifacts as "store_test_artifacts!: bool",
+                fast_forward_merges as "fast_forward_merges!: bool",
+                conventional_commits as "conventional_commits!: bool",
+                sign_commits as "sign_commits!: bool",
+                complete_parent_on_subtasks_done as "complete_parent_on_subtasks_done!: bool",
+                require_successful_attempt as "require_successful_attempt!: bool",
+                auto_archive_on_merge as "auto_archive_on_merge!: bool",
+                stash_before_merge as "stash_before_merge!: bool",
+                drop_stash_after_merge as "drop_stash_after_merge!: bool",
+                require_tests_before_merge as "require_tests_before_merge!: bool",
+                cherry_pick_single_commits as "cherry_pick_single_commits!: bool",
+                enforce_testing_criteria as "enforce_testing_criteria!: bool",
+                allow_partial_merge as "allow_partial_merge!: bool",
+                progress_repo,
+                test_targets,
+                pre_merge_command,
+                auto_merge_target_allowlist,
+                test_profiles,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
             FROM project_review_settings
@@ -117,6 +255,25 @@ impl ProjectReviewSettings {
                 enabled as "enabled!: bool",
                 auto_merge_enabled as "auto_merge_enabled!: bool",
                 run_tests_enabled as "run_tests_enabled!: bool",
+                fetch_before_merge as "fetch_before_merge!: bool",
+                store_test_artifacts as "store_test_artifacts!: bool",
+                fast_forward_merges as "fast_forward_merges!: bool",
+                conventional_commits as "conventional_commits!: bool",
+                sign_commits as "sign_commits!: bool",
+                complete_parent_on_subtasks_done as "complete_parent_on_subtasks_done!: bool",
+                require_successful_attempt as "require_successful_attempt!: bool",
+                auto_archive_on_merge as "auto_archive_on_merge!: bool",
+                stash_before_merge as "stash_before_merge!: bool",
+                drop_stash_after_merge as "drop_stash_after_merge!: bool",
+                require_tests_before_merge as "require_tests_before_merge!: bool",
+                cherry_pick_single_commits as "cherry_pick_single_commits!: bool",
+                enforce_testing_criteria as "enforce_testing_criteria!: bool",
+                allow_partial_merge as "allow_partial_merge!: bool",
+                progress_repo,
+                test_targets,
+                pre_merge_command,
+                auto_merge_target_allowlist,
+                test_profiles,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -134,8 +291,124 @@ impl ProjectReviewSettings {
         project_id: Uuid,
         enabled: bool,
     ) -> Result<Self, sqlx::Error> {
-        // Default: auto_merge and run_tests are enabled
-        Self::create_or_update(pool, project_id, enabled, true, true).await
+        // Keep existing toggles; new projects default to auto_merge and run_tests enabled
+        let existing = Self::find_by_project_id(pool, project_id).await?;
+        let auto_merge_enabled = existing.as_ref().is_none_or(|s| s.auto_merge_enabled);
+        let run_tests_enabled = existing.as_ref().is_none_or(|s| s.run_tests_enabled);
+        Self::create_or_update(pool, project_id, enabled, auto_merge_enabled, run_tests_enabled)
+            .await
+    }
+
+    /// Apply a settings update in one transaction, creating the project's settings if needed.
+    /// Options left `None` keep their current value; an empty text setting clears it.
+    pub async fn update(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        update: &UpdateReviewAutomationSettings,
+    ) -> Result<Self, sqlx::Error> {
+        let progress_repo = update.progress_repo.as_deref().map(str::trim);
+        let test_targets = update.test_targets.as_deref().map(str::trim);
+        let pre_merge_command = update.pre_merge_command.as_deref().map(str::trim);
+        let auto_merge_target_allowlist =
+            update.auto_merge_target_allowlist.as_deref().map(str::trim);
+        let test_profiles = update.test_profiles.as_deref().map(str::trim);
+
+        let mut tx = pool.begin().await?;
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO project_review_settings (id, project_id, enabled, auto_merge_enabled, run_tests_enabled)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT(project_id) DO NOTHING"#,
+            id,
+            project_id,
+            update.enabled,
+            update.auto_merge_enabled,
+            update.run_tests_enabled
+        )
+        .execute(&mut *tx)
+        .await?;
+        let settings = sqlx::query_as!(
+            ProjectReviewSettings,
+            r#"UPDATE project_review_settings
+            SET enabled = $2,
+                auto_merge_enabled = $3,
+                run_tests_enabled = $4,
+                fetch_before_merge = COALESCE($5, fetch_before_merge),
+                store_test_artifacts = COALESCE($6, store_test_artifacts),
+                fast_forward_merges = COALESCE($7, fast_forward_merges),
+                conventional_commits = COALESCE($8, conventional_commits),
+                sign_commits = COALESCE($9, sign_commits),
+                complete_parent_on_subtasks_done = COALESCE($10, complete_parent_on_subtasks_done),
+                require_successful_attempt = COALESCE($11, require_successful_attempt),
+                auto_archive_on_merge = COALESCE($12, auto_archive_on_merge),
+                stash_before_merge = COALESCE($13, stash_before_merge),
+                drop_stash_after_merge = COALESCE($14, drop_stash_after_merge),
+                require_tests_before_merge = COALESCE($15, require_tests_before_merge),
+                cherry_pick_single_commits = COALESCE($16, cherry_pick_single_commits),
+                enforce_testing_criteria = COALESCE($17, enforce_testing_criteria),
+                allow_partial_merge = COALESCE($18, allow_partial_merge),
+                progress_repo = CASE WHEN $19 IS NULL THEN progress_repo ELSE NULLIF($19, '') END,
+                test_targets = CASE WHEN $20 IS NULL THEN test_targets ELSE NULLIF($20, '') END,
+                pre_merge_command = CASE WHEN $21 IS NULL THEN pre_merge_command ELSE NULLIF($21, '') END,
+                auto_merge_target_allowlist = CASE WHEN $22 IS NULL THEN auto_merge_target_allowlist ELSE NULLIF($22, '') END,
+                test_profiles = CASE WHEN $23 IS NULL THEN test_profiles ELSE NULLIF($23, '') END,
+                updated_at = datetime('now', 'subsec')
+            WHERE project_id = $1
+            RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                auto_merge_enabled as "auto_merge_enabled!: bool",
+                run_tests_enabled as "run_tests_enabled!: bool",
+                fetch_before_merge as "fetch_before_merge!: bool",
+                store_test_artifacts as "store_test_artifacts!: bool",
+                fast_forward_merges as "fast_forward_merges!: bool",
+                conventional_commits as "conventional_commits!: bool",
+                sign_commits as "sign_commits!: bool",
+                complete_parent_on_subtasks_done as "complete_parent_on_subtasks_done!: bool",
+                require_successful_attempt as "require_successful_attempt!: bool",
+                auto_archive_on_merge as "auto_archive_on_merge!: bool",
+                stash_before_merge as "stash_before_merge!: bool",
+                drop_stash_after_merge as "drop_stash_after_merge!: bool",
+                require_tests_before_merge as "require_tests_before_merge!: bool",
+                cherry_pick_single_commits as "cherry_pick_single_commits!: bool",
+                enforce_testing_criteria as "enforce_testing_criteria!: bool",
+                allow_partial_merge as "allow_partial_merge!: bool",
+                progress_repo,
+                test_targets,
+                pre_merge_command,
+                auto_merge_target_allowlist,
+                test_profiles,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            update.enabled,
+            update.auto_merge_enabled,
+            update.run_tests_enabled,
+            update.fetch_before_merge,
+            update.store_test_artifacts,
+            update.fast_forward_merges,
+            update.conventional_commits,
+            update.sign_commits,
+            update.complete_parent_on_subtasks_done,
+            update.require_successful_attempt,
+            update.auto_archive_on_merge,
+            update.stash_before_merge,
+            update.drop_stash_after_merge,
+            update.require_tests_before_merge,
+            update.cherry_pick_single_commits,
+            update.enforce_testing_criteria,
+            update.allow_partial_merge,
+            progress_repo,
+            test_targets,
+            pre_merge_command,
+            auto_merge_target_allowlist,
+            test_profiles
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(settings)
     }
 
     pub async fn find_all_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
@@ -147,6 +420,25 @@ impl ProjectReviewSettings {
                 enabled as "enabled!: bool",
                 auto_merge_enabled as "auto_merge_enabled!: bool",
                 run_tests_enabled as "run_tests_enabled!: bool",
+                fetch_before_merge as "fetch_before_merge!: bool",
+                store_test_artifacts as "store_test_artifacts!: bool",
+                fast_forward_merges as "fast_forward_merges!: bool",
+                conventional_commits as "conventional_commits!: bool",
+                sign_commits as "sign_commits!: bool",
+                complete_parent_on_subtasks_done as "complete_parent_on_subtasks_done!: bool",
+                require_successful_attempt as "require_successful_attempt!: bool",
+                auto_archive_on_merge as "auto_archive_on_merge!: bool",
+                stash_before_merge as "stash_before_merge!: bool",
+                drop_stash_after_merge as "drop_stash_after_merge!: bool",
+                require_tests_before_merge as "require_tests_before_merge!: bool",
+                cherry_pick_single_commits as "cherry_pick_single_commits!: bool",
+                enforce_testing_criteria as "enforce_testing_criteria!: bool",
+                allow_partial_merge as "allow_partial_merge!: bool",
+                progress_repo,
+                test_targets,
+                pre_merge_command,
+                auto_merge_target_allowlist,
+                test_profiles,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
             FROM project_review_settings
@@ -165,12 +457,26 @@ impl ReviewAutomationLog {
         action: ReviewAction,
         output: Option<String>,
         error_message: Option<String>,
+    ) -> Result<Self, sqlx::Error> {
+        Self::create_with_artifact(pool, task_id, workspace_id, action, output, error_message, None)
+            .await
+    }
+
+    /// Create a log entry whose full output lives in an artifact file on disk
+    pub async fn create_with_artifact(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        workspace_id: Uuid,
+        action: ReviewAction,
+        output: Option<String>,
+        error_message: Option<String>,
+        artifact_path: Option<String>,
     ) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
         sqlx::query_as!(
             ReviewAutomationLog,
-            r#"INSERT INTO review_automation_logs (id, task_id, workspace_id, action, output, error_message)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            r#"INSERT INTO review_automation_logs (id, task_id, workspace_id, action, output, error_message, artifact_path)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING
                 id as "id!: Uuid",
                 task_id as "task_id!: Uuid",
@@ -178,18 +484,40 @@ impl ReviewAutomationLog {
                 action as "action!: ReviewAction",
                 output,
                 error_message,
+                artifact_path,
                 created_at as "created_at!: DateTime<Utc>""#,
             id,
             task_id,
             workspace_id,
             action,
             output,
-            error_message
+            error_message,
+            artifact_path
         )
         .fetch_one(pool)
         .await
     }
 
+    pub async fn find_by_id(pool: &SqlitePool, log_id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewAutomationLog,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                action as "action!: ReviewAction",
+                output,
+                error_message,
+                artifact_path,
+                created_at as "created_at!: DateTime<Utc>"
+            FROM review_automation_logs
+            WHERE id = $1"#,
+            log_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_latest_by_project_id(
         pool: &SqlitePool,
         project_id: Uuid,
@@ -203,6 +531,7 @@ impl ReviewAutomationLog {
                 ral.action as "action!: ReviewAction",
                 ral.output,
                 ral.error_message,
+                ral.artifact_path,
                 ral.created_at as "created_at!: DateTime<Utc>"
             FROM review_automation_logs ral
             JOIN tasks t ON ral.task_id = t.id
@@ -229,6 +558,7 @@ impl ReviewAutomationLog {
                 ral.action as "action!: ReviewAction",
                 ral.output,
                 ral.error_message,
+                ral.artifact_path,
                 ral.created_at as "created_at!: DateTime<Utc>"
             FROM review_automation_logs ral
             JOIN tasks t ON ral.task_id = t.id
@@ -255,6 +585,7 @@ impl ReviewAutomationLog {
                 action as "action!: ReviewAction",
                 output,
                 error_message,
+                artifact_path,
                 created_at as "created_at!: DateTime<Utc>"
             FROM review_automation_logs
             WHERE task_id = $1
@@ -265,7 +596,7 @@ impl ReviewAutomationLog {
         .await
     }
 
-    /// Count the number of merge conflict attempts for a task
+    /// Count the merge conflict attempts for a task since its last `conflict_resolved` entry
     pub async fn count_merge_conflicts(
         pool: &SqlitePool,
         task_id: Uuid,
@@ -273,11 +604,71 @@ impl ReviewAutomationLog {
         let result = sqlx::query_scalar!(
             r#"SELECT COUNT(*) as "count!: i64"
             FROM review_automation_logs
-            WHERE task_id = $1 AND action = 'merge_conflict'"#,
+            WHERE task_id = $1 AND action = 'merge_conflict'
+              AND rowid > COALESCE(
+                  (SELECT MAX(rowid) FROM review_automation_logs
+                   WHERE task_id = $1 AND action = 'conflict_resolved'),
+                  0
+              )"#,
             task_id
         )
         .fetch_one(pool)
         .await?;
         Ok(result)
     }
+
+    /// Count the failed testing criteria checks for a task since its last criteria verdict
+    pub async fn count_criteria_check_failures(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+            FROM review_automation_logs
+            WHERE task_id = $1 AND action = 'error'
+              AND error_message LIKE 'Testing criteria check failed:%'
+              AND rowid > COALESCE(
+                  (SELECT MAX(rowid) FROM review_automation_logs
+                   WHERE task_id = $1 AND action IN ('criteria_met', 'criteria_unmet')),
+                  0
+              )"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(result)
+    }
+
+    /// Reset a task's merge conflict count by logging a `conflict_resolved` entry against the
+    /// workspace of its latest conflict. Returns `None` if the task has no conflicts logged.
+    pub async fn reset_conflict_count(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let workspace_id = sqlx::query_scalar!(
+            r#"SELECT workspace_id as "workspace_id!: Uuid"
+            FROM review_automation_logs
+            WHERE task_id = $1 AND action = 'merge_conflict'
+            ORDER BY rowid DESC
+            LIMIT 1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        match workspace_id {
+            Some(workspace_id) => Ok(Some(
+                Self::create(
+                    pool,
+                    task_id,
+                    workspace_id,
+                    ReviewAction::ConflictResolved,
+                    None,
+                    None,
+                )
+                .await?,
+            )),
+            None => Ok(None),
+        }
+    }
 }
@@ -1,11 +1,26 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Transaction, Type};
 use strum_macros::{Display, EnumString};
+use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use utils::log_msg::LogMsg;
+
+use super::{
+    execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+    image::Image,
+    project::Project,
+    task_status_history::TaskStatusHistory,
+    workspace::Workspace,
+};
+
+/// Characters of output kept from a failed attempt, from the end
+const FAILURE_OUTPUT_TAIL_CHARS: usize = 4000;
 
 #[derive(
     Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
@@ -20,6 +35,38 @@ pub enum TaskStatus {
     InReview,
     Done,
     Cancelled,
+    Blocked, // Waiting on something outside the board; see `blocked_reason`
+}
+
+impl TaskStatus {
+    /// Whether a task may move from this status to `next`. Work flows
+    /// Todo → InProgress → InReview → Done, may step back for rework, and can be cancelled
+    /// while active. Active tasks can be blocked; blocked tasks resume, or are cancelled.
+    /// Finished (Done/Cancelled) tasks can only be reopened to Todo.
+    pub fn can_transition_to(&self, next: &TaskStatus) -> bool {
+        use TaskStatus::*;
+
+        if self == next {
+            return true;
+        }
+        match self {
+            Todo => matches!(next, InProgress | Cancelled | Blocked),
+            InProgress => matches!(next, Todo | InReview | Done | Cancelled | Blocked),
+            InReview => matches!(next, Todo | InProgress | Done | Cancelled | Blocked),
+            Blocked => matches!(next, Todo | InProgress | Cancelled),
+            Done | Cancelled => matches!(next, Todo),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TaskError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("Invalid task status transition from {from} to {to}")]
+    InvalidStatusTransition { from: TaskStatus, to: TaskStatus },
 }
 
 /// Source of task creation
@@ -60,6 +107,8 @@ pub enum TaskType {
     /// Implementation tasks: actual feature implementation
     #[default]
     Implementation,
+    /// Testing tasks: test coverage for implemented features
+    Testing,
     /// Integration tasks: wire all layers together
     Integration,
 }
@@ -82,6 +131,13 @@ pub struct Task {
     pub parent_task_id: Option<Uuid>,             // Link to parent task when broken down
     pub prevent_breakdown: bool,                  // Prevent automatic task breakdown
     pub post_task_actions: Option<String>,        // Instructions for updating .progress file
+    pub source_feature: Option<String>,           // Requirements feature this task was generated from
+    pub archived: bool,                           // Hidden from the board by default
+    pub needs_attention_reason: Option<String>, // Set when automation exhausted every recovery path
+    pub commit_count: Option<i32>, // Commits the latest attempt added, recorded on entering InReview
+    pub requirements_id: Option<Uuid>, // Requirements record this task was generated from
+    pub blocked_reason: Option<String>, // Why the task is blocked (set while status is Blocked)
+    pub edited_at: Option<DateTime<Utc>>, // When a user last changed the title or description
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -96,6 +152,51 @@ pub struct TaskWithAttemptStatus {
     pub executor: String,
 }
 
+/// Number of tasks sharing one status, layer, type and source combination
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskStatsGroup {
+    pub status: TaskStatus,
+    pub layer: Option<TaskLayer>,
+    pub task_type: Option<TaskType>,
+    pub source: TaskSource,
+    pub count: i64,
+}
+
+/// Aggregate task counts for a project, for progress and burndown views
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct ProjectTaskStats {
+    pub total: i64,
+    pub by_status: BTreeMap<String, i64>,
+    pub by_layer: BTreeMap<String, i64>, // Tasks without a layer are counted under "none"
+    pub by_task_type: BTreeMap<String, i64>, // Tasks without a type are counted under "none"
+    pub by_source: BTreeMap<String, i64>,
+    pub needs_grooming: i64, // AI-generated Todo tasks nobody has edited yet
+    pub groups: Vec<TaskStatsGroup>,
+}
+
+impl ProjectTaskStats {
+    pub fn from_groups(groups: Vec<TaskStatsGroup>) -> Self {
+        let mut stats = Self::default();
+        for group in &groups {
+            let layer = group
+                .layer
+                .as_ref()
+                .map_or("none".to_string(), |l| l.to_string());
+            let task_type = group
+                .task_type
+                .as_ref()
+                .map_or("none".to_string(), |t| t.to_string());
+            stats.total += group.count;
+            *stats.by_status.entry(group.status.to_string()).or_default() += group.count;
+            *stats.by_layer.entry(layer).or_default() += group.count;
+            *stats.by_task_type.entry(task_type).or_default() += group.count;
+            *stats.by_source.entry(group.source.to_string()).or_default() += group.count;
+        }
+        stats.groups = groups;
+        stats
+    }
+}
+
 impl std::ops::Deref for TaskWithAttemptStatus {
     type Target = Task;
     fn deref(&self) -> &Self::Target {
@@ -109,6 +210,43 @@ impl std::ops::DerefMut for TaskWithAttemptStatus {
     }
 }
 
+/// Why the task's most recent attempt failed, for retrying with that context
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskFailureContext {
+    pub execution_process_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub exit_code: Option<i64>,
+    pub output: String, // Tail of the process's stdout and stderr
+}
+
+/// Task plus the ids of images attached to it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskWithImages {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub task: Task,
+    pub image_ids: Vec<Uuid>,
+}
+
+impl std::ops::Deref for TaskWithImages {
+    type Target = Task;
+    fn deref(&self) -> &Self::Target {
+        &self.task
+    }
+}
+
+/// Task detail view: the task with its attempt status, images and related tasks
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskDetails {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub task: TaskWithAttemptStatus,
+    pub image_ids: Vec<Uuid>,
+    pub parent_task: Option<Task>, // Task this one was broken down from
+    pub subtasks: Vec<Task>,
+    pub dependencies: Vec<Task>, // Tasks whose workspaces this one branched from, nearest first
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct TaskRelationships {
     pub parent_task: Option<Task>, // The task that owns the parent workspace
@@ -132,6 +270,8 @@ pub struct CreateTask {
     pub parent_task_id: Option<Uuid>,    // Link to parent task when broken down
     pub prevent_breakdown: Option<bool>, // Prevent automatic task breakdown
     pub post_task_actions: Option<String>, // Instructions for updating .progress file
+    pub source_feature: Option<String>,    // Requirements feature this task was generated from
+    pub requirements_id: Option<Uuid>,     // Requirements record this task was generated from
 }
 
 impl CreateTask {
@@ -155,6 +295,8 @@ impl CreateTask {
             parent_task_id: None,
             prevent_breakdown: None,
             post_task_actions: None,
+            source_feature: None,
+            requirements_id: None,
         }
     }
 
@@ -184,6 +326,8 @@ impl CreateTask {
             parent_task_id: None,
             prevent_breakdown: None,
             post_task_actions,
+            source_feature: None,
+            requirements_id: None,
         }
     }
 
@@ -215,6 +359,8 @@ impl CreateTask {
             parent_task_id: Some(parent_task_id),
             prevent_breakdown: Some(true), // Subtasks should not be broken down further
             post_task_actions,
+            source_feature: None,
+            requirements_id: None,
         }
     }
 }
@@ -241,9 +387,12 @@ impl Task {
         Project::find_by_id(pool, self.project_id).await
     }
 
+    /// Tasks for a project with attempt status. Archived tasks are skipped unless
+    /// `include_archived` is set.
     pub async fn find_by_project_id_with_attempt_status(
         pool: &SqlitePool,
         project_id: Uuid,
+        include_archived: bool,
     ) -> Result<Vec<TaskWithAttemptStatus>, sqlx::Error> {
         let records = sqlx::query!(
             r#"SELECT
@@ -263,6 +412,13 @@ impl Task {
   t.parent_task_id                AS "parent_task_id: Uuid",
   t.prevent_breakdown             AS "prevent_breakdown!: i64",
   t.post_task_actions,
+  t.source_feature,
+  t.archived                      AS "archived!: i64",
+  t.needs_attention_reason,
+  t.commit_count                  AS "commit_count: i32",
+  t.requirements_id               AS "requirements_id: Uuid",
+  t.blocked_reason,
+  t.edited_at                     AS "edited_at: DateTime<Utc>",
   t.created_at                    AS "created_at!: DateTime<Utc>",
   t.updated_at                    AS "updated_at!: DateTime<Utc>",
 
@@ -299,8 +455,10 @@ impl Task {
 
 FROM tasks t
 WHERE t.project_id = $1
+  AND ($2 OR t.archived = 0)
 ORDER BY t.created_at DESC"#,
-            project_id
+            project_id,
+            include_archived
         )
         .fetch_all(pool)
         .await?;
@@ -325,6 +483,13 @@ ORDER BY t.created_at DESC"#,
                     parent_task_id: rec.parent_task_id,
                     prevent_breakdown: rec.prevent_breakdown != 0,
                     post_task_actions: rec.post_task_actions,
+                    source_feature: rec.source_feature,
+                    archived: rec.archived != 0,
+                    needs_attention_reason: rec.needs_attention_reason,
+                    commit_count: rec.commit_count,
+                    requirements_id: rec.requirements_id,
+                    blocked_reason: rec.blocked_reason,
+                    edited_at: rec.edited_at,
                     created_at: rec.created_at,
                     updated_at: rec.updated_at,
                 },
@@ -337,10 +502,177 @@ ORDER BY t.created_at DESC"#,
         Ok(tasks)
     }
 
+    /// Images attached to a task, in attachment order
+    pub async fn images(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Image>, sqlx::Error> {
+        Image::find_by_task_id(pool, task_id).await
+    }
+
+    /// Attach the ids of this task's images for API responses
+    pub async fn with_images(self, pool: &SqlitePool) -> Result<TaskWithImages, sqlx::Error> {
+        let image_ids = Self::images(pool, self.id)
+            .await?
+            .into_iter()
+            .map(|image| image.id)
+            .collect();
+        Ok(TaskWithImages {
+            task: self,
+            image_ids,
+        })
+    }
+
+    /// Attach the attempt status fields shown on the board
+    pub async fn with_attempt_status(
+        self,
+        pool: &SqlitePool,
+    ) -> Result<TaskWithAttemptStatus, sqlx::Error> {
+        let rec = sqlx::query!(
+            r#"SELECT
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+     WHERE w.task_id       = $1
+       AND ep.status        = 'running'
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
+
+  CASE WHEN (
+    SELECT ep.status
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+     WHERE w.task_id       = $1
+     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     ORDER BY ep.created_at DESC
+     LIMIT 1
+  ) IN ('failed','killed') THEN 1 ELSE 0 END
+                                 AS "last_attempt_failed!: i64",
+
+  ( SELECT s.executor
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      WHERE w.task_id = $1
+     ORDER BY s.created_at DESC
+      LIMIT 1
+    )                               AS "executor: String""#,
+            self.id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(TaskWithAttemptStatus {
+            task: self,
+            has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+            last_attempt_failed: rec.last_attempt_failed != 0,
+            executor: rec.executor.unwrap_or_default(),
+        })
+    }
+
+    /// The output of the task's most recent attempt process, if that process failed or was
+    /// killed (the same process `last_attempt_failed` looks at)
+    pub async fn last_failure_context(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<TaskFailureContext>, sqlx::Error> {
+        let Some(process) = sqlx::query!(
+            r#"SELECT ep.id as "id!: Uuid",
+                      ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                      ep.status as "status!: ExecutionProcessStatus",
+                      ep.exit_code
+               FROM workspaces w
+               JOIN sessions s ON s.workspace_id = w.id
+               JOIN execution_processes ep ON ep.session_id = s.id
+               WHERE w.task_id = $1
+                 AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+               ORDER BY ep.created_at DESC
+               LIMIT 1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+        if !matches!(
+            process.status,
+            ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
+        ) {
+            return Ok(None);
+        }
+
+        let records = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
+        // Unparseable logs still leave the exit code to go on
+        let output: String = ExecutionProcessLogs::parse_logs(&records)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(text) | LogMsg::Stderr(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        let skipped = output
+            .chars()
+            .count()
+            .saturating_sub(FAILURE_OUTPUT_TAIL_CHARS);
+
+        Ok(Some(TaskFailureContext {
+            execution_process_id: process.id,
+            run_reason: process.run_reason,
+            exit_code: process.exit_code,
+            output: output.chars().skip(skipped).collect(),
+        }))
+    }
+
+    /// Load the task's detail view: attempt status, images, breakdown parent, subtasks, and the
+    /// chain of tasks whose workspaces it was created from
+    pub async fn with_details(self, pool: &SqlitePool) -> Result<TaskDetails, sqlx::Error> {
+        let parent_task = match self.parent_task_id {
+            Some(parent_task_id) => Self::find_by_id(pool, parent_task_id).await?,
+            None => None,
+        };
+        let subtasks = Self::find_subtasks(pool, self.id).await?;
+
+        let mut dependencies: Vec<Task> = Vec::new();
+        let mut next_workspace_id = self.parent_workspace_id;
+        while let Some(workspace_id) = next_workspace_id {
+            let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+                break;
+            };
+            // Guard against malformed cycles in the workspace chain
+            if workspace.task_id == self.id
+                || dependencies.iter().any(|t| t.id == workspace.task_id)
+            {
+                break;
+            }
+            let Some(task) = Self::find_by_id(pool, workspace.task_id).await? else {
+                break;
+            };
+            next_workspace_id = task.parent_workspace_id;
+            dependencies.push(task);
+        }
+
+        let image_ids = Self::images(pool, self.id)
+            .await?
+            .into_iter()
+            .map(|image| image.id)
+            .collect();
+        let task = self.with_attempt_status(pool).await?;
+
+        Ok(TaskDetails {
+            task,
+            image_ids,
+            parent_task,
+            subtasks,
+            dependencies,
+        })
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE id = $1"#,
             id
@@ -352,7 +684,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE rowid = $1"#,
             rowid
@@ -361,19 +693,22 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'e, E>(
+        executor: E,
         data: &CreateTask,
         task_id: Uuid,
-    ) -> Result<Self, sqlx::Error> {
+    ) -> Result<Self, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let status = data.status.clone().unwrap_or_default();
         let source = data.source.clone().unwrap_or_default();
         let prevent_breakdown = data.prevent_breakdown.unwrap_or(false);
         sqlx::query_as!(
             Task,
-            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id, source, layer, task_type, sequence, testing_criteria, parent_task_id, prevent_breakdown, post_task_actions)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id, source, layer, task_type, sequence, testing_criteria, parent_task_id, prevent_breakdown, post_task_actions, source_feature, requirements_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             data.project_id,
             data.title,
@@ -387,9 +722,11 @@ ORDER BY t.created_at DESC"#,
             data.testing_criteria,
             data.parent_task_id,
             prevent_breakdown,
-            data.post_task_actions
+            data.post_task_actions,
+            data.source_feature,
+            data.requirements_id
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
     }
 
@@ -399,55 +736,299 @@ ORDER BY t.created_at DESC"#,
         project_id: Uuid,
         title: String,
         description: Option<String>,
-        status: TaskStatus,
         parent_workspace_id: Option<Uuid>,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Task,
             r#"UPDATE tasks
-               SET title = $3, description = $4, status = $5, parent_workspace_id = $6
+               SET title = $3, description = $4, parent_workspace_id = $5,
+                   edited_at = CASE WHEN title IS NOT $3 OR description IS NOT $4
+                                    THEN CURRENT_TIMESTAMP ELSE edited_at END
                WHERE id = $1 AND project_id = $2
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             project_id,
             title,
             description,
-            status,
             parent_workspace_id
         )
         .fetch_one(pool)
         .await
     }
 
+    /// Move a task to `status`, rejecting transitions `TaskStatus::can_transition_to` disallows.
+    /// The change is recorded in the task's status history with `actor` and `reason`. The
+    /// current status is read in the same transaction as the write, so a concurrent change
+    /// can't slip in between the check and the update.
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
         status: TaskStatus,
+        actor: Option<&str>,
+        reason: Option<&str>,
+    ) -> Result<(), TaskError> {
+        let allowed = |current: &TaskStatus| current.can_transition_to(&status);
+        Self::transition(pool, id, status.clone(), actor, reason, None, allowed).await
+    }
+
+    /// Move a task to `status` when `allowed` accepts its current status, setting
+    /// `blocked_reason`; the check and the write share one transaction
+    async fn transition(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: TaskStatus,
+        actor: Option<&str>,
+        reason: Option<&str>,
+        blocked_reason: Option<&str>,
+        allowed: impl FnOnce(&TaskStatus) -> bool,
+    ) -> Result<(), TaskError> {
+        let mut tx = pool.begin().await?;
+        let current = Self::find_status(&mut *tx, id)
+            .await?
+            .ok_or(TaskError::TaskNotFound)?;
+
+        if !allowed(&current) {
+            return Err(TaskError::InvalidStatusTransition {
+                from: current,
+                to: status,
+            });
+        }
+
+        Self::set_status(&mut tx, id, current, status, actor, reason, blocked_reason).await?;
+        Ok(tx.commit().await?)
+    }
+
+    /// Move a task to `status` without validating the transition, for recovery paths and
+    /// deliberate reopens. Recorded in the status history like `update_status`.
+    pub async fn update_status_force(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: TaskStatus,
+        actor: Option<&str>,
+        reason: Option<&str>,
     ) -> Result<(), sqlx::Error> {
-        // Set stage_started_at when entering InProgress or InReview, clear it otherwise
-        let should_set_stage_time =
-            matches!(status, TaskStatus::InProgress | TaskStatus::InReview);
+        let mut tx = pool.begin().await?;
+        let Some(current) = Self::find_status(&mut *tx, id).await? else {
+            return Ok(());
+        };
+        Self::set_status(&mut tx, id, current, status, actor, reason, None).await?;
+        tx.commit().await
+    }
+
+    async fn find_status<'e, E>(executor: E, id: Uuid) -> Result<Option<TaskStatus>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_scalar!(
+            r#"SELECT status as "status!: TaskStatus" FROM tasks WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Write the new status and its history entry in the caller's transaction
+    #[allow(clippy::too_many_arguments)]
+    async fn set_status(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: Uuid,
+        current: TaskStatus,
+        status: TaskStatus,
+        actor: Option<&str>,
+        reason: Option<&str>,
+        blocked_reason: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        // Set stage_started_at when entering InProgress or InReview, clear it otherwise. Any
+        // status change replaces the block reason, so leaving Blocked clears it.
+        let should_set_stage_time = matches!(status, TaskStatus::InProgress | TaskStatus::InReview);
 
         if should_set_stage_time {
             sqlx::query!(
-                "UPDATE tasks SET status = $2, stage_started_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+                "UPDATE tasks SET status = $2, stage_started_at = CURRENT_TIMESTAMP, blocked_reason = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
                 id,
-                status
+                status,
+                blocked_reason
             )
-            .execute(pool)
+            .execute(&mut **tx)
             .await?;
         } else {
             sqlx::query!(
-                "UPDATE tasks SET status = $2, stage_started_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+                "UPDATE tasks SET status = $2, stage_started_at = NULL, blocked_reason = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
                 id,
-                status
+                status,
+                blocked_reason
             )
-            .execute(pool)
+            .execute(&mut **tx)
             .await?;
         }
+
+        TaskStatusHistory::create(&mut **tx, id, current, status, actor, reason).await
+    }
+
+    /// Block a task on something outside the board, e.g. a missing third-party API key.
+    /// Blocked tasks are skipped by agent selection and stage timeouts.
+    pub async fn block(
+        pool: &SqlitePool,
+        id: Uuid,
+        reason: &str,
+        actor: Option<&str>,
+    ) -> Result<(), TaskError> {
+        let status = TaskStatus::Blocked;
+        let allowed = |current: &TaskStatus| current.can_transition_to(&TaskStatus::Blocked);
+        let reason = Some(reason);
+        Self::transition(pool, id, status, actor, reason, reason, allowed).await
+    }
+
+    /// Move a blocked task back to Todo and clear its reason
+    pub async fn unblock(
+        pool: &SqlitePool,
+        id: Uuid,
+        actor: Option<&str>,
+    ) -> Result<(), TaskError> {
+        let allowed = |current: &TaskStatus| *current == TaskStatus::Blocked;
+        let reason = Some("Unblocked");
+        Self::transition(pool, id, TaskStatus::Todo, actor, reason, None, allowed).await
+    }
+
+    pub async fn set_archived(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        archived: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET archived = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            task_id,
+            archived
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record how many commits the task's latest attempt added
+    pub async fn set_commit_count(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        commit_count: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET commit_count = $2 WHERE id = $1",
+            task_id,
+            commit_count
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Flag a task that automation could not recover, or clear the flag with `None`
+    pub async fn set_needs_attention(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET needs_attention_reason = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            task_id,
+            reason
+        )
+        .execute(pool)
+        .await?;
         Ok(())
     }
 
+    /// Tasks automation gave up on and left for a human, most recently flagged first
+    pub async fn find_needing_attention(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1
+                 AND needs_attention_reason IS NOT NULL
+               ORDER BY updated_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// InProgress tasks with no attempt running, e.g. selected by the agent but the auto-start
+    /// failed, oldest first. A task counts as attempted while an unarchived workspace of it has a
+    /// running process or one started since the task entered InProgress; a workspace row alone
+    /// doesn't count, since it outlives a failed start.
+    pub async fn find_inprogress_without_attempt(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks t
+               WHERE t.project_id = $1
+                 AND t.status = 'inprogress'
+                 AND t.archived = 0
+                 AND NOT EXISTS (
+                     SELECT 1
+                       FROM workspaces w
+                       JOIN sessions s ON s.workspace_id = w.id
+                       JOIN execution_processes ep ON ep.session_id = s.id
+                      WHERE w.task_id = t.id
+                        AND w.archived = 0
+                        AND (ep.status = 'running'
+                             OR t.stage_started_at IS NULL
+                             OR datetime(ep.created_at) >= datetime(t.stage_started_at))
+                 )
+               ORDER BY t.stage_started_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Task counts for a project grouped by status, layer, task type and source
+    pub async fn stats_by_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ProjectTaskStats, sqlx::Error> {
+        let groups = sqlx::query_as!(
+            TaskStatsGroup,
+            r#"SELECT status as "status!: TaskStatus", layer as "layer: TaskLayer", task_type as "task_type: TaskType", source as "source!: TaskSource", COUNT(*) as "count!: i64"
+               FROM tasks
+               WHERE project_id = $1
+               GROUP BY status, layer, task_type, source
+               ORDER BY status, layer, task_type, source"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut stats = ProjectTaskStats::from_groups(groups);
+        stats.needs_grooming = Self::count_ungroomed_ai_tasks(pool, project_id).await?;
+        Ok(stats)
+    }
+
+    /// Unarchived AI-generated tasks still in Todo whose title and description nobody has
+    /// edited, i.e. the AI's plan awaiting human review
+    pub async fn count_ungroomed_ai_tasks(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM tasks
+               WHERE project_id = $1
+                 AND source = 'ai_generated'
+                 AND status = 'todo'
+                 AND edited_at IS NULL
+                 AND archived = 0"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     /// Find tasks that have been stalled in a given status for longer than the timeout
     pub async fn find_stalled_tasks(
         pool: &SqlitePool,
@@ -458,7 +1039,7 @@ ORDER BY t.created_at DESC"#,
         let timeout_str = format!("-{} minutes", timeout_minutes);
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE project_id = $1
                  AND status = $2
@@ -509,7 +1090,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_subtasks(pool: &SqlitePool, parent_task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE parent_task_id = $1
                ORDER BY sequence ASC, created_at ASC"#,
@@ -563,6 +1144,117 @@ ORDER BY t.created_at DESC"#,
         Ok(result.rows_affected())
     }
 
+    /// Unarchived AI-generated tasks of a project, in generation sequence order
+    pub async fn find_ai_generated_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1 AND source = 'ai_generated' AND archived = 0
+               ORDER BY sequence ASC, created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether a task has a finished coding agent run and nothing still running, i.e. an
+    /// attempt review automation can pick up
+    pub async fn has_completed_attempt(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                   SELECT 1
+                   FROM workspaces w
+                   JOIN sessions s ON s.workspace_id = w.id
+                   JOIN execution_processes ep ON ep.session_id = s.id
+                   WHERE w.task_id = $1
+                     AND w.archived = 0
+                     AND ep.run_reason = 'codingagent'
+                     AND ep.status = 'completed'
+               ) AND NOT EXISTS (
+                   SELECT 1
+                   FROM workspaces w
+                   JOIN sessions s ON s.workspace_id = w.id
+                   JOIN execution_processes ep ON ep.session_id = s.id
+                   WHERE w.task_id = $1
+                     AND ep.status = 'running'
+               ) as "completed!: bool""#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Tasks generated from the given requirements feature, in sequence order
+    pub async fn find_by_feature(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        source_feature: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1 AND source_feature = $2
+               ORDER BY sequence ASC, created_at ASC"#,
+            project_id,
+            source_feature
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Delete AI-generated tasks that were produced from the given requirements feature and
+    /// haven't been touched: still in Todo and never edited. Other tasks are kept along with
+    /// the user's changes and history.
+    pub async fn delete_untouched_ai_generated_by_source_feature<'e, E>(
+        executor: E,
+        project_id: Uuid,
+        source_feature: &str,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            "DELETE FROM tasks WHERE project_id = $1 AND source = 'ai_generated' AND source_feature = $2 AND status = 'todo' AND edited_at IS NULL",
+            project_id,
+            source_feature
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// The highest sequence among the project's tasks of each type, for numbering new tasks
+    /// after the existing ones
+    pub async fn max_sequences_by_type<'e, E>(
+        executor: E,
+        project_id: Uuid,
+    ) -> Result<Vec<(Option<TaskType>, i32)>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let records = sqlx::query!(
+            r#"SELECT task_type as "task_type: TaskType", MAX(sequence) as "max_sequence!: i32"
+               FROM tasks
+               WHERE project_id = $1 AND sequence IS NOT NULL
+               GROUP BY task_type"#,
+            project_id
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|record| (record.task_type, record.max_sequence))
+            .collect())
+    }
+
     pub async fn find_children_by_workspace_id(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -570,7 +1262,7 @@ ORDER BY t.created_at DESC"#,
         // Find only child tasks that have this workspace as their parent
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE parent_workspace_id = $1
                ORDER BY created_at DESC"#,
@@ -580,6 +1272,26 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Tasks whose parent_workspace_id points at a workspace that no longer exists or has been
+    /// archived, left behind when workspaces are removed outside the normal delete path
+    pub async fn find_with_dangling_parent_workspace(
+        pool: &SqlitePool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", source as "source!: TaskSource", layer as "layer: TaskLayer", task_type as "task_type: TaskType", sequence as "sequence: i32", testing_criteria, stage_started_at as "stage_started_at: DateTime<Utc>", complexity_score as "complexity_score: i32", parent_task_id as "parent_task_id: Uuid", prevent_breakdown as "prevent_breakdown!: bool", post_task_actions, source_feature, archived as "archived!: bool", needs_attention_reason, commit_count as "commit_count: i32", requirements_id as "requirements_id: Uuid", blocked_reason, edited_at as "edited_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE parent_workspace_id IS NOT NULL
+                 AND NOT EXISTS (
+                     SELECT 1 FROM workspaces w
+                     WHERE w.id = tasks.parent_workspace_id AND w.archived = 0
+                 )
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn find_relationships_for_workspace(
         pool: &SqlitePool,
         workspace: &Workspace,
@@ -619,11 +1331,13 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_in_review_with_completed_attempts(
         pool: &SqlitePool,
         project_id: Uuid,
+        require_success: bool,
     ) -> Result<Vec<(Task, Workspace)>, sqlx::Error> {
         // Find tasks in review status that:
         // 1. Have at least one workspace
         // 2. Have no currently running execution processes
-        // 3. Have at least one completed execution process (to ensure work was done)
+        // 3. Have at least one completed execution process (to ensure work was done);
+        //    with `require_success`, failed or killed runs don't count
         let records = sqlx::query!(
             r#"SELECT
                 t.id as "task_id!: Uuid",
@@ -642,6 +1356,13 @@ ORDER BY t.created_at DESC"#,
                 t.parent_task_id as "task_parent_task_id: Uuid",
                 t.prevent_breakdown as "task_prevent_breakdown!: bool",
                 t.post_task_actions as "task_post_task_actions",
+                t.source_feature as "task_source_feature",
+                t.archived as "task_archived!: bool",
+                t.needs_attention_reason as "task_needs_attention_reason",
+                t.commit_count as "task_commit_count: i32",
+                t.requirements_id as "task_requirements_id: Uuid",
+                t.blocked_reason as "task_blocked_reason",
+                t.edited_at as "task_edited_at: DateTime<Utc>",
                 t.created_at as "task_created_at!: DateTime<Utc>",
                 t.updated_at as "task_updated_at!: DateTime<Utc>",
                 w.id as "workspace_id!: Uuid",
@@ -660,6 +1381,8 @@ ORDER BY t.created_at DESC"#,
             WHERE t.project_id = $1
               AND t.status = 'inreview'
               AND w.archived = 0
+              -- Flagged tasks wait for a human instead of being reviewed again every poll
+              AND t.needs_attention_reason IS NULL
               -- Has at least one completed execution process (codingagent)
               AND EXISTS (
                   SELECT 1
@@ -667,7 +1390,8 @@ ORDER BY t.created_at DESC"#,
                   JOIN execution_processes ep ON ep.session_id = s.id
                   WHERE s.workspace_id = w.id
                     AND ep.run_reason = 'codingagent'
-                    AND ep.status IN ('completed', 'failed', 'killed')
+                    AND (ep.status = 'completed'
+                         OR ($2 = 0 AND ep.status IN ('failed', 'killed')))
               )
               -- No running execution processes
               AND NOT EXISTS (
@@ -679,7 +1403,8 @@ ORDER BY t.created_at DESC"#,
               )
             ORDER BY t.created_at ASC
             LIMIT 1"#,
-            project_id
+            project_id,
+            require_success
         )
         .fetch_all(pool)
         .await?;
@@ -704,6 +1429,13 @@ ORDER BY t.created_at DESC"#,
                     parent_task_id: rec.task_parent_task_id,
                     prevent_breakdown: rec.task_prevent_breakdown,
                     post_task_actions: rec.task_post_task_actions,
+                    source_feature: rec.task_source_feature,
+                    archived: rec.task_archived,
+                    needs_attention_reason: rec.task_needs_attention_reason,
+                    commit_count: rec.task_commit_count,
+                    requirements_id: rec.task_requirements_id,
+                    blocked_reason: rec.task_blocked_reason,
+                    edited_at: rec.task_edited_at,
                     created_at: rec.task_created_at,
                     updated_at: rec.task_updated_at,
                 };
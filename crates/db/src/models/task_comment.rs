@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A human comment on a task
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskComment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub author: Option<String>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateTaskComment {
+    pub author: Option<String>,
+    pub content: String,
+}
+
+impl TaskComment {
+    /// All comments on a task, oldest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                author,
+                content,
+                created_at as "created_at!: DateTime<Utc>"
+            FROM task_comments
+            WHERE task_id = $1
+            ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                author,
+                content,
+                created_at as "created_at!: DateTime<Utc>"
+            FROM task_comments
+            WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The most recent `limit` comments on a task, oldest first
+    pub async fn find_recent(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut comments = sqlx::query_as!(
+            TaskComment,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                author,
+                content,
+                created_at as "created_at!: DateTime<Utc>"
+            FROM task_comments
+            WHERE task_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2"#,
+            task_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+        comments.reverse();
+        Ok(comments)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &CreateTaskComment,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskComment,
+            r#"INSERT INTO task_comments (id, task_id, author, content)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                author,
+                content,
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            data.author,
+            data.content
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_comments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Render the comment as a single line for agent prompts, e.g. `alice: please add tests`
+    pub fn to_prompt_line(&self) -> String {
+        match self.author.as_deref().filter(|a| !a.trim().is_empty()) {
+            Some(author) => format!("{}: {}", author.trim(), self.content.trim()),
+            None => self.content.trim().to_string(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// The most recent complexity analysis of a task, as stored
+#[derive(Debug, Clone, FromRow)]
+pub struct TaskComplexityAnalysis {
+    pub task_id: Uuid,
+    pub complexity_score: i64,
+    pub can_be_broken_down: bool,
+    pub reasoning: String,
+    /// JSON array of suggested subtasks
+    pub subtasks: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskComplexityAnalysis {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComplexityAnalysis,
+            r#"SELECT
+                task_id as "task_id!: Uuid",
+                complexity_score,
+                can_be_broken_down as "can_be_broken_down!: bool",
+                reasoning,
+                subtasks,
+                created_at as "created_at!: DateTime<Utc>"
+            FROM task_complexity_analyses
+            WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store an analysis, replacing any earlier one for the same task
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        complexity_score: i64,
+        can_be_broken_down: bool,
+        reasoning: &str,
+        subtasks: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_complexity_analyses
+                (task_id, complexity_score, can_be_broken_down, reasoning, subtasks)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT(task_id) DO UPDATE SET
+                complexity_score = excluded.complexity_score,
+                can_be_broken_down = excluded.can_be_broken_down,
+                reasoning = excluded.reasoning,
+                subtasks = excluded.subtasks,
+                created_at = datetime('now', 'subsec')"#,
+            task_id,
+            complexity_score,
+            can_be_broken_down,
+            reasoning,
+            subtasks
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// One change of a task's status
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskStatusHistory {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub from_status: TaskStatus,
+    pub to_status: TaskStatus,
+    /// `user`, or the service that made the change, e.g. `review_automation`
+    pub actor: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskStatusHistory {
+    pub async fn create<'e, E>(
+        executor: E,
+        task_id: Uuid,
+        from_status: TaskStatus,
+        to_status: TaskStatus,
+        actor: Option<&str>,
+        reason: Option<&str>,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO task_status_history (id, task_id, from_status, to_status, actor, reason)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+            id,
+            task_id,
+            from_status,
+            to_status,
+            actor,
+            reason
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// A task's status changes, oldest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskStatusHistory,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                from_status as "from_status!: TaskStatus",
+                to_status as "to_status!: TaskStatus",
+                actor,
+                reason,
+                created_at as "created_at!: DateTime<Utc>"
+            FROM task_status_history
+            WHERE task_id = $1
+            ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -371,6 +371,41 @@ impl Workspace {
         .await
     }
 
+    /// Archived workspaces whose worktrees are still on disk and have no running processes
+    pub async fn find_archived_for_cleanup(
+        pool: &SqlitePool,
+    ) -> Result<Vec<Workspace>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"
+            SELECT
+                w.id as "id!: Uuid",
+                w.task_id as "task_id!: Uuid",
+                w.container_ref,
+                w.branch as "branch!",
+                w.agent_working_dir,
+                w.setup_completed_at as "setup_completed_at: DateTime<Utc>",
+                w.created_at as "created_at!: DateTime<Utc>",
+                w.updated_at as "updated_at!: DateTime<Utc>",
+                w.archived as "archived!: bool",
+                w.pinned as "pinned!: bool",
+                w.name
+            FROM workspaces w
+            WHERE w.archived = 1
+                AND w.container_ref IS NOT NULL
+                AND w.id NOT IN (
+                    SELECT DISTINCT s.workspace_id
+                    FROM sessions s
+                    JOIN execution_processes ep ON s.id = ep.session_id
+                    WHERE ep.completed_at IS NULL
+                )
+            ORDER BY w.updated_at ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn create(
         pool: &SqlitePool,
         data: &CreateWorkspace,
@@ -470,6 +505,29 @@ impl Workspace {
         Ok(())
     }
 
+    /// Archive a task's unarchived workspaces that never ran a process, e.g. left behind by a
+    /// failed start. Returns the number archived.
+    pub async fn archive_unstarted_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE workspaces SET archived = 1, updated_at = datetime('now', 'subsec')
+               WHERE task_id = $1
+                 AND archived = 0
+                 AND NOT EXISTS (
+                     SELECT 1
+                       FROM sessions s
+                       JOIN execution_processes ep ON ep.session_id = s.id
+                      WHERE s.workspace_id = workspaces.id
+                 )"#,
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     /// Update workspace fields. Only non-None values will be updated.
     /// For `name`, pass `Some("")` to clear the name, `Some("foo")` to set it, or `None` to leave unchanged.
     pub async fn update(
@@ -31,6 +31,7 @@ use services::services::{
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
+    task_reconciler::{self, TaskReconcilerService},
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -132,6 +133,11 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, analytics).await
     }
 
+    async fn spawn_task_reconciler_service(&self) -> tokio::task::JoinHandle<()> {
+        TaskReconcilerService::spawn(self.db().clone(), task_reconciler::DEFAULT_POLL_INTERVAL)
+            .await
+    }
+
     async fn spawn_agent_activity_service(&self) -> tokio::task::JoinHandle<()>;
 
     async fn spawn_review_automation_service(&self) -> tokio::task::JoinHandle<()>;
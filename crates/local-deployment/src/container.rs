@@ -20,6 +20,7 @@ use db::{
         execution_process_repo_state::ExecutionProcessRepoState,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
+        session::Session,
         task::{Task, TaskStatus},
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
@@ -93,7 +94,7 @@ impl LocalContainerService {
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
-        let notification_service = NotificationService::new(config.clone());
+        let notification_service = NotificationService::new(config.clone(), db.pool.clone());
 
         let container = LocalContainerService {
             db,
@@ -191,9 +192,27 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Remove worktrees of archived workspaces so `.git/worktrees` doesn't accumulate stale entries
+    pub async fn cleanup_archived_workspaces(db: &DBService) -> Result<(), DeploymentError> {
+        let archived_workspaces = Workspace::find_archived_for_cleanup(&db.pool).await?;
+        if archived_workspaces.is_empty() {
+            tracing::debug!("No archived workspaces with worktrees found");
+            return Ok(());
+        }
+        tracing::info!(
+            "Found {} archived workspaces to clean up",
+            archived_workspaces.len()
+        );
+        for workspace in &archived_workspaces {
+            Self::cleanup_workspace(db, workspace).await;
+        }
+        Ok(())
+    }
+
     pub fn spawn_workspace_cleanup(&self) {
         let db = self.db.clone();
         let cleanup_expired = Self::cleanup_expired_workspaces;
+        let cleanup_archived = Self::cleanup_archived_workspaces;
         tokio::spawn(async move {
             WorkspaceManager::cleanup_orphan_workspaces(&db.pool).await;
 
@@ -205,6 +224,9 @@ impl LocalContainerService {
                 cleanup_expired(&db).await.unwrap_or_else(|e| {
                     tracing::error!("Failed to clean up expired workspaces: {}", e)
                 });
+                cleanup_archived(&db).await.unwrap_or_else(|e| {
+                    tracing::error!("Failed to clean up archived workspaces: {}", e)
+                });
             }
         });
     }
@@ -930,6 +952,10 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn branch_name_template(&self) -> Option<String> {
+        self.config.read().await.branch_name_template.clone()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1233,8 +1259,14 @@ impl ContainerService for LocalContainerService {
                 ctx.execution_process.run_reason,
                 ExecutionProcessRunReason::DevServer
             )
-            && let Err(e) =
-                Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
+            && let Err(e) = Task::update_status(
+                &self.db.pool,
+                ctx.task.id,
+                TaskStatus::InReview,
+                Some("execution"),
+                Some("Execution stopped"),
+            )
+            .await
         {
             tracing::error!("Failed to update task status to InReview: {e}");
         }
@@ -1409,20 +1441,58 @@ impl ContainerService for LocalContainerService {
 
 #[async_trait]
 impl WorkspaceStarter for LocalContainerService {
-    async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
+    async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task: &Task) -> String {
         // Delegate to ContainerService's default implementation
-        ContainerService::git_branch_from_workspace(self, workspace_id, task_title).await
+        ContainerService::git_branch_from_workspace(self, workspace_id, task).await
     }
 
     async fn start_workspace(
         &self,
         workspace: &Workspace,
         executor_profile_id: executors::profile::ExecutorProfileId,
+        prompt_context: Option<String>,
     ) -> Result<(), String> {
-        ContainerService::start_workspace(self, workspace, executor_profile_id)
+        ContainerService::start_workspace_with_context(
+            self,
+            workspace,
+            executor_profile_id,
+            prompt_context.as_deref(),
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    async fn stop_workspace(&self, workspace_id: Uuid) -> Result<(), String> {
+        let sessions = Session::find_by_workspace_id(&self.db.pool, workspace_id)
             .await
-            .map(|_| ())
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?;
+
+        for session in sessions {
+            let processes = ExecutionProcess::find_by_session_id(&self.db.pool, session.id, false)
+                .await
+                .map_err(|e| e.to_string())?;
+            for process in processes {
+                // Processes may already be marked finished in the DB; stop anything still
+                // holding a live child
+                if self.get_child_from_store(&process.id).await.is_none() {
+                    continue;
+                }
+                if let Err(e) = self
+                    .stop_execution(&process, ExecutionProcessStatus::Killed)
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to stop execution process {} for workspace {}: {}",
+                        process.id,
+                        workspace_id,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(())
     }
 }
 
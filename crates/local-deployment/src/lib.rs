@@ -1,11 +1,11 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use db::DBService;
 use deployment::{Deployment, DeploymentError, RemoteClientNotConfigured};
 use executors::profile::ExecutorConfigs;
 use services::services::{
-    agent_activity::{AgentActivityService, AutoAttemptConfig},
+    agent_activity::{AgentActivityService, AutoAttemptConfig, WorkspaceStarter},
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
     auth::AuthContext,
@@ -18,11 +18,12 @@ use services::services::{
     image::ImageService,
     oauth_credentials::OAuthCredentials,
     project::ProjectService,
+    project_concurrency::DEFAULT_PROJECT_CONCURRENCY,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
-    review_automation::ReviewAutomationService,
-    task_timeout::TaskTimeoutService,
+    review_automation::{self, GitOps, ReviewAutomationService},
+    task_timeout::{self, TaskTimeoutService},
     worktree_manager::WorktreeManager,
 };
 use tokio::sync::RwLock;
@@ -278,30 +279,55 @@ impl Deployment for LocalDeployment {
     async fn spawn_agent_activity_service(&self) -> tokio::task::JoinHandle<()> {
         let db = self.db.clone();
         let notification_service = self.container.notification_service().clone();
+        let project_concurrency = self.project_concurrency().await;
 
-        // Create auto-attempt config with all necessary services
-        let auto_attempt = AutoAttemptConfig {
-            git_service: self.git.clone(),
-            config: self.config.clone(),
-            workspace_starter: Arc::new(self.container.clone()),
-        };
-
-        AgentActivityService::spawn(db, notification_service, Some(auto_attempt)).await
+        AgentActivityService::spawn(
+            db,
+            notification_service,
+            Some(self.auto_attempt_config()),
+            project_concurrency,
+        )
+        .await
     }
 
     async fn spawn_review_automation_service(&self) -> tokio::task::JoinHandle<()> {
         let db = self.db.clone();
-        let git_service = self.git.clone();
+        let git_service: Arc<dyn GitOps> = Arc::new(self.git.clone());
         let notification_service = self.container.notification_service().clone();
+        let poll_interval = self.config.read().await.review_poll_interval_secs.map_or(
+            review_automation::DEFAULT_POLL_INTERVAL,
+            Duration::from_secs,
+        );
+        let project_concurrency = self.project_concurrency().await;
 
-        ReviewAutomationService::spawn(db, git_service, notification_service).await
+        ReviewAutomationService::spawn(
+            db,
+            git_service,
+            notification_service,
+            poll_interval,
+            project_concurrency,
+        )
+        .await
     }
 
     async fn spawn_task_timeout_service(&self) -> tokio::task::JoinHandle<()> {
         let db = self.db.clone();
         let notification_service = self.container.notification_service().clone();
+        let workspace_starter: Arc<dyn WorkspaceStarter> = Arc::new(self.container.clone());
+        let poll_interval = self
+            .config
+            .read()
+            .await
+            .task_timeout_poll_interval_secs
+            .map_or(task_timeout::DEFAULT_POLL_INTERVAL, Duration::from_secs);
 
-        TaskTimeoutService::spawn(db, notification_service).await
+        TaskTimeoutService::spawn(
+            db,
+            notification_service,
+            Some(workspace_starter),
+            poll_interval,
+        )
+        .await
     }
 }
 
@@ -366,4 +392,22 @@ impl LocalDeployment {
     pub fn pty(&self) -> &PtyService {
         &self.pty
     }
+
+    /// Services needed to auto-start attempts for tasks the agent picks up
+    pub fn auto_attempt_config(&self) -> AutoAttemptConfig {
+        AutoAttemptConfig {
+            git_service: self.git.clone(),
+            config: self.config.clone(),
+            workspace_starter: Arc::new(self.container.clone()),
+        }
+    }
+
+    /// How many projects each background service processes at once
+    async fn project_concurrency(&self) -> usize {
+        self.config
+            .read()
+            .await
+            .background_project_concurrency
+            .unwrap_or(DEFAULT_PROJECT_CONCURRENCY)
+    }
 }
@@ -32,25 +32,58 @@ fn generate_types_content() -> String {
         db::models::task::TaskType::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
+        db::models::task::TaskStatsGroup::decl(),
+        db::models::task::ProjectTaskStats::decl(),
+        db::models::task::TaskWithImages::decl(),
+        db::models::task::TaskDetails::decl(),
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
         db::models::project_requirements::GenerationStatus::decl(),
+        db::models::project_requirements::FeatureGranularity::decl(),
         db::models::project_requirements::ExtractedFeature::decl(),
         db::models::project_requirements::AnalysisResult::decl(),
         db::models::project_requirements::ProjectRequirements::decl(),
         db::models::project_requirements::CreateProjectRequirements::decl(),
         db::models::project_requirements::ProjectRequirementsStatus::decl(),
+        db::models::project_requirements::ProjectRequirementsVersion::decl(),
         db::models::agent_activity::AgentAction::decl(),
+        db::models::agent_activity::WorkingDirStrategy::decl(),
         db::models::agent_activity::ProjectAgentSettings::decl(),
+        db::models::agent_activity::SelectionFactors::decl(),
+        db::models::agent_activity::SelectionReasoning::decl(),
         db::models::agent_activity::AgentActivityLog::decl(),
         db::models::agent_activity::AgentActivityStatus::decl(),
+        db::models::agent_activity::UpdateAgentActivitySettings::decl(),
         db::models::agent_activity::AgentTriggerResponse::decl(),
+        services::services::claude_api::ClaudeHealthStatus::decl(),
+        services::services::claude_api::CallQueueStatus::decl(),
+        services::services::claude_api::ClaudeHealth::decl(),
+        services::services::agent_activity::SelectionPromptPreview::decl(),
+        services::services::complexity_analyzer::ComplexityAnalysisResponse::decl(),
+        services::services::complexity_analyzer::SubtaskSuggestion::decl(),
+        db::models::project_notification_channel::NotificationChannelType::decl(),
+        db::models::project_notification_channel::ProjectNotificationChannel::decl(),
+        db::models::project_notification_channel::CreateProjectNotificationChannel::decl(),
+        db::models::project_notification_channel::UpdateProjectNotificationChannel::decl(),
+        db::models::project_prompt_version::ProjectPromptVersion::decl(),
+        db::models::project_prompt_version::SetProjectPromptVersion::decl(),
+        db::models::task_comment::TaskComment::decl(),
+        db::models::task_comment::CreateTaskComment::decl(),
+        db::models::task_status_history::TaskStatusHistory::decl(),
+        services::services::prompts::PromptTemplateVersions::decl(),
         db::models::review_automation::ReviewAction::decl(),
         db::models::review_automation::ProjectReviewSettings::decl(),
         db::models::review_automation::ReviewAutomationLog::decl(),
         db::models::review_automation::ReviewAutomationStatus::decl(),
         db::models::review_automation::ReviewAutomationSettingsResponse::decl(),
+        db::models::review_automation::UpdateReviewAutomationSettings::decl(),
+        db::models::review_automation::ReviewAutomationSettingsUpdateResponse::decl(),
+        services::services::review_automation::RepoMergePreview::decl(),
+        services::services::task_export::ExportFormat::decl(),
+        services::services::task_export::ExportedTask::decl(),
+        services::services::task_export::TaskImportRowResult::decl(),
+        services::services::task_export::TaskImportResult::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceRepo::decl(),
@@ -109,6 +142,8 @@ fn generate_types_content() -> String {
         utils::api::projects::RemoteProject::decl(),
         utils::api::projects::ListProjectsResponse::decl(),
         utils::api::projects::RemoteProjectMembersResponse::decl(),
+        server::error::RetryableErrorData::decl(),
+        server::routes::automation::ProjectAutomationOverview::decl(),
         server::routes::projects::CreateRemoteProjectRequest::decl(),
         server::routes::projects::LinkToExistingRequest::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
@@ -136,6 +171,11 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::SetTaskArchivedRequest::decl(),
+        server::routes::tasks::SubmitForReviewRequest::decl(),
+        server::routes::tasks::BlockTaskRequest::decl(),
+        server::routes::tasks::FindSimilarTasksRequest::decl(),
+        services::services::task_similarity::SimilarTask::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
@@ -1,20 +1,22 @@
 use axum::{
     Json,
     extract::multipart::MultipartError,
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use db::models::{
     execution_process::ExecutionProcessError, project::ProjectError,
     project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
-    workspace::WorkspaceError,
+    task::TaskError, workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::{command::CommandBuildError, executors::ExecutorError};
 use git2::Error as Git2Error;
 use local_deployment::pty::PtyError;
+use serde::Serialize;
 use services::services::{
     agent_activity::AgentActivityError,
+    claude_api::ClaudeApiError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
     git::GitServiceError,
@@ -30,6 +32,16 @@ use services::services::{
 use thiserror::Error;
 use utils::response::ApiResponse;
 
+/// Seconds clients are asked to wait before retrying after a transient AI service failure
+const AI_RETRY_AFTER_SECS: u32 = 30;
+
+/// Error data attached to responses for transient failures that are safe to retry
+#[derive(Debug, Serialize, ts_rs::TS)]
+pub struct RetryableErrorData {
+    pub retryable: bool,
+    pub retry_after_secs: u32,
+}
+
 #[derive(Debug, Error, ts_rs::TS)]
 #[ts(type = "string")]
 pub enum ApiError {
@@ -79,6 +91,13 @@ pub enum ApiError {
     Conflict(String),
     #[error("Forbidden: {0}")]
     Forbidden(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Too many requests: {message}")]
+    TooManyRequests {
+        message: String,
+        retry_after_secs: u32,
+    },
     #[error(transparent)]
     CommandBuilder(#[from] CommandBuildError),
     #[error(transparent)]
@@ -109,6 +128,37 @@ impl From<RemoteClientNotConfigured> for ApiError {
     }
 }
 
+impl ApiError {
+    /// The Claude API failure behind a service error, if any
+    fn claude_api_error(&self) -> Option<&ClaudeApiError> {
+        match self {
+            ApiError::RequirementsAnalyzer(RequirementsAnalyzerError::ClaudeApi(err))
+            | ApiError::AgentActivity(AgentActivityError::ClaudeApi(err))
+            | ApiError::ReviewAutomation(ReviewAutomationError::ClaudeApi(err)) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Transient AI failures are retryable (503); anything else, such as a missing or rejected
+/// key, is an upstream problem (502) that retrying will not fix.
+fn claude_api_status(err: &ClaudeApiError) -> StatusCode {
+    if err.should_retry() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::BAD_GATEWAY
+    }
+}
+
+/// Illegal status transitions conflict with the task's current state; a missing task is a 404.
+fn task_error_status(err: &TaskError) -> StatusCode {
+    match err {
+        TaskError::InvalidStatusTransition { .. } => StatusCode::CONFLICT,
+        TaskError::TaskNotFound => StatusCode::NOT_FOUND,
+        TaskError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status_code, error_type) = match &self {
@@ -191,6 +241,8 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
             ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "ForbiddenError"),
+            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "NotFoundError"),
+            ApiError::TooManyRequests { .. } => (StatusCode::TOO_MANY_REQUESTS, "TooManyRequests"),
             ApiError::Pty(err) => match err {
                 PtyError::SessionNotFound(_) => (StatusCode::NOT_FOUND, "PtyError"),
                 PtyError::SessionClosed => (StatusCode::GONE, "PtyError"),
@@ -203,8 +255,8 @@ impl IntoResponse for ApiError {
                 RequirementsAnalyzerError::AlreadyInProgress => {
                     (StatusCode::CONFLICT, "RequirementsAnalyzerError")
                 }
-                RequirementsAnalyzerError::ClaudeApi(_) => {
-                    (StatusCode::SERVICE_UNAVAILABLE, "RequirementsAnalyzerError")
+                RequirementsAnalyzerError::ClaudeApi(e) => {
+                    (claude_api_status(e), "RequirementsAnalyzerError")
                 }
                 RequirementsAnalyzerError::Database(_) => {
                     (StatusCode::INTERNAL_SERVER_ERROR, "RequirementsAnalyzerError")
@@ -220,8 +272,8 @@ impl IntoResponse for ApiError {
                 AgentActivityError::NotEnabled => {
                     (StatusCode::BAD_REQUEST, "AgentActivityError")
                 }
-                AgentActivityError::ClaudeApi(_) => {
-                    (StatusCode::SERVICE_UNAVAILABLE, "AgentActivityError")
+                AgentActivityError::ClaudeApi(e) => {
+                    (claude_api_status(e), "AgentActivityError")
                 }
                 AgentActivityError::Database(_) => {
                     (StatusCode::INTERNAL_SERVER_ERROR, "AgentActivityError")
@@ -232,6 +284,19 @@ impl IntoResponse for ApiError {
                 AgentActivityError::NoRepositories => {
                     (StatusCode::BAD_REQUEST, "AgentActivityError")
                 }
+                AgentActivityError::InvalidSettings(_) => {
+                    (StatusCode::BAD_REQUEST, "AgentActivityError")
+                }
+                AgentActivityError::TaskNotFound => {
+                    (StatusCode::NOT_FOUND, "AgentActivityError")
+                }
+                AgentActivityError::TaskNotTodo => {
+                    (StatusCode::CONFLICT, "AgentActivityError")
+                }
+                AgentActivityError::ComplexityNotAnalyzed => {
+                    (StatusCode::NOT_FOUND, "AgentActivityError")
+                }
+                AgentActivityError::Task(e) => (task_error_status(e), "AgentActivityError"),
             },
             ApiError::ReviewAutomation(err) => match err {
                 ReviewAutomationError::MergeConflict(_) => {
@@ -252,6 +317,25 @@ impl IntoResponse for ApiError {
                 ReviewAutomationError::CommandFailed(_) => {
                     (StatusCode::INTERNAL_SERVER_ERROR, "ReviewAutomationError")
                 }
+                ReviewAutomationError::InvalidSettings(_) => {
+                    (StatusCode::BAD_REQUEST, "ReviewAutomationError")
+                }
+                ReviewAutomationError::Task(e) => (task_error_status(e), "ReviewAutomationError"),
+                ReviewAutomationError::NothingToRevert(_) => {
+                    (StatusCode::CONFLICT, "ReviewAutomationError")
+                }
+                ReviewAutomationError::TargetNotAllowed(_) => {
+                    (StatusCode::FORBIDDEN, "ReviewAutomationError")
+                }
+                ReviewAutomationError::ClaudeApi(e) => {
+                    (claude_api_status(e), "ReviewAutomationError")
+                }
+                ReviewAutomationError::NotFound(_) => {
+                    (StatusCode::NOT_FOUND, "ReviewAutomationError")
+                }
+                ReviewAutomationError::Io(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "ReviewAutomationError")
+                }
             },
         };
 
@@ -329,8 +413,47 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
             ApiError::Forbidden(msg) => msg.clone(),
-            _ => format!("{}: {}", error_type, self),
+            ApiError::NotFound(msg) => msg.clone(),
+            ApiError::TooManyRequests { message, .. } => message.clone(),
+            _ => match self.claude_api_error() {
+                Some(ClaudeApiError::MissingApiKey) => {
+                    "AI service is not configured. Set the ANTHROPIC_API_KEY environment variable."
+                        .to_string()
+                }
+                Some(ClaudeApiError::InvalidApiKey) => {
+                    "AI service rejected the configured API key. Check ANTHROPIC_API_KEY."
+                        .to_string()
+                }
+                Some(err) if err.should_retry() => {
+                    "AI service is temporarily unavailable. Please retry shortly.".to_string()
+                }
+                _ => format!("{}: {}", error_type, self),
+            },
         };
+
+        let retry_after_secs = match &self {
+            ApiError::TooManyRequests { retry_after_secs, .. } => Some(*retry_after_secs),
+            _ if self.claude_api_error().is_some_and(|e| e.should_retry()) => {
+                Some(AI_RETRY_AFTER_SECS)
+            }
+            _ => None,
+        };
+        if let Some(retry_after_secs) = retry_after_secs {
+            let response = ApiResponse::<(), RetryableErrorData>::error_with_message_and_data(
+                &error_message,
+                RetryableErrorData {
+                    retryable: true,
+                    retry_after_secs,
+                },
+            );
+            return (
+                status_code,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                Json(response),
+            )
+                .into_response();
+        }
+
         let response = ApiResponse::<()>::error(&error_message);
         (status_code, Json(response)).into_response()
     }
@@ -358,7 +481,7 @@ impl From<ProjectServiceError> for ApiError {
                 "A repository with this name already exists in the project".to_string(),
             ),
             ProjectServiceError::RepositoryNotFound => {
-                ApiError::BadRequest("Repository not found".to_string())
+                ApiError::NotFound("Repository not found".to_string())
             }
             ProjectServiceError::GitError(msg) => {
                 ApiError::BadRequest(format!("Git operation failed: {}", msg))
@@ -384,7 +507,7 @@ impl From<RepoServiceError> for ApiError {
             RepoServiceError::NotGitRepository(path) => {
                 ApiError::BadRequest(format!("Path is not a git repository: {}", path.display()))
             }
-            RepoServiceError::NotFound => ApiError::BadRequest("Repository not found".to_string()),
+            RepoServiceError::NotFound => ApiError::NotFound("Repository not found".to_string()),
             RepoServiceError::DirectoryAlreadyExists(path) => {
                 ApiError::BadRequest(format!("Directory already exists: {}", path.display()))
             }
@@ -403,7 +526,7 @@ impl From<ProjectRepoError> for ApiError {
         match err {
             ProjectRepoError::Database(db_err) => ApiError::Database(db_err),
             ProjectRepoError::NotFound => {
-                ApiError::BadRequest("Repository not found in project".to_string())
+                ApiError::NotFound("Repository not found in project".to_string())
             }
             ProjectRepoError::AlreadyExists => {
                 ApiError::Conflict("Repository already exists in project".to_string())
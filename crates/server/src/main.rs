@@ -1,4 +1,5 @@
 use anyhow::{self, Error as AnyhowError};
+use db::models::project_requirements::ProjectRequirements;
 use deployment::{Deployment, DeploymentError};
 use server::{DeploymentImpl, routes};
 use services::services::container::ContainerService;
@@ -60,6 +61,10 @@ async fn main() -> Result<(), VibeKanbanError> {
         .cleanup_orphan_executions()
         .await
         .map_err(DeploymentError::from)?;
+    let interrupted = ProjectRequirements::fail_interrupted(&deployment.db().pool).await?;
+    if interrupted > 0 {
+        tracing::info!("Marked {interrupted} interrupted requirements analyses as failed");
+    }
     deployment
         .container()
         .backfill_before_head_commits()
@@ -71,6 +76,7 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_task_reconciler_service().await;
     deployment.spawn_agent_activity_service().await;
     deployment.spawn_review_automation_service().await;
     deployment.spawn_task_timeout_service().await;
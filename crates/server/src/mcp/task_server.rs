@@ -152,7 +152,7 @@ pub struct ListTasksRequest {
     #[schemars(description = "The ID of the project to list tasks from")]
     pub project_id: Uuid,
     #[schemars(
-        description = "Optional status filter: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'"
+        description = "Optional status filter: 'todo', 'inprogress', 'inreview', 'done', 'cancelled', 'blocked'"
     )]
     pub status: Option<String>,
     #[schemars(description = "Maximum number of tasks to return (default: 50)")]
@@ -248,7 +248,9 @@ pub struct UpdateTaskRequest {
     pub title: Option<String>,
     #[schemars(description = "New description for the task")]
     pub description: Option<String>,
-    #[schemars(description = "New status: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'")]
+    #[schemars(
+        description = "New status: 'todo', 'inprogress', 'inreview', 'done', 'cancelled', 'blocked'"
+    )]
     pub status: Option<String>,
 }
 
@@ -792,7 +794,7 @@ impl TaskServer {
                 Ok(s) => Some(s),
                 Err(_) => {
                     return Self::err(
-                        "Invalid status filter. Valid values: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'".to_string(),
+                        "Invalid status filter. Valid values: 'todo', 'inprogress', 'inreview', 'done', 'cancelled', 'blocked'".to_string(),
                         Some(status_str.to_string()),
                     );
                 }
@@ -931,7 +933,7 @@ impl TaskServer {
                 Ok(s) => Some(s),
                 Err(_) => {
                     return Self::err(
-                        "Invalid status filter. Valid values: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'".to_string(),
+                        "Invalid status filter. Valid values: 'todo', 'inprogress', 'inreview', 'done', 'cancelled', 'blocked'".to_string(),
                         Some(status_str.to_string()),
                     );
                 }
@@ -1,16 +1,28 @@
 //! Routes for agent activity (autonomous task selection).
 
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
 use axum::{
     Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{get, post, put},
+};
+use db::models::agent_activity::{
+    AgentActivityStatus, AgentTriggerResponse, ProjectAgentSettings, UpdateAgentActivitySettings,
+    WorkingDirStrategy,
 };
-use db::models::agent_activity::{AgentActivityStatus, AgentTriggerResponse, ProjectAgentSettings};
+use db::models::task::TaskLayer;
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
 use services::services::{
-    agent_activity::AgentActivityService,
+    agent_activity::{AgentActivityService, SelectionPromptPreview},
+    agent_activity_digest::ActivityNotifier,
+    complexity_analyzer::ComplexityAnalysisResponse,
     container::ContainerService,
 };
 use ts_rs::TS;
@@ -24,6 +36,22 @@ use crate::{DeploymentImpl, error::ApiError};
 pub struct AgentActivitySettingsResponse {
     pub enabled: bool,
     pub interval_seconds: i32,
+    pub max_concurrent_layers: i32,
+    pub digest_window_minutes: Option<i32>,
+    pub working_dir_strategy: WorkingDirStrategy,
+    pub working_dir_path: Option<String>,
+    pub auto_breakdown_fullstack: bool,
+    pub min_complexity_for_auto_attempt: i32,
+    pub max_in_progress_tasks: i32,
+    pub max_prompt_description_chars: i32,
+    pub max_breakdown_subtasks: i32,
+    pub auto_attempt_retries: i32,
+    pub include_recent_comments: bool,
+    pub integration_blocks_all: bool,
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    pub quiet_hours_utc_offset_minutes: i32,
+    pub default_layer: Option<TaskLayer>,
 }
 
 impl From<ProjectAgentSettings> for AgentActivitySettingsResponse {
@@ -31,6 +59,22 @@ impl From<ProjectAgentSettings> for AgentActivitySettingsResponse {
         Self {
             enabled: settings.enabled,
             interval_seconds: settings.interval_seconds,
+            max_concurrent_layers: settings.max_concurrent_layers,
+            digest_window_minutes: settings.digest_window_minutes,
+            working_dir_strategy: settings.working_dir_strategy,
+            working_dir_path: settings.working_dir_path,
+            auto_breakdown_fullstack: settings.auto_breakdown_fullstack,
+            min_complexity_for_auto_attempt: settings.min_complexity_for_auto_attempt,
+            max_in_progress_tasks: settings.max_in_progress_tasks,
+            max_prompt_description_chars: settings.max_prompt_description_chars,
+            max_breakdown_subtasks: settings.max_breakdown_subtasks,
+            auto_attempt_retries: settings.auto_attempt_retries,
+            include_recent_comments: settings.include_recent_comments,
+            integration_blocks_all: settings.integration_blocks_all,
+            quiet_hours_start: settings.quiet_hours_start,
+            quiet_hours_end: settings.quiet_hours_end,
+            quiet_hours_utc_offset_minutes: settings.quiet_hours_utc_offset_minutes,
+            default_layer: settings.default_layer,
         }
     }
 }
@@ -73,6 +117,32 @@ pub async fn disable_agent_activity(
     Ok(ResponseJson(ApiResponse::success(settings.into())))
 }
 
+/// Update agent activity settings for a project
+pub async fn update_agent_activity_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    axum::Json(payload): axum::Json<UpdateAgentActivitySettings>,
+) -> Result<ResponseJson<ApiResponse<AgentActivitySettingsResponse>>, ApiError> {
+    let settings =
+        AgentActivityService::update_settings(&deployment.db().pool, project_id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "agent_activity_settings_updated",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "interval_seconds": settings.interval_seconds,
+                "max_concurrent_layers": settings.max_concurrent_layers,
+                "max_in_progress_tasks": settings.max_in_progress_tasks,
+                "digest_enabled": settings.digest_window_minutes.is_some(),
+                "quiet_hours_enabled": settings.quiet_hours_start.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(settings.into())))
+}
+
 /// Get agent activity status for a project
 pub async fn get_agent_activity_status(
     State(deployment): State<DeploymentImpl>,
@@ -82,17 +152,102 @@ pub async fn get_agent_activity_status(
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+/// Preview the task-selection prompt without calling the AI
+pub async fn get_agent_activity_prompt_preview(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<SelectionPromptPreview>>, ApiError> {
+    let preview =
+        AgentActivityService::preview_selection_prompt(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(preview)))
+}
+
+/// Run complexity analysis for a task on demand, storing the score without breaking it down
+pub async fn analyze_task_complexity(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ComplexityAnalysisResponse>>, ApiError> {
+    let analysis =
+        AgentActivityService::analyze_task_complexity(&deployment.db().pool, task_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_complexity_analyzed",
+            serde_json::json!({
+                "task_id": task_id.to_string(),
+                "complexity_score": analysis.complexity_score,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(analysis)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskComplexityQuery {
+    /// Run an analysis when the task has none stored
+    #[serde(default)]
+    pub analyze: bool,
+}
+
+pub async fn get_task_complexity(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+    Query(query): Query<TaskComplexityQuery>,
+) -> Result<ResponseJson<ApiResponse<ComplexityAnalysisResponse>>, ApiError> {
+    let analysis =
+        AgentActivityService::task_complexity(&deployment.db().pool, task_id, query.analyze)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(analysis)))
+}
+
+/// Minimum time between manual triggers for the same project
+const MANUAL_TRIGGER_COOLDOWN: Duration = Duration::from_secs(5);
+
+/// When each project was last triggered manually
+fn last_manual_triggers() -> &'static Mutex<HashMap<Uuid, Instant>> {
+    static LAST_TRIGGERS: OnceLock<Mutex<HashMap<Uuid, Instant>>> = OnceLock::new();
+    LAST_TRIGGERS.get_or_init(Default::default)
+}
+
+/// Record a manual trigger for the project, or reject it if the cooldown has not elapsed.
+/// Triggers spawn an AI selection each, so spamming the button would waste calls and race
+/// the background loop.
+fn claim_manual_trigger(project_id: Uuid) -> Result<(), ApiError> {
+    let mut last_triggers = last_manual_triggers()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    if let Some(last) = last_triggers.get(&project_id) {
+        let elapsed = now.duration_since(*last);
+        if elapsed < MANUAL_TRIGGER_COOLDOWN {
+            let retry_after_secs = (MANUAL_TRIGGER_COOLDOWN - elapsed).as_secs_f64().ceil() as u32;
+            return Err(ApiError::TooManyRequests {
+                message: format!(
+                    "Agent activity was triggered moments ago; try again in {retry_after_secs}s"
+                ),
+                retry_after_secs,
+            });
+        }
+    }
+    last_triggers.retain(|_, last| now.duration_since(*last) < MANUAL_TRIGGER_COOLDOWN);
+    last_triggers.insert(project_id, now);
+    Ok(())
+}
+
 /// Manually trigger agent activity to select next task
 pub async fn trigger_agent_activity(
     State(deployment): State<DeploymentImpl>,
     Path(project_id): Path<Uuid>,
 ) -> Result<ResponseJson<ApiResponse<AgentTriggerResponse>>, ApiError> {
+    claim_manual_trigger(project_id)?;
+
     let notification_service = deployment.container().notification_service().clone();
 
     // Manual trigger doesn't use auto-attempt (user can start attempt separately)
     let response = AgentActivityService::check_and_select_next_task(
         &deployment.db().pool,
-        &notification_service,
+        &ActivityNotifier::immediate(&notification_service),
         project_id,
         None, // No auto-attempt for manual triggers
     )
@@ -112,13 +267,52 @@ pub async fn trigger_agent_activity(
     Ok(ResponseJson(ApiResponse::success(response)))
 }
 
-pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
-    Router::new().nest(
-        "/projects/{project_id}/agent-activity",
-        Router::new()
-            .route("/enable", post(enable_agent_activity))
-            .route("/disable", post(disable_agent_activity))
-            .route("/status", get(get_agent_activity_status))
-            .route("/trigger", post(trigger_agent_activity)),
+/// Manually choose the next task for the agent, bypassing AI selection
+pub async fn select_agent_activity_task(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<AgentTriggerResponse>>, ApiError> {
+    let notification_service = deployment.container().notification_service().clone();
+    let auto_attempt = deployment.auto_attempt_config();
+
+    let response = AgentActivityService::select_specific_task(
+        &deployment.db().pool,
+        &ActivityNotifier::immediate(&notification_service),
+        project_id,
+        task_id,
+        Some(&auto_attempt),
     )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "agent_activity_task_selected_manually",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "task_id": task_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .nest(
+            "/projects/{project_id}/agent-activity",
+            Router::new()
+                .route("/enable", post(enable_agent_activity))
+                .route("/disable", post(disable_agent_activity))
+                .route("/settings", put(update_agent_activity_settings))
+                .route("/status", get(get_agent_activity_status))
+                .route("/prompt-preview", get(get_agent_activity_prompt_preview))
+                .route("/trigger", post(trigger_agent_activity))
+                .route("/select/{task_id}", post(select_agent_activity_task)),
+        )
+        .route(
+            "/tasks/{task_id}/analyze-complexity",
+            post(analyze_task_complexity),
+        )
+        .route("/tasks/{task_id}/complexity", get(get_task_complexity))
 }
@@ -0,0 +1,82 @@
+//! Instance-wide view of the autonomous features (agent activity, review automation).
+
+use std::collections::HashMap;
+
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use chrono::{DateTime, Utc};
+use db::models::{
+    agent_activity::{AgentActivityLog, ProjectAgentSettings},
+    project::Project,
+    review_automation::{
+        ProjectReviewSettings, ReviewAutomationLog, ReviewAutomationSettingsResponse,
+    },
+};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// A project with at least one autonomous feature enabled
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectAutomationOverview {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub agent_settings: Option<ProjectAgentSettings>,
+    pub review_settings: Option<ReviewAutomationSettingsResponse>,
+    /// Most recent agent activity or review automation log entry
+    pub last_activity_at: Option<DateTime<Utc>>,
+}
+
+/// List every project with agent activity and/or review automation enabled
+pub async fn get_automation_overview(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectAutomationOverview>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let mut agent_settings: HashMap<Uuid, ProjectAgentSettings> =
+        ProjectAgentSettings::find_all_enabled(pool)
+            .await?
+            .into_iter()
+            .map(|settings| (settings.project_id, settings))
+            .collect();
+    let mut review_settings: HashMap<Uuid, ProjectReviewSettings> =
+        ProjectReviewSettings::find_all_enabled(pool)
+            .await?
+            .into_iter()
+            .map(|settings| (settings.project_id, settings))
+            .collect();
+
+    let mut overview = Vec::new();
+    for project in Project::find_all(pool).await? {
+        let agent = agent_settings.remove(&project.id);
+        let review = review_settings.remove(&project.id);
+        if agent.is_none() && review.is_none() {
+            continue;
+        }
+
+        let last_agent_activity = AgentActivityLog::find_latest_by_project_id(pool, project.id)
+            .await?
+            .map(|log| log.created_at);
+        let last_review_activity = ReviewAutomationLog::find_latest_by_project_id(pool, project.id)
+            .await?
+            .map(|log| log.created_at);
+
+        overview.push(ProjectAutomationOverview {
+            project_id: project.id,
+            project_name: project.name,
+            agent_settings: agent,
+            review_settings: review.map(Into::into),
+            last_activity_at: last_agent_activity.max(last_review_activity),
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(overview)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().route("/automation/overview", get(get_automation_overview))
+}
@@ -18,10 +18,14 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{
-    Config, ConfigError, SoundFile,
-    editor::{EditorConfig, EditorType},
-    save_config_to_file,
+use services::services::{
+    branch_name::validate_branch_name_template,
+    config::{
+        Config, ConfigError, SoundFile,
+        editor::{EditorConfig, EditorType},
+        save_config_to_file,
+    },
+    project_concurrency::MAX_PROJECT_CONCURRENCY,
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -123,6 +127,40 @@ async fn update_config(
         ));
     }
 
+    if let Some(template) = &new_config.branch_name_template
+        && let Err(e) = validate_branch_name_template(template)
+    {
+        return ResponseJson(ApiResponse::error(&format!(
+            "Invalid branch name template: {e}"
+        )));
+    }
+
+    for (name, secs) in [
+        (
+            "review_poll_interval_secs",
+            new_config.review_poll_interval_secs,
+        ),
+        (
+            "task_timeout_poll_interval_secs",
+            new_config.task_timeout_poll_interval_secs,
+        ),
+    ] {
+        if secs.is_some_and(|s| !(1..=3600).contains(&s)) {
+            return ResponseJson(ApiResponse::error(&format!(
+                "{name} must be between 1 and 3600"
+            )));
+        }
+    }
+
+    if new_config
+        .background_project_concurrency
+        .is_some_and(|n| !(1..=MAX_PROJECT_CONCURRENCY).contains(&n))
+    {
+        return ResponseJson(ApiResponse::error(&format!(
+            "background_project_concurrency must be between 1 and {MAX_PROJECT_CONCURRENCY}"
+        )));
+    }
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
@@ -1,6 +1,13 @@
 use axum::response::Json;
+use services::services::claude_api::ClaudeHealth;
 use utils::response::ApiResponse;
 
 pub async fn health_check() -> Json<ApiResponse<String>> {
     Json(ApiResponse::success("OK".to_string()))
 }
+
+/// Verify the Anthropic API key with a 1-token request, so operators can confirm the AI
+/// integration works before enabling autonomous features
+pub async fn claude_health_check() -> Json<ApiResponse<ClaudeHealth>> {
+    Json(ApiResponse::success(ClaudeHealth::check().await))
+}
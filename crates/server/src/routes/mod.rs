@@ -8,6 +8,7 @@ use crate::{DeploymentImpl, middleware};
 
 pub mod agent_activity;
 pub mod approvals;
+pub mod automation;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
@@ -16,10 +17,12 @@ pub mod events;
 pub mod execution_processes;
 pub mod frontend;
 pub mod health;
+pub mod notification_channels;
 pub mod images;
 pub mod oauth;
 pub mod organizations;
 pub mod projects;
+pub mod prompts;
 pub mod repo;
 pub mod requirements;
 pub mod review_automation;
@@ -27,6 +30,7 @@ pub mod scratch;
 pub mod sessions;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_comments;
 pub mod tasks;
 pub mod terminal;
 
@@ -34,11 +38,13 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
+        .route("/health/claude", get(health::claude_health_check))
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
+        .merge(task_comments::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
         .merge(oauth::router())
@@ -48,6 +54,9 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(requirements::router(&deployment))
         .merge(agent_activity::router(&deployment))
         .merge(review_automation::router(&deployment))
+        .merge(automation::router(&deployment))
+        .merge(notification_channels::router(&deployment))
+        .merge(prompts::router(&deployment))
         .merge(events::router(&deployment))
         .merge(approvals::router())
         .merge(scratch::router(&deployment))
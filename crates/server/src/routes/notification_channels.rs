@@ -0,0 +1,151 @@
+//! Routes for configuring where a project's notifications are delivered.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    project::Project,
+    project_notification_channel::{
+        CreateProjectNotificationChannel, ProjectNotificationChannel,
+        UpdateProjectNotificationChannel,
+    },
+};
+use deployment::Deployment;
+use services::services::notification::NotificationService;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// List the notification channels configured for a project
+pub async fn get_notification_channels(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectNotificationChannel>>>, ApiError> {
+    let channels =
+        ProjectNotificationChannel::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(channels)))
+}
+
+/// Add a notification channel to a project
+pub async fn create_notification_channel(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<CreateProjectNotificationChannel>,
+) -> Result<ResponseJson<ApiResponse<ProjectNotificationChannel>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Project::find_by_id(pool, project_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Project not found".to_string()))?;
+
+    let config = payload
+        .config
+        .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+    NotificationService::validate_channel_config(payload.channel_type, &config)
+        .map_err(ApiError::BadRequest)?;
+
+    let channel = ProjectNotificationChannel::create(
+        pool,
+        project_id,
+        payload.channel_type,
+        &config,
+        payload.enabled.unwrap_or(true),
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "notification_channel_created",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "channel_type": channel.channel_type.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(channel)))
+}
+
+/// Update a project's notification channel
+pub async fn update_notification_channel(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, channel_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateProjectNotificationChannel>,
+) -> Result<ResponseJson<ApiResponse<ProjectNotificationChannel>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let existing = find_project_channel(pool, project_id, channel_id).await?;
+
+    let config = payload.config.unwrap_or(existing.config.0);
+    NotificationService::validate_channel_config(existing.channel_type, &config)
+        .map_err(ApiError::BadRequest)?;
+
+    let channel = ProjectNotificationChannel::update(
+        pool,
+        channel_id,
+        &config,
+        payload.enabled.unwrap_or(existing.enabled),
+    )
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Notification channel not found".to_string()))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "notification_channel_updated",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "channel_type": channel.channel_type.to_string(),
+                "enabled": channel.enabled,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(channel)))
+}
+
+/// Remove a notification channel from a project
+pub async fn delete_notification_channel(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, channel_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let channel = find_project_channel(pool, project_id, channel_id).await?;
+    ProjectNotificationChannel::delete(pool, channel.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "notification_channel_deleted",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "channel_type": channel.channel_type.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+async fn find_project_channel(
+    pool: &sqlx::SqlitePool,
+    project_id: Uuid,
+    channel_id: Uuid,
+) -> Result<ProjectNotificationChannel, ApiError> {
+    ProjectNotificationChannel::find_by_id(pool, channel_id)
+        .await?
+        .filter(|channel| channel.project_id == project_id)
+        .ok_or_else(|| ApiError::NotFound("Notification channel not found".to_string()))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().nest(
+        "/projects/{project_id}/notification-channels",
+        Router::new()
+            .route("/", get(get_notification_channels).post(create_notification_channel))
+            .route(
+                "/{channel_id}",
+                put(update_notification_channel).delete(delete_notification_channel),
+            ),
+    )
+}
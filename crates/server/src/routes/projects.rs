@@ -16,13 +16,17 @@ use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
+    task::{ProjectTaskStats, Task},
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
-    file_search::SearchQuery, project::ProjectServiceError,
+    file_search::SearchQuery,
+    git_host::ProviderKind,
+    project::ProjectServiceError,
     remote_client::CreateRemoteProjectPayload,
+    task_export::{self, TaskImportResult},
 };
 use ts_rs::TS;
 use utils::{
@@ -266,6 +270,15 @@ pub async fn update_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    if let Some(provider) = payload.git_host_provider.as_deref().map(str::trim)
+        && !provider.is_empty()
+        && ProviderKind::from_setting(provider).is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Unknown git host provider '{provider}' (expected github, gitlab or azure_devops)"
+        ))));
+    }
+
     match deployment
         .project()
         .update_project(&deployment.db().pool, &existing_project, payload)
@@ -568,6 +581,46 @@ pub async fn get_project_repository(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ImportTasksQuery {
+    #[serde(default)]
+    pub atomic: bool,
+}
+
+/// Task counts for the project by status, layer, type and source
+pub async fn get_project_stats(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectTaskStats>>, ApiError> {
+    let stats = Task::stats_by_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
+
+/// Bulk-create tasks from a JSON array in the task plan export schema
+pub async fn import_tasks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ImportTasksQuery>,
+    Json(rows): Json<Vec<serde_json::Value>>,
+) -> Result<ResponseJson<ApiResponse<TaskImportResult>>, ApiError> {
+    let result =
+        task_export::import_tasks(&deployment.db().pool, project.id, rows, query.atomic).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "tasks_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "imported": result.imported,
+                "failed": result.failed,
+                "atomic": query.atomic,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -582,6 +635,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             post(link_project_to_existing_remote).delete(unlink_project),
         )
         .route("/link/create", post(create_and_link_remote_project))
+        .route("/stats", get(get_project_stats))
+        .route("/tasks/import", post(import_tasks))
         .route(
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
@@ -0,0 +1,81 @@
+//! Routes for choosing which prompt template versions a project uses.
+
+use std::str::FromStr;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::project_prompt_version::{ProjectPromptVersion, SetProjectPromptVersion};
+use deployment::Deployment;
+use services::services::prompts::{self, PromptTemplateVersions, TemplateName};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// List every prompt template with its versions and the one the project uses
+pub async fn get_prompt_versions(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<PromptTemplateVersions>>>, ApiError> {
+    let versions = prompts::project_versions(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(versions)))
+}
+
+/// Pin a prompt template version for a project, or follow the latest when `version` is null
+pub async fn set_prompt_version(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, template_name)): Path<(Uuid, String)>,
+    Json(payload): Json<SetProjectPromptVersion>,
+) -> Result<ResponseJson<ApiResponse<PromptTemplateVersions>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Ok(name) = TemplateName::from_str(&template_name) else {
+        return Err(ApiError::NotFound(format!(
+            "Prompt template '{template_name}' not found"
+        )));
+    };
+
+    match payload.version {
+        Some(version) => {
+            if prompts::find(name, version).is_none() {
+                return Err(ApiError::BadRequest(format!(
+                    "Prompt template '{template_name}' has no version {version}"
+                )));
+            }
+            ProjectPromptVersion::upsert(pool, project_id, &template_name, version).await?;
+        }
+        None => {
+            ProjectPromptVersion::delete(pool, project_id, &template_name).await?;
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "prompt_version_set",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "template": template_name,
+                "version": payload.version,
+            }),
+        )
+        .await;
+
+    let versions = prompts::project_versions(pool, project_id)
+        .await?
+        .into_iter()
+        .find(|versions| versions.name == name.as_ref())
+        .ok_or_else(|| ApiError::NotFound("Prompt template not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(versions)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().nest(
+        "/projects/{project_id}/prompts",
+        Router::new()
+            .route("/", get(get_prompt_versions))
+            .route("/{template_name}", put(set_prompt_version)),
+    )
+}
@@ -1,12 +1,24 @@
 use axum::{
     Router,
-    extract::{Path, State},
-    response::Json as ResponseJson,
-    routing::post,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Json as ResponseJson},
+    routing::{get, post},
+};
+use db::models::{
+    project::Project,
+    project_requirements::{
+        CreateProjectRequirements, ProjectRequirements, ProjectRequirementsStatus,
+        ProjectRequirementsVersion,
+    },
+    task::Task,
 };
-use db::models::project_requirements::{CreateProjectRequirements, ProjectRequirementsStatus};
 use deployment::Deployment;
-use services::services::requirements_analyzer::RequirementsAnalyzer;
+use serde::Deserialize;
+use services::services::{
+    requirements_analyzer::RequirementsAnalyzer,
+    task_export::{self, ExportFormat},
+};
 use uuid::Uuid;
 use utils::response::ApiResponse;
 
@@ -22,17 +34,7 @@ pub async fn create_requirements(
     let analyzer = RequirementsAnalyzer::new(deployment.db().pool.clone())?;
 
     let requirements = analyzer.create_and_analyze(project_id, payload).await?;
-
-    let status = ProjectRequirementsStatus {
-        id: requirements.id,
-        project_id: requirements.project_id,
-        generation_status: requirements.generation_status.clone(),
-        analysis_result: requirements.parsed_analysis(),
-        tasks_generated: None,
-        error_message: requirements.error_message,
-        created_at: requirements.created_at,
-        updated_at: requirements.updated_at,
-    };
+    let status = requirements_status(requirements, false);
 
     deployment
         .track_if_analytics_allowed(
@@ -47,35 +49,118 @@ pub async fn create_requirements(
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// GET /api/projects/{project_id}/requirements
-/// Get requirements status and analysis result
+#[derive(Debug, Deserialize)]
+pub struct GetRequirementsQuery {
+    #[serde(default)]
+    pub include_raw: bool,
+}
+
+/// GET /api/projects/{project_id}/requirements?include_raw=true
+/// Get requirements status and analysis result, plus the submitted text when `include_raw` is set
 pub async fn get_requirements(
     State(deployment): State<DeploymentImpl>,
     Path(project_id): Path<Uuid>,
+    Query(query): Query<GetRequirementsQuery>,
 ) -> Result<ResponseJson<ApiResponse<Option<ProjectRequirementsStatus>>>, ApiError> {
     let analyzer = RequirementsAnalyzer::new(deployment.db().pool.clone())?;
 
     let requirements = analyzer.get_status(project_id).await?;
 
-    let status = requirements.map(|req| {
-        // Count generated tasks
-        let tasks_generated = None; // Could query tasks table if needed
-
-        ProjectRequirementsStatus {
-            id: req.id,
-            project_id: req.project_id,
-            generation_status: req.generation_status.clone(),
-            analysis_result: req.parsed_analysis(),
-            tasks_generated,
-            error_message: req.error_message,
-            created_at: req.created_at,
-            updated_at: req.updated_at,
-        }
-    });
+    let status =
+        requirements.map(|requirements| requirements_status(requirements, query.include_raw));
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// POST /api/projects/{project_id}/requirements/reanalyze
+/// Re-run analysis on the stored requirements text, e.g. after changing rules or models
+pub async fn reanalyze_requirements(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ProjectRequirementsStatus>>, ApiError> {
+    let analyzer = RequirementsAnalyzer::new(deployment.db().pool.clone())?;
+
+    let requirements = analyzer.reanalyze(project_id).await?;
+    let status = requirements_status(requirements, false);
+
+    deployment
+        .track_if_analytics_allowed(
+            "requirements_reanalyzed",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "requirements_id": status.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// PUT /api/projects/{project_id}/requirements
+/// Edit the requirements text and update the task plan for the features that changed
+pub async fn update_requirements(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    axum::Json(payload): axum::Json<CreateProjectRequirements>,
+) -> Result<ResponseJson<ApiResponse<ProjectRequirementsStatus>>, ApiError> {
+    let analyzer = RequirementsAnalyzer::new(deployment.db().pool.clone())?;
+
+    let requirements = analyzer.update_and_reanalyze(project_id, payload).await?;
+    let status = requirements_status(requirements, false);
+
+    deployment
+        .track_if_analytics_allowed(
+            "requirements_updated",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "requirements_id": status.id.to_string(),
+            }),
+        )
+        .await;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+/// GET /api/projects/{project_id}/requirements/versions
+/// Earlier versions of the requirements, saved each time they were edited, newest first
+pub async fn get_requirements_versions(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectRequirementsVersion>>>, ApiError> {
+    let analyzer = RequirementsAnalyzer::new(deployment.db().pool.clone())?;
+
+    let versions = analyzer.versions(project_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(versions)))
+}
+
+fn requirements_status(
+    requirements: ProjectRequirements,
+    include_raw: bool,
+) -> ProjectRequirementsStatus {
+    let (raw_requirements, prd_content) = if include_raw {
+        (
+            Some(requirements.raw_requirements),
+            requirements.prd_content,
+        )
+    } else {
+        (None, None)
+    };
+    ProjectRequirementsStatus {
+        id: requirements.id,
+        project_id: requirements.project_id,
+        generation_status: requirements.generation_status.clone(),
+        analysis_result: requirements.parsed_analysis(),
+        tasks_generated: None,
+        error_message: requirements.error_message,
+        raw_requirements,
+        prd_content,
+        granularity: requirements.granularity,
+        created_at: requirements.created_at,
+        updated_at: requirements.updated_at,
+    }
+}
+
 /// DELETE /api/projects/{project_id}/requirements
 /// Delete requirements and optionally the generated tasks
 pub async fn delete_requirements(
@@ -99,9 +184,64 @@ pub async fn delete_requirements(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// POST /api/projects/{project_id}/requirements/features/{feature_id}/regenerate
+/// Regenerate the tasks for a single feature, replacing the not-yet-started ones previously
+/// generated for it
+pub async fn regenerate_feature(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, feature_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<usize>>, ApiError> {
+    let analyzer = RequirementsAnalyzer::new(deployment.db().pool.clone())?;
+
+    let tasks_created = analyzer.regenerate_feature(project_id, feature_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "requirements_feature_regenerated",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "feature_id": feature_id.to_string(),
+                "tasks_created": tasks_created,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(tasks_created)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// GET /api/projects/{project_id}/requirements/export?format=markdown|json
+/// Export the AI-generated task plan as a single Markdown or JSON document
+pub async fn export_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let pool = &deployment.db().pool;
+    let project = Project::find_by_id(pool, project_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Project not found".to_string()))?;
+    let tasks = Task::find_ai_generated_by_project_id(pool, project_id).await?;
+
+    let document = task_export::render(query.format, &project.name, &tasks)
+        .map_err(std::io::Error::from)?;
+
+    Ok(([(header::CONTENT_TYPE, query.format.content_type())], document))
+}
+
 pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new().nest(
         "/projects/{project_id}/requirements",
-        Router::new().route("/", post(create_requirements).get(get_requirements).delete(delete_requirements)),
+        Router::new()
+            .route("/", post(create_requirements).get(get_requirements).put(update_requirements).delete(delete_requirements))
+            .route("/reanalyze", post(reanalyze_requirements))
+            .route("/versions", get(get_requirements_versions))
+            .route("/features/{feature_id}/regenerate", post(regenerate_feature))
+            .route("/export", get(export_tasks)),
     )
 }
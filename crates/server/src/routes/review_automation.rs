@@ -4,13 +4,21 @@ use axum::{
     Router,
     extract::{Path, State},
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{get, post, put},
 };
-use db::models::review_automation::{
-    ReviewAutomationLog, ReviewAutomationSettingsResponse, ReviewAutomationStatus,
+use db::models::{
+    review_automation::{
+        ReviewAutomationLog, ReviewAutomationSettingsResponse,
+        ReviewAutomationSettingsUpdateResponse, ReviewAutomationStatus,
+        UpdateReviewAutomationSettings,
+    },
+    workspace::Workspace,
 };
 use deployment::Deployment;
-use services::services::review_automation::ReviewAutomationService;
+use services::services::{
+    command_allowlist::CommandPolicy,
+    review_automation::{RepoMergePreview, ReviewAutomationError, ReviewAutomationService},
+};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -25,6 +33,59 @@ pub async fn get_review_automation_logs_by_task(
     Ok(ResponseJson(ApiResponse::success(logs)))
 }
 
+/// Reset a task's merge conflict count so manual conflict resolution gives it a fresh budget
+pub async fn reset_task_conflict_count(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<ReviewAutomationLog>>>, ApiError> {
+    let log = ReviewAutomationService::reset_conflict_count(&deployment.db().pool, task_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_automation_conflicts_reset",
+            serde_json::json!({
+                "task_id": task_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(log)))
+}
+
+/// Revert a task's auto-merges on their target branches and send the task back to Todo
+pub async fn revert_task_merge(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReviewAutomationLog>>>, ApiError> {
+    let logs = ReviewAutomationService::revert_task_merges(
+        &deployment.db().pool,
+        deployment.git(),
+        task_id,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_automation_merge_reverted",
+            serde_json::json!({
+                "task_id": task_id.to_string(),
+                "reverted_merges": logs.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(logs)))
+}
+
+/// Get the full output of a review log, including output stored as an artifact file
+pub async fn get_review_automation_log_artifact(
+    State(deployment): State<DeploymentImpl>,
+    Path(log_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<String>>>, ApiError> {
+    let output = ReviewAutomationService::get_log_artifact(&deployment.db().pool, log_id).await?;
+    Ok(ResponseJson(ApiResponse::success(output)))
+}
+
 /// Enable review automation for a project
 pub async fn enable_review_automation(
     State(deployment): State<DeploymentImpl>,
@@ -63,6 +124,47 @@ pub async fn disable_review_automation(
     Ok(ResponseJson(ApiResponse::success(settings.into())))
 }
 
+/// Update review automation toggles for a project
+pub async fn update_review_automation_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    axum::Json(payload): axum::Json<UpdateReviewAutomationSettings>,
+) -> Result<ResponseJson<ApiResponse<ReviewAutomationSettingsUpdateResponse>>, ApiError> {
+    let command_policy = {
+        let config = deployment.config().read().await;
+        CommandPolicy::new(
+            config.test_command_allowlist.as_deref(),
+            config.allow_shell_in_test_commands,
+        )
+    };
+    let (settings, warnings) = ReviewAutomationService::update_settings(
+        &deployment.db().pool,
+        project_id,
+        &payload,
+        &command_policy,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_automation_settings_updated",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "enabled": settings.enabled,
+                "auto_merge_enabled": settings.auto_merge_enabled,
+                "run_tests_enabled": settings.run_tests_enabled,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        ReviewAutomationSettingsUpdateResponse {
+            settings: settings.into(),
+            warnings,
+        },
+    )))
+}
+
 /// Get review automation status for a project
 pub async fn get_review_automation_status(
     State(deployment): State<DeploymentImpl>,
@@ -81,6 +183,23 @@ pub async fn get_review_automation_logs(
     Ok(ResponseJson(ApiResponse::success(logs)))
 }
 
+/// Predict merge conflicts for a task's latest workspace without merging
+pub async fn get_task_merge_preview(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoMergePreview>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace = Workspace::fetch_all(pool, Some(task_id))
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ReviewAutomationError::NoWorkspaceContainer)?;
+
+    let preview =
+        ReviewAutomationService::merge_preview(pool, deployment.git(), &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(preview)))
+}
+
 pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .nest(
@@ -88,6 +207,7 @@ pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             Router::new()
                 .route("/enable", post(enable_review_automation))
                 .route("/disable", post(disable_review_automation))
+                .route("/settings", put(update_review_automation_settings))
                 .route("/status", get(get_review_automation_status))
                 .route("/logs", get(get_review_automation_logs)),
         )
@@ -95,4 +215,14 @@ pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/tasks/{task_id}/review-logs",
             get(get_review_automation_logs_by_task),
         )
+        .route(
+            "/tasks/{task_id}/review-logs/reset-conflicts",
+            post(reset_task_conflict_count),
+        )
+        .route("/tasks/{task_id}/merge-preview", get(get_task_merge_preview))
+        .route("/tasks/{task_id}/revert-merge", post(revert_task_merge))
+        .route(
+            "/review-logs/{log_id}/artifact",
+            get(get_review_automation_log_artifact),
+        )
 }
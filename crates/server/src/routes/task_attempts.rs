@@ -47,7 +47,7 @@ use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
     file_search::SearchQuery,
-    git::{ConflictOp, GitCliError, GitServiceError},
+    git::{ConflictOp, GitCliError, GitService, GitServiceError},
     workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
@@ -201,7 +201,7 @@ pub async fn create_task_attempt(
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
         .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
+        .git_branch_from_workspace(&attempt_id, &task)
         .await;
 
     let workspace = Workspace::create(
@@ -452,6 +452,7 @@ pub async fn merge_task_attempt(
         commit_message.push_str("\n\n");
         commit_message.push_str(description);
     }
+    let commit_message = GitService::with_task_id_trailer(&commit_message, task.id);
 
     let merge_commit_id = deployment.git().merge_changes(
         &repo.path,
@@ -467,9 +468,11 @@ pub async fn merge_task_attempt(
         workspace_repo.repo_id,
         &workspace_repo.target_branch,
         &merge_commit_id,
+        None,
     )
     .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    // The merge already happened, so record it whatever status the task is currently in
+    Task::update_status_force(pool, task.id, TaskStatus::Done, Some("user"), Some("Merged")).await?;
     if !workspace.pinned {
         Workspace::set_archived(pool, workspace.id, true).await?;
     }
@@ -8,6 +8,7 @@ use axum::{
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus},
+    project::Project,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
@@ -99,7 +100,23 @@ Analyze the changes in this branch and write:
    - Any important implementation details
    - At the end, include a note: "This PR was written using [Vibe Kanban](https://vibekanban.com)"
 
-Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps)."#;
+Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, glab mr update for GitLab, az repos pr update for Azure DevOps)."#;
+
+/// The git host provider configured for the workspace's project, if any
+async fn configured_provider(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<Option<ProviderKind>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    Ok(Project::find_by_id(pool, task.project_id)
+        .await?
+        .and_then(|project| project.git_host_provider)
+        .and_then(|provider| ProviderKind::from_setting(&provider)))
+}
 
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
@@ -280,7 +297,8 @@ pub async fn create_pr(
         }
     }
 
-    let git_host = match git_host::GitHostService::from_url(&target_remote_url) {
+    let configured = configured_provider(&deployment, &workspace).await?;
+    let git_host = match git_host::GitHostService::for_project(&target_remote_url, configured) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -420,7 +438,8 @@ pub async fn attach_existing_pr(
         &git.resolve_remote_name_for_branch(&repo.path, &workspace_repo.target_branch)?,
     )?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote_url) {
+    let configured = configured_provider(&deployment, &workspace).await?;
+    let git_host = match git_host::GitHostService::for_project(&remote_url, configured) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -480,9 +499,17 @@ pub async fn attach_existing_pr(
             .await?;
         }
 
-        // If PR is merged, mark task as done and archive workspace
+        // If PR is merged, mark task as done and archive workspace. The merge already happened,
+        // so record it whatever status the task is currently in.
         if matches!(pr_info.status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            Task::update_status_force(
+                pool,
+                task.id,
+                TaskStatus::Done,
+                Some("user"),
+                Some("Pull request merged"),
+            )
+            .await?;
             if !workspace.pinned {
                 Workspace::set_archived(pool, workspace.id, true).await?;
             }
@@ -540,7 +567,8 @@ pub async fn get_pr_comments(
         &git.resolve_remote_name_for_branch(&repo.path, &workspace_repo.target_branch)?,
     )?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote_url) {
+    let configured = configured_provider(&deployment, &workspace).await?;
+    let git_host = match git_host::GitHostService::for_project(&remote_url, configured) {
         Ok(host) => host,
         Err(GitHostError::CliNotInstalled { provider }) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -0,0 +1,88 @@
+//! Routes for human comments on a task, which the agent can include as feedback.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{
+    task::Task,
+    task_comment::{CreateTaskComment, TaskComment},
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// List a task's comments, oldest first
+pub async fn get_task_comments(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskComment>>>, ApiError> {
+    let comments = TaskComment::find_by_task_id(&deployment.db().pool, task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(comments)))
+}
+
+/// Add a comment to a task
+pub async fn create_task_comment(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<CreateTaskComment>,
+) -> Result<ResponseJson<ApiResponse<TaskComment>>, ApiError> {
+    if payload.content.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Comment content cannot be empty".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, task_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Task not found".to_string()))?;
+    let comment = TaskComment::create(pool, task.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_comment_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+/// Remove a comment from a task
+pub async fn delete_task_comment(
+    State(deployment): State<DeploymentImpl>,
+    Path((task_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let comment = TaskComment::find_by_id(pool, comment_id)
+        .await?
+        .filter(|comment| comment.task_id == task_id)
+        .ok_or_else(|| ApiError::NotFound("Comment not found".to_string()))?;
+    TaskComment::delete(pool, comment.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_comment_deleted",
+            serde_json::json!({ "task_id": task_id.to_string() }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().nest(
+        "/tasks/{task_id}/comments",
+        Router::new()
+            .route("/", get(get_task_comments).post(create_task_comment))
+            .route("/{comment_id}", delete(delete_task_comment)),
+    )
+}
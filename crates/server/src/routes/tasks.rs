@@ -1,10 +1,10 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -14,8 +14,13 @@ use axum::{
 };
 use db::models::{
     image::TaskImage,
+    merge::Merge,
     repo::{Repo, RepoError},
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    task::{
+        CreateTask, Task, TaskDetails, TaskStatus, TaskWithAttemptStatus, TaskWithImages,
+        UpdateTask,
+    },
+    task_status_history::TaskStatusHistory,
     workspace::{CreateWorkspace, Workspace},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
@@ -23,7 +28,12 @@ use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, workspace_manager::WorkspaceManager};
+use services::services::{
+    container::ContainerService,
+    review_automation::ReviewAutomationService,
+    task_similarity::{SimilarTask, rank_similar},
+    workspace_manager::WorkspaceManager,
+};
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -37,15 +47,20 @@ use crate::{
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskQuery {
     pub project_id: Uuid,
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
-    let tasks =
-        Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
-            .await?;
+    let tasks = Task::find_by_project_id_with_attempt_status(
+        &deployment.db().pool,
+        query.project_id,
+        query.include_archived,
+    )
+    .await?;
 
     Ok(ResponseJson(ApiResponse::success(tasks)))
 }
@@ -97,17 +112,37 @@ async fn handle_tasks_ws(
     Ok(())
 }
 
+/// The task with its attempt status, images, parent task, subtasks and dependencies
 pub async fn get_task(
     Extension(task): Extension<Task>,
-    State(_deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDetails>>, ApiError> {
+    let task = task.with_details(&deployment.db().pool).await?;
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// Merges recorded for any of the task's attempts, newest first
+pub async fn get_task_merges(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Merge>>>, ApiError> {
+    let merges = Merge::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(merges)))
+}
+
+/// Every status change of a task, oldest first
+pub async fn get_task_history(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskStatusHistory>>>, ApiError> {
+    let history = TaskStatusHistory::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(history)))
+}
+
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTask>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<TaskWithImages>>, ApiError> {
     let id = Uuid::new_v4();
 
     tracing::debug!(
@@ -134,6 +169,7 @@ pub async fn create_task(
         )
         .await;
 
+    let task = task.with_images(&deployment.db().pool).await?;
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -178,7 +214,7 @@ pub async fn create_task_and_start(
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
         .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
+        .git_branch_from_workspace(&attempt_id, &task)
         .await;
 
     // Compute agent_working_dir based on repo count:
@@ -250,7 +286,7 @@ pub async fn update_task(
     State(deployment): State<DeploymentImpl>,
 
     Json(payload): Json<UpdateTask>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<TaskWithImages>>, ApiError> {
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
     let description = match payload.description {
@@ -258,18 +294,30 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
-    let status = payload.status.unwrap_or(existing_task.status);
     let parent_workspace_id = payload
         .parent_workspace_id
         .or(existing_task.parent_workspace_id);
 
+    // Status changes go through the same transition rules and history as everything else
+    if let Some(status) = payload.status
+        && status != existing_task.status
+    {
+        Task::update_status(
+            &deployment.db().pool,
+            existing_task.id,
+            status,
+            Some("user"),
+            None,
+        )
+        .await?;
+    }
+
     let task = Task::update(
         &deployment.db().pool,
         existing_task.id,
         existing_task.project_id,
         title,
         description,
-        status,
         parent_workspace_id,
     )
     .await?;
@@ -279,6 +327,231 @@ pub async fn update_task(
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
+    let task = task.with_images(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetTaskArchivedRequest {
+    pub archived: bool,
+}
+
+pub async fn set_task_archived(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskArchivedRequest>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Task::set_archived(pool, task.id, payload.archived).await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_archived",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "archived": payload.archived,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SubmitForReviewRequest {
+    /// Run review automation now instead of waiting for its next poll
+    #[serde(default)]
+    pub run_review_now: bool,
+}
+
+/// Hand a task a human worked on to review automation: moves an InProgress task with a
+/// finished attempt to InReview
+pub async fn submit_task_for_review(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SubmitForReviewRequest>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if task.status != TaskStatus::InProgress {
+        return Err(ApiError::BadRequest(format!(
+            "Only in-progress tasks can be submitted for review (task is {})",
+            task.status
+        )));
+    }
+    if !Task::has_completed_attempt(pool, task.id).await? {
+        return Err(ApiError::BadRequest(
+            "Task has no completed attempt to review".to_string(),
+        ));
+    }
+
+    Task::update_status(
+        pool,
+        task.id,
+        TaskStatus::InReview,
+        Some("user"),
+        Some("Submitted for review"),
+    )
+    .await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+
+    if payload.run_review_now
+        && let Some(workspace) = Workspace::fetch_all(pool, Some(task.id))
+            .await?
+            .into_iter()
+            .find(|w| !w.archived)
+    {
+        let service = ReviewAutomationService::new(
+            deployment.db().clone(),
+            Arc::new(deployment.git().clone()),
+            deployment.container().notification_service().clone(),
+        );
+        let task = task.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.process_task(&task, &workspace).await {
+                tracing::warn!(task_id = %task.id, "Immediate review failed: {e}");
+            }
+        });
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_submitted_for_review",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "run_review_now": payload.run_review_now,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BlockTaskRequest {
+    pub reason: String,
+}
+
+/// Block a task on something outside the board, e.g. a missing third-party API key, so agent
+/// selection and stage timeouts leave it alone
+pub async fn block_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BlockTaskRequest>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A reason is required to block a task".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    Task::block(pool, task.id, reason, Some("user")).await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_blocked",
+            serde_json::json!({ "task_id": task.id.to_string() }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// Move a blocked task back to Todo
+pub async fn unblock_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Task::unblock(pool, task.id, Some("user")).await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_unblocked",
+            serde_json::json!({ "task_id": task.id.to_string() }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// Tasks automation gave up on, e.g. cancelled after repeated conflicts with a failed breakdown,
+/// and InProgress tasks stuck without an attempt
+pub async fn get_tasks_needing_attention(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<Task>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut tasks = Task::find_needing_attention(pool, project_id).await?;
+    // InProgress tasks with no attempt are stuck until the timeout service moves them back
+    for task in Task::find_inprogress_without_attempt(pool, project_id).await? {
+        if !tasks.iter().any(|t| t.id == task.id) {
+            tasks.push(task);
+        }
+    }
+    Ok(ResponseJson(ApiResponse::success(tasks)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct FindSimilarTasksRequest {
+    pub title: String,
+    pub description: Option<String>,
+    /// Maximum number of tasks to return (default 5)
+    pub limit: Option<usize>,
+}
+
+/// Existing unarchived tasks similar to a title and description, most similar first, so near
+/// duplicates can be spotted before a task is added
+pub async fn find_similar_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<FindSimilarTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<SimilarTask>>>, ApiError> {
+    let tasks =
+        Task::find_by_project_id_with_attempt_status(&deployment.db().pool, project_id, false)
+            .await?
+            .into_iter()
+            .map(|t| t.task)
+            .collect();
+    let similar = rank_similar(
+        &payload.title,
+        payload.description.as_deref(),
+        tasks,
+        payload.limit.unwrap_or(5).min(50),
+    );
+    Ok(ResponseJson(ApiResponse::success(similar)))
+}
+
+/// Dismiss a task's needs-attention flag once a human has dealt with it
+pub async fn clear_task_needs_attention(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Task::set_needs_attention(pool, task.id, None).await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_needs_attention_cleared",
+            serde_json::json!({ "task_id": task.id.to_string() }),
+        )
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -392,10 +665,17 @@ pub async fn delete_task(
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
-        .route("/", delete(delete_task));
+        .route("/", delete(delete_task))
+        .route("/archive", post(set_task_archived))
+        .route("/submit-for-review", post(submit_task_for_review))
+        .route("/block", post(block_task))
+        .route("/unblock", post(unblock_task))
+        .route("/needs-attention", delete(clear_task_needs_attention));
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/merges", get(get_task_merges))
+        .route("/history", get(get_task_history))
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
@@ -406,5 +686,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
-    Router::new().nest("/tasks", inner)
+    Router::new()
+        .nest("/tasks", inner)
+        .route(
+            "/projects/{project_id}/tasks/needs-attention",
+            get(get_tasks_needing_attention),
+        )
+        .route(
+            "/projects/{project_id}/tasks/find-similar",
+            post(find_similar_tasks),
+        )
 }
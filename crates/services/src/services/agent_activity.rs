@@ -1,34 +1,52 @@
 //! Service for autonomous task selection using AI analysis.
 
-use std::{path::Path, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use db::{
     DBService,
     models::{
         agent_activity::{
-            AgentAction, AgentActivityLog, AgentActivityStatus, AgentTriggerResponse,
-            ProjectAgentSettings,
+            AgentAction, AgentActivityLog, AgentActivityStatus, AgentSettingsUpdate,
+            AgentTriggerResponse, ProjectAgentSettings, SelectionFactors, SelectionReasoning,
+            UpdateAgentActivitySettings, WorkingDirStrategy,
         },
+        execution_process::ExecutionProcess,
         project_repo::ProjectRepo,
-        task::{CreateTask, Task, TaskLayer, TaskStatus, TaskType, TaskWithAttemptStatus},
+        task::{
+            CreateTask, Task, TaskError, TaskFailureContext, TaskLayer, TaskStatus, TaskType,
+            TaskWithAttemptStatus,
+        },
+        task_comment::TaskComment,
         workspace::{CreateWorkspace, Workspace},
         workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 use executors::profile::ExecutorProfileId;
+use futures::{StreamExt, future};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use thiserror::Error;
-use tokio::{sync::RwLock, time::interval};
+use tokio::{
+    sync::RwLock,
+    time::{interval, sleep},
+};
 use tracing::{debug, error, info, warn};
+use ts_rs::TS;
 use uuid::Uuid;
 
 use super::{
-    claude_api::{ClaudeApiClient, ClaudeApiError},
+    agent_activity_digest::{ActivityDigest, ActivityNotifier, DigestEvent},
+    claude_api::{ClaudeApiClient, ClaudeApiError, ClaudeHealth, ClaudeHealthStatus, ModelRole},
+    complexity_analyzer::{
+        ComplexityAnalysisResponse, ComplexityAnalyzer, ComplexityAnalyzerError,
+    },
     config::Config,
     git::GitService,
     notification::NotificationService,
+    project_concurrency::{DEFAULT_PROJECT_CONCURRENCY, process_concurrently},
+    prompts::{self, PromptTemplate, TemplateName},
+    quiet_hours::{self, QuietHours},
 };
 
 #[derive(Debug, Error)]
@@ -47,27 +65,97 @@ pub enum AgentActivityError {
     WorkspaceCreation(String),
     #[error("no repositories for project")]
     NoRepositories,
+    #[error("invalid settings: {0}")]
+    InvalidSettings(String),
+    #[error("task not found")]
+    TaskNotFound,
+    #[error("task is not in Todo")]
+    TaskNotTodo,
+    #[error("task has not been analyzed for complexity")]
+    ComplexityNotAnalyzed,
+    #[error(transparent)]
+    Task(#[from] TaskError),
+}
+
+impl From<ComplexityAnalyzerError> for AgentActivityError {
+    fn from(err: ComplexityAnalyzerError) -> Self {
+        match err {
+            ComplexityAnalyzerError::Database(e) => Self::Database(e),
+            ComplexityAnalyzerError::ClaudeApi(e) => Self::ClaudeApi(e),
+            ComplexityAnalyzerError::Task(e) => Self::Task(e),
+        }
+    }
 }
 
 /// Trait for starting workspaces - implemented by container services
 #[async_trait]
 pub trait WorkspaceStarter: Send + Sync {
     /// Generate a git branch name for a workspace
-    async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String;
+    async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task: &Task) -> String;
 
-    /// Start a workspace with the given executor profile
+    /// Start a workspace with the given executor profile. `prompt_context` is appended to the
+    /// task prompt sent to the coding agent.
     async fn start_workspace(
         &self,
         workspace: &Workspace,
         executor_profile_id: ExecutorProfileId,
+        prompt_context: Option<String>,
     ) -> Result<(), String>;
+
+    /// Stop all running processes for a workspace
+    async fn stop_workspace(&self, workspace_id: Uuid) -> Result<(), String>;
+}
+
+/// The task-selection prompt the agent would send, for debugging selection behaviour
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SelectionPromptPreview {
+    pub prompt: Option<String>,
+    pub system_prompt: Option<String>,
+    pub candidate_task_ids: Vec<Uuid>,
+    pub blocked_reason: Option<String>, // Why no prompt would be sent right now
+    pub prompt_version: Option<String>,
 }
 
-/// Response from AI task selection
+/// Response from AI task selection. Older prompt versions return freeform `reasoning`; newer
+/// ones return a summary, the runner-up and a factor breakdown instead.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct TaskSelectionResponse {
     task_id: String,
-    reasoning: String,
+    #[serde(default)]
+    reasoning: Option<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    runner_up_task_id: Option<String>,
+    #[serde(default)]
+    factors: Option<SelectionFactors>,
+}
+
+impl TaskSelectionResponse {
+    /// The reasoning text to log, plus structured reasoning when the AI returned it. A runner-up
+    /// that isn't another candidate is dropped.
+    fn into_reasoning(
+        self,
+        task_id: Uuid,
+        candidate_ids: &[Uuid],
+    ) -> (String, Option<SelectionReasoning>) {
+        let structured = self.summary.is_some() || self.factors.is_some();
+        let reasoning = self.summary.or(self.reasoning).unwrap_or_default();
+        if !structured {
+            return (reasoning, None);
+        }
+
+        let runner_up_task_id = self
+            .runner_up_task_id
+            .and_then(|id| Uuid::parse_str(&id).ok())
+            .filter(|id| *id != task_id && candidate_ids.contains(id));
+        let details = SelectionReasoning {
+            summary: reasoning.clone(),
+            runner_up_task_id,
+            factors: self.factors.unwrap_or_default(),
+        };
+        (reasoning, Some(details))
+    }
 }
 
 /// Task info sent to AI for selection
@@ -79,24 +167,15 @@ struct TaskInfo {
     layer: Option<String>,
     task_type: Option<String>,
     sequence: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    recent_comments: Vec<String>,
 }
 
-/// Response from AI complexity analysis
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ComplexityAnalysisResponse {
-    complexity_score: i32,
-    can_be_broken_down: bool,
-    reasoning: String,
-    subtasks: Option<Vec<SubtaskSuggestion>>,
-}
+/// Actor recorded in the status history of tasks this service moves
+const STATUS_ACTOR: &str = "agent_activity";
 
-/// Suggested subtask from AI complexity analysis
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SubtaskSuggestion {
-    title: String,
-    description: String,
-    layer: Option<String>,
-}
+/// How many of a task's most recent human comments are included in agent prompts
+const RECENT_COMMENTS_LIMIT: i64 = 5;
 
 /// Configuration for auto-attempt feature
 pub struct AutoAttemptConfig {
@@ -111,20 +190,26 @@ pub struct AgentActivityService {
     notification_service: NotificationService,
     poll_interval: Duration,
     auto_attempt: Option<AutoAttemptConfig>,
+    digest: ActivityDigest,
+    project_concurrency: usize,
 }
 
 impl AgentActivityService {
-    /// Spawn the background agent activity service
+    /// Spawn the background agent activity service, checking up to `project_concurrency`
+    /// projects at once
     pub async fn spawn(
         db: DBService,
         notification_service: NotificationService,
         auto_attempt: Option<AutoAttemptConfig>,
+        project_concurrency: usize,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
             notification_service,
             poll_interval: Duration::from_secs(10), // Check every 10 seconds for faster response
             auto_attempt,
+            digest: ActivityDigest::new(),
+            project_concurrency,
         };
         tokio::spawn(async move {
             service.start().await;
@@ -137,9 +222,21 @@ impl AgentActivityService {
             self.poll_interval
         );
 
-        // Check if ANTHROPIC_API_KEY is set at startup
-        if std::env::var("ANTHROPIC_API_KEY").is_err() {
-            warn!("ANTHROPIC_API_KEY not set - agent activity AI task selection will fail");
+        // Check the Anthropic key works before the first cycle needs it
+        let health = ClaudeHealth::check().await;
+        match health.status {
+            ClaudeHealthStatus::Ok => info!("Claude API key verified"),
+            ClaudeHealthStatus::MissingKey => {
+                warn!("ANTHROPIC_API_KEY not set - agent activity AI task selection will fail")
+            }
+            ClaudeHealthStatus::InvalidKey => error!(
+                error = health.error.as_deref().unwrap_or_default(),
+                "ANTHROPIC_API_KEY was rejected - agent activity AI task selection will fail"
+            ),
+            ClaudeHealthStatus::Unreachable => error!(
+                error = health.error.as_deref().unwrap_or_default(),
+                "Could not reach the Claude API - agent activity AI task selection may fail"
+            ),
         }
 
         let mut interval = interval(self.poll_interval);
@@ -149,6 +246,7 @@ impl AgentActivityService {
             if let Err(e) = self.check_all_enabled_projects().await {
                 error!("Error checking enabled projects for agent activity: {}", e);
             }
+            self.digest.flush_due(&self.notification_service).await;
         }
     }
 
@@ -166,15 +264,43 @@ impl AgentActivityService {
             enabled_projects.len()
         );
 
-        for settings in enabled_projects {
-            match Self::check_and_select_next_task(
-                &self.db.pool,
-                &self.notification_service,
-                settings.project_id,
-                self.auto_attempt.as_ref(),
-            )
-            .await
-            {
+        let now = chrono::Utc::now();
+        let active_projects = enabled_projects.into_iter().filter(|settings| {
+            let quiet = quiet_hours::is_quiet(Some(settings), now);
+            if quiet {
+                debug!(
+                    project_id = %settings.project_id,
+                    "Agent activity: quiet hours, skipping selection"
+                );
+            }
+            !quiet
+        });
+
+        process_concurrently(
+            active_projects,
+            self.project_concurrency,
+            |settings| async move {
+                let notifier = match settings.digest_window_minutes {
+                    Some(minutes) if minutes > 0 => ActivityNotifier::digest(
+                        &self.notification_service,
+                        &self.digest,
+                        Duration::from_secs(minutes as u64 * 60),
+                    ),
+                    _ => ActivityNotifier::immediate(&self.notification_service),
+                };
+
+                let result = Self::check_and_select_next_task(
+                    &self.db.pool,
+                    &notifier,
+                    settings.project_id,
+                    self.auto_attempt.as_ref(),
+                )
+                .await;
+                (settings, result)
+            },
+        )
+        .for_each(|(settings, result)| {
+            match result {
                 Ok(response) => {
                     if response.action == AgentAction::Selected {
                         info!(
@@ -207,183 +333,195 @@ impl AgentActivityService {
                     );
                 }
             }
-        }
+            future::ready(())
+        })
+        .await;
 
         Ok(())
     }
 }
 
 impl AgentActivityService {
-    /// Get layers that already have running non-Integration tasks
-    /// (layers with InProgress or InReview tasks that are NOT Integration type)
-    fn get_active_layers(tasks: &[TaskWithAttemptStatus]) -> Vec<TaskLayer> {
+    /// Whether a task runs alone instead of concurrently by layer. Integration tasks do unless
+    /// the project has turned off `integration_blocks_all`.
+    fn runs_exclusively(task_type: &Option<TaskType>, integration_blocks_all: bool) -> bool {
+        integration_blocks_all && *task_type == Some(TaskType::Integration)
+    }
+
+    /// The layer a task counts as for concurrency: its own, or the project's default layer when
+    /// it has none. Only selection uses this; the stored task keeps its missing layer.
+    fn effective_layer<'a>(
+        layer: Option<&'a TaskLayer>,
+        default_layer: Option<&'a TaskLayer>,
+    ) -> Option<&'a TaskLayer> {
+        layer.or(default_layer)
+    }
+
+    /// Get layers that already have running layered tasks
+    /// (layers with InProgress or InReview tasks that don't run exclusively)
+    fn get_active_layers(
+        tasks: &[TaskWithAttemptStatus],
+        integration_blocks_all: bool,
+        default_layer: Option<&TaskLayer>,
+    ) -> Vec<TaskLayer> {
         tasks
             .iter()
             .filter(|t| {
-                t.task_type != Some(TaskType::Integration)
+                !Self::runs_exclusively(&t.task_type, integration_blocks_all)
                     && (t.status == TaskStatus::InProgress || t.status == TaskStatus::InReview)
             })
-            .filter_map(|t| t.layer.clone())
+            .filter_map(|t| Self::effective_layer(t.layer.as_ref(), default_layer).cloned())
             .collect()
     }
 
-    /// Check if there's an active Integration task
-    fn has_active_integration_task(tasks: &[TaskWithAttemptStatus]) -> bool {
+    /// Check if there's an active Integration task that blocks everything else
+    fn has_active_integration_task(
+        tasks: &[TaskWithAttemptStatus],
+        integration_blocks_all: bool,
+    ) -> bool {
         tasks.iter().any(|t| {
-            t.task_type == Some(TaskType::Integration)
+            Self::runs_exclusively(&t.task_type, integration_blocks_all)
                 && (t.status == TaskStatus::InProgress || t.status == TaskStatus::InReview)
         })
     }
 
-    /// Main entry point: check conditions and select next task if applicable
+    /// Main entry point: check conditions and select next task if applicable.
+    /// Runs in an `agent_activity_cycle` span so every log line of one cycle can be correlated.
+    #[tracing::instrument(
+        name = "agent_activity_cycle",
+        skip_all,
+        fields(project_id = %project_id, task_id = tracing::field::Empty)
+    )]
     pub async fn check_and_select_next_task(
         pool: &SqlitePool,
-        notification_service: &NotificationService,
+        notifier: &ActivityNotifier<'_>,
         project_id: Uuid,
         auto_attempt: Option<&AutoAttemptConfig>,
     ) -> Result<AgentTriggerResponse, AgentActivityError> {
         // Get all tasks for the project to check status
-        let all_tasks = Task::find_by_project_id_with_attempt_status(pool, project_id).await?;
+        let all_tasks =
+            Task::find_by_project_id_with_attempt_status(pool, project_id, true).await?;
+
+        let agent_settings = ProjectAgentSettings::find_by_project_id(pool, project_id).await?;
+        let auto_breakdown_fullstack = agent_settings
+            .as_ref()
+            .is_none_or(|s| s.auto_breakdown_fullstack);
 
         // First, check for any Fullstack tasks that need to be broken down
-        for task in all_tasks.iter() {
-            if task.status == TaskStatus::Todo && task.layer == Some(TaskLayer::Fullstack) {
-                if let Some(task_full) = Task::find_by_id(pool, task.id).await? {
-                    info!(
-                        task_id = %task.id,
-                        "Agent activity: breaking down Fullstack task into layers"
-                    );
-                    if let Ok(created_count) =
-                        Self::breakdown_fullstack_task(pool, &task_full, project_id).await
-                    {
-                        if created_count > 0 {
-                            AgentActivityLog::create(
-                                pool,
-                                project_id,
-                                Some(task.id),
-                                AgentAction::Replaced,
-                                Some(format!(
-                                    "Fullstack task broken into {} layer-specific subtasks",
-                                    created_count
-                                )),
-                            )
-                            .await?;
-
-                            notification_service
-                                .notify(
-                                    "Task Breakdown",
-                                    &format!(
-                                        "Fullstack task '{}' split into {} subtasks",
-                                        task.title, created_count
-                                    ),
+        if auto_breakdown_fullstack {
+            for task in all_tasks.iter() {
+                if task.status == TaskStatus::Todo && task.layer == Some(TaskLayer::Fullstack) {
+                    if let Some(task_full) = Task::find_by_id(pool, task.id).await? {
+                        info!(
+                            task_id = %task.id,
+                            "Agent activity: breaking down Fullstack task into layers"
+                        );
+                        if let Ok(created_count) =
+                            Self::breakdown_fullstack_task(pool, &task_full, project_id).await
+                        {
+                            if created_count > 0 {
+                                AgentActivityLog::create(
+                                    pool,
+                                    project_id,
+                                    Some(task.id),
+                                    AgentAction::Replaced,
+                                    Some(format!(
+                                        "Fullstack task broken into {} layer-specific subtasks",
+                                        created_count
+                                    )),
                                 )
-                                .await;
-
-                            return Ok(AgentTriggerResponse {
-                                action: AgentAction::Replaced,
-                                task_id: Some(task.id),
-                                reasoning: Some(format!(
-                                    "Fullstack task broken into {} layer-specific subtasks",
-                                    created_count
-                                )),
-                            });
+                                .await?;
+
+                                notifier
+                                    .notify(
+                                        project_id,
+                                        DigestEvent::BrokenDown,
+                                        "Task Breakdown",
+                                        &format!(
+                                            "Fullstack task '{}' split into {} subtasks",
+                                            task.title, created_count
+                                        ),
+                                    )
+                                    .await;
+
+                                return Ok(AgentTriggerResponse {
+                                    action: AgentAction::Replaced,
+                                    task_id: Some(task.id),
+                                    reasoning: Some(format!(
+                                        "Fullstack task broken into {} layer-specific subtasks",
+                                        created_count
+                                    )),
+                                });
+                            }
                         }
                     }
                 }
             }
         }
 
-        // Get active layers (layers with InProgress/InReview non-Integration tasks)
-        let active_layers = Self::get_active_layers(&all_tasks);
-        let active_layer_count = active_layers.len();
-        let has_active_integration = Self::has_active_integration_task(&all_tasks);
-
-        // Concurrency rules:
-        // 1. Non-Integration tasks can run concurrently by layer (up to 3: Frontend, Backend, Data)
-        // 2. Integration tasks run sequentially (only when nothing else is in progress)
-        // 3. Mock tasks take priority over Implementation tasks
-        // 4. If an Integration task is active, block everything else
+        let max_concurrent_layers = agent_settings
+            .as_ref()
+            .map_or(3, |s| s.max_concurrent_layers) as usize;
+        let integration_blocks_all = agent_settings
+            .as_ref()
+            .is_none_or(|s| s.integration_blocks_all);
+        let default_layer = agent_settings
+            .as_ref()
+            .and_then(|s| s.default_layer.clone());
+
+        // Host-wide limit: don't pick up new work while too many attempts are already running
+        if let Some(auto_attempt_config) = auto_attempt
+            && let Some(reason) = Self::attempt_capacity_reached(pool, auto_attempt_config).await?
+        {
+            info!(project_id = %project_id, "Agent activity: {}", reason);
+            AgentActivityLog::create(
+                pool,
+                project_id,
+                None,
+                AgentAction::Skipped,
+                Some(reason.clone()),
+            )
+            .await?;
 
-        // If there's an active Integration task, block all new tasks
-        if has_active_integration {
-            return Err(AgentActivityError::TaskAlreadyInProgress);
+            return Ok(AgentTriggerResponse {
+                action: AgentAction::Skipped,
+                task_id: None,
+                reasoning: Some(reason),
+            });
         }
 
-        // Check for available non-Integration tasks that can run (in a layer not already active)
-        let has_available_layered_task = all_tasks.iter().any(|t| {
-            t.status == TaskStatus::Todo
-                && t.task_type != Some(TaskType::Integration)
-                && t.layer
-                    .as_ref()
-                    .map(|l| !active_layers.contains(l))
-                    .unwrap_or(false) // Must have a layer for concurrent execution
-        });
-
-        // Check if there's any active task
-        let has_any_active_task = all_tasks
-            .iter()
-            .any(|t| t.status == TaskStatus::InProgress || t.status == TaskStatus::InReview);
-
-        let tasks: Vec<TaskWithAttemptStatus> = if has_available_layered_task && active_layer_count < 3 {
-            // Can start a non-Integration task in an available layer
-            info!(
-                project_id = %project_id,
-                active_layer_count = active_layer_count,
-                "Agent activity: selecting layered task (concurrent by layer allowed)"
+        // Project-wide limit on active tasks, on top of the layer rules
+        let max_in_progress_tasks = agent_settings
+            .as_ref()
+            .map_or(0, |s| s.max_in_progress_tasks);
+        if Self::in_progress_cap_reached(&all_tasks, max_in_progress_tasks) {
+            let reason = format!(
+                "{max_in_progress_tasks} tasks already in progress (max_in_progress_tasks)"
             );
+            info!(project_id = %project_id, "Agent activity: {}", reason);
+            AgentActivityLog::create(
+                pool,
+                project_id,
+                None,
+                AgentAction::Skipped,
+                Some(reason.clone()),
+            )
+            .await?;
 
-            // Get all eligible non-Integration tasks in available layers
-            let eligible: Vec<TaskWithAttemptStatus> = all_tasks
-                .clone()
-                .into_iter()
-                .filter(|t| {
-                    t.status == TaskStatus::Todo
-                        && t.task_type != Some(TaskType::Integration)
-                        && t.layer
-                            .as_ref()
-                            .map(|l| !active_layers.contains(l))
-                            .unwrap_or(false)
-                })
-                .collect();
-
-            // Prioritize: Architecture > Implementation
-            let has_arch = eligible.iter().any(|t| t.task_type == Some(TaskType::Architecture));
+            return Ok(AgentTriggerResponse {
+                action: AgentAction::Skipped,
+                task_id: None,
+                reasoning: Some(reason),
+            });
+        }
 
-            if has_arch {
-                eligible.into_iter().filter(|t| t.task_type == Some(TaskType::Architecture)).collect()
-            } else {
-                eligible
-            }
-        } else if has_any_active_task {
-            // Something is active and we can't start more layered tasks - block
-            return Err(AgentActivityError::TaskAlreadyInProgress);
-        } else {
-            // Nothing active - can start any todo task
-            // Priority: Sequence 1 (init) > Architecture > Mock > Implementation > Integration
-            let todo_tasks: Vec<TaskWithAttemptStatus> = all_tasks
-                .into_iter()
-                .filter(|t| t.status == TaskStatus::Todo)
-                .collect();
-
-            // CRITICAL: Initialization tasks (sequence=1) have highest priority
-            // These set up the project to be runnable
-            let has_init = todo_tasks.iter().any(|t| t.sequence == Some(1));
-            let has_arch = todo_tasks.iter().any(|t| t.task_type == Some(TaskType::Architecture));
-            let has_impl = todo_tasks.iter().any(|t| t.task_type == Some(TaskType::Implementation));
-
-            if has_init {
-                // Sequence 1 tasks are initialization - do these first!
-                todo_tasks.into_iter().filter(|t| t.sequence == Some(1)).collect()
-            } else if has_arch {
-                // Architecture tasks set up structure
-                todo_tasks.into_iter().filter(|t| t.task_type == Some(TaskType::Architecture)).collect()
-            } else if has_impl {
-                todo_tasks.into_iter().filter(|t| t.task_type == Some(TaskType::Implementation)).collect()
-            } else {
-                // Only Integration tasks left
-                todo_tasks
-            }
-        };
+        let tasks = Self::eligible_tasks(
+            project_id,
+            all_tasks,
+            max_concurrent_layers,
+            integration_blocks_all,
+            default_layer.as_ref(),
+        )?;
 
         if tasks.is_empty() {
             AgentActivityLog::create(
@@ -409,11 +547,42 @@ impl AgentActivityService {
         );
 
         // Use AI to select the best task
-        match Self::select_task_with_ai(&tasks).await {
-            Ok((task_id, reasoning)) => {
-                let task = Task::find_by_id(pool, task_id)
-                    .await?
-                    .ok_or(AgentActivityError::NoTasksAvailable)?;
+        let template = prompts::active(pool, project_id, TemplateName::TaskSelection).await?;
+        let comments =
+            Self::recent_comments_for_tasks(pool, &tasks, agent_settings.as_ref()).await?;
+        let max_description_chars = Self::max_description_chars(agent_settings.as_ref());
+        let system_prefix = prompts::system_prompt_prefix(pool, project_id).await?;
+        match Self::select_task_with_ai(
+            template,
+            &tasks,
+            &comments,
+            max_description_chars,
+            system_prefix.as_deref(),
+        )
+        .await
+        {
+            Ok((chosen_id, reasoning, reasoning_details)) => {
+                let Some((task, reasoning, reasoning_details)) =
+                    Self::confirm_selection(pool, &tasks, chosen_id, reasoning, reasoning_details)
+                        .await?
+                else {
+                    let reason = "Every candidate was taken while the AI was selecting".to_string();
+                    AgentActivityLog::create(
+                        pool,
+                        project_id,
+                        None,
+                        AgentAction::Skipped,
+                        Some(reason.clone()),
+                    )
+                    .await?;
+                    return Ok(AgentTriggerResponse {
+                        action: AgentAction::Skipped,
+                        task_id: None,
+                        reasoning: Some(reason),
+                    });
+                };
+                let task_id = task.id;
+                tracing::Span::current().record("task_id", tracing::field::display(task_id));
 
                 // Check complexity (skip for subtasks and tasks with prevent_breakdown flag)
                 if task.complexity_score.is_none()
@@ -424,7 +593,8 @@ impl AgentActivityService {
                         pool,
                         &task,
                         project_id,
-                        notification_service,
+                        agent_settings.as_ref(),
+                        notifier,
                     )
                     .await
                     {
@@ -449,30 +619,70 @@ impl AgentActivityService {
                     }
                 }
 
-                Task::update_status(pool, task_id, TaskStatus::InProgress).await?;
+                Task::update_status(
+                    pool,
+                    task_id,
+                    TaskStatus::InProgress,
+                    Some(STATUS_ACTOR),
+                    Some(reasoning.as_str()),
+                )
+                .await?;
 
-                AgentActivityLog::create(
+                AgentActivityLog::create_with_details(
                     pool,
                     project_id,
                     Some(task_id),
                     AgentAction::Selected,
                     Some(reasoning.clone()),
+                    reasoning_details,
+                    Some(template.label()),
                 )
                 .await?;
 
-                notification_service
-                    .notify("Task Selected", &format!("Starting: {}", task.title))
+                notifier
+                    .notify(
+                        project_id,
+                        DigestEvent::Selected,
+                        "Task Selected",
+                        &format!("Starting: {}", task.title),
+                    )
                     .await;
 
-                if let Some(auto_attempt_config) = auto_attempt {
-                    if let Err(e) =
-                        Self::auto_start_attempt(pool, &task, project_id, auto_attempt_config).await
+                // The score may have been stored by the complexity analysis above
+                let complexity_score = Task::find_by_id(pool, task_id)
+                    .await?
+                    .and_then(|t| t.complexity_score);
+                if auto_attempt.is_some()
+                    && !Self::meets_auto_attempt_threshold(complexity_score, agent_settings.as_ref())
+                {
+                    info!(
+                        task_id = %task_id,
+                        complexity_score = ?complexity_score,
+                        "Task below auto-attempt complexity threshold, leaving for manual start"
+                    );
+                } else if let Some(auto_attempt_config) = auto_attempt {
+                    if let Err(e) = Self::auto_start_attempt(
+                        pool,
+                        &task,
+                        project_id,
+                        auto_attempt_config,
+                        agent_settings.as_ref(),
+                    )
+                    .await
                     {
                         warn!(
                             task_id = %task_id,
                             error = %e,
                             "Failed to auto-start attempt for task"
                         );
+                        Self::revert_failed_auto_start(pool, task_id, project_id, &e).await?;
+                        return Ok(AgentTriggerResponse {
+                            action: AgentAction::Skipped,
+                            task_id: Some(task_id),
+                            reasoning: Some(format!(
+                                "Failed to start attempt, moved back to Todo: {e}"
+                            )),
+                        });
                     } else {
                         info!(task_id = %task_id, "Auto-started attempt for selected task");
                     }
@@ -499,6 +709,317 @@ impl AgentActivityService {
         }
     }
 
+    /// Start a specific Todo task next, bypassing AI selection and priority ordering.
+    /// Layer concurrency rules and the in-progress cap still apply.
+    pub async fn select_specific_task(
+        pool: &SqlitePool,
+        notifier: &ActivityNotifier<'_>,
+        project_id: Uuid,
+        task_id: Uuid,
+        auto_attempt: Option<&AutoAttemptConfig>,
+    ) -> Result<AgentTriggerResponse, AgentActivityError> {
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .filter(|t| t.project_id == project_id)
+            .ok_or(AgentActivityError::TaskNotFound)?;
+        if task.status != TaskStatus::Todo {
+            return Err(AgentActivityError::TaskNotTodo);
+        }
+
+        let all_tasks =
+            Task::find_by_project_id_with_attempt_status(pool, project_id, true).await?;
+        let agent_settings = ProjectAgentSettings::find_by_project_id(pool, project_id).await?;
+        let max_concurrent_layers = agent_settings
+            .as_ref()
+            .map_or(3, |s| s.max_concurrent_layers) as usize;
+        let integration_blocks_all = agent_settings
+            .as_ref()
+            .is_none_or(|s| s.integration_blocks_all);
+        let default_layer = agent_settings
+            .as_ref()
+            .and_then(|s| s.default_layer.clone());
+        let max_in_progress_tasks = agent_settings
+            .as_ref()
+            .map_or(0, |s| s.max_in_progress_tasks);
+        if Self::in_progress_cap_reached(&all_tasks, max_in_progress_tasks)
+            || !Self::can_start_now(
+                task_id,
+                &all_tasks,
+                max_concurrent_layers,
+                integration_blocks_all,
+                default_layer.as_ref(),
+            )
+        {
+            return Err(AgentActivityError::TaskAlreadyInProgress);
+        }
+
+        let reasoning = "manual override".to_string();
+        Task::update_status(
+            pool,
+            task_id,
+            TaskStatus::InProgress,
+            Some("user"),
+            Some(reasoning.as_str()),
+        )
+        .await?;
+
+        AgentActivityLog::create(
+            pool,
+            project_id,
+            Some(task_id),
+            AgentAction::Selected,
+            Some(reasoning.clone()),
+        )
+        .await?;
+
+        notifier
+            .notify(
+                project_id,
+                DigestEvent::Selected,
+                "Task Selected",
+                &format!("Starting: {}", task.title),
+            )
+            .await;
+
+        if let Some(auto_attempt_config) = auto_attempt {
+            if let Err(e) = Self::auto_start_attempt(
+                pool,
+                &task,
+                project_id,
+                auto_attempt_config,
+                agent_settings.as_ref(),
+            )
+            .await
+            {
+                warn!(
+                    task_id = %task_id,
+                    error = %e,
+                    "Failed to auto-start attempt for manually selected task"
+                );
+                Self::revert_failed_auto_start(pool, task_id, project_id, &e).await?;
+                return Err(e);
+            } else {
+                info!(task_id = %task_id, "Auto-started attempt for manually selected task");
+            }
+        }
+
+        Ok(AgentTriggerResponse {
+            action: AgentAction::Selected,
+            task_id: Some(task_id),
+            reasoning: Some(reasoning),
+        })
+    }
+
+    /// If the configured `max_concurrent_attempts` is reached, the reason to skip selection
+    async fn attempt_capacity_reached(
+        pool: &SqlitePool,
+        auto_attempt: &AutoAttemptConfig,
+    ) -> Result<Option<String>, AgentActivityError> {
+        let Some(max_attempts) = auto_attempt.config.read().await.max_concurrent_attempts else {
+            return Ok(None);
+        };
+
+        let running = ExecutionProcess::count_workspaces_with_running_processes(pool).await?;
+        if running < i64::from(max_attempts) {
+            return Ok(None);
+        }
+
+        Ok(Some(format!(
+            "At capacity: {running} attempts running across all projects (max {max_attempts})"
+        )))
+    }
+
+    /// Whether the layer concurrency rules allow the task to start alongside the active tasks.
+    /// These are the rules [`Self::eligible_tasks`] applies before its priority ordering.
+    fn can_start_now(
+        task_id: Uuid,
+        all_tasks: &[TaskWithAttemptStatus],
+        max_concurrent_layers: usize,
+        integration_blocks_all: bool,
+        default_layer: Option<&TaskLayer>,
+    ) -> bool {
+        Self::startable_tasks(
+            all_tasks,
+            max_concurrent_layers,
+            integration_blocks_all,
+            default_layer,
+        )
+        .is_ok_and(|tasks| tasks.iter().any(|t| t.id == task_id))
+    }
+
+    /// Whether `max_in_progress_tasks` unarchived tasks are already InProgress or InReview,
+    /// whatever their layer. 0 means no cap.
+    fn in_progress_cap_reached(
+        all_tasks: &[TaskWithAttemptStatus],
+        max_in_progress_tasks: i32,
+    ) -> bool {
+        let active = all_tasks
+            .iter()
+            .filter(|t| {
+                !t.archived && matches!(t.status, TaskStatus::InProgress | TaskStatus::InReview)
+            })
+            .count();
+        max_in_progress_tasks > 0 && active >= max_in_progress_tasks as usize
+    }
+
+    /// Narrow the project's tasks down to the candidates the AI may choose from,
+    /// applying the layer concurrency and priority rules
+    fn eligible_tasks(
+        project_id: Uuid,
+        all_tasks: Vec<TaskWithAttemptStatus>,
+        max_concurrent_layers: usize,
+        integration_blocks_all: bool,
+        default_layer: Option<&TaskLayer>,
+    ) -> Result<Vec<TaskWithAttemptStatus>, AgentActivityError> {
+        let startable = Self::startable_tasks(
+            &all_tasks,
+            max_concurrent_layers,
+            integration_blocks_all,
+            default_layer,
+        )?;
+
+        // Priority rules, on top of the concurrency rules in `startable_tasks`:
+        // 1. Tasks that can run concurrently by layer come first
+        // 2. Among those, Architecture tasks take priority over Implementation tasks
+        // 3. With nothing layered to start, Sequence 1 (init) > Architecture > Implementation >
+        //    Testing > Integration
+        let active_layers =
+            Self::get_active_layers(&all_tasks, integration_blocks_all, default_layer);
+        let layered: Vec<TaskWithAttemptStatus> = startable
+            .iter()
+            .filter(|t| {
+                Self::runs_in_free_layer(t, &active_layers, integration_blocks_all, default_layer)
+            })
+            .cloned()
+            .collect();
+
+        let tasks: Vec<TaskWithAttemptStatus> =
+            if !layered.is_empty() && active_layers.len() < max_concurrent_layers {
+                // Can start a non-Integration task in an available layer
+                info!(
+                    project_id = %project_id,
+                    active_layer_count = active_layers.len(),
+                    "Agent activity: selecting layered task (concurrent by layer allowed)"
+                );
+
+                // Prioritize: Architecture > Implementation
+                let has_arch = layered
+                    .iter()
+                    .any(|t| t.task_type == Some(TaskType::Architecture));
+
+                if has_arch {
+                    layered
+                        .into_iter()
+                        .filter(|t| t.task_type == Some(TaskType::Architecture))
+                        .collect()
+                } else {
+                    layered
+                }
+            } else {
+                // Nothing active - can start any todo task
+                // Priority: Sequence 1 (init) > Architecture > Implementation > Testing > Integration
+                let todo_tasks = startable;
+
+                // CRITICAL: Initialization tasks (sequence=1) have highest priority
+                // These set up the project to be runnable
+                let has_init = todo_tasks.iter().any(|t| t.sequence == Some(1));
+                let has_arch = todo_tasks
+                    .iter()
+                    .any(|t| t.task_type == Some(TaskType::Architecture));
+                let has_impl = todo_tasks
+                    .iter()
+                    .any(|t| t.task_type == Some(TaskType::Implementation));
+                let has_testing = todo_tasks
+                    .iter()
+                    .any(|t| t.task_type == Some(TaskType::Testing));
+
+                if has_init {
+                    // Sequence 1 tasks are initialization - do these first!
+                    todo_tasks
+                        .into_iter()
+                        .filter(|t| t.sequence == Some(1))
+                        .collect()
+                } else if has_arch {
+                    // Architecture tasks set up structure
+                    todo_tasks
+                        .into_iter()
+                        .filter(|t| t.task_type == Some(TaskType::Architecture))
+                        .collect()
+                } else if has_impl {
+                    todo_tasks
+                        .into_iter()
+                        .filter(|t| t.task_type == Some(TaskType::Implementation))
+                        .collect()
+                } else if has_testing {
+                    todo_tasks
+                        .into_iter()
+                        .filter(|t| t.task_type == Some(TaskType::Testing))
+                        .collect()
+                } else {
+                    // Only Integration tasks left
+                    todo_tasks
+                }
+            };
+
+        Ok(tasks)
+    }
+
+    /// The Todo tasks the layer concurrency rules allow to start now, before any priority
+    /// ordering. Errors with `TaskAlreadyInProgress` when the active tasks block every start.
+    fn startable_tasks(
+        all_tasks: &[TaskWithAttemptStatus],
+        max_concurrent_layers: usize,
+        integration_blocks_all: bool,
+        default_layer: Option<&TaskLayer>,
+    ) -> Result<Vec<TaskWithAttemptStatus>, AgentActivityError> {
+        // Concurrency rules:
+        // 1. Non-Integration tasks can run concurrently by layer (up to max_concurrent_layers, default 3)
+        // 2. Integration tasks run sequentially (only when nothing else is in progress)
+        // 3. If an Integration task is active, block everything else
+        // With integration_blocks_all off, Integration tasks follow the layer rules (1) instead
+        if Self::has_active_integration_task(all_tasks, integration_blocks_all) {
+            return Err(AgentActivityError::TaskAlreadyInProgress);
+        }
+
+        let todo = all_tasks.iter().filter(|t| t.status == TaskStatus::Todo);
+        let has_any_active_task = all_tasks
+            .iter()
+            .any(|t| t.status == TaskStatus::InProgress || t.status == TaskStatus::InReview);
+        if !has_any_active_task {
+            return Ok(todo.cloned().collect());
+        }
+
+        let active_layers =
+            Self::get_active_layers(all_tasks, integration_blocks_all, default_layer);
+        let startable: Vec<TaskWithAttemptStatus> = if active_layers.len() < max_concurrent_layers {
+            todo.filter(|t| {
+                Self::runs_in_free_layer(t, &active_layers, integration_blocks_all, default_layer)
+            })
+            .cloned()
+            .collect()
+        } else {
+            Vec::new()
+        };
+        if startable.is_empty() {
+            // Something is active and no more layered tasks can start - block
+            return Err(AgentActivityError::TaskAlreadyInProgress);
+        }
+        Ok(startable)
+    }
+
+    /// Whether a task can run alongside the active tasks: it isn't exclusive and has a layer
+    /// (or a default) that no active task occupies
+    fn runs_in_free_layer(
+        task: &Task,
+        active_layers: &[TaskLayer],
+        integration_blocks_all: bool,
+        default_layer: Option<&TaskLayer>,
+    ) -> bool {
+        !Self::runs_exclusively(&task.task_type, integration_blocks_all)
+            && Self::effective_layer(task.layer.as_ref(), default_layer)
+                .is_some_and(|l| !active_layers.contains(l))
+    }
+
     /// Break down a Fullstack task into Frontend, Backend, and Data subtasks
     async fn breakdown_fullstack_task(
         pool: &SqlitePool,
@@ -534,144 +1055,97 @@ impl AgentActivityService {
             created_count += 1;
         }
 
-        // Cancel the original Fullstack task
-        Task::update_status(pool, task.id, TaskStatus::Cancelled).await?;
+        // Cancel the original Fullstack task and hide it from the board
+        Task::update_status(
+            pool,
+            task.id,
+            TaskStatus::Cancelled,
+            Some(STATUS_ACTOR),
+            Some("Broken down by layer"),
+        )
+        .await?;
+        Task::set_archived(pool, task.id, true).await?;
 
         Ok(created_count)
     }
 
+    /// Analyze a task's complexity on demand without breaking it down
+    pub async fn analyze_task_complexity(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<ComplexityAnalysisResponse, AgentActivityError> {
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(AgentActivityError::TaskNotFound)?;
+        let agent_settings =
+            ProjectAgentSettings::find_by_project_id(pool, task.project_id).await?;
+        Ok(ComplexityAnalyzer::new(pool.clone())?
+            .with_max_description_chars(Self::max_description_chars(agent_settings.as_ref()))
+            .analyze(&task)
+            .await?)
+    }
+
+    /// The task's stored complexity analysis. When there is none, runs a fresh analysis if
+    /// `analyze` is set and fails with `ComplexityNotAnalyzed` otherwise.
+    pub async fn task_complexity(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        analyze: bool,
+    ) -> Result<ComplexityAnalysisResponse, AgentActivityError> {
+        if Task::find_by_id(pool, task_id).await?.is_none() {
+            return Err(AgentActivityError::TaskNotFound);
+        }
+        if let Some(analysis) = ComplexityAnalyzer::stored_analysis(pool, task_id).await? {
+            return Ok(analysis);
+        }
+        if !analyze {
+            return Err(AgentActivityError::ComplexityNotAnalyzed);
+        }
+        Self::analyze_task_complexity(pool, task_id).await
+    }
+
     /// Analyze task complexity using AI and break down if needed
     /// Returns Some(count) if task was broken down, None otherwise
     async fn analyze_complexity_and_maybe_breakdown(
         pool: &SqlitePool,
         task: &Task,
         project_id: Uuid,
-        notification_service: &NotificationService,
+        agent_settings: Option<&ProjectAgentSettings>,
+        notifier: &ActivityNotifier<'_>,
     ) -> Result<Option<usize>, AgentActivityError> {
-        let claude = ClaudeApiClient::from_env()?;
-
-        let prompt = format!(
-            r#"Analyze the complexity of this software development task:
-
-## Task
-Title: {}
-Description: {}
-Layer: {}
-Type: {}
-
-## Criteria for High Complexity (score >= 7):
-- Would take > 4 hours of work
-- Touches > 3 files/components
-- Has unclear boundaries
-- Can be split into independently testable parts
-- Requires multiple distinct implementation steps
-
-## Output Format (JSON only):
-{{
-  "complexity_score": <1-10>,
-  "can_be_broken_down": <true/false>,
-  "reasoning": "<brief explanation>",
-  "subtasks": [
-    {{"title": "<subtask title>", "description": "<what to do>", "layer": "<data|backend|frontend|null>"}},
-    ...
-  ]
-}}
-
-If complexity_score < 7 or can_be_broken_down is false, subtasks can be empty array.
-Limit to 2-4 subtasks maximum if breaking down."#,
-            task.title,
-            task.description.as_deref().unwrap_or("(no description)"),
-            task.layer
-                .as_ref()
-                .map(|l| l.to_string())
-                .unwrap_or_else(|| "unspecified".to_string()),
-            task.task_type
-                .as_ref()
-                .map(|t| t.to_string())
-                .unwrap_or_else(|| "implementation".to_string()),
-        );
-
-        let system = Some(
-            "You are a software project complexity analyzer. Analyze tasks and suggest breakdowns for complex work. Output valid JSON only.".to_string()
-        );
-
-        let analysis: ComplexityAnalysisResponse = claude.ask_json(&prompt, system).await?;
-
-        // Store complexity score
-        Task::update_complexity_score(pool, task.id, analysis.complexity_score).await?;
-
-        info!(
-            task_id = %task.id,
-            complexity_score = analysis.complexity_score,
-            can_breakdown = analysis.can_be_broken_down,
-            "Agent activity: complexity analysis complete"
-        );
-
-        // Check if we should break down
-        if analysis.complexity_score >= 7
-            && analysis.can_be_broken_down
-            && analysis.subtasks.as_ref().map(|s| s.len()).unwrap_or(0) >= 2
-        {
-            let subtasks = analysis.subtasks.unwrap();
-            let mut created_count = 0;
-
-            for (i, subtask) in subtasks.iter().enumerate() {
-                let layer = subtask.layer.as_ref().and_then(|l| match l.as_str() {
-                    "data" => Some(TaskLayer::Data),
-                    "backend" => Some(TaskLayer::Backend),
-                    "frontend" => Some(TaskLayer::Frontend),
-                    "fullstack" => Some(TaskLayer::Fullstack),
-                    "devops" => Some(TaskLayer::Devops),
-                    "testing" => Some(TaskLayer::Testing),
-                    _ => task.layer.clone(),
-                });
-
-                let create_data = CreateTask::subtask_of(
-                    task.project_id,
-                    subtask.title.clone(),
-                    Some(subtask.description.clone()),
-                    layer.or_else(|| task.layer.clone()),
-                    task.task_type.clone(),
-                    task.sequence.unwrap_or(0) * 10 + i as i32,
-                    task.testing_criteria.clone(),
-                    None,
-                    task.id,
-                );
-
-                Task::create(pool, &create_data, Uuid::new_v4()).await?;
-                created_count += 1;
-            }
-
-            // Cancel the original task
-            Task::update_status(pool, task.id, TaskStatus::Cancelled).await?;
+        let analyzer = ComplexityAnalyzer::new(pool.clone())?
+            .with_max_description_chars(Self::max_description_chars(agent_settings));
+        let max_subtasks = agent_settings.map_or(5, |s| s.max_breakdown_subtasks.max(0)) as usize;
+        let Some(created_count) = analyzer
+            .analyze_and_breakdown(task, project_id, max_subtasks)
+            .await?
+        else {
+            return Ok(None);
+        };
 
-            // Log the replacement
-            AgentActivityLog::create(
-                pool,
+        notifier
+            .notify(
                 project_id,
-                Some(task.id),
-                AgentAction::Replaced,
-                Some(format!(
-                    "Complex task (score {}) broken into {} subtasks: {}",
-                    analysis.complexity_score, created_count, analysis.reasoning
-                )),
+                DigestEvent::BrokenDown,
+                "Task Breakdown",
+                &format!(
+                    "Complex task '{}' split into {} subtasks",
+                    task.title, created_count
+                ),
             )
-            .await?;
-
-            notification_service
-                .notify(
-                    "Task Breakdown",
-                    &format!(
-                        "Complex task '{}' split into {} subtasks",
-                        task.title, created_count
-                    ),
-                )
-                .await;
+            .await;
 
-            return Ok(Some(created_count));
-        }
+        Ok(Some(created_count))
+    }
 
-        Ok(None)
+    /// Whether a task is complex enough to auto-start an attempt for. Trivial tasks stay
+    /// InProgress for batch or manual handling; tasks without a score are always started.
+    fn meets_auto_attempt_threshold(
+        complexity_score: Option<i32>,
+        agent_settings: Option<&ProjectAgentSettings>,
+    ) -> bool {
+        let min_complexity = agent_settings.map_or(0, |s| s.min_complexity_for_auto_attempt);
+        complexity_score.is_none_or(|score| score >= min_complexity)
     }
 
     /// Auto-start an attempt for a task using default settings
@@ -680,6 +1154,7 @@ Limit to 2-4 subtasks maximum if breaking down."#,
         task: &Task,
         project_id: Uuid,
         auto_attempt: &AutoAttemptConfig,
+        agent_settings: Option<&ProjectAgentSettings>,
     ) -> Result<(), AgentActivityError> {
         // Get repos for the project
         let repos = ProjectRepo::find_repos_for_project(pool, project_id).await?;
@@ -695,17 +1170,15 @@ Limit to 2-4 subtasks maximum if breaking down."#,
         let workspace_id = Uuid::new_v4();
         let git_branch_name = auto_attempt
             .workspace_starter
-            .git_branch_from_workspace(&workspace_id, &task.title)
+            .git_branch_from_workspace(&workspace_id, task)
             .await;
 
-        // Compute agent_working_dir based on repo count:
-        // - Single repo: use repo name as working dir
-        // - Multiple repos: use None (agent runs in workspace root)
-        let agent_working_dir = if repos.len() == 1 {
-            Some(repos[0].name.clone())
-        } else {
-            None
-        };
+        let default_strategy = WorkingDirStrategy::default();
+        let (strategy, explicit_path) = agent_settings.map_or((&default_strategy, None), |s| {
+            (&s.working_dir_strategy, s.working_dir_path.as_deref())
+        });
+        let repo_names: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        let agent_working_dir = resolve_agent_working_dir(strategy, explicit_path, &repo_names);
 
         // Create workspace
         let workspace = Workspace::create(
@@ -724,10 +1197,12 @@ Limit to 2-4 subtasks maximum if breaking down."#,
         let workspace_repos: Vec<CreateWorkspaceRepo> = repos
             .iter()
             .map(|repo| {
-                // Get current branch for the repo, fallback to "main"
+                // Get current branch for the repo, fallback to its default branch
+                let repo_path = Path::new(&repo.path);
                 let target_branch = auto_attempt
                     .git_service
-                    .get_current_branch(Path::new(&repo.path))
+                    .get_current_branch(repo_path)
+                    .or_else(|_| auto_attempt.git_service.default_branch(repo_path))
                     .unwrap_or_else(|_| "main".to_string());
 
                 CreateWorkspaceRepo {
@@ -739,12 +1214,51 @@ Limit to 2-4 subtasks maximum if breaking down."#,
 
         WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
 
-        // Start the workspace
-        auto_attempt
-            .workspace_starter
-            .start_workspace(&workspace, executor_profile_id)
-            .await
-            .map_err(AgentActivityError::WorkspaceCreation)?;
+        // Pass recent human feedback along so the agent can incorporate it
+        let comments_context = if agent_settings.is_some_and(|s| s.include_recent_comments) {
+            let comments = Self::recent_comments(pool, task.id).await?;
+            format_recent_comments(&comments)
+        } else {
+            None
+        };
+        // A retry after a failed attempt learns why it failed
+        let failure_context = Task::last_failure_context(pool, task.id)
+            .await?
+            .map(|failure| format_failure_context(&failure));
+        let prompt_context = match (failure_context, comments_context) {
+            (Some(failure), Some(comments)) => Some(format!("{failure}\n\n{comments}")),
+            (failure, comments) => failure.or(comments),
+        };
+
+        // Start the workspace, retrying transient failures with backoff
+        let retries = agent_settings.map_or(2, |s| s.auto_attempt_retries.max(0)) as u32;
+        let mut attempt = 0;
+        loop {
+            match auto_attempt
+                .workspace_starter
+                .start_workspace(
+                    &workspace,
+                    executor_profile_id.clone(),
+                    prompt_context.clone(),
+                )
+                .await
+            {
+                Ok(()) => break,
+                Err(e) if attempt < retries => {
+                    let delay = auto_start_backoff(attempt);
+                    warn!(
+                        task_id = %task.id,
+                        attempt = attempt + 1,
+                        error = %e,
+                        "Failed to start workspace, retrying in {:?}",
+                        delay
+                    );
+                    sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(AgentActivityError::WorkspaceCreation(e)),
+            }
+        }
 
         info!(
             workspace_id = %workspace.id,
@@ -755,68 +1269,219 @@ Limit to 2-4 subtasks maximum if breaking down."#,
         Ok(())
     }
 
-    /// Use AI to select the best task from the list
-    async fn select_task_with_ai(
+    /// Put a task whose auto-attempt could not be started back in Todo so it isn't
+    /// left InProgress with nothing running, archiving the workspace the failed start left behind
+    async fn revert_failed_auto_start(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        project_id: Uuid,
+        error: &AgentActivityError,
+    ) -> Result<(), AgentActivityError> {
+        Workspace::archive_unstarted_for_task(pool, task_id).await?;
+        Task::update_status(
+            pool,
+            task_id,
+            TaskStatus::Todo,
+            Some(STATUS_ACTOR),
+            Some("Auto-start failed"),
+        )
+        .await?;
+        AgentActivityLog::create(
+            pool,
+            project_id,
+            Some(task_id),
+            AgentAction::Error,
+            Some(format!(
+                "Failed to start attempt, moved back to Todo: {}",
+                error
+            )),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// The most recent human comments on a task, oldest first, rendered for prompts
+    async fn recent_comments(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<String>, AgentActivityError> {
+        Ok(
+            TaskComment::find_recent(pool, task_id, RECENT_COMMENTS_LIMIT)
+                .await?
+                .iter()
+                .map(TaskComment::to_prompt_line)
+                .collect(),
+        )
+    }
+
+    /// Recent comments for each candidate task, or nothing when the project hasn't opted in
+    async fn recent_comments_for_tasks(
+        pool: &SqlitePool,
         tasks: &[TaskWithAttemptStatus],
-    ) -> Result<(Uuid, String), AgentActivityError> {
-        let claude = ClaudeApiClient::from_env()?;
+        agent_settings: Option<&ProjectAgentSettings>,
+    ) -> Result<HashMap<Uuid, Vec<String>>, AgentActivityError> {
+        let mut comments = HashMap::new();
+        if !agent_settings.is_some_and(|s| s.include_recent_comments) {
+            return Ok(comments);
+        }
+        for task in tasks {
+            let task_comments = Self::recent_comments(pool, task.id).await?;
+            if !task_comments.is_empty() {
+                comments.insert(task.id, task_comments);
+            }
+        }
+        Ok(comments)
+    }
 
+    /// How many characters of each task description go into selection and complexity prompts
+    fn max_description_chars(agent_settings: Option<&ProjectAgentSettings>) -> usize {
+        agent_settings.map_or(prompts::DEFAULT_MAX_DESCRIPTION_CHARS, |s| {
+            s.max_prompt_description_chars.max(0) as usize
+        })
+    }
+
+    /// Build the (prompt, system prompt) pair sent to the AI for task selection
+    fn build_selection_prompt(
+        template: &PromptTemplate,
+        tasks: &[TaskWithAttemptStatus],
+        comments: &HashMap<Uuid, Vec<String>>,
+        max_description_chars: usize,
+        system_prefix: Option<&str>,
+    ) -> Result<(String, String), AgentActivityError> {
         // Convert tasks to simplified format for AI
         let task_infos: Vec<TaskInfo> = tasks
             .iter()
             .map(|t| TaskInfo {
                 id: t.id.to_string(),
                 title: t.title.clone(),
-                description: t.description.clone(),
+                description: t
+                    .description
+                    .as_deref()
+                    .map(|d| prompts::truncate_description(t.id, d, max_description_chars)),
                 layer: t.layer.as_ref().map(|l| l.to_string()),
                 task_type: t.task_type.as_ref().map(|tt| tt.to_string()),
                 sequence: t.sequence,
+                recent_comments: comments.get(&t.id).cloned().unwrap_or_default(),
             })
             .collect();
 
         let tasks_json = serde_json::to_string_pretty(&task_infos)
             .map_err(|e| AgentActivityError::ClaudeApi(ClaudeApiError::Serde(e.to_string())))?;
 
-        let prompt = format!(
-            r#"You are a task prioritization assistant. Analyze the following tasks and select the ONE task that should be worked on next.
-
-## CRITICAL: Prioritization Rules (in strict order):
-1. **INITIALIZATION FIRST**: Tasks that initialize or set up the project MUST come first. Look for:
-   - Tasks with sequence=1 (highest priority)
-   - Architecture tasks that set up project structure, configs, or scaffolding
-   - Tasks with titles containing: "init", "setup", "scaffold", "configure", "create project", "initialize"
-   - The project must be runnable in the browser after these tasks complete!
+        let prompt = template.render(&[("tasks", &tasks_json)]);
+        let system = prompts::prefix_system_prompt(system_prefix, template.system);
 
-2. **Sequence order**: Lower sequence number = higher priority (sequence 1 before 2, 2 before 3, etc.)
-
-3. **Task type order**: architecture → mock → implementation → integration
-   - Architecture tasks set up structure (do these early)
-   - Mock tasks enable parallel development
-   - Implementation tasks build features
-   - Integration tasks come last (they wire everything together)
-
-4. **Layer dependencies**: data → backend → frontend → fullstack
-   - Data layer should be set up before backend
-   - Backend before frontend (frontend needs API endpoints)
+        Ok((prompt, system))
+    }
 
-5. **Unblocking**: Prefer tasks that enable other tasks to proceed
+    /// Build the task-selection prompt the agent would send right now, without calling the AI
+    /// or breaking down any tasks
+    pub async fn preview_selection_prompt(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<SelectionPromptPreview, AgentActivityError> {
+        let all_tasks =
+            Task::find_by_project_id_with_attempt_status(pool, project_id, true).await?;
+        let agent_settings = ProjectAgentSettings::find_by_project_id(pool, project_id).await?;
+        let max_concurrent_layers = agent_settings
+            .as_ref()
+            .map_or(3, |s| s.max_concurrent_layers) as usize;
+        let integration_blocks_all = agent_settings
+            .as_ref()
+            .is_none_or(|s| s.integration_blocks_all);
+        let default_layer = agent_settings
+            .as_ref()
+            .and_then(|s| s.default_layer.clone());
+        let max_in_progress_tasks = agent_settings
+            .as_ref()
+            .map_or(0, |s| s.max_in_progress_tasks);
+
+        if Self::in_progress_cap_reached(&all_tasks, max_in_progress_tasks) {
+            return Ok(SelectionPromptPreview {
+                prompt: None,
+                system_prompt: None,
+                candidate_task_ids: Vec::new(),
+                blocked_reason: Some(format!(
+                    "{max_in_progress_tasks} tasks are already in progress; no new task would be \
+                     selected"
+                )),
+                prompt_version: None,
+            });
+        }
 
-## Tasks:
-{tasks_json}
+        let tasks = match Self::eligible_tasks(
+            project_id,
+            all_tasks,
+            max_concurrent_layers,
+            integration_blocks_all,
+            default_layer.as_ref(),
+        ) {
+            Ok(tasks) => tasks,
+            Err(AgentActivityError::TaskAlreadyInProgress) => {
+                return Ok(SelectionPromptPreview {
+                    prompt: None,
+                    system_prompt: None,
+                    candidate_task_ids: Vec::new(),
+                    blocked_reason: Some(
+                        "Another task is in progress; no new task would be selected".to_string(),
+                    ),
+                    prompt_version: None,
+                });
+            }
+            Err(e) => return Err(e),
+        };
 
-## Output Format:
-Return ONLY valid JSON:
-{{
-  "task_id": "uuid-of-selected-task",
-  "reasoning": "Brief explanation of why this task was selected"
-}}"#
-        );
+        if tasks.is_empty() {
+            return Ok(SelectionPromptPreview {
+                prompt: None,
+                system_prompt: None,
+                candidate_task_ids: Vec::new(),
+                blocked_reason: Some("No eligible tasks available".to_string()),
+                prompt_version: None,
+            });
+        }
 
-        let system = Some(
-            "You are a task prioritization assistant. Your PRIMARY goal is ensuring the codebase is always runnable. Initialization and setup tasks MUST be completed first. Select the most appropriate task based on strict priority order. Output valid JSON only.".to_string(),
-        );
+        let template = prompts::active(pool, project_id, TemplateName::TaskSelection).await?;
+        let comments =
+            Self::recent_comments_for_tasks(pool, &tasks, agent_settings.as_ref()).await?;
+        let system_prefix = prompts::system_prompt_prefix(pool, project_id).await?;
+        let (prompt, system) = Self::build_selection_prompt(
+            template,
+            &tasks,
+            &comments,
+            Self::max_description_chars(agent_settings.as_ref()),
+            system_prefix.as_deref(),
+        )?;
+        Ok(SelectionPromptPreview {
+            prompt: Some(prompt),
+            system_prompt: Some(system),
+            candidate_task_ids: tasks.iter().map(|t| t.id).collect(),
+            blocked_reason: None,
+            prompt_version: Some(template.label()),
+        })
+    }
 
-        let response: TaskSelectionResponse = claude.ask_json(&prompt, system).await?;
+    /// Use AI to select the best task from the list, returning its reasoning and, when the
+    /// prompt asks for it, the structured breakdown
+    async fn select_task_with_ai(
+        template: &PromptTemplate,
+        tasks: &[TaskWithAttemptStatus],
+        comments: &HashMap<Uuid, Vec<String>>,
+        max_description_chars: usize,
+        system_prefix: Option<&str>,
+    ) -> Result<(Uuid, String, Option<SelectionReasoning>), AgentActivityError> {
+        let claude = ClaudeApiClient::from_env()?;
+        let (prompt, system) = Self::build_selection_prompt(
+            template,
+            tasks,
+            comments,
+            max_description_chars,
+            system_prefix,
+        )?;
+
+        let response: TaskSelectionResponse = claude
+            .ask_json(ModelRole::Selection, &prompt, Some(system))
+            .await?;
 
         // Parse and validate the task ID
         let task_id = Uuid::parse_str(&response.task_id).map_err(|_| {
@@ -833,7 +1498,45 @@ Return ONLY valid JSON:
             )));
         }
 
-        Ok((task_id, response.reasoning))
+        let candidate_ids: Vec<Uuid> = tasks.iter().map(|t| t.id).collect();
+        let (reasoning, details) = response.into_reasoning(task_id, &candidate_ids);
+        Ok((task_id, reasoning, details))
+    }
+
+    /// Tasks can change between reading the candidates and the AI responding. Keep the AI's
+    /// choice if it is still an unarchived Todo task; otherwise fall back to the first candidate
+    /// that still is, in [`fallback_order`]. None when every candidate was taken meanwhile.
+    async fn confirm_selection(
+        pool: &SqlitePool,
+        tasks: &[TaskWithAttemptStatus],
+        chosen_id: Uuid,
+        reasoning: String,
+        reasoning_details: Option<SelectionReasoning>,
+    ) -> Result<Option<(Task, String, Option<SelectionReasoning>)>, AgentActivityError> {
+        let still_selectable =
+            |task: Option<Task>| task.filter(|t| t.status == TaskStatus::Todo && !t.archived);
+
+        if let Some(task) = still_selectable(Task::find_by_id(pool, chosen_id).await?) {
+            return Ok(Some((task, reasoning, reasoning_details)));
+        }
+
+        for candidate in fallback_order(tasks) {
+            if candidate.id == chosen_id {
+                continue;
+            }
+            if let Some(task) = still_selectable(Task::find_by_id(pool, candidate.id).await?) {
+                warn!(
+                    chosen_task_id = %chosen_id,
+                    fallback_task_id = %task.id,
+                    "AI-selected task is no longer eligible, falling back by priority order"
+                );
+                let reasoning = format!(
+                    "AI-selected task {chosen_id} was no longer eligible; fell back to the next task by priority order"
+                );
+                return Ok(Some((task, reasoning, None)));
+            }
+        }
+        Ok(None)
     }
 
     /// Get the current agent activity status for a project
@@ -847,6 +1550,7 @@ Return ONLY valid JSON:
         Ok(AgentActivityStatus {
             enabled: settings.as_ref().map(|s| s.enabled).unwrap_or(false),
             interval_seconds: settings.as_ref().map(|s| s.interval_seconds).unwrap_or(60),
+            digest_window_minutes: settings.as_ref().and_then(|s| s.digest_window_minutes),
             last_run: latest_log.as_ref().map(|l| l.created_at),
             last_selected_task_id: latest_log
                 .as_ref()
@@ -864,6 +1568,124 @@ Return ONLY valid JSON:
         Ok(ProjectAgentSettings::set_enabled(pool, project_id, true).await?)
     }
 
+    /// Update polling interval, layer concurrency and notification digest window for a project
+    pub async fn update_settings(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        update: &UpdateAgentActivitySettings,
+    ) -> Result<ProjectAgentSettings, AgentActivityError> {
+        if !(5..=3600).contains(&update.interval_seconds) {
+            return Err(AgentActivityError::InvalidSettings(
+                "interval_seconds must be between 5 and 3600".to_string(),
+            ));
+        }
+        if !(1..=6).contains(&update.max_concurrent_layers) {
+            return Err(AgentActivityError::InvalidSettings(
+                "max_concurrent_layers must be between 1 and 6".to_string(),
+            ));
+        }
+        if update
+            .digest_window_minutes
+            .flatten()
+            .is_some_and(|m| !(1..=1440).contains(&m))
+        {
+            return Err(AgentActivityError::InvalidSettings(
+                "digest_window_minutes must be between 1 and 1440".to_string(),
+            ));
+        }
+        if update
+            .min_complexity_for_auto_attempt
+            .is_some_and(|m| !(0..=10).contains(&m))
+        {
+            return Err(AgentActivityError::InvalidSettings(
+                "min_complexity_for_auto_attempt must be between 0 and 10".to_string(),
+            ));
+        }
+        if update.max_in_progress_tasks.is_some_and(|m| m < 0) {
+            return Err(AgentActivityError::InvalidSettings(
+                "max_in_progress_tasks must be 0 (no cap) or more".to_string(),
+            ));
+        }
+        if update
+            .auto_attempt_retries
+            .is_some_and(|r| !(0..=5).contains(&r))
+        {
+            return Err(AgentActivityError::InvalidSettings(
+                "auto_attempt_retries must be between 0 and 5".to_string(),
+            ));
+        }
+        if update
+            .max_breakdown_subtasks
+            .is_some_and(|m| !(2..=20).contains(&m))
+        {
+            return Err(AgentActivityError::InvalidSettings(
+                "max_breakdown_subtasks must be between 2 and 20".to_string(),
+            ));
+        }
+        if update
+            .max_prompt_description_chars
+            .is_some_and(|m| !(200..=100_000).contains(&m))
+        {
+            return Err(AgentActivityError::InvalidSettings(
+                "max_prompt_description_chars must be between 200 and 100000".to_string(),
+            ));
+        }
+        let working_dir_path = update
+            .working_dir_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+        if update.working_dir_strategy == Some(WorkingDirStrategy::Explicit) {
+            let Some(path) = working_dir_path else {
+                return Err(AgentActivityError::InvalidSettings(
+                    "working_dir_path is required for the explicit strategy".to_string(),
+                ));
+            };
+            let path = Path::new(path);
+            if path.is_absolute()
+                || path
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir))
+            {
+                return Err(AgentActivityError::InvalidSettings(
+                    "working_dir_path must be relative to the workspace root".to_string(),
+                ));
+            }
+        }
+
+        let existing = ProjectAgentSettings::find_by_project_id(pool, project_id).await?;
+        let quiet_hours = resolve_quiet_hours(update, existing.as_ref())
+            .map_err(AgentActivityError::InvalidSettings)?;
+        let default_layer =
+            resolve_default_layer(update).map_err(AgentActivityError::InvalidSettings)?;
+        Ok(ProjectAgentSettings::update(
+            pool,
+            project_id,
+            &AgentSettingsUpdate {
+                interval_seconds: update.interval_seconds,
+                max_concurrent_layers: update.max_concurrent_layers,
+                digest_window_minutes: update.digest_window_minutes,
+                working_dir: update.working_dir_strategy.clone().map(|strategy| {
+                    let path = working_dir_path
+                        .filter(|_| strategy == WorkingDirStrategy::Explicit)
+                        .map(str::to_string);
+                    (strategy, path)
+                }),
+                auto_breakdown_fullstack: update.auto_breakdown_fullstack,
+                min_complexity_for_auto_attempt: update.min_complexity_for_auto_attempt,
+                max_in_progress_tasks: update.max_in_progress_tasks,
+                max_prompt_description_chars: update.max_prompt_description_chars,
+                max_breakdown_subtasks: update.max_breakdown_subtasks,
+                auto_attempt_retries: update.auto_attempt_retries,
+                include_recent_comments: update.include_recent_comments,
+                integration_blocks_all: update.integration_blocks_all,
+                quiet_hours,
+                default_layer,
+            },
+        )
+        .await?)
+    }
+
     /// Disable agent activity for a project
     pub async fn disable(
         pool: &SqlitePool,
@@ -872,3 +1694,457 @@ Return ONLY valid JSON:
         Ok(ProjectAgentSettings::set_enabled(pool, project_id, false).await?)
     }
 }
+
+/// Resolve the agent working directory for a new workspace.
+/// `None` means the agent runs in the workspace root, which contains one directory per repo.
+fn resolve_agent_working_dir(
+    strategy: &WorkingDirStrategy,
+    explicit_path: Option<&str>,
+    repo_names: &[&str],
+) -> Option<String> {
+    match strategy {
+        WorkingDirStrategy::RepoName => match repo_names {
+            [single] => Some(single.to_string()),
+            _ => None,
+        },
+        WorkingDirStrategy::WorkspaceRoot => None,
+        WorkingDirStrategy::Explicit => explicit_path
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string),
+    }
+}
+
+/// The quiet hours window (start, end, UTC offset) to save for `update`, validated against the
+/// current settings it keeps; `None` when the update leaves quiet hours unchanged
+/// The default layer an update asks for: `None` leaves the setting alone, `Some(None)` clears it
+fn resolve_default_layer(
+    update: &UpdateAgentActivitySettings,
+) -> Result<Option<Option<TaskLayer>>, String> {
+    match update.default_layer.as_deref().map(str::trim) {
+        None => Ok(None),
+        Some("") => Ok(Some(None)),
+        Some(layer) => layer.parse().map(|l| Some(Some(l))).map_err(|_| {
+            "default_layer must be one of data, backend, frontend, fullstack, devops, testing"
+                .to_string()
+        }),
+    }
+}
+
+fn resolve_quiet_hours(
+    update: &UpdateAgentActivitySettings,
+    existing: Option<&ProjectAgentSettings>,
+) -> Result<Option<(Option<String>, Option<String>, i32)>, String> {
+    let start = update.quiet_hours_start.as_deref().map(str::trim);
+    let end = update.quiet_hours_end.as_deref().map(str::trim);
+    if start.is_none() && end.is_none() && update.quiet_hours_utc_offset_minutes.is_none() {
+        return Ok(None);
+    }
+
+    let offset = update
+        .quiet_hours_utc_offset_minutes
+        .or(existing.map(|s| s.quiet_hours_utc_offset_minutes))
+        .unwrap_or(0);
+    let (start, end) = match (start, end) {
+        (None, None) => (
+            existing.and_then(|s| s.quiet_hours_start.clone()),
+            existing.and_then(|s| s.quiet_hours_end.clone()),
+        ),
+        (Some(""), Some("")) => (None, None),
+        (Some(start), Some(end)) if !start.is_empty() && !end.is_empty() => {
+            (Some(start.to_string()), Some(end.to_string()))
+        }
+        _ => {
+            return Err("quiet_hours_start and quiet_hours_end must be set together".to_string());
+        }
+    };
+    match (&start, &end) {
+        (Some(start), Some(end)) => QuietHours::new(start, end, offset).map(|_| ())?,
+        _ => quiet_hours::validate_utc_offset(offset)?,
+    }
+    Ok(Some((start, end, offset)))
+}
+
+/// Render recent human comments as a prompt section for the coding agent, or None if there
+/// are none
+fn format_recent_comments(comments: &[String]) -> Option<String> {
+    if comments.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = comments.iter().map(|c| format!("- {c}")).collect();
+    Some(format!(
+        "Recent comments from reviewers (incorporate this feedback):\n{}",
+        lines.join("\n")
+    ))
+}
+
+/// Render a task's previous failed attempt as a prompt section for the retry
+fn format_failure_context(failure: &TaskFailureContext) -> String {
+    let exit = failure
+        .exit_code
+        .map_or(String::new(), |code| format!(" (exit code {code})"));
+    let output = failure.output.trim();
+    let output = if output.is_empty() {
+        "(no output captured)"
+    } else {
+        output
+    };
+    format!(
+        "Previous attempt failed{exit}. Avoid repeating the same mistake; it failed with:\n\
+         ```\n{output}\n```"
+    )
+}
+
+/// Deterministic fallback order for the eligible candidates, which are already narrowed to the
+/// highest priority tier: lowest sequence first (unsequenced last), then oldest, with the id
+/// breaking any remaining tie
+fn fallback_order(tasks: &[TaskWithAttemptStatus]) -> Vec<&TaskWithAttemptStatus> {
+    let mut ordered: Vec<&TaskWithAttemptStatus> = tasks.iter().collect();
+    ordered.sort_by_key(|t| (t.sequence.is_none(), t.sequence, t.created_at, t.id));
+    ordered
+}
+
+/// Delay before the next try after a failed workspace start: 2s, 4s, 8s, ... capped at 30s
+fn auto_start_backoff(attempt: u32) -> Duration {
+    Duration::from_secs((2u64 << attempt.min(4)).min(30))
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::execution_process::ExecutionProcessRunReason;
+
+    use super::*;
+    use crate::services::test_support;
+
+    #[test]
+    fn recent_comments_render_as_a_prompt_section() {
+        assert_eq!(format_recent_comments(&[]), None);
+        let comments = vec![
+            "alice: add tests for the empty case".to_string(),
+            "Use the existing error type".to_string(),
+        ];
+        assert_eq!(
+            format_recent_comments(&comments).unwrap(),
+            "Recent comments from reviewers (incorporate this feedback):\n\
+             - alice: add tests for the empty case\n\
+             - Use the existing error type"
+        );
+    }
+
+    #[test]
+    fn failure_context_renders_exit_code_and_output() {
+        let failure = |exit_code: Option<i64>, output: &str| TaskFailureContext {
+            execution_process_id: Uuid::nil(),
+            run_reason: ExecutionProcessRunReason::CodingAgent,
+            exit_code,
+            output: output.to_string(),
+        };
+        assert_eq!(
+            format_failure_context(&failure(Some(101), "error[E0425]: cannot find value\n")),
+            "Previous attempt failed (exit code 101). Avoid repeating the same mistake; it \
+             failed with:\n```\nerror[E0425]: cannot find value\n```"
+        );
+        assert!(format_failure_context(&failure(None, "  ")).contains("(no output captured)"));
+    }
+
+    #[test]
+    fn digest_window_update_tells_absent_from_null() {
+        let parse = |body: serde_json::Value| {
+            serde_json::from_value::<UpdateAgentActivitySettings>(body)
+                .unwrap()
+                .digest_window_minutes
+        };
+        let base = || serde_json::json!({ "interval_seconds": 60, "max_concurrent_layers": 3 });
+
+        assert_eq!(parse(base()), None);
+        let mut clear = base();
+        clear["digest_window_minutes"] = serde_json::Value::Null;
+        assert_eq!(parse(clear), Some(None));
+        let mut set = base();
+        set["digest_window_minutes"] = 15.into();
+        assert_eq!(parse(set), Some(Some(15)));
+    }
+
+    #[test]
+    fn quiet_hours_update_sets_clears_and_validates_the_window() {
+        let update = |start: Option<&str>, end: Option<&str>, offset: Option<i32>| {
+            UpdateAgentActivitySettings {
+                interval_seconds: 60,
+                max_concurrent_layers: 3,
+                digest_window_minutes: None,
+                working_dir_strategy: None,
+                working_dir_path: None,
+                auto_breakdown_fullstack: None,
+                min_complexity_for_auto_attempt: None,
+                max_prompt_description_chars: None,
+                max_breakdown_subtasks: None,
+                auto_attempt_retries: None,
+                include_recent_comments: None,
+                integration_blocks_all: None,
+                quiet_hours_start: start.map(str::to_string),
+                quiet_hours_end: end.map(str::to_string),
+                quiet_hours_utc_offset_minutes: offset,
+                max_in_progress_tasks: None,
+                default_layer: None,
+            }
+        };
+
+        assert_eq!(
+            resolve_quiet_hours(&update(None, None, None), None),
+            Ok(None)
+        );
+        assert_eq!(
+            resolve_quiet_hours(&update(Some("22:00"), Some(" 07:00 "), Some(60)), None),
+            Ok(Some((
+                Some("22:00".to_string()),
+                Some("07:00".to_string()),
+                60
+            )))
+        );
+        assert_eq!(
+            resolve_quiet_hours(&update(Some(""), Some(""), None), None),
+            Ok(Some((None, None, 0)))
+        );
+        assert!(resolve_quiet_hours(&update(Some("22:00"), None, None), None).is_err());
+        assert!(resolve_quiet_hours(&update(Some("22:00"), Some(""), None), None).is_err());
+        assert!(resolve_quiet_hours(&update(None, None, Some(24 * 60)), None).is_err());
+    }
+
+    /// A Todo candidate created `age_minutes` ago
+    fn candidate(id: u128, sequence: Option<i32>, age_minutes: i64) -> TaskWithAttemptStatus {
+        let now = chrono::Utc::now();
+        TaskWithAttemptStatus {
+            task: Task {
+                id: Uuid::from_u128(id),
+                sequence,
+                created_at: now - chrono::Duration::minutes(age_minutes),
+                updated_at: now,
+                ..test_support::task(&format!("Task {id}"))
+            },
+            has_in_progress_attempt: false,
+            last_attempt_failed: false,
+            executor: String::new(),
+        }
+    }
+
+    #[test]
+    fn fallback_order_is_sequence_then_age_then_id() {
+        let tasks = vec![
+            candidate(5, None, 60),
+            candidate(4, Some(2), 10),
+            candidate(3, Some(2), 30),
+            candidate(2, Some(1), 0),
+            candidate(1, None, 60),
+        ];
+
+        let order: Vec<u128> = fallback_order(&tasks)
+            .iter()
+            .map(|t| t.id.as_u128())
+            .collect();
+        assert_eq!(order, vec![2, 3, 4, 1, 5]);
+    }
+
+    #[test]
+    fn in_progress_cap_counts_active_tasks_in_any_layer() {
+        let with_status = |id: u128, status: TaskStatus, layer: TaskLayer| {
+            let mut task = candidate(id, None, 0);
+            task.task.status = status;
+            task.task.layer = Some(layer);
+            task
+        };
+        let mut archived = with_status(4, TaskStatus::InProgress, TaskLayer::Frontend);
+        archived.task.archived = true;
+        let tasks = vec![
+            with_status(1, TaskStatus::InProgress, TaskLayer::Backend),
+            with_status(2, TaskStatus::InReview, TaskLayer::Backend),
+            with_status(3, TaskStatus::Done, TaskLayer::Frontend),
+            with_status(5, TaskStatus::Todo, TaskLayer::Frontend),
+            archived,
+        ];
+
+        assert!(!AgentActivityService::in_progress_cap_reached(&tasks, 0));
+        assert!(!AgentActivityService::in_progress_cap_reached(&tasks, 3));
+        assert!(AgentActivityService::in_progress_cap_reached(&tasks, 2));
+        assert!(AgentActivityService::in_progress_cap_reached(&tasks, 1));
+    }
+
+    #[test]
+    fn default_layer_lets_layerless_task_run_alongside_other_layers() {
+        let mut frontend = candidate(1, None, 0);
+        frontend.task.status = TaskStatus::InProgress;
+        frontend.task.layer = Some(TaskLayer::Frontend);
+        let layerless = candidate(2, None, 0);
+        let tasks = vec![frontend, layerless.clone()];
+
+        assert!(matches!(
+            AgentActivityService::eligible_tasks(Uuid::nil(), tasks.clone(), 3, true, None),
+            Err(AgentActivityError::TaskAlreadyInProgress)
+        ));
+        assert!(!AgentActivityService::can_start_now(
+            layerless.id,
+            &tasks,
+            3,
+            true,
+            None
+        ));
+
+        let backend = Some(&TaskLayer::Backend);
+        let eligible =
+            AgentActivityService::eligible_tasks(Uuid::nil(), tasks.clone(), 3, true, backend)
+                .unwrap();
+        assert_eq!(
+            eligible.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![layerless.id]
+        );
+        assert!(AgentActivityService::can_start_now(
+            layerless.id,
+            &tasks,
+            3,
+            true,
+            backend
+        ));
+        // Selection only borrows the default; the task itself stays layerless
+        assert_eq!(eligible[0].layer, None);
+
+        // A default matching the active layer still waits for it
+        let frontend_default = Some(&TaskLayer::Frontend);
+        assert!(
+            AgentActivityService::eligible_tasks(Uuid::nil(), tasks, 3, true, frontend_default)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn manual_start_follows_concurrency_rules_but_not_priority() {
+        let mut architecture = candidate(1, None, 0);
+        architecture.task.task_type = Some(TaskType::Architecture);
+        architecture.task.layer = Some(TaskLayer::Backend);
+        let mut implementation = candidate(2, None, 0);
+        implementation.task.task_type = Some(TaskType::Implementation);
+        implementation.task.layer = Some(TaskLayer::Frontend);
+        let tasks = vec![architecture.clone(), implementation.clone()];
+
+        let eligible =
+            AgentActivityService::eligible_tasks(Uuid::nil(), tasks.clone(), 3, true, None)
+                .unwrap();
+        assert_eq!(
+            eligible.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![architecture.id]
+        );
+        assert!(AgentActivityService::can_start_now(
+            implementation.id,
+            &tasks,
+            3,
+            true,
+            None
+        ));
+
+        // Once its layer is busy, the task waits like any AI-selected one
+        let mut frontend = candidate(3, None, 0);
+        frontend.task.status = TaskStatus::InProgress;
+        frontend.task.layer = Some(TaskLayer::Frontend);
+        let tasks = vec![architecture.clone(), implementation.clone(), frontend];
+        assert!(!AgentActivityService::can_start_now(
+            implementation.id,
+            &tasks,
+            3,
+            true,
+            None
+        ));
+        assert!(AgentActivityService::can_start_now(
+            architecture.id,
+            &tasks,
+            3,
+            true,
+            None
+        ));
+    }
+
+    #[test]
+    fn auto_start_backoff_doubles_up_to_cap() {
+        assert_eq!(auto_start_backoff(0), Duration::from_secs(2));
+        assert_eq!(auto_start_backoff(1), Duration::from_secs(4));
+        assert_eq!(auto_start_backoff(2), Duration::from_secs(8));
+        assert_eq!(auto_start_backoff(4), Duration::from_secs(30));
+        assert_eq!(auto_start_backoff(40), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn selection_response_accepts_freeform_reasoning() {
+        let response: TaskSelectionResponse = serde_json::from_str(
+            r#"{"task_id": "00000000-0000-0000-0000-000000000001", "reasoning": "Setup first"}"#,
+        )
+        .unwrap();
+        let (reasoning, details) = response.into_reasoning(Uuid::nil(), &[]);
+        assert_eq!(reasoning, "Setup first");
+        assert!(details.is_none());
+    }
+
+    #[test]
+    fn selection_response_parses_structured_reasoning() {
+        let chosen = Uuid::new_v4();
+        let runner_up = Uuid::new_v4();
+        let json = serde_json::json!({
+            "task_id": chosen.to_string(),
+            "runner_up_task_id": runner_up.to_string(),
+            "summary": "Unblocks 3 tasks",
+            "factors": {"priority": "sequence 1", "unblocking": "3 frontend tasks"}
+        });
+        let response: TaskSelectionResponse = serde_json::from_value(json).unwrap();
+
+        let (reasoning, details) = response.into_reasoning(chosen, &[chosen, runner_up]);
+        let details = details.unwrap();
+        assert_eq!(reasoning, "Unblocks 3 tasks");
+        assert_eq!(details.runner_up_task_id, Some(runner_up));
+        assert_eq!(
+            details.factors.unblocking.as_deref(),
+            Some("3 frontend tasks")
+        );
+        assert_eq!(details.factors.layer_readiness, None);
+    }
+
+    #[test]
+    fn selection_response_drops_unknown_runner_up() {
+        let chosen = Uuid::new_v4();
+        let json = serde_json::json!({
+            "task_id": chosen.to_string(),
+            "runner_up_task_id": "not-a-task",
+            "summary": "Only candidate"
+        });
+        let response: TaskSelectionResponse = serde_json::from_value(json).unwrap();
+
+        let (_, details) = response.into_reasoning(chosen, &[chosen]);
+        assert_eq!(details.unwrap().runner_up_task_id, None);
+    }
+
+    #[test]
+    fn repo_name_strategy_uses_repo_for_single_repo_only() {
+        let strategy = WorkingDirStrategy::RepoName;
+        assert_eq!(
+            resolve_agent_working_dir(&strategy, None, &["api"]),
+            Some("api".to_string())
+        );
+        assert_eq!(resolve_agent_working_dir(&strategy, None, &["api", "web"]), None);
+    }
+
+    #[test]
+    fn workspace_root_strategy_always_uses_root() {
+        let strategy = WorkingDirStrategy::WorkspaceRoot;
+        assert_eq!(resolve_agent_working_dir(&strategy, None, &["api"]), None);
+        assert_eq!(resolve_agent_working_dir(&strategy, None, &["api", "web"]), None);
+    }
+
+    #[test]
+    fn explicit_strategy_uses_configured_path() {
+        let strategy = WorkingDirStrategy::Explicit;
+        assert_eq!(
+            resolve_agent_working_dir(&strategy, Some("api/services"), &["api"]),
+            Some("api/services".to_string())
+        );
+        assert_eq!(
+            resolve_agent_working_dir(&strategy, Some("web"), &["api", "web"]),
+            Some("web".to_string())
+        );
+        assert_eq!(resolve_agent_working_dir(&strategy, Some("  "), &["api"]), None);
+    }
+}
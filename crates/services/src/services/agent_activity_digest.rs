@@ -0,0 +1,216 @@
+//! Batching of agent activity notifications into periodic per-project digests.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use tokio::{sync::Mutex, time::Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+use super::notification::NotificationService;
+
+/// Kind of agent activity event that can be summarized in a digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestEvent {
+    Selected,
+    BrokenDown,
+}
+
+/// Events accumulated for a single project since its window opened
+#[derive(Debug)]
+struct DigestEntry {
+    opened_at: Instant,
+    window: Duration,
+    selected: usize,
+    broken_down: usize,
+}
+
+impl DigestEntry {
+    fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.selected > 0 {
+            parts.push(format!(
+                "selected {} task{}",
+                self.selected,
+                if self.selected == 1 { "" } else { "s" }
+            ));
+        }
+        if self.broken_down > 0 {
+            parts.push(format!("broke down {}", self.broken_down));
+        }
+        format!("Agent {}", parts.join(", "))
+    }
+}
+
+/// In-memory buffer of agent activity events keyed by project
+#[derive(Debug, Clone, Default)]
+pub struct ActivityDigest {
+    entries: Arc<Mutex<HashMap<Uuid, DigestEntry>>>,
+}
+
+impl ActivityDigest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an event, opening a new window for the project if none is open
+    pub async fn record(&self, project_id: Uuid, window: Duration, event: DigestEvent) {
+        let mut entries = self.entries.lock().await;
+        let entry = entries.entry(project_id).or_insert_with(|| DigestEntry {
+            opened_at: Instant::now(),
+            window,
+            selected: 0,
+            broken_down: 0,
+        });
+
+        match event {
+            DigestEvent::Selected => entry.selected += 1,
+            DigestEvent::BrokenDown => entry.broken_down += 1,
+        }
+    }
+
+    /// Remove and return the entries of every project whose window has elapsed
+    async fn take_due(&self) -> Vec<(Uuid, DigestEntry)> {
+        let mut entries = self.entries.lock().await;
+        let due_ids: Vec<Uuid> = entries
+            .iter()
+            .filter(|(_, entry)| entry.opened_at.elapsed() >= entry.window)
+            .map(|(id, _)| *id)
+            .collect();
+        due_ids
+            .into_iter()
+            .filter_map(|id| entries.remove(&id).map(|entry| (id, entry)))
+            .collect()
+    }
+
+    /// Send one summary notification for every project whose window has elapsed
+    pub async fn flush_due(&self, notification_service: &NotificationService) {
+        for (project_id, entry) in self.take_due().await {
+            debug!(
+                project_id = %project_id,
+                selected = entry.selected,
+                broken_down = entry.broken_down,
+                "Agent activity: flushing notification digest"
+            );
+            notification_service
+                .notify(project_id, "Agent Activity Digest", &entry.summary())
+                .await;
+        }
+    }
+}
+
+/// Routes agent activity notifications either straight to the user or into a project digest
+#[derive(Clone, Copy)]
+pub struct ActivityNotifier<'a> {
+    notification_service: &'a NotificationService,
+    digest: Option<(&'a ActivityDigest, Duration)>,
+}
+
+impl<'a> ActivityNotifier<'a> {
+    /// Send every event as its own notification (the default)
+    pub fn immediate(notification_service: &'a NotificationService) -> Self {
+        Self {
+            notification_service,
+            digest: None,
+        }
+    }
+
+    /// Buffer events into `digest`, flushed once per `window`
+    pub fn digest(
+        notification_service: &'a NotificationService,
+        digest: &'a ActivityDigest,
+        window: Duration,
+    ) -> Self {
+        Self {
+            notification_service,
+            digest: Some((digest, window)),
+        }
+    }
+
+    pub async fn notify(&self, project_id: Uuid, event: DigestEvent, title: &str, message: &str) {
+        match self.digest {
+            Some((digest, window)) => digest.record(project_id, window, event).await,
+            None => {
+                self.notification_service
+                    .notify(project_id, title, message)
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(selected: usize, broken_down: usize) -> DigestEntry {
+        DigestEntry {
+            opened_at: Instant::now(),
+            window: Duration::ZERO,
+            selected,
+            broken_down,
+        }
+    }
+
+    #[test]
+    fn summary_lists_only_events_that_happened() {
+        assert_eq!(entry(1, 0).summary(), "Agent selected 1 task");
+        assert_eq!(entry(3, 0).summary(), "Agent selected 3 tasks");
+        assert_eq!(entry(0, 2).summary(), "Agent broke down 2");
+        assert_eq!(
+            entry(2, 1).summary(),
+            "Agent selected 2 tasks, broke down 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn record_counts_events_per_project_in_the_first_window() {
+        let digest = ActivityDigest::new();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        digest
+            .record(first, Duration::ZERO, DigestEvent::Selected)
+            .await;
+        digest
+            .record(first, Duration::from_secs(3600), DigestEvent::Selected)
+            .await;
+        digest
+            .record(first, Duration::ZERO, DigestEvent::BrokenDown)
+            .await;
+        digest
+            .record(second, Duration::ZERO, DigestEvent::BrokenDown)
+            .await;
+
+        let entries = digest.entries.lock().await;
+        let first = &entries[&first];
+        assert_eq!((first.selected, first.broken_down), (2, 1));
+        assert_eq!(first.window, Duration::ZERO);
+        let second = &entries[&second];
+        assert_eq!((second.selected, second.broken_down), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn only_elapsed_windows_are_flushed() {
+        let digest = ActivityDigest::new();
+        let (due, open) = (Uuid::new_v4(), Uuid::new_v4());
+        digest
+            .record(due, Duration::ZERO, DigestEvent::Selected)
+            .await;
+        digest
+            .record(open, Duration::from_secs(3600), DigestEvent::Selected)
+            .await;
+
+        let flushed = digest.take_due().await;
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].0, due);
+        assert_eq!(flushed[0].1.summary(), "Agent selected 1 task");
+
+        assert!(digest.take_due().await.is_empty());
+        assert!(digest.entries.lock().await.contains_key(&open));
+
+        digest
+            .record(due, Duration::ZERO, DigestEvent::BrokenDown)
+            .await;
+        let reopened = digest.take_due().await;
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(reopened[0].1.summary(), "Agent broke down 1");
+    }
+}
@@ -177,7 +177,14 @@ impl Approvals {
             ) && let Ok(ctx) =
                 ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
                 && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
+                && let Err(e) = Task::update_status(
+                    pool,
+                    ctx.task.id,
+                    TaskStatus::InProgress,
+                    Some("approvals"),
+                    Some("Approval answered"),
+                )
+                .await
             {
                 tracing::warn!(
                     "Failed to update task status to InProgress after approval response: {}",
@@ -285,7 +292,14 @@ impl Approvals {
 pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_id: Uuid) {
     if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
         && ctx.task.status == TaskStatus::InProgress
-        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InReview).await
+        && let Err(e) = Task::update_status(
+            pool,
+            ctx.task.id,
+            TaskStatus::InReview,
+            Some("approvals"),
+            Some("Waiting for approval"),
+        )
+        .await
     {
         tracing::warn!(
             "Failed to update task status to InReview for approval request: {}",
@@ -57,17 +57,23 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
-        let task_name = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
-            .await
-            .map(|ctx| ctx.task.title)
-            .unwrap_or_else(|_| "Unknown task".to_string());
-
-        self.notification_service
-            .notify(
-                &format!("Approval Needed: {}", task_name),
-                &format!("Tool '{}' requires approval", tool_name),
-            )
-            .await;
+        let message = format!("Tool '{}' requires approval", tool_name);
+        match ExecutionProcess::load_context(&self.db.pool, self.execution_process_id).await {
+            Ok(ctx) => {
+                self.notification_service
+                    .notify(
+                        ctx.task.project_id,
+                        &format!("Approval Needed: {}", ctx.task.title),
+                        &message,
+                    )
+                    .await
+            }
+            Err(_) => {
+                self.notification_service
+                    .notify_global("Approval Needed: Unknown task", &message)
+                    .await
+            }
+        }
 
         let status = waiter.clone().await;
 
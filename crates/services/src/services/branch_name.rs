@@ -0,0 +1,163 @@
+//! Branch names for attempts built from a configurable template, e.g. `feat/{layer}/{slug}`.
+//!
+//! Supported placeholders are `{layer}`, `{type}`, `{slug}` (a short id from the task title)
+//! and `{short_id}` (the first characters of the workspace id). Placeholders for unset task
+//! fields render empty, and the result is sanitized to a valid git branch name.
+
+use db::models::task::Task;
+use utils::text::{git_branch_id, short_uuid};
+use uuid::Uuid;
+
+const PLACEHOLDERS: [&str; 4] = ["{layer}", "{type}", "{slug}", "{short_id}"];
+
+/// Render a branch name for a task's workspace from `template`
+pub fn render_branch_name(template: &str, task: &Task, workspace_id: &Uuid) -> String {
+    let layer = task
+        .layer
+        .as_ref()
+        .map(|l| l.to_string())
+        .unwrap_or_default();
+    let task_type = task
+        .task_type
+        .as_ref()
+        .map(|t| t.to_string())
+        .unwrap_or_default();
+    let rendered = template
+        .replace("{layer}", &layer)
+        .replace("{type}", &task_type)
+        .replace("{slug}", &git_branch_id(&task.title))
+        .replace("{short_id}", &short_uuid(workspace_id));
+
+    let name = sanitize_branch_name(&rendered);
+    if name.is_empty() {
+        short_uuid(workspace_id)
+    } else {
+        name
+    }
+}
+
+/// Reject templates that use placeholders other than the supported ones
+pub fn validate_branch_name_template(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err("unclosed '{' in branch name template".to_string());
+        };
+        let placeholder = &rest[start..start + len + 1];
+        if !PLACEHOLDERS.contains(&placeholder) {
+            return Err(format!(
+                "unknown placeholder {placeholder} (expected one of {})",
+                PLACEHOLDERS.join(", ")
+            ));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    Ok(())
+}
+
+/// Replace characters git doesn't allow in refs with hyphens and drop empty or dot-led path
+/// components, so the result passes `git check-ref-format --branch`
+fn sanitize_branch_name(name: &str) -> String {
+    name.split('/')
+        .map(|component| {
+            let mut cleaned = String::new();
+            for c in component.chars() {
+                let c = if c.is_ascii_alphanumeric() || matches!(c, '_' | '.') {
+                    c
+                } else {
+                    '-'
+                };
+                // Collapse runs of hyphens and the ".." sequence git forbids
+                if (c == '-' || c == '.') && cleaned.ends_with(c) {
+                    continue;
+                }
+                cleaned.push(c);
+            }
+            let cleaned = cleaned.trim_matches(|c| c == '-' || c == '.');
+            cleaned.strip_suffix(".lock").unwrap_or(cleaned).to_string()
+        })
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::task::{TaskLayer, TaskType};
+
+    use super::*;
+    use crate::services::test_support;
+
+    fn task(title: &str, layer: Option<TaskLayer>, task_type: Option<TaskType>) -> Task {
+        Task {
+            layer,
+            task_type,
+            ..test_support::task(title)
+        }
+    }
+
+    fn workspace_id() -> Uuid {
+        Uuid::parse_str("abcd1234-0000-0000-0000-000000000000").unwrap()
+    }
+
+    #[test]
+    fn renders_each_placeholder() {
+        let task = task(
+            "Add login form",
+            Some(TaskLayer::Frontend),
+            Some(TaskType::Implementation),
+        );
+        let render = |template| render_branch_name(template, &task, &workspace_id());
+
+        assert_eq!(render("{layer}"), "frontend");
+        assert_eq!(render("{type}"), "implementation");
+        assert_eq!(render("{slug}"), "add-login-form");
+        assert_eq!(render("{short_id}"), "abcd");
+        assert_eq!(
+            render("feat/{layer}/{short_id}-{slug}"),
+            "feat/frontend/abcd-add-login-form"
+        );
+    }
+
+    #[test]
+    fn missing_task_fields_drop_their_path_component() {
+        let task = task("Add login form", None, None);
+        assert_eq!(
+            render_branch_name("feat/{layer}/{slug}", &task, &workspace_id()),
+            "feat/add-login-form"
+        );
+        assert_eq!(render_branch_name("{type}", &task, &workspace_id()), "abcd");
+    }
+
+    #[test]
+    fn titles_and_templates_are_sanitized() {
+        for (template, title) in [
+            ("feat/{slug}", "Fix a/b paths"),
+            ("feat/{slug}", "Add spaces   and\ttabs"),
+            ("feat/{slug}", "Übersetzung für Ñandú 日本語"),
+            ("my team/{slug}..x", "Title"),
+            ("/.hidden//{slug}.lock", "Title"),
+        ] {
+            let name = render_branch_name(template, &task(title, None, None), &workspace_id());
+            assert!(
+                git2::Branch::name_is_valid(&name).unwrap(),
+                "{template} with {title:?} rendered invalid branch {name:?}"
+            );
+        }
+        assert_eq!(
+            render_branch_name(
+                "my team/{slug}",
+                &task("Title", None, None),
+                &workspace_id()
+            ),
+            "my-team/title"
+        );
+    }
+
+    #[test]
+    fn validates_placeholders() {
+        assert!(validate_branch_name_template("feat/{layer}/{type}/{short_id}-{slug}").is_ok());
+        assert!(validate_branch_name_template("feat/{title}").is_err());
+        assert!(validate_branch_name_template("feat/{slug").is_err());
+    }
+}
@@ -1,16 +1,28 @@
 //! Claude API client for AI-powered features.
 
-use std::time::Duration;
+use std::{
+    sync::{
+        OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
 use backon::{ExponentialBuilder, Retryable};
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::sync::{Semaphore, SemaphorePermit};
 use tracing::warn;
+use ts_rs::TS;
 
 const CLAUDE_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
 const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// Anthropic's status code for a temporarily overloaded API
+const OVERLOADED_STATUS: u16 = 529;
+/// Claude requests in flight at once when CLAUDE_MAX_CONCURRENT_CALLS is not set
+const DEFAULT_MAX_CONCURRENT_CALLS: usize = 4;
 
 #[derive(Debug, Clone, Error)]
 pub enum ClaudeApiError {
@@ -22,6 +34,8 @@ pub enum ClaudeApiError {
     Http { status: u16, body: String },
     #[error("rate limited")]
     RateLimited,
+    #[error("api overloaded")]
+    Overloaded,
     #[error("invalid api key")]
     InvalidApiKey,
     #[error("json error: {0}")]
@@ -34,11 +48,187 @@ impl ClaudeApiError {
     /// Returns true if the error is transient and should be retried.
     pub fn should_retry(&self) -> bool {
         match self {
-            Self::Transport(_) | Self::Timeout | Self::RateLimited => true,
+            Self::Transport(_) | Self::Timeout | Self::RateLimited | Self::Overloaded => true,
             Self::Http { status, .. } => (500..=599).contains(status),
             _ => false,
         }
     }
+
+    /// Delay before retrying after this error, given the default backoff delay. Overload
+    /// clears on a longer timescale than other server errors, so wait more patiently.
+    pub fn retry_delay(&self, default: Duration) -> Duration {
+        match self {
+            Self::Overloaded => {
+                (default * 5).clamp(Duration::from_secs(10), Duration::from_secs(120))
+            }
+            _ => default,
+        }
+    }
+}
+
+/// Whether the configured API key can reach Claude
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaudeHealthStatus {
+    Ok,
+    MissingKey,
+    InvalidKey,
+    Unreachable,
+}
+
+/// Result of [`ClaudeApiClient::verify`]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ClaudeHealth {
+    pub status: ClaudeHealthStatus,
+    pub error: Option<String>,
+    pub queue: CallQueueStatus,
+}
+
+impl ClaudeHealth {
+    /// Verify the key from ANTHROPIC_API_KEY, reporting a missing key instead of failing
+    pub async fn check() -> Self {
+        match ClaudeApiClient::from_env() {
+            Ok(client) => client.verify().await,
+            Err(e) => Self::from_result(Err(e)),
+        }
+    }
+
+    /// An authenticated response means the key works, even if it was rate limited or
+    /// couldn't be parsed
+    fn from_result(result: Result<(), ClaudeApiError>) -> Self {
+        let status = match &result {
+            Ok(()) | Err(ClaudeApiError::RateLimited | ClaudeApiError::Serde(_)) => {
+                ClaudeHealthStatus::Ok
+            }
+            Err(ClaudeApiError::MissingApiKey) => ClaudeHealthStatus::MissingKey,
+            Err(ClaudeApiError::InvalidApiKey | ClaudeApiError::Http { status: 403, .. }) => {
+                ClaudeHealthStatus::InvalidKey
+            }
+            Err(_) => ClaudeHealthStatus::Unreachable,
+        };
+        Self {
+            status,
+            error: result.err().map(|e| e.to_string()),
+            queue: CallQueue::global().status(),
+        }
+    }
+}
+
+/// Snapshot of the shared Claude call queue
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct CallQueueStatus {
+    pub max_concurrent: usize,
+    pub in_flight: usize,
+    pub queued: usize,
+}
+
+/// Bounds how many Claude requests are in flight at once across every client, so background
+/// services working on many projects queue up instead of running into rate limits
+#[derive(Debug)]
+pub struct CallQueue {
+    permits: Semaphore,
+    max_concurrent: usize,
+    queued: AtomicUsize,
+}
+
+impl CallQueue {
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            permits: Semaphore::new(max_concurrent),
+            max_concurrent,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// The queue all clients share, sized by CLAUDE_MAX_CONCURRENT_CALLS
+    pub fn global() -> &'static Self {
+        static QUEUE: OnceLock<CallQueue> = OnceLock::new();
+        QUEUE.get_or_init(|| {
+            let max_concurrent = std::env::var("CLAUDE_MAX_CONCURRENT_CALLS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_CALLS);
+            Self::new(max_concurrent)
+        })
+    }
+
+    /// Wait for a free slot. The request counts as in flight until the permit is dropped.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        struct Waiting<'a>(&'a AtomicUsize);
+        impl Drop for Waiting<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        let _waiting = Waiting(&self.queued);
+        self.permits
+            .acquire()
+            .await
+            .expect("the call queue semaphore is never closed")
+    }
+
+    pub fn status(&self) -> CallQueueStatus {
+        CallQueueStatus {
+            max_concurrent: self.max_concurrent,
+            in_flight: self.max_concurrent - self.permits.available_permits(),
+            queued: self.queued.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// The AI features that can each be served by a different model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelRole {
+    Selection,
+    Complexity,
+    Requirements,
+    ConflictBreakdown,
+    CriteriaCheck,
+}
+
+/// Model overrides per AI feature, e.g. a cheap model for the frequent selection and
+/// complexity calls. Roles without an override use the client's model.
+#[derive(Debug, Clone, Default)]
+pub struct ModelRoles {
+    pub selection: Option<String>,
+    pub complexity: Option<String>,
+    pub requirements: Option<String>,
+    pub conflict_breakdown: Option<String>,
+    pub criteria_check: Option<String>,
+}
+
+impl ModelRoles {
+    /// Read overrides from CLAUDE_MODEL_SELECTION, CLAUDE_MODEL_COMPLEXITY,
+    /// CLAUDE_MODEL_REQUIREMENTS, CLAUDE_MODEL_CONFLICT_BREAKDOWN and
+    /// CLAUDE_MODEL_CRITERIA_CHECK
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            selection: var("CLAUDE_MODEL_SELECTION"),
+            complexity: var("CLAUDE_MODEL_COMPLEXITY"),
+            requirements: var("CLAUDE_MODEL_REQUIREMENTS"),
+            conflict_breakdown: var("CLAUDE_MODEL_CONFLICT_BREAKDOWN"),
+            criteria_check: var("CLAUDE_MODEL_CRITERIA_CHECK"),
+        }
+    }
+
+    fn get(&self, role: ModelRole) -> Option<&str> {
+        match role {
+            ModelRole::Selection => self.selection.as_deref(),
+            ModelRole::Complexity => self.complexity.as_deref(),
+            ModelRole::Requirements => self.requirements.as_deref(),
+            ModelRole::ConflictBreakdown => self.conflict_breakdown.as_deref(),
+            ModelRole::CriteriaCheck => self.criteria_check.as_deref(),
+        }
+    }
 }
 
 /// A message in the conversation
@@ -112,18 +302,22 @@ pub struct Usage {
 #[derive(Debug, Clone)]
 pub struct ClaudeApiClient {
     http: Client,
+    api_url: String,
     api_key: String,
     model: String,
+    roles: ModelRoles,
+    queue: &'static CallQueue,
 }
 
 impl ClaudeApiClient {
     const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+    const VERIFY_TIMEOUT: Duration = Duration::from_secs(15);
 
     /// Create a new client using the ANTHROPIC_API_KEY environment variable
     pub fn from_env() -> Result<Self, ClaudeApiError> {
         let api_key =
             std::env::var("ANTHROPIC_API_KEY").map_err(|_| ClaudeApiError::MissingApiKey)?;
-        Self::new(api_key, None)
+        Ok(Self::new(api_key, None)?.with_model_roles(ModelRoles::from_env()))
     }
 
     /// Create a new client with the given API key
@@ -136,48 +330,89 @@ impl ClaudeApiClient {
 
         Ok(Self {
             http,
+            api_url: CLAUDE_API_URL.to_string(),
             api_key,
             model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            roles: ModelRoles::default(),
+            queue: CallQueue::global(),
         })
     }
 
-    /// Send a completion request to Claude
+    pub fn with_model_roles(mut self, roles: ModelRoles) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    /// Send requests to `api_url` instead of the Anthropic API, e.g. a local stub in tests
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
+    /// The model to use for an AI feature
+    pub fn model_for(&self, role: ModelRole) -> &str {
+        self.roles.get(role).unwrap_or(&self.model)
+    }
+
+    /// Send a completion request to Claude using the given model
     pub async fn complete(
         &self,
+        model: &str,
         messages: Vec<Message>,
         system: Option<String>,
         max_tokens: u32,
     ) -> Result<ClaudeResponse, ClaudeApiError> {
         let request = ClaudeRequest {
-            model: self.model.clone(),
+            model: model.to_string(),
             max_tokens,
             messages,
             system,
         };
 
-        (|| async { self.send_request(&request).await })
-            .retry(
-                &ExponentialBuilder::default()
-                    .with_min_delay(Duration::from_secs(1))
-                    .with_max_delay(Duration::from_secs(30))
-                    .with_max_times(3)
-                    .with_jitter(),
+        // Hold a queue slot only while the request is sent, not while backing off
+        let send = || async {
+            let _permit = self.queue.acquire().await;
+            self.send_request(&request).await
+        };
+        send.retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &ClaudeApiError| e.should_retry())
+        .adjust(|e, dur| dur.map(|d| e.retry_delay(d)))
+        .notify(|e, dur| {
+            warn!(
+                "Claude API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                e
             )
-            .when(|e: &ClaudeApiError| e.should_retry())
-            .notify(|e, dur| {
-                warn!(
-                    "Claude API call failed, retrying after {:.2}s: {}",
-                    dur.as_secs_f64(),
-                    e
-                )
-            })
+        })
+        .await
+    }
+
+    /// Check the key works by sending a 1-token request, without retries. Skips the call
+    /// queue so health checks answer promptly under load.
+    pub async fn verify(&self) -> ClaudeHealth {
+        let request = ClaudeRequest {
+            model: self.model.clone(),
+            max_tokens: 1,
+            messages: vec![Message::user("ping")],
+            system: None,
+        };
+        let result = tokio::time::timeout(Self::VERIFY_TIMEOUT, self.send_request(&request))
             .await
+            .unwrap_or(Err(ClaudeApiError::Timeout))
+            .map(|_| ());
+        ClaudeHealth::from_result(result)
     }
 
     async fn send_request(&self, request: &ClaudeRequest) -> Result<ClaudeResponse, ClaudeApiError> {
         let res = self
             .http
-            .post(CLAUDE_API_URL)
+            .post(&self.api_url)
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", ANTHROPIC_VERSION)
             .header("content-type", "application/json")
@@ -194,6 +429,7 @@ impl ClaudeApiClient {
             }
             StatusCode::UNAUTHORIZED => Err(ClaudeApiError::InvalidApiKey),
             StatusCode::TOO_MANY_REQUESTS => Err(ClaudeApiError::RateLimited),
+            s if s.as_u16() == OVERLOADED_STATUS => Err(ClaudeApiError::Overloaded),
             s => {
                 let status = s.as_u16();
                 let body = res.text().await.unwrap_or_default();
@@ -205,11 +441,17 @@ impl ClaudeApiClient {
     /// Simple helper to send a single user message and get a response
     pub async fn ask(
         &self,
+        role: ModelRole,
         prompt: &str,
         system: Option<String>,
     ) -> Result<String, ClaudeApiError> {
         let response = self
-            .complete(vec![Message::user(prompt)], system, 4096)
+            .complete(
+                self.model_for(role),
+                vec![Message::user(prompt)],
+                system,
+                4096,
+            )
             .await?;
 
         response
@@ -221,21 +463,29 @@ impl ClaudeApiClient {
     /// Send a prompt expecting JSON in the response
     pub async fn ask_json<T: for<'de> Deserialize<'de>>(
         &self,
+        role: ModelRole,
         prompt: &str,
         system: Option<String>,
     ) -> Result<T, ClaudeApiError> {
-        self.ask_json_with_max_tokens(prompt, system, 4096).await
+        self.ask_json_with_max_tokens(role, prompt, system, 4096)
+            .await
     }
 
     /// Send a prompt expecting JSON in the response with custom max_tokens
     pub async fn ask_json_with_max_tokens<T: for<'de> Deserialize<'de>>(
         &self,
+        role: ModelRole,
         prompt: &str,
         system: Option<String>,
         max_tokens: u32,
     ) -> Result<T, ClaudeApiError> {
         let response = self
-            .complete(vec![Message::user(prompt)], system, max_tokens)
+            .complete(
+                self.model_for(role),
+                vec![Message::user(prompt)],
+                system,
+                max_tokens,
+            )
             .await?
             .text()
             .map(|s| s.to_string())
@@ -310,6 +560,125 @@ fn extract_json(text: &str) -> &str {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn call_queue_holds_calls_beyond_the_limit() {
+        let queue = CallQueue::new(1);
+        let first = queue.acquire().await;
+        let mut second = std::pin::pin!(queue.acquire());
+        assert!(futures::poll!(second.as_mut()).is_pending());
+        assert_eq!(
+            queue.status(),
+            CallQueueStatus {
+                max_concurrent: 1,
+                in_flight: 1,
+                queued: 1,
+            }
+        );
+
+        drop(first);
+        let _second = second.await;
+        assert_eq!(queue.status().in_flight, 1);
+        assert_eq!(queue.status().queued, 0);
+    }
+
+    #[tokio::test]
+    async fn abandoned_waits_leave_the_queue() {
+        let queue = CallQueue::new(1);
+        let _first = queue.acquire().await;
+        let waited = tokio::time::timeout(Duration::from_millis(10), queue.acquire()).await;
+        assert!(waited.is_err());
+        assert_eq!(queue.status().queued, 0);
+    }
+
+    #[test]
+    fn test_health_from_result() {
+        let status = |result| ClaudeHealth::from_result(result).status;
+        assert_eq!(status(Ok(())), ClaudeHealthStatus::Ok);
+        assert_eq!(
+            status(Err(ClaudeApiError::RateLimited)),
+            ClaudeHealthStatus::Ok
+        );
+        assert_eq!(
+            status(Err(ClaudeApiError::InvalidApiKey)),
+            ClaudeHealthStatus::InvalidKey
+        );
+        assert_eq!(
+            status(Err(ClaudeApiError::MissingApiKey)),
+            ClaudeHealthStatus::MissingKey
+        );
+        assert_eq!(
+            status(Err(ClaudeApiError::Timeout)),
+            ClaudeHealthStatus::Unreachable
+        );
+        assert_eq!(
+            status(Err(ClaudeApiError::Http {
+                status: 503,
+                body: String::new()
+            })),
+            ClaudeHealthStatus::Unreachable
+        );
+        assert!(ClaudeHealth::from_result(Ok(())).error.is_none());
+    }
+
+    #[test]
+    fn test_overloaded_retries_with_longer_backoff() {
+        assert!(ClaudeApiError::Overloaded.should_retry());
+
+        let default = Duration::from_secs(2);
+        let server_error = ClaudeApiError::Http {
+            status: 503,
+            body: String::new(),
+        };
+        assert_eq!(server_error.retry_delay(default), default);
+        assert_eq!(
+            ClaudeApiError::Overloaded.retry_delay(default),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            ClaudeApiError::Overloaded.retry_delay(Duration::from_secs(30)),
+            Duration::from_secs(120)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overloaded_status_maps_to_overloaded() {
+        let app = axum::Router::new().route(
+            "/v1/messages",
+            axum::routing::post(|| async {
+                axum::http::StatusCode::from_u16(OVERLOADED_STATUS).unwrap()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let client = ClaudeApiClient::new("test-key".to_string(), None)
+            .unwrap()
+            .with_api_url(format!("http://{addr}/v1/messages"));
+
+        let request = ClaudeRequest {
+            model: client.model.clone(),
+            max_tokens: 1,
+            messages: vec![Message::user("ping")],
+            system: None,
+        };
+        assert!(matches!(
+            client.send_request(&request).await,
+            Err(ClaudeApiError::Overloaded)
+        ));
+    }
+
+    #[test]
+    fn test_model_roles_default_to_client_model() {
+        let client = ClaudeApiClient::new("key".to_string(), Some("base".to_string()))
+            .unwrap()
+            .with_model_roles(ModelRoles {
+                selection: Some("cheap".to_string()),
+                ..Default::default()
+            });
+        assert_eq!(client.model_for(ModelRole::Selection), "cheap");
+        assert_eq!(client.model_for(ModelRole::Requirements), "base");
+    }
+
     #[test]
     fn test_extract_json_plain() {
         let input = r#"{"key": "value"}"#;
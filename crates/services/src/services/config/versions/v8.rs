@@ -47,6 +47,30 @@ pub struct Config {
     pub beta_workspaces_invitation_sent: bool,
     #[serde(default)]
     pub commit_reminder: bool,
+    /// Host-wide cap on attempts auto-started by agent activity across all projects (None: no cap)
+    #[serde(default)]
+    pub max_concurrent_attempts: Option<u32>,
+    /// Template for attempt branch names, e.g. `feat/{layer}/{short_id}-{slug}` (None: use
+    /// `git_branch_prefix`)
+    #[serde(default)]
+    pub branch_name_template: Option<String>,
+    /// Programs project review settings may use for test and pre-merge commands (None: the
+    /// built-in allowlist)
+    #[serde(default)]
+    pub test_command_allowlist: Option<Vec<String>>,
+    /// Let project test and pre-merge commands use shell metacharacters such as `&&` and `|`
+    #[serde(default)]
+    pub allow_shell_in_test_commands: bool,
+    /// Seconds between review automation polls (None: 10)
+    #[serde(default)]
+    pub review_poll_interval_secs: Option<u64>,
+    /// Seconds between task timeout checks (None: 10)
+    #[serde(default)]
+    pub task_timeout_poll_interval_secs: Option<u64>,
+    /// Projects the agent activity and review automation services each process at once
+    /// (None: 4)
+    #[serde(default)]
+    pub background_project_concurrency: Option<usize>,
 }
 
 impl Config {
@@ -75,6 +99,13 @@ impl Config {
             beta_workspaces: false,
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
+            max_concurrent_attempts: None,
+            branch_name_template: None,
+            test_command_allowlist: None,
+            allow_shell_in_test_commands: false,
+            review_poll_interval_secs: None,
+            task_timeout_poll_interval_secs: None,
+            background_project_concurrency: None,
         }
     }
 
@@ -128,6 +159,13 @@ impl Default for Config {
             beta_workspaces: false,
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
+            max_concurrent_attempts: None,
+            branch_name_template: None,
+            test_command_allowlist: None,
+            allow_shell_in_test_commands: false,
+            review_poll_interval_secs: None,
+            task_timeout_poll_interval_secs: None,
+            background_project_concurrency: None,
         }
     }
 }
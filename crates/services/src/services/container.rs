@@ -20,7 +20,7 @@ use db::{
         },
         repo::Repo,
         session::{CreateSession, Session, SessionError},
-        task::{Task, TaskStatus},
+        task::{Task, TaskError, TaskStatus},
         workspace::{Workspace, WorkspaceError},
         workspace_repo::WorkspaceRepo,
     },
@@ -51,6 +51,7 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    branch_name::render_branch_name,
     git::{GitService, GitServiceError},
     notification::NotificationService,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
@@ -74,6 +75,8 @@ pub enum ContainerError {
     WorkspaceManager(#[from] WorkspaceManagerError),
     #[error(transparent)]
     Session(#[from] SessionError),
+    #[error(transparent)]
+    Task(#[from] TaskError),
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
@@ -160,6 +163,46 @@ pub trait ContainerService {
         action.next_action.is_none()
     }
 
+    /// Record how many commits the attempt added across its repos (best-effort)
+    async fn record_commit_count(&self, ctx: &ExecutionContext) {
+        let Some(container_ref) = &ctx.workspace.container_ref else {
+            return;
+        };
+        let workspace_root = PathBuf::from(container_ref);
+        let Ok(workspace_repos) =
+            WorkspaceRepo::find_by_workspace_id(&self.db().pool, ctx.workspace.id).await
+        else {
+            return;
+        };
+
+        let mut total = 0;
+        for repo in &ctx.repos {
+            let Some(workspace_repo) = workspace_repos.iter().find(|wr| wr.repo_id == repo.id)
+            else {
+                continue;
+            };
+            match self.git().commit_count_between(
+                &workspace_root.join(&repo.name),
+                &workspace_repo.target_branch,
+                &ctx.workspace.branch,
+            ) {
+                Ok(count) => total += count,
+                Err(e) => {
+                    tracing::warn!(
+                        task_id = %ctx.task.id,
+                        repo = %repo.name,
+                        "Failed to count attempt commits: {e}"
+                    );
+                    return;
+                }
+            }
+        }
+
+        if let Err(e) = Task::set_commit_count(&self.db().pool, ctx.task.id, total as i32).await {
+            tracing::warn!(task_id = %ctx.task.id, "Failed to record commit count: {e}");
+        }
+    }
+
     /// Finalize task execution by updating status to InReview and sending notifications
     async fn finalize_task(&self, ctx: &ExecutionContext) {
         // Validate database state if task involved database changes
@@ -197,11 +240,18 @@ pub trait ContainerService {
             }
         }
 
-        if let Err(e) =
-            Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await
+        if let Err(e) = Task::update_status(
+            &self.db().pool,
+            ctx.task.id,
+            TaskStatus::InReview,
+            Some("execution"),
+            Some("Execution finished"),
+        )
+        .await
         {
             tracing::error!("Failed to update task status to InReview: {e}");
         }
+        self.record_commit_count(ctx).await;
 
         // Skip notification if process was intentionally killed by user
         if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
@@ -226,7 +276,9 @@ pub trait ContainerService {
                 return;
             }
         };
-        self.notification_service().notify(&title, &message).await;
+        self.notification_service()
+            .notify(ctx.task.project_id, &title, &message)
+            .await;
     }
 
     /// Cleanup executions marked as running in the db, call at startup
@@ -292,8 +344,14 @@ pub trait ContainerService {
                 && let Ok(Some(workspace)) =
                     Workspace::find_by_id(&self.db().pool, session.workspace_id).await
                 && let Ok(Some(task)) = workspace.parent_task(&self.db().pool).await
-                && let Err(e) =
-                    Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await
+                && let Err(e) = Task::update_status(
+                    &self.db().pool,
+                    task.id,
+                    TaskStatus::InReview,
+                    Some("execution"),
+                    Some("Orphaned execution marked as failed"),
+                )
+                .await
             {
                 tracing::error!(
                     "Failed to update task status to InReview for orphaned session: {}",
@@ -567,8 +625,17 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
-    async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
-        let task_title_id = git_branch_id(task_title);
+    /// Branch naming template from the config; `None` keeps the `<prefix>/<id>-<slug>` format
+    async fn branch_name_template(&self) -> Option<String>;
+
+    async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task: &Task) -> String {
+        if let Some(template) = self.branch_name_template().await
+            && !template.trim().is_empty()
+        {
+            return render_branch_name(&template, task, workspace_id);
+        }
+
+        let task_title_id = git_branch_id(&task.title);
         let prefix = self.git_branch_prefix().await;
 
         if prefix.is_empty() {
@@ -879,6 +946,17 @@ pub trait ContainerService {
         &self,
         workspace: &Workspace,
         executor_profile_id: ExecutorProfileId,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        self.start_workspace_with_context(workspace, executor_profile_id, None)
+            .await
+    }
+
+    /// Start a workspace, appending `prompt_context` (e.g. reviewer feedback) to the task prompt
+    async fn start_workspace_with_context(
+        &self,
+        workspace: &Workspace,
+        executor_profile_id: ExecutorProfileId,
+        prompt_context: Option<&str>,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Create container
         self.create(workspace).await?;
@@ -906,7 +984,10 @@ pub trait ContainerService {
         )
         .await?;
 
-        let prompt = task.to_prompt();
+        let prompt = match prompt_context.filter(|context| !context.trim().is_empty()) {
+            Some(context) => format!("{}\n\n{}", task.to_prompt(), context),
+            None => task.to_prompt(),
+        };
 
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -982,7 +1063,26 @@ pub trait ContainerService {
         if task.status != TaskStatus::InProgress
             && run_reason != &ExecutionProcessRunReason::DevServer
         {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+            // Starting work on a finished task (e.g. a follow-up) deliberately reopens it
+            if matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled) {
+                Task::update_status_force(
+                    &self.db().pool,
+                    task.id,
+                    TaskStatus::InProgress,
+                    Some("execution"),
+                    Some("Reopened by a new execution"),
+                )
+                .await?;
+            } else {
+                Task::update_status(
+                    &self.db().pool,
+                    task.id,
+                    TaskStatus::InProgress,
+                    Some("execution"),
+                    Some("Execution started"),
+                )
+                .await?;
+            }
         }
         // Create new execution process record
         // Capture current HEAD per repository as the "before" commit for this execution
@@ -1073,7 +1173,14 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            Task::update_status(
+                &self.db().pool,
+                task.id,
+                TaskStatus::InReview,
+                Some("execution"),
+                Some("Execution failed to start"),
+            )
+            .await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
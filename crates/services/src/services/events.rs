@@ -49,13 +49,14 @@ impl EventService {
         task_id: Uuid,
     ) -> Result<(), SqlxError> {
         if let Some(task) = Task::find_by_id(pool, task_id).await? {
-            let tasks = Task::find_by_project_id_with_attempt_status(pool, task.project_id).await?;
+            let tasks =
+                Task::find_by_project_id_with_attempt_status(pool, task.project_id, true).await?;
 
             if let Some(task_with_status) = tasks
                 .into_iter()
                 .find(|task_with_status| task_with_status.id == task_id)
             {
-                msg_store.push_patch(task_patch::replace(&task_with_status));
+                msg_store.push_patch(task_patch::sync(&task_with_status));
             }
         }
 
@@ -276,20 +277,14 @@ impl EventService {
                                         Task::find_by_project_id_with_attempt_status(
                                             &db.pool,
                                             task.project_id,
+                                            true,
                                         )
                                         .await
                                         && let Some(task_with_status) =
                                             task_list.into_iter().find(|t| t.id == task.id)
                                     {
-                                        let patch = match hook.operation {
-                                            SqliteOperation::Insert => {
-                                                task_patch::add(&task_with_status)
-                                            }
-                                            SqliteOperation::Update => {
-                                                task_patch::replace(&task_with_status)
-                                            }
-                                            _ => task_patch::replace(&task_with_status), // fallback
-                                        };
+                                        // Archiving takes the task off the live board
+                                        let patch = task_patch::sync(&task_with_status);
                                         msg_store_for_hook.push_patch(patch);
                                         return;
                                     }
@@ -351,12 +346,13 @@ impl EventService {
                                             Task::find_by_project_id_with_attempt_status(
                                                 &db.pool,
                                                 task.project_id,
+                                                true,
                                             )
                                             .await
                                         && let Some(task_with_status) =
                                             task_list.into_iter().find(|t| t.id == workspace.task_id)
                                     {
-                                        let patch = task_patch::replace(&task_with_status);
+                                        let patch = task_patch::sync(&task_with_status);
                                         msg_store_for_hook.push_patch(patch);
                                     }
                                     return;
@@ -372,12 +368,13 @@ impl EventService {
                                             Task::find_by_project_id_with_attempt_status(
                                                 &db.pool,
                                                 task.project_id,
+                                                true,
                                             )
                                             .await
                                         && let Some(task_with_status) =
                                             task_list.into_iter().find(|t| t.id == *task_id)
                                     {
-                                        let patch = task_patch::replace(&task_with_status);
+                                        let patch = task_patch::sync(&task_with_status);
                                         msg_store_for_hook.push_patch(patch);
                                     }
                                     return;
@@ -46,6 +46,17 @@ pub mod task_patch {
                 .expect("Task path should be valid"),
         })])
     }
+
+    /// Create patch for a created or changed task. The board only shows unarchived tasks, so
+    /// archived tasks are removed and the rest are added, which also replaces an existing entry
+    /// and brings back an unarchived one.
+    pub fn sync(task: &TaskWithAttemptStatus) -> Patch {
+        if task.archived {
+            remove(task.id)
+        } else {
+            add(task)
+        }
+    }
 }
 
 /// Helper functions for creating project-specific patches
@@ -214,3 +225,45 @@ pub mod scratch_patch {
         })])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use db::models::task::Task;
+
+    use super::*;
+    use crate::services::test_support;
+
+    fn board_task(archived: bool) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            task: Task {
+                archived,
+                ..test_support::task("Add login")
+            },
+            has_in_progress_attempt: false,
+            last_attempt_failed: false,
+            executor: String::new(),
+        }
+    }
+
+    #[test]
+    fn archived_tasks_leave_the_board() {
+        let task = board_task(true);
+        let patch = task_patch::sync(&task);
+        assert_eq!(patch.0.len(), 1);
+        let PatchOperation::Remove(op) = &patch.0[0] else {
+            panic!("expected a remove, got {patch:?}");
+        };
+        assert_eq!(op.path.to_string(), format!("/tasks/{}", task.id));
+    }
+
+    #[test]
+    fn unarchived_tasks_are_added_or_replaced() {
+        let task = board_task(false);
+        let patch = task_patch::sync(&task);
+        let PatchOperation::Add(op) = &patch.0[0] else {
+            panic!("expected an add, got {patch:?}");
+        };
+        assert_eq!(op.path.to_string(), format!("/tasks/{}", task.id));
+        assert_eq!(op.value["title"], "Add login");
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
 
 use chrono::{DateTime, Utc};
 use git2::{
@@ -9,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
 use utils::diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts};
+use uuid::Uuid;
 
 mod cli;
 
@@ -37,11 +42,18 @@ pub enum GitServiceError {
     WorktreeDirty(String, String),
     #[error("Rebase in progress; resolve or abort it before retrying")]
     RebaseInProgress,
+    #[error("Branch {0} is already checked out at {1}")]
+    BranchCheckedOut(String, String),
+    #[error("Commit {0} has already been reverted")]
+    AlreadyReverted(String),
 }
 /// Service for managing Git operations in task execution workflows
 #[derive(Clone)]
 pub struct GitService {}
 
+/// Commit message trailer (and git note) key linking a merge commit to its task
+pub const TASK_ID_TRAILER: &str = "Task-Id";
+
 // Max inline diff size for UI (in bytes). Files larger than this will have
 // their contents omitted from the diff stream to avoid UI crashes.
 const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
@@ -291,6 +303,41 @@ impl GitService {
         Ok(true)
     }
 
+    /// Stage and commit only `paths`, leaving any other changes in the worktree untouched
+    pub fn commit_paths(
+        &self,
+        path: &Path,
+        paths: &[&str],
+        message: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        let mut args = vec!["add", "--"];
+        args.extend_from_slice(paths);
+        git.git(path, args)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        self.ensure_cli_commit_identity(path)?;
+        let mut args = vec!["commit", "-m", message, "--"];
+        args.extend_from_slice(paths);
+        git.git(path, args)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        Ok(())
+    }
+
+    /// Fold the changes to `paths` into the HEAD commit, keeping its message
+    pub fn amend_paths(&self, path: &Path, paths: &[&str]) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        let mut args = vec!["add", "--"];
+        args.extend_from_slice(paths);
+        git.git(path, args)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        self.ensure_cli_commit_identity(path)?;
+        let mut args = vec!["commit", "--amend", "--no-edit", "--"];
+        args.extend_from_slice(paths);
+        git.git(path, args)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        Ok(())
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
@@ -793,6 +840,41 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
     ) -> Result<String, GitServiceError> {
+        self.merge_changes_with_signing(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            false,
+        )
+    }
+
+    /// Like [`Self::merge_changes`], optionally signing the squash commit with the repo's
+    /// `user.signingkey`. If signing is requested but no key is configured, the commit is
+    /// created unsigned and a warning is logged.
+    pub fn merge_changes_with_signing(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        sign_commits: bool,
+    ) -> Result<String, GitServiceError> {
+        let signing_key = if sign_commits {
+            let key = GitCli::new().signing_key(base_worktree_path);
+            if key.is_none() {
+                tracing::warn!(
+                    "Commit signing requested but no user.signingkey is configured for {}; merging unsigned",
+                    base_worktree_path.display()
+                );
+            }
+            key
+        } else {
+            None
+        };
+
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
         let base_repo = self.open_repo(base_worktree_path)?;
@@ -835,6 +917,7 @@ impl GitService {
                         base_branch_name,
                         task_branch_name,
                         commit_message,
+                        signing_key.as_deref(),
                     )
                     .map_err(|e| {
                         GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
@@ -868,6 +951,7 @@ impl GitService {
                     &signature,
                     commit_message,
                     base_branch_name,
+                    signing_key.as_deref(),
                 )?;
 
                 // Update the task branch to the new squash commit so follow-up
@@ -884,6 +968,563 @@ impl GitService {
             }
         }
     }
+
+    /// Number of commits on `branch_name` that are not on `target_branch`,
+    /// like `git rev-list --count target..branch`
+    pub fn commit_count_between(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+        branch_name: &str,
+    ) -> Result<usize, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let branch = Self::find_branch(&repo, branch_name)?;
+        let target = Self::find_branch(&repo, target_branch)?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(branch.get().peel_to_commit()?.id())?;
+        revwalk.hide(target.get().peel_to_commit()?.id())?;
+        Ok(revwalk.count())
+    }
+
+    /// Cherry-pick `commit` (a sha or branch name) onto `target_branch`, keeping its author and
+    /// message, and return the new commit's sha. A conflicting cherry-pick is aborted and
+    /// reported as [`GitServiceError::MergeConflicts`].
+    pub fn cherry_pick(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+        target_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let pick = repo.revparse_single(commit)?.peel_to_commit()?;
+        let pick_sha = pick.id().to_string();
+
+        match self.find_checkout_path_for_branch(repo_path, target_branch)? {
+            Some(target_checkout_path) => {
+                // target branch is checked out somewhere - cherry-pick in its working tree via CLI
+                let git_cli = GitCli::new();
+                if git_cli
+                    .has_staged_changes(&target_checkout_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+                    })?
+                {
+                    return Err(GitServiceError::WorktreeDirty(
+                        target_branch.to_string(),
+                        "staged changes present".to_string(),
+                    ));
+                }
+
+                self.ensure_cli_commit_identity(&target_checkout_path)?;
+                git_cli
+                    .cherry_pick(&target_checkout_path, target_branch, &pick_sha)
+                    .map_err(|e| {
+                        if git_cli
+                            .is_cherry_pick_in_progress(&target_checkout_path)
+                            .unwrap_or(false)
+                        {
+                            let _ = git_cli.abort_cherry_pick(&target_checkout_path);
+                            GitServiceError::MergeConflicts(format!(
+                                "Cherry-picking {pick_sha} onto '{target_branch}' conflicted: {e}"
+                            ))
+                        } else {
+                            GitServiceError::InvalidRepository(format!(
+                                "git cherry-pick failed: {e}"
+                            ))
+                        }
+                    })
+            }
+            None => {
+                // target branch not checked out anywhere - cherry-pick in memory and move the ref
+                let target = Self::find_branch(&repo, target_branch)?
+                    .get()
+                    .peel_to_commit()?;
+                let mut index = repo.cherrypick_commit(&pick, &target, 0, None)?;
+                if index.has_conflicts() {
+                    return Err(GitServiceError::MergeConflicts(format!(
+                        "Cherry-picking {pick_sha} onto '{target_branch}' conflicted"
+                    )));
+                }
+
+                let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+                let committer = self.signature_with_fallback(&repo)?;
+                let commit_id = repo.commit(
+                    Some(&format!("refs/heads/{target_branch}")),
+                    &pick.author(),
+                    &committer,
+                    pick.message().unwrap_or_default(),
+                    &tree,
+                    &[&target],
+                )?;
+                Ok(commit_id.to_string())
+            }
+        }
+    }
+
+    /// Revert `merge_commit` on `target_branch` with a new commit and return its sha. Merge
+    /// commits are reverted against their first parent. When `base_commit`, the target head
+    /// before the merge, isn't that parent (a fast-forward brought in several commits), the whole
+    /// `base_commit..merge_commit` range is reverted in the one commit. Fails if the commit isn't
+    /// on the branch or the branch already has a revert of it; a conflicting revert is aborted
+    /// and reported as [`GitServiceError::MergeConflicts`].
+    pub fn revert_merge(
+        &self,
+        repo_path: &Path,
+        merge_commit: &str,
+        base_commit: Option<&str>,
+        target_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let commit = repo.revparse_single(merge_commit)?.peel_to_commit()?;
+        let sha = commit.id().to_string();
+        let target = Self::find_branch(&repo, target_branch)?
+            .get()
+            .peel_to_commit()?;
+        if target.id() != commit.id() && !repo.graph_descendant_of(target.id(), commit.id())? {
+            return Err(GitServiceError::InvalidRepository(format!(
+                "Commit {sha} is not on '{target_branch}'"
+            )));
+        }
+
+        // Both git and the in-memory path below word revert messages this way
+        let revert_marker = format!("This reverts commit {sha}");
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(target.id())?;
+        revwalk.hide(commit.id())?;
+        for oid in revwalk {
+            if repo
+                .find_commit(oid?)?
+                .message()
+                .is_some_and(|message| message.contains(&revert_marker))
+            {
+                return Err(GitServiceError::AlreadyReverted(sha));
+            }
+        }
+
+        if let Some(base_commit) = base_commit {
+            let base = repo.revparse_single(base_commit)?.peel_to_commit()?;
+            if commit.parent_id(0).ok() != Some(base.id()) {
+                if !repo.graph_descendant_of(commit.id(), base.id())? {
+                    return Err(GitServiceError::InvalidRepository(format!(
+                        "Commit {sha} does not descend from {base_commit}"
+                    )));
+                }
+                return self.revert_range(&repo, repo_path, &commit, &base, &target, target_branch);
+            }
+        }
+
+        let mainline = if commit.parent_count() > 1 { 1 } else { 0 };
+        match self.find_checkout_path_for_branch(repo_path, target_branch)? {
+            Some(target_checkout_path) => {
+                // target branch is checked out somewhere - revert in its working tree via CLI
+                let git_cli = GitCli::new();
+                if git_cli
+                    .has_staged_changes(&target_checkout_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+                    })?
+                {
+                    return Err(GitServiceError::WorktreeDirty(
+                        target_branch.to_string(),
+                        "staged changes present".to_string(),
+                    ));
+                }
+
+                self.ensure_cli_commit_identity(&target_checkout_path)?;
+                git_cli
+                    .revert(&target_checkout_path, target_branch, &sha, mainline)
+                    .map_err(|e| {
+                        if git_cli
+                            .is_revert_in_progress(&target_checkout_path)
+                            .unwrap_or(false)
+                        {
+                            let _ = git_cli.abort_revert(&target_checkout_path);
+                            GitServiceError::MergeConflicts(format!(
+                                "Reverting {sha} on '{target_branch}' conflicted: {e}"
+                            ))
+                        } else {
+                            GitServiceError::InvalidRepository(format!("git revert failed: {e}"))
+                        }
+                    })
+            }
+            None => {
+                // target branch not checked out anywhere - revert in memory and move the ref
+                let mut index = repo.revert_commit(&commit, &target, mainline, None)?;
+                if index.has_conflicts() {
+                    return Err(GitServiceError::MergeConflicts(format!(
+                        "Reverting {sha} on '{target_branch}' conflicted"
+                    )));
+                }
+
+                let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+                let signature = self.signature_with_fallback(&repo)?;
+                let message = format!(
+                    "Revert \"{}\"\n\n{revert_marker}.\n",
+                    commit.summary().unwrap_or_default()
+                );
+                let commit_id = repo.commit(
+                    Some(&format!("refs/heads/{target_branch}")),
+                    &signature,
+                    &signature,
+                    &message,
+                    &tree,
+                    &[&target],
+                )?;
+                Ok(commit_id.to_string())
+            }
+        }
+    }
+
+    /// Revert everything `base..commit` brought onto `target_branch` as one commit: the base tree
+    /// is merged onto the target with `commit` as the common ancestor. A checked-out target is
+    /// fast-forwarded to the revert so its working tree follows.
+    fn revert_range(
+        &self,
+        repo: &Repository,
+        repo_path: &Path,
+        commit: &git2::Commit,
+        base: &git2::Commit,
+        target: &git2::Commit,
+        target_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        let sha = commit.id().to_string();
+        let mut index = repo.merge_trees(&commit.tree()?, &target.tree()?, &base.tree()?, None)?;
+        if index.has_conflicts() {
+            return Err(GitServiceError::MergeConflicts(format!(
+                "Reverting {}..{sha} on '{target_branch}' conflicted",
+                base.id()
+            )));
+        }
+
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        let signature = self.signature_with_fallback(repo)?;
+        // Keeps the "This reverts commit <sha>" wording the already-reverted check looks for
+        let message = format!(
+            "Revert \"{}\"\n\nThis reverts commit {sha} and the commits since {}.\n",
+            commit.summary().unwrap_or_default(),
+            base.id()
+        );
+        match self.find_checkout_path_for_branch(repo_path, target_branch)? {
+            Some(target_checkout_path) => {
+                let git_cli = GitCli::new();
+                if git_cli
+                    .has_staged_changes(&target_checkout_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+                    })?
+                {
+                    return Err(GitServiceError::WorktreeDirty(
+                        target_branch.to_string(),
+                        "staged changes present".to_string(),
+                    ));
+                }
+
+                let revert = repo.commit(None, &signature, &signature, &message, &tree, &[target])?;
+                git_cli
+                    .merge_fast_forward(&target_checkout_path, target_branch, &revert.to_string())
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git merge --ff-only failed: {e}"))
+                    })
+            }
+            None => {
+                let revert = repo.commit(
+                    Some(&format!("refs/heads/{target_branch}")),
+                    &signature,
+                    &signature,
+                    &message,
+                    &tree,
+                    &[target],
+                )?;
+                Ok(revert.to_string())
+            }
+        }
+    }
+
+    /// Undo a merge that was just made by moving `branch` from `merged_head` back to
+    /// `previous_head`. Fails without touching anything if the branch has moved on from
+    /// `merged_head`, so later commits on the branch are never dropped.
+    pub fn reset_branch(
+        &self,
+        repo_path: &Path,
+        branch: &str,
+        merged_head: &str,
+        previous_head: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let head = Self::find_branch(&repo, branch)?
+            .get()
+            .peel_to_commit()?
+            .id();
+        if head.to_string() != merged_head {
+            return Err(GitServiceError::InvalidRepository(format!(
+                "'{branch}' has moved past {merged_head}; not resetting it"
+            )));
+        }
+        let previous = repo.revparse_single(previous_head)?.peel_to_commit()?.id();
+
+        match self.find_checkout_path_for_branch(repo_path, branch)? {
+            Some(checkout_path) => {
+                // branch is checked out somewhere - reset its working tree via CLI
+                GitCli::new()
+                    .reset_keep(&checkout_path, &previous.to_string())
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git reset failed: {e}"))
+                    })
+            }
+            None => {
+                // branch not checked out anywhere - just move the ref
+                repo.reference(
+                    &format!("refs/heads/{branch}"),
+                    previous,
+                    true,
+                    "Roll back merge",
+                )?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Commits `branch_name` has that `target_branch` doesn't (ahead) and the reverse (behind)
+    pub fn ahead_behind(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<(usize, usize), GitServiceError> {
+        self.get_branch_status(worktree_path, branch_name, target_branch)
+    }
+
+    /// Whether `branch_name` has any commits that `target_branch` does not
+    pub fn branch_has_commits_ahead(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<bool, GitServiceError> {
+        let (ahead, _) = self.get_branch_status(worktree_path, branch_name, target_branch)?;
+        Ok(ahead > 0)
+    }
+
+    /// Whether `branch_name` is strictly ahead of `target_branch` with no divergence,
+    /// so the target can be fast-forwarded instead of receiving a merge commit.
+    pub fn can_fast_forward(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<bool, GitServiceError> {
+        let (ahead, behind) = self.get_branch_status(worktree_path, branch_name, target_branch)?;
+        Ok(ahead > 0 && behind == 0)
+    }
+
+    /// Fast-forward the base branch to the task branch. Returns the resulting base sha,
+    /// which is the task branch head.
+    pub fn fast_forward_merge(
+        &self,
+        base_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        if !self.can_fast_forward(base_worktree_path, task_branch_name, base_branch_name)? {
+            return Err(GitServiceError::BranchesDiverged(format!(
+                "Cannot fast-forward: '{base_branch_name}' is not an ancestor of '{task_branch_name}'",
+            )));
+        }
+
+        match self.find_checkout_path_for_branch(base_worktree_path, base_branch_name)? {
+            Some(base_checkout_path) => {
+                // base branch is checked out somewhere - fast-forward its working tree via CLI
+                let git_cli = GitCli::new();
+                if git_cli
+                    .has_staged_changes(&base_checkout_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+                    })?
+                {
+                    return Err(GitServiceError::WorktreeDirty(
+                        base_branch_name.to_string(),
+                        "staged changes present".to_string(),
+                    ));
+                }
+
+                git_cli
+                    .merge_fast_forward(&base_checkout_path, base_branch_name, task_branch_name)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("CLI fast-forward failed: {e}"))
+                    })
+            }
+            None => {
+                // base branch not checked out anywhere - just move the ref
+                let repo = self.open_repo(base_worktree_path)?;
+                let task_oid = Self::find_branch(&repo, task_branch_name)?
+                    .get()
+                    .peel_to_commit()?
+                    .id();
+                repo.reference(
+                    &format!("refs/heads/{base_branch_name}"),
+                    task_oid,
+                    true,
+                    "Fast-forward merge",
+                )?;
+                Ok(task_oid.to_string())
+            }
+        }
+    }
+
+    /// Dry-run a merge of `target_branch` into `branch_name` in the repository at
+    /// `worktree_path`. Returns the conflicting files, or None if the merge would be clean.
+    /// The merge happens in memory, so no worktree or index is touched.
+    pub fn would_conflict(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<Option<Vec<String>>, GitServiceError> {
+        let conflicted_files = GitCli::new()
+            .merge_tree_conflicts(worktree_path, branch_name, target_branch)
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git merge-tree failed: {e}"))
+            })?;
+        Ok((!conflicted_files.is_empty()).then_some(conflicted_files))
+    }
+
+    /// Diff hunks touching `paths` on both sides of merging `branch_name` into `target_branch`,
+    /// each relative to their merge base. Each side is truncated to about `max_bytes`.
+    pub fn conflict_hunks(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+        paths: &[String],
+        max_bytes: usize,
+    ) -> Result<String, GitServiceError> {
+        if paths.is_empty() {
+            return Ok(String::new());
+        }
+
+        let git_cli = GitCli::new();
+        let mut out = String::new();
+        for (side, range) in [
+            (branch_name, format!("{target_branch}...{branch_name}")),
+            (target_branch, format!("{branch_name}...{target_branch}")),
+        ] {
+            let mut args = vec!["diff", "--no-color", range.as_str(), "--"];
+            args.extend(paths.iter().map(String::as_str));
+            let mut diff = git_cli.git(worktree_path, args)?;
+            if diff.len() > max_bytes {
+                let mut cut = max_bytes;
+                while !diff.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                diff.truncate(cut);
+                diff.push_str("\n... (truncated)\n");
+            }
+            out.push_str(&format!("### Changes on {side}\n{diff}\n"));
+        }
+        Ok(out)
+    }
+
+    /// Paths (relative to the repo root) changed on the branch checked out at `worktree_path`
+    /// since it forked from `target_branch`. Changes made on the target meanwhile are excluded.
+    pub fn changed_files(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let range = format!("{target_branch}...HEAD");
+        let output = GitCli::new().git(
+            worktree_path,
+            ["diff", "--name-only", "--no-renames", range.as_str()],
+        )?;
+        Ok(output
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Paths changed on `target_branch` since the branch checked out at `worktree_path` forked
+    /// from it: the target-side counterpart of [`Self::changed_files`]
+    pub fn changed_files_on_target(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let range = format!("HEAD...{target_branch}");
+        let output = GitCli::new().git(
+            worktree_path,
+            ["diff", "--name-only", "--no-renames", range.as_str()],
+        )?;
+        Ok(output
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Append a `Task-Id: <uuid>` trailer to a commit message
+    pub fn with_task_id_trailer(commit_message: &str, task_id: Uuid) -> String {
+        format!("{}\n\n{TASK_ID_TRAILER}: {task_id}", commit_message.trim_end())
+    }
+
+    /// Attach a `Task-Id` git note to an existing commit, for merges that did not create
+    /// their own commit (e.g. fast-forwards)
+    pub fn add_task_id_note(
+        &self,
+        repo_path: &Path,
+        commit_sha: &str,
+        task_id: Uuid,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let oid = git2::Oid::from_str(commit_sha)
+            .map_err(|_| GitServiceError::InvalidRepository("Invalid commit SHA".into()))?;
+        let signature = self.signature_with_fallback(&repo)?;
+        repo.note(
+            &signature,
+            &signature,
+            None,
+            oid,
+            &format!("{TASK_ID_TRAILER}: {task_id}"),
+            true,
+        )?;
+        Ok(())
+    }
+
+    /// Find the most recent commit on `branch_name` tagged with `task_id`, either by a
+    /// `Task-Id` trailer in its message or by a `Task-Id` git note
+    pub fn find_commit_by_task_id(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        task_id: Uuid,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let head = Self::find_branch(&repo, branch_name)?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let needle = format!("{TASK_ID_TRAILER}: {task_id}");
+        let has_tag = |text: &str| text.lines().any(|line| line.trim() == needle);
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(head)?;
+        revwalk.set_sorting(Sort::TIME)?;
+        for oid in revwalk {
+            let oid = oid?;
+            let commit = repo.find_commit(oid)?;
+            if commit.message().is_some_and(has_tag) {
+                return Ok(Some(oid.to_string()));
+            }
+            if let Ok(note) = repo.find_note(None, oid)
+                && note.message().is_some_and(has_tag)
+            {
+                return Ok(Some(oid.to_string()));
+            }
+        }
+        Ok(None)
+    }
+
     fn get_branch_status_inner(
         &self,
         repo: &Repository,
@@ -959,6 +1600,55 @@ impl GitService {
         self.get_branch_status_inner(&repo, &branch_ref, &base_branch_ref)
     }
 
+    /// Fetch `branch_name` from the default remote and fast-forward the local branch to it,
+    /// so divergence checks see upstream commits. Returns `Ok(false)` without fetching when
+    /// the repository has no remote configured.
+    pub fn fetch_and_fast_forward_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let remote_name = self.default_remote_name(&repo);
+        let Ok(remote) = repo.find_remote(&remote_name) else {
+            return Ok(false);
+        };
+
+        let remote_ref = format!("refs/remotes/{remote_name}/{branch_name}");
+        let refspec = format!("+refs/heads/{branch_name}:{remote_ref}");
+        self.fetch_from_remote(&repo, &remote, &refspec)?;
+
+        let local_oid = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let remote_oid = repo.refname_to_id(&remote_ref)?;
+
+        // Only fast-forward; if the local branch has commits of its own, leave it for the merge
+        if local_oid == remote_oid || !repo.graph_descendant_of(remote_oid, local_oid)? {
+            return Ok(true);
+        }
+
+        match self.find_checkout_path_for_branch(repo_path, branch_name)? {
+            Some(checkout_path) => {
+                let upstream = format!("{remote_name}/{branch_name}");
+                GitCli::new().git(&checkout_path, ["merge", "--ff-only", upstream.as_str()])?;
+            }
+            None => {
+                repo.reference(
+                    &format!("refs/heads/{branch_name}"),
+                    remote_oid,
+                    true,
+                    "Fast-forward to remote before merge",
+                )?;
+            }
+        }
+
+        Ok(true)
+    }
+
     pub fn is_worktree_clean(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let repo = self.open_repo(worktree_path)?;
         match self.check_worktree_clean(&repo) {
@@ -968,6 +1658,42 @@ impl GitService {
         }
     }
 
+    /// Stash uncommitted changes in a worktree, including untracked files. Returns false when
+    /// there was nothing to stash.
+    pub fn stash(&self, worktree_path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        let git = GitCli::new();
+        let status = git.git(worktree_path, ["status", "--porcelain"])?;
+        if status.trim().is_empty() {
+            return Ok(false);
+        }
+        self.ensure_cli_commit_identity(worktree_path)?;
+        git.git(
+            worktree_path,
+            ["stash", "push", "--include-untracked", "-m", message],
+        )?;
+        Ok(true)
+    }
+
+    /// Restore the most recent stash. On failure (e.g. the changes conflict) the stash is kept.
+    pub fn stash_pop(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        GitCli::new()
+            .git(worktree_path, ["stash", "pop"])
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git stash pop failed: {e}"))
+            })?;
+        Ok(())
+    }
+
+    /// Discard the most recent stash
+    pub fn stash_drop(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        GitCli::new()
+            .git(worktree_path, ["stash", "drop"])
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git stash drop failed: {e}"))
+            })?;
+        Ok(())
+    }
+
     /// Check if the worktree is clean (no uncommitted changes to tracked files)
     fn check_worktree_clean(&self, repo: &Repository) -> Result<(), GitServiceError> {
         let mut status_options = git2::StatusOptions::new();
@@ -1046,6 +1772,74 @@ impl GitService {
         }
     }
 
+    /// Resolve the repository's default branch: the branch `origin/HEAD` points at, else an
+    /// existing local branch (the configured `init.defaultBranch`, `main` or `master`), else the
+    /// configured `init.defaultBranch`. Results are cached per repository path.
+    pub fn default_branch(&self, repo_path: &Path) -> Result<String, GitServiceError> {
+        static CACHE: OnceLock<Mutex<HashMap<PathBuf, String>>> = OnceLock::new();
+        let cache = CACHE.get_or_init(Default::default);
+        let key = repo_path
+            .canonicalize()
+            .unwrap_or_else(|_| repo_path.to_path_buf());
+
+        if let Some(branch) = cache.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            return Ok(branch.clone());
+        }
+
+        let repo = self.open_repo(repo_path)?;
+        let branch = Self::resolve_default_branch(&repo);
+        cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, branch.clone());
+        Ok(branch)
+    }
+
+    fn resolve_default_branch(repo: &Repository) -> String {
+        if let Ok(origin_head) = repo.find_reference("refs/remotes/origin/HEAD")
+            && let Some(target) = origin_head.symbolic_target()
+            && let Some(branch) = target.strip_prefix("refs/remotes/origin/")
+        {
+            return branch.to_string();
+        }
+
+        // The global init.defaultBranch often names a branch this repo never had, so it only
+        // wins when it exists here or nothing else does
+        let configured = repo
+            .config()
+            .and_then(|config| config.get_string("init.defaultBranch"))
+            .ok()
+            .filter(|name| !name.is_empty());
+        let existing = configured
+            .iter()
+            .map(String::as_str)
+            .chain(["main", "master"])
+            .find(|name| repo.find_branch(name, BranchType::Local).is_ok())
+            .map(str::to_string);
+        existing
+            .or(configured)
+            .unwrap_or_else(|| "main".to_string())
+    }
+
+    /// Read a file's contents at the tip of `branch_name`, or None if it doesn't exist there
+    pub fn read_file_at_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        file_path: &Path,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch = Self::find_branch(&repo, branch_name)?;
+        let tree = branch.get().peel_to_commit()?.tree()?;
+        let entry = match tree.get_path(file_path) {
+            Ok(entry) => entry,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let blob = entry.to_object(&repo)?.peel_to_blob()?;
+        Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+    }
+
     /// Get the commit OID (as hex string) for a given branch without modifying HEAD
     pub fn get_branch_oid(
         &self,
@@ -1188,13 +1982,66 @@ impl GitService {
         Ok(())
     }
 
-    /// Remove a worktree
+    /// Create `branch` off `target_branch` unless it already exists, returning whether it was
+    /// created. An existing branch is reused so a retried attempt keeps its commits, unless it's
+    /// checked out somewhere other than `worktree_path`.
+    pub fn create_branch_for_worktree(
+        &self,
+        repo_path: &Path,
+        branch: &str,
+        target_branch: &str,
+        worktree_path: &Path,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if repo.find_branch(branch, BranchType::Local).is_ok() {
+            if let Some(checkout_path) = self.find_checkout_path_for_branch(repo_path, branch)?
+                && !same_path(&checkout_path, worktree_path)
+            {
+                return Err(GitServiceError::BranchCheckedOut(
+                    branch.to_string(),
+                    checkout_path.display().to_string(),
+                ));
+            }
+            tracing::info!("Reusing existing branch {branch} for worktree");
+            return Ok(false);
+        }
+
+        let target_commit = Self::find_branch(&repo, target_branch)?
+            .into_reference()
+            .peel_to_commit()?;
+        repo.branch(branch, &target_commit, false)?;
+        Ok(true)
+    }
+
+    /// Create a worktree at `worktree_path` on `branch`, branching off `target_branch` when the
+    /// branch doesn't exist yet (see [`Self::create_branch_for_worktree`]). Returns the path of
+    /// the new worktree.
+    pub fn create_worktree(
+        &self,
+        repo_path: &Path,
+        branch: &str,
+        target_branch: &str,
+        worktree_path: &Path,
+    ) -> Result<PathBuf, GitServiceError> {
+        self.create_branch_for_worktree(repo_path, branch, target_branch, worktree_path)?;
+        if let Some(parent) = worktree_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        self.add_worktree(repo_path, worktree_path, branch, false)?;
+        Ok(worktree_path.to_path_buf())
+    }
+
+    /// Remove a worktree. If its directory is already gone, only the stale
+    /// `.git/worktrees` metadata is pruned.
     pub fn remove_worktree(
         &self,
         repo_path: &Path,
         worktree_path: &Path,
         force: bool,
     ) -> Result<(), GitServiceError> {
+        if !worktree_path.exists() {
+            return self.prune_worktrees(repo_path);
+        }
         let git = GitCli::new();
         git.worktree_remove(repo_path, worktree_path, force)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
@@ -1286,6 +2133,7 @@ impl GitService {
     }
 
     /// Perform a squash merge of task branch into base branch, but fail on conflicts
+    #[allow(clippy::too_many_arguments)]
     fn perform_squash_merge(
         &self,
         repo: &Repository,
@@ -1294,6 +2142,7 @@ impl GitService {
         signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        signing_key: Option<&str>,
     ) -> Result<git2::Oid, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
@@ -1314,14 +2163,33 @@ impl GitService {
         let tree = repo.find_tree(tree_id)?;
 
         // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
-            None,           // Don't update any reference yet
-            signature,      // Author
-            signature,      // Committer
-            commit_message, // Custom message
-            &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
-        )?;
+        let squash_commit_id = match signing_key {
+            // libgit2 cannot sign, so let git build the signed commit object from the same tree
+            Some(key) => {
+                let repo_path = repo.workdir().unwrap_or_else(|| repo.path());
+                self.ensure_cli_commit_identity(repo_path)?;
+                let sha = GitCli::new()
+                    .commit_tree_signed(
+                        repo_path,
+                        &tree_id.to_string(),
+                        &base_commit.id().to_string(),
+                        commit_message,
+                        key,
+                    )
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git commit-tree failed: {e}"))
+                    })?;
+                git2::Oid::from_str(&sha)?
+            }
+            None => repo.commit(
+                None,           // Don't update any reference yet
+                signature,      // Author
+                signature,      // Committer
+                commit_message, // Custom message
+                &tree,          // Merged tree content
+                &[base_commit], // Single parent: base branch commit
+            )?,
+        };
 
         // Update the base branch reference to point to the new commit
         let refname = format!("refs/heads/{base_branch_name}");
@@ -1875,3 +2743,9 @@ impl GitService {
         Ok(stats)
     }
 }
+
+/// Whether two paths point at the same location, resolving symlinks when they exist
+fn same_path(a: &Path, b: &Path) -> bool {
+    let resolve = |path: &Path| dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    resolve(a) == resolve(b)
+}
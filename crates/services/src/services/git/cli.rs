@@ -596,11 +596,113 @@ impl GitCli {
         base_branch: &str,
         from_branch: &str,
         message: &str,
+        signing_key: Option<&str>,
     ) -> Result<String, GitCliError> {
         self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
         self.git(repo_path, ["merge", "--squash", "--no-commit", from_branch])
             .map(|_| ())?;
-        self.git(repo_path, ["commit", "-m", message]).map(|_| ())?;
+        match signing_key {
+            Some(key) => {
+                let sign_arg = format!("-S{key}");
+                self.git(repo_path, ["commit", sign_arg.as_str(), "-m", message])
+            }
+            None => self.git(repo_path, ["commit", "-m", message]),
+        }
+        .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Create a signed commit object for `tree` on top of `parent` without touching any ref or
+    /// working tree. Signing uses the repo's configured format (`gpg.format`). Returns the sha.
+    pub fn commit_tree_signed(
+        &self,
+        repo_path: &Path,
+        tree: &str,
+        parent: &str,
+        message: &str,
+        signing_key: &str,
+    ) -> Result<String, GitCliError> {
+        let sign_arg = format!("-S{signing_key}");
+        let sha = self
+            .git(
+                repo_path,
+                [
+                    "commit-tree",
+                    tree,
+                    "-p",
+                    parent,
+                    sign_arg.as_str(),
+                    "-m",
+                    message,
+                ],
+            )?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// The key configured for commit signing (`user.signingkey`), if any.
+    pub fn signing_key(&self, repo_path: &Path) -> Option<String> {
+        self.git(repo_path, ["config", "--get", "user.signingkey"])
+            .ok()
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+    }
+
+    /// Checkout base branch and fast-forward it to from_branch. Returns new HEAD sha.
+    pub fn merge_fast_forward(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Checkout base branch and cherry-pick `commit` onto it. Returns new HEAD sha.
+    pub fn cherry_pick(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        commit: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["cherry-pick", commit]).map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Revert `commit` on `base_branch` with a new commit and return its sha. `mainline` picks
+    /// the parent to revert against for merge commits (0 for regular commits).
+    pub fn revert(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        commit: &str,
+        mainline: u32,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        let mut args: Vec<OsString> = vec!["revert".into(), "--no-edit".into()];
+        if mainline > 0 {
+            args.push("-m".into());
+            args.push(mainline.to_string().into());
+        }
+        args.push(commit.into());
+        self.git(repo_path, args)?;
         let sha = self
             .git(repo_path, ["rev-parse", "HEAD"])?
             .trim()
@@ -608,6 +710,46 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Move the checked-out branch back to `commit`, updating the working tree. Refuses, rather
+    /// than discarding them, when local changes touch files that differ between the two.
+    pub fn reset_keep(&self, worktree_path: &Path, commit: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["reset", "--keep", commit])
+            .map(|_| ())
+    }
+
+    /// Merge `theirs` into `ours` in memory with `git merge-tree --write-tree`, without touching
+    /// the index or any worktree. Returns the conflicted paths, empty when the merge is clean.
+    pub fn merge_tree_conflicts(
+        &self,
+        repo_path: &Path,
+        ours: &str,
+        theirs: &str,
+    ) -> Result<Vec<String>, GitCliError> {
+        // Exit code 1 means conflicts; stdout is the merged tree id followed by conflicted paths
+        let out =
+            Command::new(resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?)
+                .arg("-C")
+                .arg(repo_path)
+                .args(["merge-tree", "--write-tree", "--name-only", "--no-messages"])
+                .arg(ours)
+                .arg(theirs)
+                .output()
+                .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
+        let conflicts: Vec<String> = String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .skip(1)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        match out.status.code() {
+            Some(0) => Ok(Vec::new()),
+            Some(1) if !conflicts.is_empty() => Ok(conflicts),
+            _ => Err(GitCliError::CommandFailed(
+                String::from_utf8_lossy(&out.stderr).trim().to_string(),
+            )),
+        }
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
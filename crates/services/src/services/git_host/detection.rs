@@ -8,6 +8,8 @@ use super::types::ProviderKind;
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
 /// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
+/// - GitLab: URLs containing `gitlab.` (GitLab.com and most self-managed instances), or merge
+///   request URLs containing `/-/merge_requests/`
 pub fn detect_provider_from_url(url: &str) -> ProviderKind {
     let url_lower = url.to_lowercase();
 
@@ -28,6 +30,11 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
         return ProviderKind::AzureDevOps;
     }
 
+    // /-/merge_requests/ is unique to GitLab, so self-managed MR URLs are detected too
+    if url_lower.contains("gitlab.") || url_lower.contains("/-/merge_requests/") {
+        return ProviderKind::GitLab;
+    }
+
     // GitHub Enterprise (contains "github." but not the Azure patterns above)
     if url_lower.contains("github.") {
         return ProviderKind::GitHub;
@@ -42,6 +49,7 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
 /// - GitHub: `https://github.com/owner/repo/pull/123`
 /// - GitHub Enterprise: `https://github.company.com/owner/repo/pull/123`
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo/pullrequest/123`
+/// - GitLab: `https://gitlab.com/group/repo/-/merge_requests/123`
 #[cfg(test)]
 fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
     let url_lower = pr_url.to_lowercase();
@@ -137,11 +145,23 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_provider() {
+    fn test_gitlab() {
         assert_eq!(
             detect_provider_from_url("https://gitlab.com/owner/repo"),
-            ProviderKind::Unknown
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("git@gitlab.company.com:group/sub/repo.git"),
+            ProviderKind::GitLab
         );
+        assert_eq!(
+            detect_provider_from_pr_url("https://git.company.com/group/repo/-/merge_requests/7"),
+            ProviderKind::GitLab
+        );
+    }
+
+    #[test]
+    fn test_unknown_provider() {
         assert_eq!(
             detect_provider_from_url("https://bitbucket.org/owner/repo"),
             ProviderKind::Unknown
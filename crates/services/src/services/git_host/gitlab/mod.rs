@@ -0,0 +1,135 @@
+//! GitLab hosting service implementation, opening merge requests through the REST API.
+
+mod api;
+
+use std::{path::Path, time::Duration};
+
+pub use api::GitLabApi;
+use api::GitLabRepoInfo;
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::merge::PullRequestInfo;
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+};
+
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    api: GitLabApi,
+}
+
+impl GitLabProvider {
+    pub fn new() -> Result<Self, GitHostError> {
+        Ok(Self {
+            api: GitLabApi::new()?,
+        })
+    }
+
+    fn repo_info(remote_url: &str) -> Result<GitLabRepoInfo, GitHostError> {
+        GitLabRepoInfo::from_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!(
+                "Could not parse GitLab project from remote URL: {remote_url}"
+            ))
+        })
+    }
+
+    fn backoff() -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter()
+    }
+}
+
+fn notify_retry(err: &GitHostError, dur: Duration) {
+    tracing::warn!(
+        "GitLab API call failed, retrying after {:.2}s: {}",
+        dur.as_secs_f64(),
+        err
+    );
+}
+
+#[async_trait]
+impl GitHostProvider for GitLabProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        if let Some(head_url) = &request.head_repo_url
+            && head_url != remote_url
+        {
+            return Err(GitHostError::PullRequest(
+                "Cross-fork merge requests are not supported for GitLab".to_string(),
+            ));
+        }
+
+        let repo = Self::repo_info(remote_url)?;
+        let mr = (|| async { self.api.create_merge_request(&repo, request).await })
+            .retry(&Self::backoff())
+            .when(|e: &GitHostError| e.should_retry())
+            .notify(notify_retry)
+            .await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {} into {}",
+            mr.number, request.head_branch, request.base_branch
+        );
+        Ok(mr)
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let (repo, iid) = GitLabRepoInfo::from_merge_request_url(pr_url).ok_or_else(|| {
+            GitHostError::PullRequest(format!("Not a GitLab merge request URL: {pr_url}"))
+        })?;
+
+        (|| async { self.api.get_merge_request(&repo, iid).await })
+            .retry(&Self::backoff())
+            .when(|e: &GitHostError| e.should_retry())
+            .notify(notify_retry)
+            .await
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+
+        (|| async {
+            self.api
+                .list_merge_requests_for_branch(&repo, branch_name)
+                .await
+        })
+        .retry(&Self::backoff())
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(notify_retry)
+        .await
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo = Self::repo_info(remote_url)?;
+
+        (|| async { self.api.get_merge_request_notes(&repo, pr_number).await })
+            .retry(&Self::backoff())
+            .when(|e: &GitHostError| e.should_retry())
+            .notify(notify_retry)
+            .await
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::GitLab
+    }
+}
@@ -3,6 +3,7 @@ mod types;
 
 pub mod azure;
 pub mod github;
+pub mod gitlab;
 
 use std::path::Path;
 
@@ -15,7 +16,7 @@ pub use types::{
     ReviewCommentUser, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{azure::AzureDevOpsProvider, github::GitHubProvider, gitlab::GitLabProvider};
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -49,13 +50,25 @@ pub trait GitHostProvider: Send + Sync {
 #[enum_dispatch]
 pub enum GitHostService {
     GitHub(GitHubProvider),
+    GitLab(GitLabProvider),
     AzureDevOps(AzureDevOpsProvider),
 }
 
 impl GitHostService {
     pub fn from_url(url: &str) -> Result<Self, GitHostError> {
-        match detect_provider_from_url(url) {
+        Self::new(detect_provider_from_url(url))
+    }
+
+    /// The project's configured provider if it has one, otherwise the one detected from `url`.
+    /// Needed for self-managed instances whose hostnames don't identify the provider.
+    pub fn for_project(url: &str, configured: Option<ProviderKind>) -> Result<Self, GitHostError> {
+        Self::new(configured.unwrap_or_else(|| detect_provider_from_url(url)))
+    }
+
+    fn new(kind: ProviderKind) -> Result<Self, GitHostError> {
+        match kind {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
+            ProviderKind::GitLab => Ok(Self::GitLab(GitLabProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
@@ -7,14 +7,28 @@ use ts_rs::TS;
 #[serde(rename_all = "snake_case")]
 pub enum ProviderKind {
     GitHub,
+    GitLab,
     AzureDevOps,
     Unknown,
 }
 
+impl ProviderKind {
+    /// Parse a project's `git_host_provider` setting ("github", "gitlab" or "azure_devops")
+    pub fn from_setting(setting: &str) -> Option<Self> {
+        match setting {
+            "github" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            "azure_devops" => Some(Self::AzureDevOps),
+            _ => None,
+        }
+    }
+}
+
 impl std::fmt::Display for ProviderKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
+            ProviderKind::GitLab => write!(f, "GitLab"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
@@ -1,9 +1,13 @@
 pub mod agent_activity;
+pub mod agent_activity_digest;
 pub mod analytics;
 pub mod approvals;
 pub mod auth;
+pub mod branch_name;
 pub mod claude_api;
 pub mod codebase_rules;
+pub mod command_allowlist;
+pub mod complexity_analyzer;
 pub mod config;
 pub mod database_validator;
 pub mod container;
@@ -20,13 +24,23 @@ pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
 pub mod project;
+pub mod project_concurrency;
+pub mod prompts;
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
+pub mod quiet_hours;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
 pub mod requirements_analyzer;
 pub mod review_automation;
+pub mod task_export;
+pub mod task_reconciler;
+pub mod task_similarity;
 pub mod task_timeout;
+pub mod test_runner;
+#[cfg(test)]
+pub mod test_support;
+pub mod test_targets;
 pub mod workspace_manager;
 pub mod worktree_manager;
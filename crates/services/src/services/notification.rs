@@ -1,30 +1,147 @@
-use std::sync::{Arc, OnceLock};
+use std::{
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
+use db::models::{
+    agent_activity::ProjectAgentSettings,
+    project_notification_channel::{NotificationChannelType, ProjectNotificationChannel},
+};
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
 use tokio::sync::RwLock;
 use utils;
+use uuid::Uuid;
 
-use crate::services::config::{Config, NotificationConfig, SoundFile};
+use crate::services::{
+    config::{Config, NotificationConfig, SoundFile},
+    quiet_hours,
+};
+
+/// Timeout for delivering a notification to a webhook or Slack channel
+const CHANNEL_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Service for handling cross-platform notifications including sound alerts and push notifications
 #[derive(Debug, Clone)]
 pub struct NotificationService {
     config: Arc<RwLock<Config>>,
+    pool: SqlitePool,
+    http: reqwest::Client,
+}
+
+/// One send planned for a project's notification channels
+#[derive(Debug, PartialEq)]
+enum Delivery {
+    /// The global sound and push notifications
+    Local,
+    /// A POST of `body` to a webhook or Slack channel's URL
+    Request {
+        channel_id: Uuid,
+        url: String,
+        body: Value,
+    },
 }
 
 /// Cache for WSL root path from PowerShell
 static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
 impl NotificationService {
-    pub fn new(config: Arc<RwLock<Config>>) -> Self {
-        Self { config }
+    pub fn new(config: Arc<RwLock<Config>>, pool: SqlitePool) -> Self {
+        Self {
+            config,
+            pool,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Deliver a notification to every enabled channel configured for the project.
+    /// Projects without any configured channels get the global sound and push notifications.
+    /// Nothing is sent during the project's quiet hours; see [`Self::notify_critical`].
+    pub async fn notify(&self, project_id: Uuid, title: &str, message: &str) {
+        let settings = ProjectAgentSettings::find_by_project_id(&self.pool, project_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!(
+                    "Failed to load quiet hours for project {}: {}",
+                    project_id,
+                    e
+                );
+                None
+            });
+        if quiet_hours::is_quiet(settings.as_ref(), chrono::Utc::now()) {
+            tracing::debug!(
+                "Suppressed notification '{}' for project {} during quiet hours",
+                title,
+                project_id
+            );
+            return;
+        }
+        self.notify_critical(project_id, title, message).await;
+    }
+
+    /// Deliver a notification like [`Self::notify`], even during the project's quiet hours
+    pub async fn notify_critical(&self, project_id: Uuid, title: &str, message: &str) {
+        let channels = ProjectNotificationChannel::find_by_project_id(&self.pool, project_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!(
+                    "Failed to load notification channels for project {}: {}",
+                    project_id,
+                    e
+                );
+                Vec::new()
+            });
+
+        let config = self.config.read().await.notifications.clone();
+        for delivery in plan_deliveries(&channels, title, message) {
+            match delivery {
+                Delivery::Local => Self::send_notification(&config, title, message).await,
+                Delivery::Request {
+                    channel_id,
+                    url,
+                    body,
+                } => self.send_channel_request(channel_id, url, body),
+            }
+        }
     }
 
-    /// Send both sound and push notifications if enabled
-    pub async fn notify(&self, title: &str, message: &str) {
+    /// Send the global sound and push notifications, for events not tied to a project
+    pub async fn notify_global(&self, title: &str, message: &str) {
         let config = self.config.read().await.notifications.clone();
         Self::send_notification(&config, title, message).await;
     }
 
+    /// Check that a channel's config has what delivery needs
+    pub fn validate_channel_config(
+        channel_type: NotificationChannelType,
+        config: &Value,
+    ) -> Result<(), String> {
+        if !config.is_object() {
+            return Err("config must be a JSON object".to_string());
+        }
+        match channel_url_key(channel_type) {
+            Some(key) => match config.get(key).and_then(Value::as_str) {
+                Some(url) if url.starts_with("https://") || url.starts_with("http://") => Ok(()),
+                _ => Err(format!("{channel_type} channels require an http(s) '{key}'")),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// POST a notification to a webhook or Slack channel without blocking the caller
+    fn send_channel_request(&self, channel_id: Uuid, url: String, body: Value) {
+        let request = self
+            .http
+            .post(url)
+            .timeout(CHANNEL_REQUEST_TIMEOUT)
+            .json(&body);
+        tokio::spawn(async move {
+            if let Err(e) = request.send().await.and_then(|r| r.error_for_status()) {
+                tracing::warn!("Failed to deliver notification to channel {}: {}", channel_id, e);
+            }
+        });
+    }
+
     /// Internal method to send notifications with a given config
     async fn send_notification(config: &NotificationConfig, title: &str, message: &str) {
         if config.sound_enabled {
@@ -236,3 +353,175 @@ impl NotificationService {
         }
     }
 }
+
+/// Config key holding the delivery URL for channels that post over HTTP
+fn channel_url_key(channel_type: NotificationChannelType) -> Option<&'static str> {
+    match channel_type {
+        NotificationChannelType::Desktop => None,
+        NotificationChannelType::Webhook => Some("url"),
+        NotificationChannelType::Slack => Some("webhook_url"),
+    }
+}
+
+/// The sends a notification fans out to: one per enabled channel, or the global sound and push
+/// notifications when the project has no channels. Channels missing their URL are skipped.
+fn plan_deliveries(
+    channels: &[ProjectNotificationChannel],
+    title: &str,
+    message: &str,
+) -> Vec<Delivery> {
+    if channels.is_empty() {
+        return vec![Delivery::Local];
+    }
+
+    channels
+        .iter()
+        .filter(|channel| channel.enabled)
+        .filter_map(|channel| {
+            let Some(key) = channel_url_key(channel.channel_type) else {
+                return Some(Delivery::Local);
+            };
+            let Some(url) = channel.config.get(key).and_then(Value::as_str) else {
+                tracing::warn!("Notification channel {} has no URL configured", channel.id);
+                return None;
+            };
+            let body = match channel.channel_type {
+                NotificationChannelType::Slack => {
+                    json!({ "text": format!("*{title}*\n{message}") })
+                }
+                _ => json!({
+                    "project_id": channel.project_id,
+                    "title": title,
+                    "message": message,
+                }),
+            };
+            Some(Delivery::Request {
+                channel_id: channel.id,
+                url: url.to_string(),
+                body,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use sqlx::types::Json;
+
+    use super::*;
+
+    fn channel(channel_type: NotificationChannelType, config: Value) -> ProjectNotificationChannel {
+        ProjectNotificationChannel {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            channel_type,
+            config: Json(config),
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn channel_config_requires_an_http_url_where_delivery_needs_one() {
+        let validate = NotificationService::validate_channel_config;
+        assert!(validate(NotificationChannelType::Desktop, &json!({})).is_ok());
+        assert!(validate(NotificationChannelType::Desktop, &json!("on")).is_err());
+        assert!(
+            validate(
+                NotificationChannelType::Webhook,
+                &json!({ "url": "https://example.com/hook" })
+            )
+            .is_ok()
+        );
+        assert!(
+            validate(
+                NotificationChannelType::Webhook,
+                &json!({ "url": "ftp://example.com" })
+            )
+            .is_err()
+        );
+        assert!(
+            validate(
+                NotificationChannelType::Slack,
+                &json!({ "url": "https://hooks.slack.com/x" })
+            )
+            .is_err()
+        );
+        assert!(
+            validate(
+                NotificationChannelType::Slack,
+                &json!({ "webhook_url": "http://hooks.slack.com/x" })
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn projects_without_channels_get_local_notifications() {
+        assert_eq!(
+            plan_deliveries(&[], "Done", "Task done"),
+            vec![Delivery::Local]
+        );
+    }
+
+    #[test]
+    fn notifications_fan_out_to_every_enabled_channel() {
+        let webhook = channel(
+            NotificationChannelType::Webhook,
+            json!({ "url": "https://example.com/hook" }),
+        );
+        let slack = channel(
+            NotificationChannelType::Slack,
+            json!({ "webhook_url": "https://hooks.slack.com/x" }),
+        );
+        let mut disabled = channel(
+            NotificationChannelType::Webhook,
+            json!({ "url": "https://example.com/off" }),
+        );
+        disabled.enabled = false;
+        let missing_url = channel(NotificationChannelType::Webhook, json!({}));
+        let desktop = channel(NotificationChannelType::Desktop, json!({}));
+
+        let channels = [
+            webhook.clone(),
+            disabled,
+            slack.clone(),
+            missing_url,
+            desktop,
+        ];
+        let deliveries = plan_deliveries(&channels, "Done", "Task done");
+
+        assert_eq!(
+            deliveries,
+            vec![
+                Delivery::Request {
+                    channel_id: webhook.id,
+                    url: "https://example.com/hook".to_string(),
+                    body: json!({
+                        "project_id": Uuid::nil(),
+                        "title": "Done",
+                        "message": "Task done",
+                    }),
+                },
+                Delivery::Request {
+                    channel_id: slack.id,
+                    url: "https://hooks.slack.com/x".to_string(),
+                    body: json!({ "text": "*Done*\nTask done" }),
+                },
+                Delivery::Local,
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_channels_suppress_local_notifications() {
+        let mut webhook = channel(
+            NotificationChannelType::Webhook,
+            json!({ "url": "https://example.com/hook" }),
+        );
+        webhook.enabled = false;
+        assert!(plan_deliveries(&[webhook], "Done", "Task done").is_empty());
+    }
+}
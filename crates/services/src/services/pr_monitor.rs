@@ -119,7 +119,14 @@ impl PrMonitorService {
                     "PR #{} was merged, updating task {} to done and archiving workspace",
                     pr_merge.pr_info.number, workspace.task_id
                 );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                Task::update_status_force(
+                    &self.db.pool,
+                    workspace.task_id,
+                    TaskStatus::Done,
+                    Some("pr_monitor"),
+                    Some("Pull request merged"),
+                )
+                .await?;
 
                 // Archive workspace unless pinned
                 if !workspace.pinned {
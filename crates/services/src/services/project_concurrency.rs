@@ -0,0 +1,87 @@
+//! Processing several projects at once in the background services, so one slow project (a long
+//! AI call or test run) doesn't hold up every other project's cycle.
+
+use std::future::Future;
+
+use futures::{Stream, StreamExt, stream};
+
+/// Projects a background service processes at once unless configured otherwise
+pub const DEFAULT_PROJECT_CONCURRENCY: usize = 4;
+
+/// Most projects a background service may process at once. With both the agent activity and
+/// review automation services at the cap, the database pool still has room for API requests.
+pub const MAX_PROJECT_CONCURRENCY: usize = 8;
+
+/// Run `process` on each project, at most `concurrency` at a time, yielding results in the
+/// order they finish. A project's failure only shows up in its own result.
+pub fn process_concurrently<T, F, Fut>(
+    projects: impl IntoIterator<Item = T>,
+    concurrency: usize,
+    process: F,
+) -> impl Stream<Item = Fut::Output>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future,
+{
+    stream::iter(projects)
+        .map(process)
+        .buffer_unordered(concurrency.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::Notify;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn slow_project_does_not_starve_the_others() {
+        // The slow project only finishes once the fast one has, which never happens when
+        // projects are processed one after another
+        let fast_done = Notify::new();
+        let fast_done = &fast_done;
+        let finished: Vec<&str> = tokio::time::timeout(
+            Duration::from_secs(5),
+            process_concurrently(["slow", "fast"], 2, |project| async move {
+                if project == "slow" {
+                    fast_done.notified().await;
+                } else {
+                    fast_done.notify_one();
+                }
+                project
+            })
+            .collect(),
+        )
+        .await
+        .expect("the slow project blocked the fast one");
+
+        assert_eq!(finished, ["fast", "slow"]);
+    }
+
+    #[tokio::test]
+    async fn failures_stay_with_their_project() {
+        let mut results: Vec<Result<u32, String>> =
+            process_concurrently([1, 2, 3], DEFAULT_PROJECT_CONCURRENCY, |n| async move {
+                if n == 2 {
+                    Err(format!("project {n} failed"))
+                } else {
+                    Ok(n)
+                }
+            })
+            .collect()
+            .await;
+        results.sort_by_key(|r| r.clone().unwrap_or_default());
+
+        assert_eq!(results, [Err("project 2 failed".to_string()), Ok(1), Ok(3)]);
+    }
+
+    #[tokio::test]
+    async fn zero_concurrency_still_processes_projects() {
+        let processed: Vec<u32> = process_concurrently([1, 2], 0, |n| async move { n })
+            .collect()
+            .await;
+        assert_eq!(processed, [1, 2]);
+    }
+}
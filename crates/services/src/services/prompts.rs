@@ -0,0 +1,836 @@
+//! Versioned prompt templates for the AI-backed services.
+//!
+//! Templates are looked up by name and version rather than inlined at each call site, so a
+//! prompt can be tuned by adding a new version and rolling it out per project (see
+//! [`active`]). The label of the version used is recorded with the result it produced.
+//! Placeholders are written `{{name}}`; everything else in a template is sent verbatim.
+
+use db::models::{
+    project::Project, project_prompt_version::ProjectPromptVersion,
+    project_requirements::FeatureGranularity,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use strum::IntoEnumIterator;
+use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
+use tracing::warn;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// The prompt templates, named as they are stored in project pins and version labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, Display, EnumIter, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum TemplateName {
+    /// Extracts features from requirements (`requirements`, `prd_section`, `project_context`,
+    /// `granularity`). v2 grounds the analysis in the project's own context; v3 adds guidance on
+    /// how finely to decompose (see [`feature_granularity_guidance`]).
+    FeatureExtraction,
+    /// Generates implementation tasks for extracted features (`rules`, `features`,
+    /// `project_context`). v2 replaces the built-in architecture rules with the project's
+    /// context.
+    TaskGeneration,
+    /// Picks the next task for the agent to work on (`tasks`). v2 asks for structured reasoning.
+    TaskSelection,
+    /// Splits a task that keeps failing to merge into subtasks (`max_attempts`, `title`,
+    /// `description`, `layer`, `task_type`, `conflict_details`, `conflict_summary`)
+    ConflictBreakdown,
+    /// Judges whether a task's changes meet its testing criteria (`title`, `description`,
+    /// `testing_criteria`, `changed_files`, `test_output`)
+    CriteriaCheck,
+}
+
+/// A named, versioned prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptTemplate {
+    pub name: TemplateName,
+    pub version: i64,
+    pub system: &'static str,
+    pub body: &'static str,
+}
+
+impl PromptTemplate {
+    /// Render the body with the given placeholder values
+    pub fn render(&self, vars: &[(&str, &str)]) -> String {
+        render(self.body, vars)
+    }
+
+    /// `name@vN`, stored alongside results to show which prompt produced them
+    pub fn label(&self) -> String {
+        format!("{}@v{}", self.name, self.version)
+    }
+}
+
+/// Every available template version. Add a new version instead of editing a released one, so
+/// recorded labels keep pointing at the prompt that was actually sent.
+pub const TEMPLATES: &[PromptTemplate] = &[
+    PromptTemplate {
+        name: TemplateName::FeatureExtraction,
+        version: 1,
+        system: FEATURE_EXTRACTION_V1_SYSTEM,
+        body: FEATURE_EXTRACTION_V1,
+    },
+    PromptTemplate {
+        name: TemplateName::FeatureExtraction,
+        version: 2,
+        system: FEATURE_EXTRACTION_V1_SYSTEM,
+        body: FEATURE_EXTRACTION_V2,
+    },
+    PromptTemplate {
+        name: TemplateName::FeatureExtraction,
+        version: 3,
+        system: FEATURE_EXTRACTION_V1_SYSTEM,
+        body: FEATURE_EXTRACTION_V3,
+    },
+    PromptTemplate {
+        name: TemplateName::TaskGeneration,
+        version: 1,
+        system: TASK_GENERATION_V1_SYSTEM,
+        body: TASK_GENERATION_V1,
+    },
+    PromptTemplate {
+        name: TemplateName::TaskGeneration,
+        version: 2,
+        system: TASK_GENERATION_V1_SYSTEM,
+        body: TASK_GENERATION_V2,
+    },
+    PromptTemplate {
+        name: TemplateName::TaskSelection,
+        version: 1,
+        system: TASK_SELECTION_V1_SYSTEM,
+        body: TASK_SELECTION_V1,
+    },
+    PromptTemplate {
+        name: TemplateName::TaskSelection,
+        version: 2,
+        system: TASK_SELECTION_V1_SYSTEM,
+        body: TASK_SELECTION_V2,
+    },
+    PromptTemplate {
+        name: TemplateName::ConflictBreakdown,
+        version: 1,
+        system: CONFLICT_BREAKDOWN_V1_SYSTEM,
+        body: CONFLICT_BREAKDOWN_V1,
+    },
+    PromptTemplate {
+        name: TemplateName::CriteriaCheck,
+        version: 1,
+        system: CRITERIA_CHECK_V1_SYSTEM,
+        body: CRITERIA_CHECK_V1,
+    },
+];
+
+/// A template's available versions and the one a project uses
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PromptTemplateVersions {
+    pub name: String,
+    pub versions: Vec<i64>,
+    pub active_version: i64,
+    pub pinned: bool, // False when the project follows the latest version
+}
+
+/// Look up a specific template version
+pub fn find(name: TemplateName, version: i64) -> Option<&'static PromptTemplate> {
+    TEMPLATES
+        .iter()
+        .find(|template| template.name == name && template.version == version)
+}
+
+/// The newest version of a template. Every [`TemplateName`] has at least one version in
+/// [`TEMPLATES`].
+pub fn latest(name: TemplateName) -> &'static PromptTemplate {
+    TEMPLATES
+        .iter()
+        .filter(|template| template.name == name)
+        .max_by_key(|template| template.version)
+        .expect("every template name has a version in TEMPLATES")
+}
+
+/// The template version a project uses: its pinned version, or the latest when it has none
+pub async fn active(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    name: TemplateName,
+) -> Result<&'static PromptTemplate, sqlx::Error> {
+    if let Some(version) =
+        ProjectPromptVersion::find_version(pool, project_id, name.as_ref()).await?
+    {
+        if let Some(template) = find(name, version) {
+            return Ok(template);
+        }
+        warn!(
+            project_id = %project_id,
+            template = %name,
+            version,
+            "Pinned prompt version does not exist, using the latest"
+        );
+    }
+    Ok(latest(name))
+}
+
+/// The project's system prompt prefix, if it has set a non-empty one
+pub async fn system_prompt_prefix(
+    pool: &SqlitePool,
+    project_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    Ok(Project::find_by_id(pool, project_id)
+        .await?
+        .and_then(|project| project.system_prompt_prefix)
+        .filter(|prefix| !prefix.trim().is_empty()))
+}
+
+/// `system` with the project's system prompt prefix prepended
+pub async fn system_prompt(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    system: &str,
+) -> Result<String, sqlx::Error> {
+    let prefix = system_prompt_prefix(pool, project_id).await?;
+    Ok(prefix_system_prompt(prefix.as_deref(), system))
+}
+
+/// Prepend org-specific guidance to a system prompt
+pub fn prefix_system_prompt(prefix: Option<&str>, system: &str) -> String {
+    match prefix.map(str::trim).filter(|prefix| !prefix.is_empty()) {
+        Some(prefix) => format!("{prefix}\n\n{system}"),
+        None => system.to_string(),
+    }
+}
+
+/// Every template with its versions and the one the project uses
+pub async fn project_versions(
+    pool: &SqlitePool,
+    project_id: Uuid,
+) -> Result<Vec<PromptTemplateVersions>, sqlx::Error> {
+    let pinned = ProjectPromptVersion::find_by_project_id(pool, project_id).await?;
+
+    Ok(TemplateName::iter()
+        .map(|name| {
+            let pinned_version = pinned
+                .iter()
+                .find(|p| p.template_name == name.as_ref())
+                .map(|p| p.version)
+                .filter(|version| find(name, *version).is_some());
+            PromptTemplateVersions {
+                name: name.to_string(),
+                versions: TEMPLATES
+                    .iter()
+                    .filter(|template| template.name == name)
+                    .map(|template| template.version)
+                    .collect(),
+                active_version: pinned_version.unwrap_or_else(|| latest(name).version),
+                pinned: pinned_version.is_some(),
+            }
+        })
+        .collect())
+}
+
+/// Substitute `{{name}}` placeholders in one pass. Values are inserted verbatim and never
+/// re-scanned, and placeholders without a value are left as they are.
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeholder = &rest[start..start + 2 + len + 2];
+        let name = &placeholder[2..placeholder.len() - 2];
+        match vars.iter().find(|(var, _)| *var == name) {
+            Some((_, value)) => rendered.push_str(value),
+            None => rendered.push_str(placeholder),
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Default for how many characters of a task description are embedded in a prompt
+pub const DEFAULT_MAX_DESCRIPTION_CHARS: usize = 4000;
+
+/// Cut a task description to at most `max_chars` characters (plus a marker) so one very long
+/// description can't crowd the rest out of a prompt. The stored description is untouched.
+pub fn truncate_description(task_id: Uuid, description: &str, max_chars: usize) -> String {
+    let Some((cutoff, _)) = description.char_indices().nth(max_chars) else {
+        return description.to_string();
+    };
+    warn!(
+        %task_id,
+        chars = description.chars().count(),
+        max_chars,
+        "Truncating long task description in prompt"
+    );
+    format!("{}… [truncated]", description[..cutoff].trim_end())
+}
+
+/// The `granularity` value for feature extraction: how many features to aim for and how large
+/// each one should be
+pub fn feature_granularity_guidance(granularity: FeatureGranularity) -> &'static str {
+    match granularity {
+        FeatureGranularity::Coarse => {
+            "coarse. Group related work into a few broad features (typically 3-6), each covering \
+             a whole capability end to end. Do not split a capability into separate features per \
+             layer or per screen."
+        }
+        FeatureGranularity::Normal => {
+            "normal. Extract one feature per distinct capability a user or operator would \
+             recognise, splitting only where parts can be built and reviewed independently."
+        }
+        FeatureGranularity::Fine => {
+            "fine. Break the work into many small, independently deliverable features, each \
+             small enough to implement and review in a single change. Split large capabilities \
+             by layer, screen or endpoint where that keeps features small."
+        }
+    }
+}
+
+const FEATURE_EXTRACTION_V1_SYSTEM: &str = "You are a software architect analyzing requirements for an EXISTING project to extract \
+     features. Consider that you're working with an established codebase and architecture. Be \
+     concise and practical. Focus on actionable features that extend or modify the existing \
+     system. Output valid JSON only.";
+
+const FEATURE_EXTRACTION_V1: &str = r#"Analyze the following project requirements and extract distinct features that need to be implemented.
+
+IMPORTANT: This is for an EXISTING working project. Features should be analyzed in the context of extending/modifying the existing codebase.
+
+## Requirements
+{{requirements}}
+{{prd_section}}
+## Instructions
+1. Identify distinct features that need to be implemented
+2. For each feature, determine which layer it primarily belongs to:
+   - "data": Database models, schemas, migrations (SQLite)
+   - "backend": API endpoints, business logic, services (Node.js)
+   - "frontend": UI components, pages, user interactions (React + Vite + Zustand)
+   - "fullstack": Features spanning multiple layers
+   - "devops": Infrastructure, deployment, CI/CD
+   - "testing": Test coverage, test utilities
+3. Assign a priority (1=highest, 5=lowest) based on dependencies and importance
+4. Consider cross-layer dependencies - features that require data models, API contracts, and UI components
+
+## Output Format
+Return ONLY valid JSON with this structure:
+```json
+{
+  "features": [
+    {
+      "name": "Feature name",
+      "description": "Brief description of what needs to be built, including any cross-layer dependencies",
+      "layer": "backend|frontend|data|fullstack|devops|testing",
+      "priority": 1
+    }
+  ],
+  "summary": "Brief summary of the overall project scope"
+}
+```
+"#;
+
+const FEATURE_EXTRACTION_V2: &str = r#"Analyze the following project requirements and extract distinct features that need to be implemented.
+
+IMPORTANT: This is for an EXISTING working project. Features should be analyzed in the context of extending/modifying the existing codebase described below.
+
+## Project Context
+{{project_context}}
+
+## Requirements
+{{requirements}}
+{{prd_section}}
+## Instructions
+1. Identify distinct features that need to be implemented
+2. For each feature, determine which layer it primarily belongs to:
+   - "data": Database models, schemas, migrations
+   - "backend": API endpoints, business logic, services
+   - "frontend": UI components, pages, user interactions
+   - "fullstack": Features spanning multiple layers
+   - "devops": Infrastructure, deployment, CI/CD
+   - "testing": Test coverage, test utilities
+3. Assign a priority (1=highest, 5=lowest) based on dependencies and importance
+4. Consider cross-layer dependencies - features that require data models, API contracts, and UI components
+
+## Output Format
+Return ONLY valid JSON with this structure:
+```json
+{
+  "features": [
+    {
+      "name": "Feature name",
+      "description": "Brief description of what needs to be built, including any cross-layer dependencies",
+      "layer": "backend|frontend|data|fullstack|devops|testing",
+      "priority": 1
+    }
+  ],
+  "summary": "Brief summary of the overall project scope"
+}
+```
+"#;
+
+const FEATURE_EXTRACTION_V3: &str = r#"Analyze the following project requirements and extract distinct features that need to be implemented.
+
+IMPORTANT: This is for an EXISTING working project. Features should be analyzed in the context of extending/modifying the existing codebase described below.
+
+## Project Context
+{{project_context}}
+
+## Requirements
+{{requirements}}
+{{prd_section}}
+## Instructions
+1. Identify distinct features that need to be implemented
+2. For each feature, determine which layer it primarily belongs to:
+   - "data": Database models, schemas, migrations
+   - "backend": API endpoints, business logic, services
+   - "frontend": UI components, pages, user interactions
+   - "fullstack": Features spanning multiple layers
+   - "devops": Infrastructure, deployment, CI/CD
+   - "testing": Test coverage, test utilities
+3. Assign a priority (1=highest, 5=lowest) based on dependencies and importance
+4. Consider cross-layer dependencies - features that require data models, API contracts, and UI components
+5. Decompose the requirements at this level of detail: {{granularity}}
+
+## Output Format
+Return ONLY valid JSON with this structure:
+```json
+{
+  "features": [
+    {
+      "name": "Feature name",
+      "description": "Brief description of what needs to be built, including any cross-layer dependencies",
+      "layer": "backend|frontend|data|fullstack|devops|testing",
+      "priority": 1
+    }
+  ],
+  "summary": "Brief summary of the overall project scope"
+}
+```
+"#;
+
+const TASK_GENERATION_V1_SYSTEM: &str = "You are a software architect analyzing an EXISTING codebase and generating \
+     implementation tasks. You must analyze the existing project structure, identify \
+     patterns, and generate tasks that work with the existing architecture. Each task should \
+     reference specific files to modify and include a markdown template for progress \
+     tracking. Use a mock-first approach where appropriate. Output valid JSON only.";
+
+const TASK_GENERATION_V1: &str = r#"Generate implementation tasks for the following features.
+
+IMPORTANT: This is an EXISTING working project. You must analyze the existing codebase structure and generate tasks that work with the existing files and architecture.
+
+## ARCHITECTURE RULES (MUST FOLLOW)
+{{rules}}
+
+## Features to Implement
+{{features}}
+
+## Task Generation Strategy
+
+Generate tasks in this EXACT ORDER:
+
+### 1. Architecture Tasks (task_type: "architecture")
+Analyze existing architecture and define any new models/contracts needed:
+- Identify existing data models, API patterns, state management
+- Define any NEW interfaces/types needed for the features
+- Design database schema changes if needed
+- Create database migrations if database changes are required
+- Ensure database is initialized before proceeding
+
+### 2. Implementation Tasks (task_type: "implementation")
+Build real implementations working with the existing codebase:
+- Modify existing files or create new ones following project conventions
+- Integrate with existing patterns and services
+- Reference specific files that need modification
+- Implement full functionality (no mocks - build the real thing)
+- Include database operations if needed (queries, mutations)
+- Run and test migrations as part of implementation
+
+### 3. Integration Task (task_type: "integration")
+Wire new features into the existing application:
+- Connect new endpoints/components to existing infrastructure
+- Verify database migrations have been applied
+- End-to-end testing of the complete flow
+- Ensure all database tables and schemas are correct
+
+## Output Format
+Return ONLY valid JSON:
+```json
+{
+  "tasks": [
+    {
+      "title": "Short task title",
+      "description": "Detailed description explaining WHAT to build and HOW it integrates with existing code",
+      "layer": "backend|frontend|data|fullstack|devops|testing",
+      "task_type": "architecture|implementation|integration",
+      "testing_criteria": "Specific, verifiable criteria to confirm this task is complete",
+      "files_to_modify": ["path/to/file1.ts", "path/to/file2.tsx"],
+      "post_task_actions": "<markdown template - see below>",
+      "feature_id": "<Id of the feature this task implements>"
+    }
+  ]
+}
+```
+
+## post_task_actions Format
+MUST be markdown text that will be appended to .progress file. Include:
+- Task title as heading
+- Status with timestamp
+- Summary of what was done
+- List of files changed
+- Testing results
+- Separator line
+
+IMPORTANT:
+- Analyze the EXISTING project structure before generating tasks
+- Reference specific existing files that need modification in files_to_modify
+- FOLLOW THE ARCHITECTURE RULES STRICTLY - do not recreate components that already exist
+- For frontend tasks: DO NOT create new navbar/sidebar, use existing layout components
+- For backend tasks: Follow the routing, service, and model patterns
+- For database tasks: ALWAYS create migration files, run pnpm run prepare-db, never use npm run init-db
+- Database must exist before migrations run - include database creation check in architecture tasks
+- post_task_actions should be actual markdown text, not a template with placeholders
+- This markdown will be appended to .progress file when task completes
+- Include a "Rules Followed" section in post_task_actions listing which architecture rules were applied
+- Include a "Database Changes" section if migrations were created
+"#;
+
+const TASK_GENERATION_V2: &str = r#"Generate implementation tasks for the following features.
+
+IMPORTANT: This is an EXISTING working project. You must analyze the existing codebase structure and generate tasks that work with the existing files and architecture.
+
+## Project Context (MUST FOLLOW)
+{{project_context}}
+
+## Features to Implement
+{{features}}
+
+## Task Generation Strategy
+
+Generate tasks in this EXACT ORDER:
+
+### 1. Architecture Tasks (task_type: "architecture")
+Analyze existing architecture and define any new models/contracts needed:
+- Identify existing data models, API patterns, state management
+- Define any NEW interfaces/types needed for the features
+- Design database schema changes if needed
+- Create database migrations if database changes are required
+- Ensure database is initialized before proceeding
+
+### 2. Implementation Tasks (task_type: "implementation")
+Build real implementations working with the existing codebase:
+- Modify existing files or create new ones following project conventions
+- Integrate with existing patterns and services
+- Reference specific files that need modification
+- Implement full functionality (no mocks - build the real thing)
+- Include database operations if needed (queries, mutations)
+- Run and test migrations as part of implementation
+
+### 3. Integration Task (task_type: "integration")
+Wire new features into the existing application:
+- Connect new endpoints/components to existing infrastructure
+- Verify database migrations have been applied
+- End-to-end testing of the complete flow
+- Ensure all database tables and schemas are correct
+
+## Output Format
+Return ONLY valid JSON:
+```json
+{
+  "tasks": [
+    {
+      "title": "Short task title",
+      "description": "Detailed description explaining WHAT to build and HOW it integrates with existing code",
+      "layer": "backend|frontend|data|fullstack|devops|testing",
+      "task_type": "architecture|implementation|integration",
+      "testing_criteria": "Specific, verifiable criteria to confirm this task is complete",
+      "files_to_modify": ["path/to/file1.ts", "path/to/file2.tsx"],
+      "post_task_actions": "<markdown template - see below>",
+      "feature_id": "<Id of the feature this task implements>"
+    }
+  ]
+}
+```
+
+## post_task_actions Format
+MUST be markdown text that will be appended to .progress file. Include:
+- Task title as heading
+- Status with timestamp
+- Summary of what was done
+- List of files changed
+- Testing results
+- Separator line
+
+IMPORTANT:
+- Analyze the EXISTING project structure before generating tasks
+- Reference specific existing files that need modification in files_to_modify
+- FOLLOW THE PROJECT CONTEXT STRICTLY - use its tech stack and conventions, and do not recreate components that already exist
+- For database tasks: ALWAYS create migration files using the project's migration tooling
+- Database must exist before migrations run - include database creation check in architecture tasks
+- post_task_actions should be actual markdown text, not a template with placeholders
+- This markdown will be appended to .progress file when task completes
+- Include a "Conventions Followed" section in post_task_actions listing which project conventions were applied
+- Include a "Database Changes" section if migrations were created
+"#;
+
+const TASK_SELECTION_V1_SYSTEM: &str = "You are a task prioritization assistant. Your PRIMARY goal is ensuring the codebase is \
+     always runnable. Initialization and setup tasks MUST be completed first. Select the most \
+     appropriate task based on strict priority order. Output valid JSON only.";
+
+const TASK_SELECTION_V1: &str = r#"You are a task prioritization assistant. Analyze the following tasks and select the ONE task that should be worked on next.
+
+## CRITICAL: Prioritization Rules (in strict order):
+1. **INITIALIZATION FIRST**: Tasks that initialize or set up the project MUST come first. Look for:
+   - Tasks with sequence=1 (highest priority)
+   - Architecture tasks that set up project structure, configs, or scaffolding
+   - Tasks with titles containing: "init", "setup", "scaffold", "configure", "create project", "initialize"
+   - The project must be runnable in the browser after these tasks complete!
+
+2. **Sequence order**: Lower sequence number = higher priority (sequence 1 before 2, 2 before 3, etc.)
+
+3. **Task type order**: architecture → mock → implementation → integration
+   - Architecture tasks set up structure (do these early)
+   - Mock tasks enable parallel development
+   - Implementation tasks build features
+   - Integration tasks come last (they wire everything together)
+
+4. **Layer dependencies**: data → backend → frontend → fullstack
+   - Data layer should be set up before backend
+   - Backend before frontend (frontend needs API endpoints)
+
+5. **Unblocking**: Prefer tasks that enable other tasks to proceed
+
+## Tasks:
+{{tasks}}
+
+## Output Format:
+Return ONLY valid JSON:
+{
+  "task_id": "uuid-of-selected-task",
+  "reasoning": "Brief explanation of why this task was selected"
+}"#;
+
+const TASK_SELECTION_V2: &str = r#"You are a task prioritization assistant. Analyze the following tasks and select the ONE task that should be worked on next.
+
+## CRITICAL: Prioritization Rules (in strict order):
+1. **INITIALIZATION FIRST**: Tasks that initialize or set up the project MUST come first. Look for:
+   - Tasks with sequence=1 (highest priority)
+   - Architecture tasks that set up project structure, configs, or scaffolding
+   - Tasks with titles containing: "init", "setup", "scaffold", "configure", "create project", "initialize"
+   - The project must be runnable in the browser after these tasks complete!
+
+2. **Sequence order**: Lower sequence number = higher priority (sequence 1 before 2, 2 before 3, etc.)
+
+3. **Task type order**: architecture → mock → implementation → integration
+   - Architecture tasks set up structure (do these early)
+   - Mock tasks enable parallel development
+   - Implementation tasks build features
+   - Integration tasks come last (they wire everything together)
+
+4. **Layer dependencies**: data → backend → frontend → fullstack
+   - Data layer should be set up before backend
+   - Backend before frontend (frontend needs API endpoints)
+
+5. **Unblocking**: Prefer tasks that enable other tasks to proceed
+
+## Tasks:
+{{tasks}}
+
+## Output Format:
+Return ONLY valid JSON:
+{
+  "task_id": "uuid-of-selected-task",
+  "runner_up_task_id": "uuid-of-the-next-best-task, or null if there is only one task",
+  "summary": "One sentence on why this task was chosen over the runner-up",
+  "factors": {
+    "priority": "How sequence, task type and initialization order ranked it",
+    "unblocking": "Which tasks, and how many, it unblocks",
+    "layer_readiness": "Whether the layers it depends on are already in place"
+  }
+}"#;
+
+const CONFLICT_BREAKDOWN_V1_SYSTEM: &str = "You are a task breakdown assistant. Break complex tasks into smaller, independent pieces \
+     that can be merged without conflicts. Output valid JSON only.";
+
+const CONFLICT_BREAKDOWN_V1: &str = r#"A software development task has failed to merge {{max_attempts}} times due to conflicts.
+The task needs to be broken down into smaller, simpler subtasks that are less likely to cause conflicts.
+
+## Original Task
+Title: {{title}}
+Description: {{description}}
+Layer: {{layer}}
+Type: {{task_type}}
+
+## Conflict Details
+{{conflict_details}}
+
+{{conflict_summary}}
+
+## Requirements
+1. Break this task into 2-4 smaller, independent subtasks
+2. Each subtask should be small enough to avoid merge conflicts, and should isolate changes to the conflicting files listed above
+3. Subtasks should be able to be completed and merged independently
+4. Focus on making atomic, isolated changes
+
+## Output Format (JSON only):
+{
+  "subtasks": [
+    {"title": "<subtask title>", "description": "<clear description of what to do>", "layer": "<data|backend|frontend|null>"},
+    ...
+  ],
+  "reasoning": "<brief explanation of how you split the task>"
+}"#;
+
+const CRITERIA_CHECK_V1_SYSTEM: &str = "You are a strict code reviewer. Judge only whether the evidence shows each acceptance \
+     criterion is met; passing tests that don't exercise a criterion are not evidence. Output valid JSON only.";
+
+const CRITERIA_CHECK_V1: &str = r#"A task is about to be merged. Decide whether its testing criteria are satisfied.
+
+## Task
+Title: {{title}}
+Description: {{description}}
+
+## Testing Criteria
+{{testing_criteria}}
+
+## Files Changed
+{{changed_files}}
+
+## Test Output
+{{test_output}}
+
+## Output Format (JSON only):
+{
+  "met": <true if every criterion is satisfied by the changes and test output, else false>,
+  "unmet_criteria": ["<criterion that is not shown to be met>", ...],
+  "reasoning": "<brief explanation citing the tests or files that show each criterion is met or not>"
+}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_substitutes_placeholders() {
+        let rendered = render(
+            "Hello {{name}}, you have {{count}} tasks",
+            &[("name", "Ada"), ("count", "3")],
+        );
+        assert_eq!(rendered, "Hello Ada, you have 3 tasks");
+    }
+
+    #[test]
+    fn render_leaves_unknown_and_unterminated_placeholders() {
+        assert_eq!(render("{{missing}} {{", &[]), "{{missing}} {{");
+        assert_eq!(render("{\"json\": {}}", &[]), "{\"json\": {}}");
+    }
+
+    #[test]
+    fn render_does_not_expand_substituted_values() {
+        let rendered = render("{{a}} {{b}}", &[("a", "{{b}}"), ("b", "x")]);
+        assert_eq!(rendered, "{{b}} x");
+    }
+
+    #[test]
+    fn prefix_system_prompt_prepends_non_empty_prefixes() {
+        assert_eq!(
+            prefix_system_prompt(Some("Never use lodash."), "You are a planner."),
+            "Never use lodash.\n\nYou are a planner."
+        );
+        assert_eq!(
+            prefix_system_prompt(Some("  \n"), "You are a planner."),
+            "You are a planner."
+        );
+        assert_eq!(
+            prefix_system_prompt(None, "You are a planner."),
+            "You are a planner."
+        );
+    }
+
+    #[test]
+    fn truncate_description_keeps_short_text_and_marks_cuts() {
+        let id = Uuid::new_v4();
+        assert_eq!(truncate_description(id, "short", 5), "short");
+        assert_eq!(
+            truncate_description(id, "a long description", 6),
+            "a long… [truncated]"
+        );
+        assert_eq!(
+            truncate_description(id, "日本語のテキスト", 3),
+            "日本語… [truncated]"
+        );
+    }
+
+    #[test]
+    fn every_template_has_a_unique_version_and_a_latest() {
+        for template in TEMPLATES {
+            let same_version = TEMPLATES
+                .iter()
+                .filter(|t| t.name == template.name && t.version == template.version)
+                .count();
+            assert_eq!(same_version, 1, "{} is duplicated", template.label());
+        }
+        for name in TemplateName::iter() {
+            assert!(
+                TEMPLATES.iter().any(|template| template.name == name),
+                "{name} has no template"
+            );
+            assert_eq!(name.as_ref().parse::<TemplateName>(), Ok(name));
+        }
+        assert!(find(TemplateName::TaskSelection, 0).is_none());
+    }
+
+    #[test]
+    fn feature_extraction_renders_granularity_guidance() {
+        let template = latest(TemplateName::FeatureExtraction);
+        assert_eq!(template.version, 3);
+        for granularity in [
+            FeatureGranularity::Coarse,
+            FeatureGranularity::Normal,
+            FeatureGranularity::Fine,
+        ] {
+            let guidance = feature_granularity_guidance(granularity);
+            let rendered = template.render(&[
+                ("requirements", "Users can sign in"),
+                ("prd_section", ""),
+                ("project_context", "Rust + axum backend"),
+                ("granularity", guidance),
+            ]);
+            assert!(guidance.starts_with(&granularity.to_string()));
+            assert!(rendered.contains(guidance));
+            assert!(!rendered.contains("{{"), "{granularity} left a placeholder");
+        }
+    }
+
+    #[test]
+    fn task_selection_renders_tasks() {
+        let template = find(TemplateName::TaskSelection, 1).unwrap();
+        let rendered = template.render(&[("tasks", "[]")]);
+        assert!(rendered.contains("## Tasks:\n[]"));
+        assert!(!rendered.contains("{{"));
+        assert_eq!(template.label(), "task_selection@v1");
+        assert_eq!(latest(TemplateName::TaskSelection).version, 2);
+    }
+
+    #[test]
+    fn generation_prompts_render_project_context() {
+        let vars = [
+            ("requirements", "Users can sign in"),
+            ("prd_section", ""),
+            ("rules", "built-in rules"),
+            ("features", "- Auth"),
+            ("project_context", "Rust + axum backend, SvelteKit frontend"),
+        ];
+        for name in [
+            TemplateName::FeatureExtraction,
+            TemplateName::TaskGeneration,
+        ] {
+            let template = find(name, 2).unwrap();
+            let rendered = template.render(&vars);
+            assert!(rendered.contains("Rust + axum backend, SvelteKit frontend"));
+            assert!(!rendered.contains("built-in rules"));
+            assert!(
+                !rendered.contains("{{"),
+                "{} left a placeholder",
+                template.label()
+            );
+        }
+    }
+}
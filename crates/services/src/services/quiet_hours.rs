@@ -0,0 +1,133 @@
+//! Per-project quiet hours, during which the agent doesn't pick up work and only critical
+//! notifications are delivered.
+//!
+//! A window is a pair of `HH:MM` local times, start inclusive and end exclusive, in a fixed
+//! offset from UTC. A start later than the end spans midnight (e.g. `22:00`-`07:00`).
+
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+use db::models::agent_activity::ProjectAgentSettings;
+
+/// Largest offsets from UTC in use (UTC-12:00 to UTC+14:00), in minutes
+const MIN_UTC_OFFSET_MINUTES: i32 = -12 * 60;
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// A project's quiet hours window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    start: NaiveTime,
+    end: NaiveTime,
+    offset: FixedOffset,
+}
+
+impl QuietHours {
+    /// Build a window from `HH:MM` times and a UTC offset in minutes
+    pub fn new(start: &str, end: &str, utc_offset_minutes: i32) -> Result<Self, String> {
+        let start = parse_time(start).map_err(|e| format!("quiet_hours_start {e}"))?;
+        let end = parse_time(end).map_err(|e| format!("quiet_hours_end {e}"))?;
+        if start == end {
+            return Err("quiet_hours_start and quiet_hours_end must differ".to_string());
+        }
+        validate_utc_offset(utc_offset_minutes)?;
+        let offset = FixedOffset::east_opt(utc_offset_minutes * 60)
+            .ok_or_else(|| "invalid quiet_hours_utc_offset_minutes".to_string())?;
+        Ok(Self { start, end, offset })
+    }
+
+    /// The project's quiet hours, if it has a valid window configured
+    pub fn from_settings(settings: &ProjectAgentSettings) -> Option<Self> {
+        let (start, end) = (
+            settings.quiet_hours_start.as_deref()?,
+            settings.quiet_hours_end.as_deref()?,
+        );
+        Self::new(start, end, settings.quiet_hours_utc_offset_minutes).ok()
+    }
+
+    /// Whether `now` falls inside the window
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.offset).time();
+        if self.start < self.end {
+            self.start <= local && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+}
+
+/// Whether `settings` (if any) put the project in quiet hours at `now`
+pub fn is_quiet(settings: Option<&ProjectAgentSettings>, now: DateTime<Utc>) -> bool {
+    settings
+        .and_then(QuietHours::from_settings)
+        .is_some_and(|quiet_hours| quiet_hours.contains(now))
+}
+
+/// Reject offsets outside the range of real time zones
+pub fn validate_utc_offset(utc_offset_minutes: i32) -> Result<(), String> {
+    if !(MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&utc_offset_minutes) {
+        return Err(format!(
+            "quiet_hours_utc_offset_minutes must be between {MIN_UTC_OFFSET_MINUTES} and \
+             {MAX_UTC_OFFSET_MINUTES}"
+        ));
+    }
+    Ok(())
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("must be a time formatted HH:MM, got '{value}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 15, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn daytime_window_is_start_inclusive_end_exclusive() {
+        let quiet_hours = QuietHours::new("12:00", "13:30", 0).unwrap();
+        assert!(!quiet_hours.contains(at(11, 59)));
+        assert!(quiet_hours.contains(at(12, 0)));
+        assert!(quiet_hours.contains(at(13, 29)));
+        assert!(!quiet_hours.contains(at(13, 30)));
+    }
+
+    #[test]
+    fn window_spanning_midnight_covers_both_sides() {
+        let quiet_hours = QuietHours::new("22:00", "07:00", 0).unwrap();
+        assert!(quiet_hours.contains(at(22, 0)));
+        assert!(quiet_hours.contains(at(23, 59)));
+        assert!(quiet_hours.contains(at(0, 0)));
+        assert!(quiet_hours.contains(at(3, 0)));
+        assert!(!quiet_hours.contains(at(7, 0)));
+        assert!(!quiet_hours.contains(at(12, 0)));
+        assert!(!quiet_hours.contains(at(21, 59)));
+    }
+
+    #[test]
+    fn offset_shifts_the_window_from_utc() {
+        // 22:00-07:00 at UTC+02:00 is 20:00-05:00 UTC
+        let quiet_hours = QuietHours::new("22:00", "07:00", 120).unwrap();
+        assert!(quiet_hours.contains(at(20, 0)));
+        assert!(quiet_hours.contains(at(4, 59)));
+        assert!(!quiet_hours.contains(at(5, 0)));
+        assert!(!quiet_hours.contains(at(19, 59)));
+
+        // A window within one UTC day can span midnight locally: 23:00-01:00 at UTC-05:00
+        let quiet_hours = QuietHours::new("23:00", "01:00", -300).unwrap();
+        assert!(quiet_hours.contains(at(4, 0)));
+        assert!(quiet_hours.contains(at(5, 30)));
+        assert!(!quiet_hours.contains(at(6, 0)));
+    }
+
+    #[test]
+    fn rejects_invalid_windows() {
+        assert!(QuietHours::new("25:00", "07:00", 0).is_err());
+        assert!(QuietHours::new("22:00", "7am", 0).is_err());
+        assert!(QuietHours::new("22:00", "22:00", 0).is_err());
+        assert!(QuietHours::new("22:00", "07:00", 15 * 60).is_err());
+    }
+}
@@ -1,20 +1,22 @@
 //! Service for analyzing requirements and generating tasks using Claude AI.
 
 use db::models::{
+    project::Project,
     project_requirements::{
-        AnalysisResult, CreateProjectRequirements, ExtractedFeature, GenerationStatus,
-        ProjectRequirements,
+        AnalysisResult, CreateProjectRequirements, ExtractedFeature, FeatureGranularity,
+        GenerationStatus, ProjectRequirements, ProjectRequirementsVersion,
     },
     task::{CreateTask, Task, TaskLayer, TaskType},
 };
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use thiserror::Error;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use super::claude_api::{ClaudeApiClient, ClaudeApiError};
+use super::claude_api::{ClaudeApiClient, ClaudeApiError, ModelRole};
 use super::codebase_rules;
+use super::prompts::{self, TemplateName};
 
 #[derive(Debug, Error)]
 pub enum RequirementsAnalyzerError {
@@ -58,6 +60,8 @@ struct GeneratedTask {
     testing_criteria: Option<String>,
     files_to_modify: Option<Vec<String>>,
     post_task_actions: Option<String>,
+    #[serde(default)]
+    feature_id: Option<String>,
 }
 
 /// Service for analyzing requirements and generating tasks
@@ -76,7 +80,9 @@ impl RequirementsAnalyzer {
         Self { pool, claude }
     }
 
-    /// Create a new requirements record and start async analysis
+    /// Create a new requirements record and start async analysis. Fails with
+    /// `AlreadyInProgress` while another analysis for the project is running, so concurrent
+    /// submissions cannot generate duplicate task sets.
     pub async fn create_and_analyze(
         &self,
         project_id: Uuid,
@@ -84,7 +90,9 @@ impl RequirementsAnalyzer {
     ) -> Result<ProjectRequirements, RequirementsAnalyzerError> {
         let id = Uuid::new_v4();
         let requirements =
-            ProjectRequirements::create(&self.pool, id, project_id, &data).await?;
+            ProjectRequirements::create_unless_in_progress(&self.pool, id, project_id, &data)
+                .await?
+                .ok_or(RequirementsAnalyzerError::AlreadyInProgress)?;
 
         info!(
             requirements_id = %id,
@@ -92,24 +100,309 @@ impl RequirementsAnalyzer {
             "Created requirements record, starting analysis"
         );
 
-        // Clone what we need for the spawned task
+        self.spawn_analysis(
+            id,
+            project_id,
+            data.raw_requirements,
+            data.prd_content,
+            requirements.granularity,
+        );
+
+        Ok(requirements)
+    }
+
+    /// Re-run analysis for the project's latest requirements, reusing the stored requirements
+    /// text and PRD. Like an edit, the result is compared with the previous analysis: only new
+    /// or changed features get tasks generated, replacing the untouched Todo tasks of changed
+    /// features, and removed features lose theirs.
+    pub async fn reanalyze(
+        &self,
+        project_id: Uuid,
+    ) -> Result<ProjectRequirements, RequirementsAnalyzerError> {
+        let requirements = ProjectRequirements::find_by_project_id(&self.pool, project_id)
+            .await?
+            .ok_or(RequirementsAnalyzerError::NotFound)?;
+        if requirements.generation_status.is_in_progress() {
+            return Err(RequirementsAnalyzerError::AlreadyInProgress);
+        }
+
+        ProjectRequirements::update_status(
+            &self.pool,
+            requirements.id,
+            GenerationStatus::Pending,
+            None,
+        )
+        .await?;
+
+        let previous = requirements.parsed_analysis();
+        info!(
+            requirements_id = %requirements.id,
+            project_id = %project_id,
+            incremental = previous.is_some(),
+            "Re-running analysis with stored requirements"
+        );
+
+        match previous {
+            Some(previous) => self.spawn_incremental_analysis(
+                requirements.id,
+                project_id,
+                requirements.raw_requirements,
+                requirements.prd_content,
+                requirements.granularity,
+                previous,
+            ),
+            None => self.spawn_analysis(
+                requirements.id,
+                project_id,
+                requirements.raw_requirements,
+                requirements.prd_content,
+                requirements.granularity,
+            ),
+        }
+
+        ProjectRequirements::find_by_id(&self.pool, requirements.id)
+            .await?
+            .ok_or(RequirementsAnalyzerError::NotFound)
+    }
+
+    /// Replace the project's requirements text or granularity and update the task plan to match.
+    /// The previous text is kept as a version; features whose name survives the edit keep their id and tasks
+    /// unless their description or layer changed, so only new or changed features get tasks
+    /// generated and removed features lose theirs.
+    pub async fn update_and_reanalyze(
+        &self,
+        project_id: Uuid,
+        data: CreateProjectRequirements,
+    ) -> Result<ProjectRequirements, RequirementsAnalyzerError> {
+        let requirements = ProjectRequirements::find_by_project_id(&self.pool, project_id)
+            .await?
+            .ok_or(RequirementsAnalyzerError::NotFound)?;
+        if requirements.generation_status.is_in_progress() {
+            return Err(RequirementsAnalyzerError::AlreadyInProgress);
+        }
+        if requirements.raw_requirements == data.raw_requirements
+            && requirements.prd_content == data.prd_content
+            && data
+                .granularity
+                .is_none_or(|granularity| granularity == requirements.granularity)
+        {
+            return Ok(requirements);
+        }
+
+        let previous = requirements.parsed_analysis();
+        let updated = ProjectRequirements::update_content_unless_in_progress(
+            &self.pool,
+            requirements.id,
+            &data,
+        )
+        .await?
+        .ok_or(RequirementsAnalyzerError::AlreadyInProgress)?;
+
+        info!(
+            requirements_id = %updated.id,
+            project_id = %project_id,
+            incremental = previous.is_some(),
+            "Requirements edited, updating the task plan"
+        );
+
+        match previous {
+            Some(previous) => self.spawn_incremental_analysis(
+                updated.id,
+                project_id,
+                data.raw_requirements,
+                data.prd_content,
+                updated.granularity,
+                previous,
+            ),
+            // Without an earlier analysis there is nothing to diff against
+            None => self.spawn_analysis(
+                updated.id,
+                project_id,
+                data.raw_requirements,
+                data.prd_content,
+                updated.granularity,
+            ),
+        }
+
+        Ok(updated)
+    }
+
+    /// Run the incremental analysis pipeline for edited requirements in the background
+    fn spawn_incremental_analysis(
+        &self,
+        requirements_id: Uuid,
+        project_id: Uuid,
+        raw_requirements: String,
+        prd_content: Option<String>,
+        granularity: FeatureGranularity,
+        previous: AnalysisResult,
+    ) {
         let pool = self.pool.clone();
         let claude = self.claude.clone();
-        let raw_requirements = data.raw_requirements.clone();
-        let prd_content = data.prd_content.clone();
+        tokio::spawn(async move {
+            let analyzer = RequirementsAnalyzer::with_client(pool, claude);
+            if let Err(e) = analyzer
+                .run_incremental_analysis(
+                    requirements_id,
+                    project_id,
+                    &raw_requirements,
+                    prd_content.as_deref(),
+                    granularity,
+                    previous,
+                )
+                .await
+            {
+                error!(error = %e, "Incremental requirements analysis failed");
+            }
+        });
+    }
+
+    /// Re-extract features from edited requirements and regenerate tasks only for the features
+    /// that were added or changed, deleting the tasks of features that were removed
+    async fn run_incremental_analysis(
+        &self,
+        requirements_id: Uuid,
+        project_id: Uuid,
+        raw_requirements: &str,
+        prd_content: Option<&str>,
+        granularity: FeatureGranularity,
+        previous: AnalysisResult,
+    ) -> Result<(), RequirementsAnalyzerError> {
+        ProjectRequirements::update_status(
+            &self.pool,
+            requirements_id,
+            GenerationStatus::Analyzing,
+            None,
+        )
+        .await?;
+
+        let mut analysis_result = match self
+            .analyze_requirements(project_id, raw_requirements, prd_content, granularity)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                ProjectRequirements::update_status(
+                    &self.pool,
+                    requirements_id,
+                    GenerationStatus::Failed,
+                    Some(&e.to_string()),
+                )
+                .await?;
+                return Err(e);
+            }
+        };
+        let diff = diff_features(&previous.features, &mut analysis_result.features);
+        ProjectRequirements::update_analysis_result(&self.pool, requirements_id, &analysis_result)
+            .await?;
+
+        info!(
+            requirements_id = %requirements_id,
+            added = diff.added.len(),
+            changed = diff.changed.len(),
+            removed = diff.removed.len(),
+            "Compared edited requirements with the previous analysis"
+        );
 
-        // Spawn the analysis in the background
+        ProjectRequirements::update_status(
+            &self.pool,
+            requirements_id,
+            GenerationStatus::Generating,
+            None,
+        )
+        .await?;
+
+        match self
+            .apply_feature_diff(
+                requirements_id,
+                project_id,
+                &analysis_result.features,
+                &diff,
+            )
+            .await
+        {
+            Ok(task_count) => {
+                info!(
+                    requirements_id = %requirements_id,
+                    task_count = task_count,
+                    "Incremental task generation completed"
+                );
+                ProjectRequirements::update_status(
+                    &self.pool,
+                    requirements_id,
+                    GenerationStatus::Completed,
+                    None,
+                )
+                .await?;
+            }
+            Err(e) => {
+                ProjectRequirements::update_status(
+                    &self.pool,
+                    requirements_id,
+                    GenerationStatus::Failed,
+                    Some(&e.to_string()),
+                )
+                .await?;
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate tasks for added and changed features, then replace the changed features' old
+    /// untouched Todo tasks and delete the removed features' ones. Tasks are only deleted once
+    /// generation has succeeded.
+    async fn apply_feature_diff(
+        &self,
+        requirements_id: Uuid,
+        project_id: Uuid,
+        features: &[ExtractedFeature],
+        diff: &FeatureDiff,
+    ) -> Result<usize, RequirementsAnalyzerError> {
+        let regenerate: Vec<ExtractedFeature> = features
+            .iter()
+            .filter(|f| diff.added.contains(&f.id) || diff.changed.contains(&f.id))
+            .cloned()
+            .collect();
+        let tasks = if regenerate.is_empty() {
+            Vec::new()
+        } else {
+            self.generate_architecture_first_tasks(project_id, &regenerate)
+                .await?
+        };
+
+        let replaced: Vec<Uuid> = diff.changed.iter().chain(&diff.removed).copied().collect();
+        self.insert_generated_tasks(requirements_id, project_id, &regenerate, tasks, &replaced)
+            .await
+    }
+
+    /// Run the analysis pipeline for a requirements record in the background
+    fn spawn_analysis(
+        &self,
+        requirements_id: Uuid,
+        project_id: Uuid,
+        raw_requirements: String,
+        prd_content: Option<String>,
+        granularity: FeatureGranularity,
+    ) {
+        let pool = self.pool.clone();
+        let claude = self.claude.clone();
         tokio::spawn(async move {
             let analyzer = RequirementsAnalyzer::with_client(pool, claude);
             if let Err(e) = analyzer
-                .run_analysis(id, project_id, &raw_requirements, prd_content.as_deref())
+                .run_analysis(
+                    requirements_id,
+                    project_id,
+                    &raw_requirements,
+                    prd_content.as_deref(),
+                    granularity,
+                )
                 .await
             {
                 error!(error = %e, "Requirements analysis failed");
             }
         });
-
-        Ok(requirements)
     }
 
     /// Run the full analysis and task generation pipeline
@@ -119,6 +412,7 @@ impl RequirementsAnalyzer {
         project_id: Uuid,
         raw_requirements: &str,
         prd_content: Option<&str>,
+        granularity: FeatureGranularity,
     ) -> Result<(), RequirementsAnalyzerError> {
         // Phase 1: Analyze requirements to extract features
         ProjectRequirements::update_status(
@@ -129,7 +423,10 @@ impl RequirementsAnalyzer {
         )
         .await?;
 
-        let analysis_result = match self.analyze_requirements(raw_requirements, prd_content).await {
+        let analysis_result = match self
+            .analyze_requirements(project_id, raw_requirements, prd_content, granularity)
+            .await
+        {
             Ok(result) => result,
             Err(e) => {
                 ProjectRequirements::update_status(
@@ -163,7 +460,7 @@ impl RequirementsAnalyzer {
         .await?;
 
         match self
-            .generate_tasks_from_features(project_id, &analysis_result.features)
+            .generate_tasks_from_features(requirements_id, project_id, &analysis_result.features)
             .await
         {
             Ok(task_count) => {
@@ -195,79 +492,50 @@ impl RequirementsAnalyzer {
         Ok(())
     }
 
+    /// The project's own description, stack and conventions for the prompts, falling back to the
+    /// built-in codebase rules for projects that haven't set one
+    async fn project_context(&self, project_id: Uuid) -> Result<String, RequirementsAnalyzerError> {
+        let context = Project::find_by_id(&self.pool, project_id)
+            .await?
+            .and_then(|project| project.context)
+            .filter(|context| !context.trim().is_empty());
+        Ok(context.unwrap_or_else(codebase_rules::get_all_rules))
+    }
+
     /// Phase 1: Analyze requirements and extract features
     async fn analyze_requirements(
         &self,
+        project_id: Uuid,
         raw_requirements: &str,
         prd_content: Option<&str>,
+        granularity: FeatureGranularity,
     ) -> Result<AnalysisResult, RequirementsAnalyzerError> {
-        let mut prompt = format!(
-            r#"Analyze the following project requirements and extract distinct features that need to be implemented.
-
-IMPORTANT: This is for an EXISTING working project. Features should be analyzed in the context of extending/modifying the existing codebase.
-
-## Requirements
-{}
-"#,
-            raw_requirements
-        );
-
-        if let Some(prd) = prd_content {
-            prompt.push_str(&format!(
-                r#"
-## Additional PRD Content
-{}
-"#,
-                prd
-            ));
-        }
+        let template =
+            prompts::active(&self.pool, project_id, TemplateName::FeatureExtraction).await?;
+        let project_context = self.project_context(project_id).await?;
+        let prd_section = prd_content
+            .map(|prd| format!("\n## Additional PRD Content\n{prd}\n"))
+            .unwrap_or_default();
+        let granularity = prompts::feature_granularity_guidance(granularity);
+        let prompt = template.render(&[
+            ("requirements", raw_requirements),
+            ("prd_section", &prd_section),
+            ("project_context", &project_context),
+            ("granularity", granularity),
+        ]);
+        let system = Some(prompts::system_prompt(&self.pool, project_id, template.system).await?);
 
-        prompt.push_str(
-            r#"
-## Instructions
-1. Identify distinct features that need to be implemented
-2. For each feature, determine which layer it primarily belongs to:
-   - "data": Database models, schemas, migrations (SQLite)
-   - "backend": API endpoints, business logic, services (Node.js)
-   - "frontend": UI components, pages, user interactions (React + Vite + Zustand)
-   - "fullstack": Features spanning multiple layers
-   - "devops": Infrastructure, deployment, CI/CD
-   - "testing": Test coverage, test utilities
-3. Assign a priority (1=highest, 5=lowest) based on dependencies and importance
-4. Consider cross-layer dependencies - features that require data models, API contracts, and UI components
-
-## Output Format
-Return ONLY valid JSON with this structure:
-```json
-{
-  "features": [
-    {
-      "name": "Feature name",
-      "description": "Brief description of what needs to be built, including any cross-layer dependencies",
-      "layer": "backend|frontend|data|fullstack|devops|testing",
-      "priority": 1
-    }
-  ],
-  "summary": "Brief summary of the overall project scope"
-}
-```
-"#,
-        );
-
-        let system = Some(
-            "You are a software architect analyzing requirements for an EXISTING project to extract features. \
-             Consider that you're working with an established codebase and architecture. Be concise and practical. \
-             Focus on actionable features that extend or modify the existing system. Output valid JSON only."
-                .to_string(),
-        );
-
-        let response: FeatureExtractionResponse = self.claude.ask_json(&prompt, system).await?;
+        let response: FeatureExtractionResponse = self
+            .claude
+            .ask_json(ModelRole::Requirements, &prompt, system)
+            .await?;
 
         Ok(AnalysisResult {
             features: response
                 .features
                 .into_iter()
                 .map(|f| ExtractedFeature {
+                    id: Uuid::new_v4(),
                     name: f.name,
                     description: f.description,
                     layer: f.layer,
@@ -275,27 +543,71 @@ Return ONLY valid JSON with this structure:
                 })
                 .collect(),
             summary: response.summary,
+            prompt_version: Some(template.label()),
         })
     }
 
     /// Phase 2: Generate implementation tasks from features using mock-first, architecture-first approach
     async fn generate_tasks_from_features(
         &self,
+        requirements_id: Uuid,
         project_id: Uuid,
         features: &[ExtractedFeature],
     ) -> Result<usize, RequirementsAnalyzerError> {
         // Generate all tasks at once using the architecture-first approach
-        let tasks = self.generate_architecture_first_tasks(features).await?;
+        let tasks = self
+            .generate_architecture_first_tasks(project_id, features)
+            .await?;
+        self.insert_generated_tasks(requirements_id, project_id, features, tasks, &[])
+            .await
+    }
 
+    /// Insert generated tasks in a single transaction, linking each one back to the feature and
+    /// requirements record it was generated from. The untouched Todo tasks of the `replaced`
+    /// features are deleted in the same transaction, so a failure leaves the old tasks in place.
+    async fn insert_generated_tasks(
+        &self,
+        requirements_id: Uuid,
+        project_id: Uuid,
+        features: &[ExtractedFeature],
+        tasks: Vec<GeneratedTask>,
+        replaced: &[Uuid],
+    ) -> Result<usize, RequirementsAnalyzerError> {
+        let task_types: Vec<Option<TaskType>> = tasks
+            .iter()
+            .map(|task| task.task_type.as_deref().and_then(parse_task_type))
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        for feature_id in replaced {
+            Task::delete_untouched_ai_generated_by_source_feature(
+                &mut *tx,
+                project_id,
+                &feature_id.to_string(),
+            )
+            .await?;
+        }
+        let existing = Task::max_sequences_by_type(&mut *tx, project_id).await?;
+        let sequences = assign_sequences(&task_types, &existing);
         let mut total_tasks = 0;
-        for task in tasks {
-            let layer = task.layer.and_then(|l| parse_layer(&l));
-            let task_type = task.task_type.and_then(|t| parse_task_type(&t));
+        for ((task, task_type), sequence) in tasks.into_iter().zip(task_types).zip(sequences) {
+            let layer = task.layer.as_deref().and_then(parse_layer);
+
+            // Replace missing or malformed progress markdown with a template built from the task
+            let post_task_actions = match task.post_task_actions.as_deref() {
+                Some(actions) if is_valid_progress_markdown(actions) => actions.to_string(),
+                _ => {
+                    warn!(
+                        task_title = %task.title,
+                        "Generated post_task_actions missing required sections, using default template"
+                    );
+                    build_default_progress_template(&task)
+                }
+            };
 
-            // Determine sequence based on task type
-            let sequence = calculate_sequence(&task_type, total_tasks);
+            let source_feature = resolve_source_feature(features, task.feature_id.as_deref());
 
-            let create_task = CreateTask::ai_generated(
+            let mut create_task = CreateTask::ai_generated(
                 project_id,
                 task.title,
                 Some(task.description),
@@ -303,12 +615,15 @@ Return ONLY valid JSON with this structure:
                 task_type,
                 sequence,
                 task.testing_criteria,
-                task.post_task_actions,
+                Some(post_task_actions),
             );
+            create_task.source_feature = source_feature;
+            create_task.requirements_id = Some(requirements_id);
 
-            Task::create(&self.pool, &create_task, Uuid::new_v4()).await?;
+            Task::create(&mut *tx, &create_task, Uuid::new_v4()).await?;
             total_tasks += 1;
         }
+        tx.commit().await?;
 
         Ok(total_tasks)
     }
@@ -316,15 +631,18 @@ Return ONLY valid JSON with this structure:
     /// Generate tasks using mock-first, architecture-first approach
     async fn generate_architecture_first_tasks(
         &self,
+        project_id: Uuid,
         features: &[ExtractedFeature],
     ) -> Result<Vec<GeneratedTask>, RequirementsAnalyzerError> {
         let features_json = features
             .iter()
             .map(|f| {
                 format!(
-                    r#"  - Name: {}
+                    r#"  - Id: {}
+    Name: {}
     Description: {}
     Layer: {}"#,
+                    f.id,
                     f.name,
                     f.description,
                     f.layer.as_deref().unwrap_or("fullstack")
@@ -335,99 +653,64 @@ Return ONLY valid JSON with this structure:
 
         let rules = codebase_rules::get_all_rules();
 
-        let prompt = format!(
-            r#"Generate implementation tasks for the following features.
-
-IMPORTANT: This is an EXISTING working project. You must analyze the existing codebase structure and generate tasks that work with the existing files and architecture.
-
-## ARCHITECTURE RULES (MUST FOLLOW)
-{}
-
-## Features to Implement
-{}
-
-## Task Generation Strategy
-
-Generate tasks in this EXACT ORDER:
-
-### 1. Architecture Tasks (task_type: "architecture")
-Analyze existing architecture and define any new models/contracts needed:
-- Identify existing data models, API patterns, state management
-- Define any NEW interfaces/types needed for the features
-- Design database schema changes if needed
-- Create database migrations if database changes are required
-- Ensure database is initialized before proceeding
-
-### 2. Implementation Tasks (task_type: "implementation")
-Build real implementations working with the existing codebase:
-- Modify existing files or create new ones following project conventions
-- Integrate with existing patterns and services
-- Reference specific files that need modification
-- Implement full functionality (no mocks - build the real thing)
-- Include database operations if needed (queries, mutations)
-- Run and test migrations as part of implementation
-
-### 3. Integration Task (task_type: "integration")
-Wire new features into the existing application:
-- Connect new endpoints/components to existing infrastructure
-- Verify database migrations have been applied
-- End-to-end testing of the complete flow
-- Ensure all database tables and schemas are correct
-
-## Output Format
-Return ONLY valid JSON:
-```json
-{{
-  "tasks": [
-    {{
-      "title": "Short task title",
-      "description": "Detailed description explaining WHAT to build and HOW it integrates with existing code",
-      "layer": "backend|frontend|data|fullstack|devops|testing",
-      "task_type": "architecture|implementation|integration",
-      "testing_criteria": "Specific, verifiable criteria to confirm this task is complete",
-      "files_to_modify": ["path/to/file1.ts", "path/to/file2.tsx"],
-      "post_task_actions": "<markdown template - see below>"
-    }}
-  ]
-}}
-```
-
-## post_task_actions Format
-MUST be markdown text that will be appended to .progress file. Include:
-- Task title as heading
-- Status with timestamp
-- Summary of what was done
-- List of files changed
-- Testing results
-- Separator line
-
-IMPORTANT:
-- Analyze the EXISTING project structure before generating tasks
-- Reference specific existing files that need modification in files_to_modify
-- FOLLOW THE ARCHITECTURE RULES STRICTLY - do not recreate components that already exist
-- For frontend tasks: DO NOT create new navbar/sidebar, use existing layout components
-- For backend tasks: Follow the routing, service, and model patterns
-- For database tasks: ALWAYS create migration files, run pnpm run prepare-db, never use npm run init-db
-- Database must exist before migrations run - include database creation check in architecture tasks
-- post_task_actions should be actual markdown text, not a template with placeholders
-- This markdown will be appended to .progress file when task completes
-- Include a "Rules Followed" section in post_task_actions listing which architecture rules were applied
-- Include a "Database Changes" section if migrations were created
-"#,
-            rules,
-            features_json
+        let template =
+            prompts::active(&self.pool, project_id, TemplateName::TaskGeneration).await?;
+        let project_context = self.project_context(project_id).await?;
+        let prompt = template.render(&[
+            ("rules", &rules),
+            ("features", &features_json),
+            ("project_context", &project_context),
+        ]);
+        let system = Some(prompts::system_prompt(&self.pool, project_id, template.system).await?);
+        info!(
+            project_id = %project_id,
+            prompt_version = %template.label(),
+            "Generating tasks"
         );
 
-        let system = Some(
-            "You are a software architect analyzing an EXISTING codebase and generating implementation tasks. \
-             You must analyze the existing project structure, identify patterns, and generate tasks that work with \
-             the existing architecture. Each task should reference specific files to modify and include a markdown \
-             template for progress tracking. Use a mock-first approach where appropriate. Output valid JSON only."
-                .to_string(),
+        let response: TaskGenerationResponse = self
+            .claude
+            .ask_json_with_max_tokens(ModelRole::Requirements, &prompt, system, 8192)
+            .await?;
+        Ok(response.tasks)
+    }
+
+    /// Regenerate the tasks for a single feature of the latest analysis.
+    /// Prior AI-generated Todo tasks linked to the feature are replaced only after new tasks are
+    /// generated; tasks that were already started or edited are kept.
+    pub async fn regenerate_feature(
+        &self,
+        project_id: Uuid,
+        feature_id: Uuid,
+    ) -> Result<usize, RequirementsAnalyzerError> {
+        let requirements = ProjectRequirements::find_by_project_id(&self.pool, project_id)
+            .await?
+            .ok_or(RequirementsAnalyzerError::NotFound)?;
+        let analysis = requirements
+            .parsed_analysis()
+            .ok_or(RequirementsAnalyzerError::NotFound)?;
+        let feature = analysis
+            .find_feature(feature_id)
+            .cloned()
+            .ok_or(RequirementsAnalyzerError::NotFound)?;
+
+        let features = [feature];
+        let tasks = self
+            .generate_architecture_first_tasks(project_id, &features)
+            .await?;
+
+        let created = self
+            .insert_generated_tasks(requirements.id, project_id, &features, tasks, &[feature_id])
+            .await?;
+
+        info!(
+            project_id = %project_id,
+            feature_id = %feature_id,
+            created = created,
+            "Regenerated tasks for feature"
         );
 
-        let response: TaskGenerationResponse = self.claude.ask_json_with_max_tokens(&prompt, system, 8192).await?;
-        Ok(response.tasks)
+        Ok(created)
     }
 
     /// Get the current status of requirements analysis
@@ -438,6 +721,20 @@ IMPORTANT:
         Ok(ProjectRequirements::find_by_project_id(&self.pool, project_id).await?)
     }
 
+    /// Earlier versions of the project's requirements, newest first
+    pub async fn versions(
+        &self,
+        project_id: Uuid,
+    ) -> Result<Vec<ProjectRequirementsVersion>, RequirementsAnalyzerError> {
+        let requirements = ProjectRequirements::find_by_project_id(&self.pool, project_id)
+            .await?
+            .ok_or(RequirementsAnalyzerError::NotFound)?;
+        let versions =
+            ProjectRequirementsVersion::find_by_requirements_id(&self.pool, requirements.id)
+                .await?;
+        Ok(versions)
+    }
+
     /// Delete requirements and optionally the generated tasks
     pub async fn delete(
         &self,
@@ -457,6 +754,44 @@ IMPORTANT:
     }
 }
 
+/// Which features an edit to the requirements added, changed or removed, by feature id
+#[derive(Debug, Default, PartialEq)]
+struct FeatureDiff {
+    added: Vec<Uuid>,
+    changed: Vec<Uuid>,
+    removed: Vec<Uuid>,
+}
+
+/// Match freshly extracted `features` to the `previous` ones by name (ignoring case and
+/// surrounding whitespace). Matched features take over the previous id, so their tasks stay
+/// linked, and count as changed when their description or layer differs.
+fn diff_features(previous: &[ExtractedFeature], features: &mut [ExtractedFeature]) -> FeatureDiff {
+    let key = |feature: &ExtractedFeature| feature.name.trim().to_lowercase();
+    let mut diff = FeatureDiff::default();
+    let mut matched = Vec::new();
+    for feature in features.iter_mut() {
+        let name = key(feature);
+        let Some(old) = previous
+            .iter()
+            .find(|old| key(old) == name && !matched.contains(&old.id))
+        else {
+            diff.added.push(feature.id);
+            continue;
+        };
+        matched.push(old.id);
+        feature.id = old.id;
+        if old.description.trim() != feature.description.trim() || old.layer != feature.layer {
+            diff.changed.push(feature.id);
+        }
+    }
+    diff.removed = previous
+        .iter()
+        .map(|old| old.id)
+        .filter(|id| !matched.contains(id))
+        .collect();
+    diff
+}
+
 fn parse_layer(s: &str) -> Option<TaskLayer> {
     match s.to_lowercase().as_str() {
         "data" => Some(TaskLayer::Data),
@@ -473,22 +808,654 @@ fn parse_task_type(s: &str) -> Option<TaskType> {
     match s.to_lowercase().as_str() {
         "architecture" => Some(TaskType::Architecture),
         "implementation" => Some(TaskType::Implementation),
+        "testing" => Some(TaskType::Testing),
         "integration" => Some(TaskType::Integration),
         _ => None,
     }
 }
 
-/// Calculate sequence number based on task type to ensure proper ordering.
-/// Sequence ranges:
+/// Resolve the feature a generated task belongs to. Falls back to the only feature when
+/// generating for a single feature, since the model may omit or mangle the id.
+fn resolve_source_feature(features: &[ExtractedFeature], feature_id: Option<&str>) -> Option<String> {
+    if let Some(id) = feature_id.map(str::trim)
+        && let Some(feature) = features.iter().find(|f| f.id.to_string() == id)
+    {
+        return Some(feature.id.to_string());
+    }
+
+    match features {
+        [only] => Some(only.id.to_string()),
+        _ => None,
+    }
+}
+
+/// Assign each task a sequence number from its type's range, counting per type so sequences
+/// are unique and ordered within the type. Each type continues after the highest sequence
+/// in `existing` (the project's current maximum per type), so later runs don't reuse the
+/// numbers of earlier ones. Sequence ranges:
 /// - Architecture: 0-99
-/// - Implementation: 100-899
+/// - Implementation: 100-799
+/// - Testing: 800-899
 /// - Integration: 900+
-fn calculate_sequence(task_type: &Option<TaskType>, task_index: usize) -> i32 {
-    let base = match task_type {
+fn assign_sequences(
+    task_types: &[Option<TaskType>],
+    existing: &[(Option<TaskType>, i32)],
+) -> Vec<i32> {
+    // Bands in execution order: architecture, implementation (and untyped), testing, integration
+    let band = |task_type: &Option<TaskType>| match task_type {
         Some(TaskType::Architecture) => 0,
-        Some(TaskType::Implementation) => 100,
-        Some(TaskType::Integration) => 900,
-        None => 100, // Default to implementation range
+        Some(TaskType::Implementation) | None => 1,
+        Some(TaskType::Testing) => 2,
+        Some(TaskType::Integration) => 3,
     };
-    base + (task_index as i32 % 100)
+    let mut counts = [0i32; 4];
+    for task_type in task_types {
+        counts[band(task_type)] += 1;
+    }
+    let mut floors = [0, 100, 800, 900];
+    for (task_type, max_sequence) in existing {
+        let band = band(task_type);
+        floors[band] = floors[band].max(max_sequence + 1);
+    }
+
+    // A band that outgrows its range pushes the next band's start back instead of overlapping
+    let architecture_start = floors[0];
+    let implementation_start = floors[1].max(architecture_start + counts[0]);
+    let testing_start = floors[2].max(implementation_start + counts[1]);
+    let integration_start = floors[3].max(testing_start + counts[2]);
+    let mut next = [
+        architecture_start,
+        implementation_start,
+        testing_start,
+        integration_start,
+    ];
+
+    task_types
+        .iter()
+        .map(|task_type| {
+            let band = band(task_type);
+            let sequence = next[band];
+            next[band] += 1;
+            sequence
+        })
+        .collect()
+}
+
+/// Check that AI-generated progress markdown contains the sections we rely on:
+/// a heading, a status line, and a files-changed section.
+fn is_valid_progress_markdown(markdown: &str) -> bool {
+    let trimmed = markdown.trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+
+    let lower = trimmed.to_lowercase();
+    let has_heading = trimmed.lines().any(|line| line.trim_start().starts_with('#'));
+    let has_status = lower.contains("status");
+    let has_files = lower.contains("files changed") || lower.contains("files modified");
+
+    has_heading && has_status && has_files
+}
+
+/// Build a default `.progress` entry from a generated task's own fields.
+fn build_default_progress_template(task: &GeneratedTask) -> String {
+    let files = match task.files_to_modify.as_deref() {
+        Some(files) if !files.is_empty() => files
+            .iter()
+            .map(|f| format!("- `{}`", f))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => "- (not specified)".to_string(),
+    };
+
+    let testing = task
+        .testing_criteria
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or("No testing criteria specified");
+
+    format!(
+        r#"## {title}
+
+**Status:** Completed
+
+### Summary
+{description}
+
+### Files Changed
+{files}
+
+### Testing
+{testing}
+
+---
+"#,
+        title = task.title,
+        description = task.description.trim(),
+        files = files,
+        testing = testing,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::task::TaskStatus;
+
+    use super::*;
+    use crate::services::test_support;
+
+    fn generated_task(post_task_actions: Option<&str>) -> GeneratedTask {
+        GeneratedTask {
+            title: "Add login form".to_string(),
+            description: "Build the login form component".to_string(),
+            layer: Some("frontend".to_string()),
+            task_type: Some("implementation".to_string()),
+            testing_criteria: Some("Form submits credentials".to_string()),
+            files_to_modify: Some(vec!["src/components/LoginForm.tsx".to_string()]),
+            post_task_actions: post_task_actions.map(|s| s.to_string()),
+            feature_id: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_progress_markdown() {
+        let markdown = "## Add login form\n\n**Status:** Completed\n\n### Files Changed\n- a.ts\n";
+        assert!(is_valid_progress_markdown(markdown));
+    }
+
+    #[test]
+    fn test_progress_markdown_missing_sections() {
+        assert!(!is_valid_progress_markdown(""));
+        assert!(!is_valid_progress_markdown("   \n"));
+        assert!(!is_valid_progress_markdown("Status: done. Files changed: a.ts"));
+        assert!(!is_valid_progress_markdown("## Title\n**Status:** Completed"));
+    }
+
+    #[test]
+    fn test_default_progress_template_contains_required_sections() {
+        let template = build_default_progress_template(&generated_task(None));
+        assert!(template.starts_with("## Add login form"));
+        assert!(template.contains("`src/components/LoginForm.tsx`"));
+        assert!(template.contains("Form submits credentials"));
+        assert!(is_valid_progress_markdown(&template));
+    }
+
+    #[test]
+    fn test_resolve_source_feature() {
+        let feature = |name: &str| ExtractedFeature {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: String::new(),
+            layer: None,
+            priority: None,
+        };
+        let features = vec![feature("Auth"), feature("Billing")];
+        let billing_id = features[1].id.to_string();
+
+        assert_eq!(
+            resolve_source_feature(&features, Some(&billing_id)),
+            Some(billing_id.clone())
+        );
+        assert_eq!(resolve_source_feature(&features, Some("unknown")), None);
+        assert_eq!(resolve_source_feature(&features, None), None);
+        assert_eq!(
+            resolve_source_feature(&features[1..], None),
+            Some(billing_id)
+        );
+    }
+
+    #[test]
+    fn test_assign_sequences_counts_per_type() {
+        let types = [
+            Some(TaskType::Architecture),
+            Some(TaskType::Implementation),
+            Some(TaskType::Integration),
+            Some(TaskType::Implementation),
+            Some(TaskType::Testing),
+            Some(TaskType::Architecture),
+            None,
+            Some(TaskType::Testing),
+        ];
+        assert_eq!(
+            assign_sequences(&types, &[]),
+            vec![0, 100, 900, 101, 800, 1, 102, 801]
+        );
+    }
+
+    #[test]
+    fn test_assign_sequences_continue_after_existing_tasks() {
+        let types = [
+            Some(TaskType::Architecture),
+            Some(TaskType::Implementation),
+            None,
+            Some(TaskType::Testing),
+            Some(TaskType::Integration),
+        ];
+        let existing = [
+            (Some(TaskType::Architecture), 2),
+            (Some(TaskType::Implementation), 104),
+            (None, 106),
+            (Some(TaskType::Integration), 900),
+        ];
+        assert_eq!(
+            assign_sequences(&types, &existing),
+            vec![3, 107, 108, 800, 901]
+        );
+    }
+
+    #[test]
+    fn test_parse_task_type_round_trips() {
+        for task_type in [
+            TaskType::Architecture,
+            TaskType::Implementation,
+            TaskType::Testing,
+            TaskType::Integration,
+        ] {
+            assert_eq!(parse_task_type(&task_type.to_string()), Some(task_type));
+        }
+        assert_eq!(parse_task_type("Testing"), Some(TaskType::Testing));
+        assert_eq!(parse_task_type("mock"), None);
+    }
+
+    #[test]
+    fn test_assign_sequences_never_collide_when_a_range_overflows() {
+        let mut types = vec![Some(TaskType::Architecture); 120];
+        types.push(Some(TaskType::Implementation));
+        types.push(Some(TaskType::Integration));
+
+        let sequences = assign_sequences(&types, &[]);
+        let mut unique = sequences.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), sequences.len());
+        assert_eq!(sequences[119], 119);
+        assert_eq!(sequences[120], 120);
+        assert_eq!(sequences[121], 900);
+    }
+
+    #[test]
+    fn test_default_progress_template_without_files() {
+        let mut task = generated_task(None);
+        task.files_to_modify = Some(vec![]);
+        task.testing_criteria = None;
+        let template = build_default_progress_template(&task);
+        assert!(template.contains("- (not specified)"));
+        assert!(template.contains("No testing criteria specified"));
+    }
+
+    #[test]
+    fn test_diff_features_keeps_ids_of_matching_features() {
+        let feature = |name: &str, description: &str| ExtractedFeature {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: description.to_string(),
+            layer: Some("backend".to_string()),
+            priority: None,
+        };
+        let previous = vec![
+            feature("Login", "Email and password"),
+            feature("Search", "Full-text search"),
+            feature("Export", "CSV export"),
+        ];
+        let mut features = vec![
+            feature(" login ", "Email and password"),
+            feature("Search", "Full-text search with filters"),
+            feature("Billing", "Stripe checkout"),
+        ];
+        let billing_id = features[2].id;
+
+        let diff = diff_features(&previous, &mut features);
+
+        assert_eq!(features[0].id, previous[0].id);
+        assert_eq!(features[1].id, previous[1].id);
+        assert_eq!(
+            diff,
+            FeatureDiff {
+                added: vec![billing_id],
+                changed: vec![previous[1].id],
+                removed: vec![previous[2].id],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_editing_requirements_saves_the_previous_version() {
+        let (pool, project) = test_support::project_pool().await;
+        let requirements = ProjectRequirements::create_unless_in_progress(
+            &pool,
+            Uuid::new_v4(),
+            project.id,
+            &CreateProjectRequirements {
+                raw_requirements: "Users can sign in".to_string(),
+                prd_content: None,
+                granularity: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let edit = CreateProjectRequirements {
+            raw_requirements: "Users can sign in and reset their password".to_string(),
+            prd_content: Some("PRD".to_string()),
+            granularity: Some(FeatureGranularity::Fine),
+        };
+
+        // A pending analysis owns the record
+        assert!(
+            ProjectRequirements::update_content_unless_in_progress(&pool, requirements.id, &edit)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        ProjectRequirements::update_status(
+            &pool,
+            requirements.id,
+            GenerationStatus::Completed,
+            None,
+        )
+        .await
+        .unwrap();
+        let updated =
+            ProjectRequirements::update_content_unless_in_progress(&pool, requirements.id, &edit)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(updated.raw_requirements, edit.raw_requirements);
+        assert_eq!(updated.prd_content, edit.prd_content);
+        assert_eq!(updated.generation_status, GenerationStatus::Pending);
+        assert_eq!(requirements.granularity, FeatureGranularity::Normal);
+        assert_eq!(updated.granularity, FeatureGranularity::Fine);
+
+        let versions = ProjectRequirementsVersion::find_by_requirements_id(&pool, requirements.id)
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].raw_requirements, "Users can sign in");
+        assert_eq!(versions[0].prd_content, None);
+    }
+
+    #[tokio::test]
+    async fn test_restart_fails_interrupted_analyses() {
+        let (pool, project) = test_support::project_pool().await;
+        let data = CreateProjectRequirements {
+            raw_requirements: "Users can sign in".to_string(),
+            prd_content: None,
+            granularity: None,
+        };
+        let create = || {
+            ProjectRequirements::create_unless_in_progress(&pool, Uuid::new_v4(), project.id, &data)
+        };
+        let requirements = create().await.unwrap().unwrap();
+        ProjectRequirements::update_status(
+            &pool,
+            requirements.id,
+            GenerationStatus::Generating,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let interrupted = ProjectRequirements::fail_interrupted(&pool).await.unwrap();
+        assert_eq!(interrupted, 1);
+        let failed = ProjectRequirements::find_by_id(&pool, requirements.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(failed.generation_status, GenerationStatus::Failed);
+        assert!(failed.error_message.is_some());
+
+        // The project is no longer locked to the interrupted run
+        assert!(create().await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_creates_start_one_analysis() {
+        let (pool, project) = test_support::project_pool().await;
+
+        // The spawned analysis finds no features in the stub's reply
+        let claude = test_support::claude_stub(r#"{"features": []}"#).await;
+        let analyzer = RequirementsAnalyzer::with_client(pool.clone(), claude);
+        let data = CreateProjectRequirements {
+            raw_requirements: "Users can sign in".to_string(),
+            prd_content: None,
+            granularity: None,
+        };
+
+        let (first, second) = tokio::join!(
+            analyzer.create_and_analyze(project.id, data.clone()),
+            analyzer.create_and_analyze(project.id, data.clone()),
+        );
+        let started = [&first, &second].iter().filter(|r| r.is_ok()).count();
+        assert_eq!(started, 1);
+        assert!(
+            [first, second]
+                .into_iter()
+                .any(|r| matches!(r, Err(RequirementsAnalyzerError::AlreadyInProgress)))
+        );
+
+        let records: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM project_requirements WHERE project_id = $1")
+                .bind(project.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(records, 1);
+    }
+
+    #[tokio::test]
+    async fn test_testing_task_type_round_trips_through_db() {
+        let (pool, project) = test_support::project_pool().await;
+
+        let mut data =
+            CreateTask::from_title_description(project.id, "Cover sign in".to_string(), None);
+        data.task_type = Some(TaskType::Testing);
+        let task = Task::create(&pool, &data, Uuid::new_v4()).await.unwrap();
+        assert_eq!(task.task_type, Some(TaskType::Testing));
+
+        let stored = Task::find_by_id(&pool, task.id).await.unwrap().unwrap();
+        assert_eq!(stored.task_type, Some(TaskType::Testing));
+    }
+
+    #[tokio::test]
+    async fn test_generated_tasks_link_back_to_feature_and_requirements() {
+        let (pool, project) = test_support::project_pool().await;
+        let requirements = ProjectRequirements::create_unless_in_progress(
+            &pool,
+            Uuid::new_v4(),
+            project.id,
+            &CreateProjectRequirements {
+                raw_requirements: "Users can sign in".to_string(),
+                prd_content: None,
+                granularity: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let feature = ExtractedFeature {
+            id: Uuid::new_v4(),
+            name: "Login".to_string(),
+            description: String::new(),
+            layer: None,
+            priority: None,
+        };
+        let generated = GeneratedTask {
+            feature_id: Some(feature.id.to_string()),
+            ..generated_task(None)
+        };
+
+        let claude = test_support::claude_stub("").await;
+        let analyzer = RequirementsAnalyzer::with_client(pool.clone(), claude);
+        analyzer
+            .insert_generated_tasks(
+                requirements.id,
+                project.id,
+                std::slice::from_ref(&feature),
+                vec![generated],
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let tasks = Task::find_by_feature(&pool, project.id, &feature.id.to_string())
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].requirements_id, Some(requirements.id));
+    }
+
+    /// A completed requirements record whose analysis found `features`
+    async fn analyzed_requirements(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        features: Vec<ExtractedFeature>,
+    ) -> ProjectRequirements {
+        let requirements = ProjectRequirements::create_unless_in_progress(
+            pool,
+            Uuid::new_v4(),
+            project_id,
+            &CreateProjectRequirements {
+                raw_requirements: "Users can sign in".to_string(),
+                prd_content: None,
+                granularity: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let analysis = AnalysisResult {
+            features,
+            summary: None,
+            prompt_version: None,
+        };
+        ProjectRequirements::update_analysis_result(pool, requirements.id, &analysis)
+            .await
+            .unwrap();
+        ProjectRequirements::update_status(
+            pool,
+            requirements.id,
+            GenerationStatus::Completed,
+            None,
+        )
+        .await
+        .unwrap();
+        requirements
+    }
+
+    /// An AI-generated task of `feature` in `status`
+    async fn feature_task(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        feature: &ExtractedFeature,
+        title: &str,
+        status: TaskStatus,
+    ) -> Task {
+        let mut create = CreateTask::ai_generated(
+            project_id,
+            title.to_string(),
+            None,
+            None,
+            Some(TaskType::Implementation),
+            100,
+            None,
+            None,
+        );
+        create.status = Some(status);
+        create.source_feature = Some(feature.id.to_string());
+        Task::create(pool, &create, Uuid::new_v4()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_regenerating_a_feature_replaces_only_its_todo_tasks() {
+        let (pool, project) = test_support::project_pool().await;
+        let feature = ExtractedFeature {
+            id: Uuid::new_v4(),
+            name: "Login".to_string(),
+            description: String::new(),
+            layer: None,
+            priority: None,
+        };
+        analyzed_requirements(&pool, project.id, vec![feature.clone()]).await;
+        let todo = feature_task(&pool, project.id, &feature, "Old form", TaskStatus::Todo).await;
+        let started = feature_task(
+            &pool,
+            project.id,
+            &feature,
+            "Old API",
+            TaskStatus::InProgress,
+        )
+        .await;
+
+        let claude = test_support::claude_stub(
+            r#"{"tasks": [{"title": "New form", "description": "Build the form", "task_type": "implementation"}]}"#,
+        )
+        .await;
+        let analyzer = RequirementsAnalyzer::with_client(pool.clone(), claude);
+        let created = analyzer
+            .regenerate_feature(project.id, feature.id)
+            .await
+            .unwrap();
+        assert_eq!(created, 1);
+
+        let tasks = Task::find_by_feature(&pool, project.id, &feature.id.to_string())
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks.iter().all(|t| t.id != todo.id));
+        assert!(tasks.iter().any(|t| t.id == started.id));
+        let new = tasks.iter().find(|t| t.title == "New form").unwrap();
+        // Numbered after the started task instead of reusing its sequence
+        assert_eq!(new.sequence, Some(101));
+    }
+
+    #[tokio::test]
+    async fn test_reanalyzing_unchanged_requirements_keeps_the_tasks() {
+        let (pool, project) = test_support::project_pool().await;
+        let feature = ExtractedFeature {
+            id: Uuid::new_v4(),
+            name: "Login".to_string(),
+            description: "Users can sign in".to_string(),
+            layer: Some("frontend".to_string()),
+            priority: Some(1),
+        };
+        let requirements = analyzed_requirements(&pool, project.id, vec![feature.clone()]).await;
+        let task = feature_task(&pool, project.id, &feature, "Login form", TaskStatus::Todo).await;
+
+        // The model extracts the same feature again
+        let claude = test_support::claude_stub(
+            r#"{"features": [{"name": "Login", "description": "Users can sign in", "layer": "frontend", "priority": 1}]}"#,
+        )
+        .await;
+        let analyzer = RequirementsAnalyzer::with_client(pool.clone(), claude);
+        analyzer.reanalyze(project.id).await.unwrap();
+
+        let mut status = GenerationStatus::Pending;
+        for _ in 0..200 {
+            status = ProjectRequirements::find_by_id(&pool, requirements.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .generation_status;
+            if !status.is_in_progress() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(status, GenerationStatus::Completed);
+
+        let tasks = Task::find_by_project_id_with_attempt_status(&pool, project.id, true)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, task.id);
+        let analysis = ProjectRequirements::find_by_id(&pool, requirements.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .parsed_analysis()
+            .unwrap();
+        assert_eq!(analysis.features[0].id, feature.id);
+    }
 }
@@ -1,32 +1,76 @@
 //! Service for automated review processing: running tests and auto-merging branches.
 
-use std::{path::Path, process::Stdio, time::Duration};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use db::{
     DBService,
     models::{
+        agent_activity::{AgentAction, AgentActivityLog},
         merge::Merge,
+        repo::Repo,
         review_automation::{
             ProjectReviewSettings, ReviewAction, ReviewAutomationLog, ReviewAutomationStatus,
+            UpdateReviewAutomationSettings,
         },
-        task::{CreateTask, Task, TaskLayer, TaskStatus},
+        task::{CreateTask, Task, TaskError, TaskLayer, TaskStatus, TaskType},
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
 };
+use futures::{StreamExt, future};
+use globset::{Glob, GlobMatcher};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use thiserror::Error;
-use tokio::{process::Command, time::interval};
+use tokio::time::interval;
 use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use utils::assets::review_artifacts_dir;
 use uuid::Uuid;
 
-use super::{git::GitService, notification::NotificationService};
-use super::claude_api::{ClaudeApiClient, ClaudeApiError};
+use super::{
+    claude_api::{ClaudeApiClient, ClaudeApiError, ModelRole},
+    command_allowlist::CommandPolicy,
+    git::{GitService, GitServiceError},
+    notification::NotificationService,
+    project_concurrency::{DEFAULT_PROJECT_CONCURRENCY, process_concurrently},
+    prompts::{self, TemplateName},
+    test_runner::{TestRunner, WorkspaceLocation, test_runner_for},
+    test_targets::{matching_commands, parse_test_profiles, parse_test_targets, profile_commands},
+};
+
+/// Actor recorded in the status history of tasks this service moves
+const STATUS_ACTOR: &str = "review_automation";
+
+/// How often the background service polls for tasks in review when the config doesn't say
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 /// Maximum number of merge conflict attempts before cancelling and breaking down the task
 const MAX_MERGE_CONFLICT_ATTEMPTS: i64 = 5;
 
+/// Failed testing criteria checks in a row before the task is flagged for a human
+const MAX_CRITERIA_CHECK_FAILURES: i64 = 3;
+
+/// Number of trailing characters of test output kept in the log when the full output is stored as an artifact
+const TEST_OUTPUT_PREVIEW_CHARS: usize = 4000;
+
+/// File in the repo root that accumulates each merged task's `post_task_actions` notes
+const PROGRESS_FILE: &str = ".progress";
+
+/// Header of the conflicted file list stored in merge conflict log output
+const CONFLICTED_FILES_HEADER: &str = "Conflicted files:";
+
+/// Per-side size cap for the conflicting diff hunks given to the breakdown prompt
+const CONFLICT_HUNKS_MAX_BYTES: usize = 6000;
+
+/// Commits behind its target beyond which a task branch counts as heavily diverged
+const HEAVY_DIVERGENCE_BEHIND: usize = 50;
+
 /// Response from AI for breaking down a conflicting task
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ConflictBreakdownResponse {
@@ -34,6 +78,15 @@ struct ConflictBreakdownResponse {
     reasoning: String,
 }
 
+/// Response from AI for checking a task's testing criteria against its changes
+#[derive(Debug, Clone, Deserialize)]
+struct CriteriaCheckResponse {
+    met: bool,
+    #[serde(default)]
+    unmet_criteria: Vec<String>,
+    reasoning: String,
+}
+
 /// Suggested subtask from AI breakdown
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SubtaskSuggestion {
@@ -47,7 +100,7 @@ pub enum ReviewAutomationError {
     #[error("database error: {0}")]
     Database(#[from] sqlx::Error),
     #[error("git error: {0}")]
-    Git(#[from] super::git::GitServiceError),
+    Git(#[from] GitServiceError),
     #[error("test failed: {0}")]
     TestFailed(String),
     #[error("merge conflict: {0}")]
@@ -56,6 +109,76 @@ pub enum ReviewAutomationError {
     NoWorkspaceContainer,
     #[error("command execution failed: {0}")]
     CommandFailed(String),
+    #[error("invalid settings: {0}")]
+    InvalidSettings(String),
+    #[error(transparent)]
+    Task(#[from] TaskError),
+    #[error("nothing to revert: {0}")]
+    NothingToRevert(String),
+    #[error("auto-merge target not allowed: {0}")]
+    TargetNotAllowed(String),
+    #[error("claude api error: {0}")]
+    ClaudeApi(#[from] ClaudeApiError),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// How to bring a task branch into its target, chosen from how far the two have diverged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeApproach {
+    /// The target hasn't moved since the branch forked: fast-forward or merge directly
+    Direct,
+    /// The target moved but changed none of the branch's files: rebase cleanly, then merge
+    Rebase,
+    /// The target moved far or changed the branch's files: rebase (likely to conflict), then merge
+    Diverged,
+}
+
+/// Pick the merge approach for a branch `behind` commits behind its target, where
+/// `shared_files` files were changed on both sides since the fork
+fn merge_approach(behind: usize, shared_files: usize) -> MergeApproach {
+    if behind == 0 {
+        MergeApproach::Direct
+    } else if shared_files == 0 && behind <= HEAVY_DIVERGENCE_BEHIND {
+        MergeApproach::Rebase
+    } else {
+        MergeApproach::Diverged
+    }
+}
+
+/// Parse the auto-merge target allowlist: branch names or globs such as `release/*`, separated
+/// by commas or newlines. An empty list allows every branch.
+fn parse_target_allowlist(allowlist: &str) -> Result<Vec<GlobMatcher>, String> {
+    allowlist
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            Glob::new(entry)
+                .map(|glob| glob.compile_matcher())
+                .map_err(|e| format!("invalid branch pattern '{entry}': {e}"))
+        })
+        .collect()
+}
+
+/// A repo merged during an auto-merge attempt, held until the other repos' outcome decides
+/// whether it is kept or rolled back
+struct CleanMerge<'a> {
+    repo: &'a Repo,
+    target_branch: &'a str,
+    previous_head: String,
+    merge_commit: String,
+}
+
+/// Predicted outcome of merging one workspace repo into its target branch
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoMergePreview {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub target_branch: String,
+    pub conflicted_files: Option<Vec<String>>, // None when the merge would be clean
 }
 
 /// Detected project stack for running tests
@@ -81,30 +204,452 @@ impl ProjectStack {
     }
 }
 
+/// The git operations review automation performs, so the service can run against a test double
+pub trait GitOps: Send + Sync {
+    fn merge_changes_with_signing(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        sign_commits: bool,
+    ) -> Result<String, GitServiceError>;
+
+    fn can_fast_forward(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<bool, GitServiceError>;
+
+    fn fast_forward_merge(
+        &self,
+        base_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError>;
+
+    fn commit_count_between(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+        branch_name: &str,
+    ) -> Result<usize, GitServiceError>;
+
+    fn cherry_pick(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+        target_branch: &str,
+    ) -> Result<String, GitServiceError>;
+
+    fn add_task_id_note(
+        &self,
+        repo_path: &Path,
+        commit_sha: &str,
+        task_id: Uuid,
+    ) -> Result<(), GitServiceError>;
+
+    fn get_fork_point(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+        task_branch: &str,
+    ) -> Result<String, GitServiceError>;
+
+    fn rebase_branch(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        new_base_branch: &str,
+        old_base_branch: &str,
+        task_branch: &str,
+    ) -> Result<String, GitServiceError>;
+
+    fn abort_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError>;
+
+    fn branch_has_commits_ahead(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<bool, GitServiceError>;
+
+    fn ahead_behind(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<(usize, usize), GitServiceError>;
+
+    fn would_conflict(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<Option<Vec<String>>, GitServiceError>;
+
+    fn conflict_hunks(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+        paths: &[String],
+        max_bytes: usize,
+    ) -> Result<String, GitServiceError>;
+
+    fn read_file_at_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        file_path: &Path,
+    ) -> Result<Option<String>, GitServiceError>;
+
+    fn changed_files(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+    ) -> Result<Vec<String>, GitServiceError>;
+
+    fn changed_files_on_target(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+    ) -> Result<Vec<String>, GitServiceError>;
+
+    fn commit_paths(
+        &self,
+        path: &Path,
+        paths: &[&str],
+        message: &str,
+    ) -> Result<(), GitServiceError>;
+
+    fn amend_paths(&self, path: &Path, paths: &[&str]) -> Result<(), GitServiceError>;
+
+    fn fetch_and_fast_forward_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<bool, GitServiceError>;
+
+    fn remove_worktree(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        force: bool,
+    ) -> Result<(), GitServiceError>;
+
+    fn stash(&self, worktree_path: &Path, message: &str) -> Result<bool, GitServiceError>;
+
+    fn stash_pop(&self, worktree_path: &Path) -> Result<(), GitServiceError>;
+
+    fn stash_drop(&self, worktree_path: &Path) -> Result<(), GitServiceError>;
+
+    fn get_branch_oid(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<String, GitServiceError>;
+
+    fn reset_branch(
+        &self,
+        repo_path: &Path,
+        branch: &str,
+        merged_head: &str,
+        previous_head: &str,
+    ) -> Result<(), GitServiceError>;
+}
+
+impl GitOps for GitService {
+    fn merge_changes_with_signing(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        sign_commits: bool,
+    ) -> Result<String, GitServiceError> {
+        GitService::merge_changes_with_signing(
+            self,
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            sign_commits,
+        )
+    }
+
+    fn can_fast_forward(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<bool, GitServiceError> {
+        GitService::can_fast_forward(self, worktree_path, branch_name, target_branch)
+    }
+
+    fn fast_forward_merge(
+        &self,
+        base_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        GitService::fast_forward_merge(self, base_worktree_path, task_branch_name, base_branch_name)
+    }
+
+    fn commit_count_between(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+        branch_name: &str,
+    ) -> Result<usize, GitServiceError> {
+        GitService::commit_count_between(self, worktree_path, target_branch, branch_name)
+    }
+
+    fn cherry_pick(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+        target_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        GitService::cherry_pick(self, repo_path, worktree_path, commit, target_branch)
+    }
+
+    fn add_task_id_note(
+        &self,
+        repo_path: &Path,
+        commit_sha: &str,
+        task_id: Uuid,
+    ) -> Result<(), GitServiceError> {
+        GitService::add_task_id_note(self, repo_path, commit_sha, task_id)
+    }
+
+    fn get_fork_point(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+        task_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        GitService::get_fork_point(self, worktree_path, target_branch, task_branch)
+    }
+
+    fn rebase_branch(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        new_base_branch: &str,
+        old_base_branch: &str,
+        task_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        GitService::rebase_branch(
+            self,
+            repo_path,
+            worktree_path,
+            new_base_branch,
+            old_base_branch,
+            task_branch,
+        )
+    }
+
+    fn abort_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        GitService::abort_conflicts(self, worktree_path)
+    }
+
+    fn branch_has_commits_ahead(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<bool, GitServiceError> {
+        GitService::branch_has_commits_ahead(self, worktree_path, branch_name, target_branch)
+    }
+
+    fn ahead_behind(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<(usize, usize), GitServiceError> {
+        GitService::ahead_behind(self, worktree_path, branch_name, target_branch)
+    }
+
+    fn would_conflict(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+    ) -> Result<Option<Vec<String>>, GitServiceError> {
+        GitService::would_conflict(self, worktree_path, branch_name, target_branch)
+    }
+
+    fn conflict_hunks(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        target_branch: &str,
+        paths: &[String],
+        max_bytes: usize,
+    ) -> Result<String, GitServiceError> {
+        GitService::conflict_hunks(
+            self,
+            worktree_path,
+            branch_name,
+            target_branch,
+            paths,
+            max_bytes,
+        )
+    }
+
+    fn read_file_at_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        file_path: &Path,
+    ) -> Result<Option<String>, GitServiceError> {
+        GitService::read_file_at_branch(self, repo_path, branch_name, file_path)
+    }
+
+    fn changed_files(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        GitService::changed_files(self, worktree_path, target_branch)
+    }
+
+    fn changed_files_on_target(
+        &self,
+        worktree_path: &Path,
+        target_branch: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        GitService::changed_files_on_target(self, worktree_path, target_branch)
+    }
+
+    fn commit_paths(
+        &self,
+        path: &Path,
+        paths: &[&str],
+        message: &str,
+    ) -> Result<(), GitServiceError> {
+        GitService::commit_paths(self, path, paths, message)
+    }
+
+    fn amend_paths(&self, path: &Path, paths: &[&str]) -> Result<(), GitServiceError> {
+        GitService::amend_paths(self, path, paths)
+    }
+
+    fn fetch_and_fast_forward_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<bool, GitServiceError> {
+        GitService::fetch_and_fast_forward_branch(self, repo_path, branch_name)
+    }
+
+    fn remove_worktree(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        force: bool,
+    ) -> Result<(), GitServiceError> {
+        GitService::remove_worktree(self, repo_path, worktree_path, force)
+    }
+
+    fn stash(&self, worktree_path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        GitService::stash(self, worktree_path, message)
+    }
+
+    fn stash_pop(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        GitService::stash_pop(self, worktree_path)
+    }
+
+    fn stash_drop(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        GitService::stash_drop(self, worktree_path)
+    }
+
+    fn get_branch_oid(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        GitService::get_branch_oid(self, repo_path, branch_name)
+    }
+
+    fn reset_branch(
+        &self,
+        repo_path: &Path,
+        branch: &str,
+        merged_head: &str,
+        previous_head: &str,
+    ) -> Result<(), GitServiceError> {
+        GitService::reset_branch(self, repo_path, branch, merged_head, previous_head)
+    }
+}
+
 /// Background service for automated review processing
 pub struct ReviewAutomationService {
     db: DBService,
-    git_service: GitService,
+    git_service: Arc<dyn GitOps>,
     notification_service: NotificationService,
     poll_interval: Duration,
+    project_concurrency: usize,
 }
 
 impl ReviewAutomationService {
-    /// Spawn the background review automation service
+    /// Spawn the background review automation service, polling every `poll_interval` and
+    /// reviewing up to `project_concurrency` projects at once
     pub async fn spawn(
         db: DBService,
-        git_service: GitService,
+        git_service: Arc<dyn GitOps>,
         notification_service: NotificationService,
+        poll_interval: Duration,
+        project_concurrency: usize,
     ) -> tokio::task::JoinHandle<()> {
-        let service = Self {
+        let mut service = Self::new(db, git_service, notification_service);
+        service.poll_interval = poll_interval;
+        service.project_concurrency = project_concurrency;
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    pub fn new(
+        db: DBService,
+        git_service: Arc<dyn GitOps>,
+        notification_service: NotificationService,
+    ) -> Self {
+        Self {
             db,
             git_service,
             notification_service,
-            poll_interval: Duration::from_secs(10), // Check every 10 seconds for faster response
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            project_concurrency: DEFAULT_PROJECT_CONCURRENCY,
+        }
+    }
+
+    /// Review one in-review task right away instead of waiting for the next poll. Returns None
+    /// if review automation is disabled for the task's project.
+    pub async fn process_task(
+        &self,
+        task: &Task,
+        workspace: &Workspace,
+    ) -> Result<Option<ReviewAction>, ReviewAutomationError> {
+        let Some(settings) =
+            ProjectReviewSettings::find_by_project_id(&self.db.pool, task.project_id)
+                .await?
+                .filter(|s| s.enabled)
+        else {
+            return Ok(None);
         };
-        tokio::spawn(async move {
-            service.start().await;
-        })
+
+        self.process_task_review(task, workspace, &settings)
+            .await
+            .map(Some)
     }
 
     async fn start(&self) {
@@ -137,8 +682,16 @@ impl ReviewAutomationService {
             enabled_projects.len()
         );
 
-        for settings in enabled_projects {
-            match self.process_project(&settings).await {
+        process_concurrently(
+            enabled_projects,
+            self.project_concurrency,
+            |settings| async move {
+                let result = self.process_project(&settings).await;
+                (settings, result)
+            },
+        )
+        .for_each(|(settings, result)| {
+            match result {
                 Ok(Some((task, action))) => {
                     info!(
                         project_id = %settings.project_id,
@@ -161,7 +714,9 @@ impl ReviewAutomationService {
                     );
                 }
             }
-        }
+            future::ready(())
+        })
+        .await;
 
         Ok(())
     }
@@ -172,9 +727,12 @@ impl ReviewAutomationService {
         settings: &ProjectReviewSettings,
     ) -> Result<Option<(Task, ReviewAction)>, ReviewAutomationError> {
         // Find tasks in review with completed attempts
-        let tasks_with_workspaces =
-            Task::find_in_review_with_completed_attempts(&self.db.pool, settings.project_id)
-                .await?;
+        let tasks_with_workspaces = Task::find_in_review_with_completed_attempts(
+            &self.db.pool,
+            settings.project_id,
+            settings.require_successful_attempt,
+        )
+        .await?;
 
         if tasks_with_workspaces.is_empty() {
             return Ok(None);
@@ -191,13 +749,18 @@ impl ReviewAutomationService {
     }
 
     /// Process a single task's review
+    #[tracing::instrument(
+        name = "review_automation_cycle",
+        skip_all,
+        fields(project_id = %task.project_id, task_id = %task.id, workspace_id = %workspace.id)
+    )]
     async fn process_task_review(
         &self,
         task: &Task,
         workspace: &Workspace,
         settings: &ProjectReviewSettings,
     ) -> Result<ReviewAction, ReviewAutomationError> {
-        let workspace_path = workspace.container_ref.as_ref().ok_or_else(|| {
+        let container_ref = workspace.container_ref.as_ref().ok_or_else(|| {
             warn!(
                 task_id = %task.id,
                 workspace_id = %workspace.id,
@@ -205,34 +768,97 @@ impl ReviewAutomationService {
             );
             ReviewAutomationError::NoWorkspaceContainer
         })?;
+        // Containerized workspaces run their tests inside the container, where the toolchain is
+        let location = WorkspaceLocation::parse(container_ref);
+        let workspace_path = location.path();
+        let test_runner = test_runner_for(&location);
+
+        // An attempt that committed nothing leaves an empty branch; send the task back instead of
+        // running tests and merging a no-op
+        if settings.auto_merge_enabled
+            && !self
+                .workspace_has_commits(workspace, workspace_path)
+                .await?
+        {
+            ReviewAutomationLog::create(
+                &self.db.pool,
+                task.id,
+                workspace.id,
+                ReviewAction::NoChanges,
+                None,
+                Some("No changes to merge".to_string()),
+            )
+            .await?;
+
+            Task::update_status(
+                &self.db.pool,
+                task.id,
+                TaskStatus::Todo,
+                Some(STATUS_ACTOR),
+                Some("No changes to merge"),
+            )
+            .await?;
+
+            self.notification_service
+                .notify(
+                    task.project_id,
+                    "Review Automation",
+                    &format!(
+                        "No changes to merge for task '{}', moved back to Todo",
+                        task.title
+                    ),
+                )
+                .await;
+
+            return Ok(ReviewAction::NoChanges);
+        }
 
-        // Step 1: Run tests if enabled and testing_criteria exists
-        if settings.run_tests_enabled && task.testing_criteria.is_some() {
-            match self.run_tests(workspace, workspace_path).await {
+        // Step 1: Run tests if enabled and testing_criteria exists, or always when auto-merge
+        // requires passing tests
+        let tests_run = settings.run_tests_enabled
+            && (task.testing_criteria.is_some() || settings.require_tests_before_merge);
+        let mut test_output = None;
+        if tests_run {
+            match self
+                .run_tests(
+                    task,
+                    workspace,
+                    workspace_path,
+                    settings,
+                    test_runner.as_ref(),
+                )
+                .await
+            {
                 Ok(output) => {
-                    ReviewAutomationLog::create(
+                    test_output = Some(output.clone());
+                    let (output, artifact_path) = self.store_test_output(settings, workspace, output);
+                    ReviewAutomationLog::create_with_artifact(
                         &self.db.pool,
                         task.id,
                         workspace.id,
                         ReviewAction::TestPassed,
                         Some(output),
                         None,
+                        artifact_path,
                     )
                     .await?;
                 }
                 Err(ReviewAutomationError::TestFailed(output)) => {
-                    ReviewAutomationLog::create(
+                    let (output, artifact_path) = self.store_test_output(settings, workspace, output);
+                    ReviewAutomationLog::create_with_artifact(
                         &self.db.pool,
                         task.id,
                         workspace.id,
                         ReviewAction::TestFailed,
-                        Some(output.clone()),
+                        Some(output),
                         Some("Tests failed".to_string()),
+                        artifact_path,
                     )
                     .await?;
 
                     self.notification_service
                         .notify(
+                            task.project_id,
                             "Review Automation",
                             &format!("Tests failed for task: {}", task.title),
                         )
@@ -255,76 +881,302 @@ impl ReviewAutomationService {
             }
         }
 
-        // Step 2: Auto-merge if enabled
-        if settings.auto_merge_enabled {
-            match self.attempt_auto_merge(task, workspace, workspace_path).await {
-                Ok(()) => {
+        // Failed test runs returned above, so only an untested merge is left to block. Flag it so
+        // the task leaves the review queue instead of blocking the project's other tasks.
+        if settings.auto_merge_enabled && settings.require_tests_before_merge && !tests_run {
+            let reason = "Auto-merge blocked: passing tests are required but none ran";
+            ReviewAutomationLog::create(
+                &self.db.pool,
+                task.id,
+                workspace.id,
+                ReviewAction::Skipped,
+                None,
+                Some(reason.to_string()),
+            )
+            .await?;
+            Task::set_needs_attention(&self.db.pool, task.id, Some(reason)).await?;
+
+            self.notification_service
+                .notify(
+                    task.project_id,
+                    "Review Automation",
+                    &format!("Auto-merge blocked for task '{}': no tests ran", task.title),
+                )
+                .await;
+
+            return Ok(ReviewAction::Skipped);
+        }
+
+        // Have the AI confirm the task's own acceptance criteria are met before merging
+        if settings.auto_merge_enabled
+            && settings.enforce_testing_criteria
+            && let Some(testing_criteria) = task.testing_criteria.as_deref()
+        {
+            match self
+                .check_testing_criteria(
+                    task,
+                    workspace,
+                    workspace_path,
+                    testing_criteria,
+                    test_output.as_deref(),
+                )
+                .await
+            {
+                Ok(response) if response.met => {
                     ReviewAutomationLog::create(
                         &self.db.pool,
                         task.id,
                         workspace.id,
-                        ReviewAction::MergeCompleted,
-                        None,
+                        ReviewAction::CriteriaMet,
+                        Some(response.reasoning),
                         None,
                     )
                     .await?;
+                }
+                Ok(response) => {
+                    ReviewAutomationLog::create(
+                        &self.db.pool,
+                        task.id,
+                        workspace.id,
+                        ReviewAction::CriteriaUnmet,
+                        Some(unmet_criteria_report(&response)),
+                        Some("Auto-merge blocked: testing criteria not met".to_string()),
+                    )
+                    .await?;
 
-                    // Move task to done
-                    Task::update_status(&self.db.pool, task.id, TaskStatus::Done).await?;
-
-                    // Archive the workspace
-                    Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
+                    // Back to the agent, so the check isn't repeated until the work changes
+                    Task::update_status(
+                        &self.db.pool,
+                        task.id,
+                        TaskStatus::InProgress,
+                        Some(STATUS_ACTOR),
+                        Some("Testing criteria not met"),
+                    )
+                    .await?;
 
                     self.notification_service
                         .notify(
+                            task.project_id,
                             "Review Automation",
-                            &format!("Task completed: {}", task.title),
+                            &format!(
+                                "Testing criteria not met for task '{}', moved back to In Progress",
+                                task.title
+                            ),
                         )
                         .await;
 
-                    return Ok(ReviewAction::MergeCompleted);
+                    return Ok(ReviewAction::CriteriaUnmet);
                 }
-                Err(ReviewAutomationError::MergeConflict(msg)) => {
-                    // Log the conflict with detailed information
+                Err(e) => {
+                    // Fail closed: an unverified task stays in review rather than merging
                     ReviewAutomationLog::create(
                         &self.db.pool,
                         task.id,
                         workspace.id,
-                        ReviewAction::MergeConflict,
+                        ReviewAction::Error,
                         None,
-                        Some(format!(
-                            "Merge conflict detected. Details: {}",
-                            msg
-                        )),
+                        Some(format!("Testing criteria check failed: {e}")),
                     )
                     .await?;
 
-                    // Check how many times this task has had merge conflicts
-                    let conflict_count = ReviewAutomationLog::count_merge_conflicts(
+                    // Stop re-running the suite and the check every poll while the API is failing.
+                    // Every run of failures flags again, so clearing the flag allows a fresh run.
+                    let failures =
+                        ReviewAutomationLog::count_criteria_check_failures(&self.db.pool, task.id)
+                            .await?;
+                    if failures % MAX_CRITERIA_CHECK_FAILURES == 0 {
+                        let reason =
+                            format!("Testing criteria check failed {failures} times in a row: {e}");
+                        Task::set_needs_attention(&self.db.pool, task.id, Some(&reason)).await?;
+
+                        self.notification_service
+                            .notify(
+                                task.project_id,
+                                "Review Automation",
+                                &format!("Task '{}' needs a manual review: {}", task.title, reason),
+                            )
+                            .await;
+                    }
+                    return Ok(ReviewAction::Error);
+                }
+            }
+        }
+
+        // Project-specific gate (schema check, license scan, ...) that must pass before merging
+        if settings.auto_merge_enabled
+            && let Some(command) = settings.pre_merge_command.as_deref()
+        {
+            match self
+                .run_pre_merge_command(workspace, workspace_path, command, test_runner.as_ref())
+                .await
+            {
+                Ok((true, _)) => {}
+                Ok((false, output)) => {
+                    ReviewAutomationLog::create(
                         &self.db.pool,
                         task.id,
+                        workspace.id,
+                        ReviewAction::Error,
+                        Some(output),
+                        Some(format!("Pre-merge command failed: {command}")),
                     )
                     .await?;
 
-                    if conflict_count >= MAX_MERGE_CONFLICT_ATTEMPTS {
-                        // Too many failures - cancel task and break it down into simpler subtasks
-                        info!(
-                            task_id = %task.id,
-                            conflict_count = conflict_count,
-                            "Review automation: max merge conflicts reached, cancelling and breaking down task"
-                        );
-
-                        // Cancel the original task
-                        Task::update_status(&self.db.pool, task.id, TaskStatus::Cancelled).await?;
-
-                        // Archive the workspace
-                        Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
+                    // Back to the agent, so the gate isn't re-run until the work changes
+                    Task::update_status(
+                        &self.db.pool,
+                        task.id,
+                        TaskStatus::InProgress,
+                        Some(STATUS_ACTOR),
+                        Some("Pre-merge command failed"),
+                    )
+                    .await?;
 
-                        // Try to break down the task into simpler subtasks
-                        match self.breakdown_conflicting_task(&task, &msg).await {
+                    self.notification_service
+                        .notify(
+                            task.project_id,
+                            "Review Automation",
+                            &format!(
+                                "Pre-merge command failed for task '{}', moved back to In Progress",
+                                task.title
+                            ),
+                        )
+                        .await;
+
+                    return Ok(ReviewAction::Error);
+                }
+                Err(e) => {
+                    ReviewAutomationLog::create(
+                        &self.db.pool,
+                        task.id,
+                        workspace.id,
+                        ReviewAction::Error,
+                        None,
+                        Some(e.to_string()),
+                    )
+                    .await?;
+                    return Err(e);
+                }
+            }
+        }
+
+        // Step 2: Auto-merge if enabled
+        if settings.auto_merge_enabled {
+            match self
+                .attempt_auto_merge(task, workspace, workspace_path, settings)
+                .await
+            {
+                Ok(()) => {
+                    ReviewAutomationLog::create(
+                        &self.db.pool,
+                        task.id,
+                        workspace.id,
+                        ReviewAction::MergeCompleted,
+                        None,
+                        None,
+                    )
+                    .await?;
+
+                    // Move task to done
+                    Task::update_status(
+                        &self.db.pool,
+                        task.id,
+                        TaskStatus::Done,
+                        Some(STATUS_ACTOR),
+                        Some("Merged"),
+                    )
+                    .await?;
+                    if settings.auto_archive_on_merge {
+                        Task::set_archived(&self.db.pool, task.id, true).await?;
+                    }
+
+                    if settings.complete_parent_on_subtasks_done
+                        && let Some(parent_task_id) = task.parent_task_id
+                        && let Err(e) = self.rollup_parent_completion(task, parent_task_id).await
+                    {
+                        warn!(
+                            task_id = %task.id,
+                            parent_task_id = %parent_task_id,
+                            error = %e,
+                            "Review automation: failed to roll up parent task completion"
+                        );
+                    }
+
+                    // Archive the workspace
+                    Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
+
+                    self.notification_service
+                        .notify(
+                            task.project_id,
+                            "Review Automation",
+                            &format!("Task completed: {}", task.title),
+                        )
+                        .await;
+
+                    return Ok(ReviewAction::MergeCompleted);
+                }
+                Err(ReviewAutomationError::MergeConflict(msg)) => {
+                    // Log the conflict with detailed information, recording which files conflicted
+                    let conflicted_files = self
+                        .workspace_conflicted_files(workspace, workspace_path)
+                        .await;
+                    ReviewAutomationLog::create(
+                        &self.db.pool,
+                        task.id,
+                        workspace.id,
+                        ReviewAction::MergeConflict,
+                        (!conflicted_files.is_empty())
+                            .then(|| format_conflicted_files(&conflicted_files)),
+                        Some(format!(
+                            "Merge conflict detected. Details: {}",
+                            msg
+                        )),
+                    )
+                    .await?;
+
+                    // Check how many times this task has had merge conflicts
+                    let conflict_count = ReviewAutomationLog::count_merge_conflicts(
+                        &self.db.pool,
+                        task.id,
+                    )
+                    .await?;
+
+                    if conflict_count >= MAX_MERGE_CONFLICT_ATTEMPTS {
+                        // Too many failures - cancel task and break it down into simpler subtasks
+                        info!(
+                            task_id = %task.id,
+                            conflict_count = conflict_count,
+                            "Review automation: max merge conflicts reached, cancelling and breaking down task"
+                        );
+
+                        // Summarize the conflicts while the worktrees still exist
+                        let conflict_summary = self
+                            .conflict_summary(task, workspace, workspace_path)
+                            .await?;
+
+                        // Cancel the original task
+                        Task::update_status(
+                            &self.db.pool,
+                            task.id,
+                            TaskStatus::Cancelled,
+                            Some(STATUS_ACTOR),
+                            Some("Too many merge conflicts"),
+                        )
+                        .await?;
+
+                        // Archive the workspace and drop its worktrees
+                        Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
+                        self.remove_workspace_worktrees(workspace, workspace_path).await;
+
+                        // Try to break down the task into simpler subtasks
+                        match self
+                            .breakdown_conflicting_task(task, &msg, &conflict_summary)
+                            .await
+                        {
                             Ok(subtask_count) => {
                                 self.notification_service
-                                    .notify(
+                                    .notify_critical(
+                                        task.project_id,
                                         "Review Automation",
                                         &format!(
                                             "Task '{}' cancelled after {} merge conflicts. Created {} simpler subtasks.",
@@ -353,8 +1205,20 @@ impl ReviewAutomationService {
                                     "Failed to break down conflicting task"
                                 );
 
+                                // No recovery path left, so flag the task for a human
+                                Task::set_needs_attention(
+                                    &self.db.pool,
+                                    task.id,
+                                    Some(&format!(
+                                        "Cancelled after {} merge conflicts and automatic breakdown failed: {}",
+                                        conflict_count, e
+                                    )),
+                                )
+                                .await?;
+
                                 self.notification_service
-                                    .notify(
+                                    .notify_critical(
+                                        task.project_id,
                                         "Review Automation",
                                         &format!(
                                             "Task '{}' cancelled after {} merge conflicts. Manual breakdown required.",
@@ -370,7 +1234,14 @@ impl ReviewAutomationService {
 
                     // Move task back to InProgress so the agent can resolve conflicts
                     // This mirrors what happens when user clicks "Resolve Conflicts"
-                    Task::update_status(&self.db.pool, task.id, TaskStatus::InProgress).await?;
+                    Task::update_status(
+                        &self.db.pool,
+                        task.id,
+                        TaskStatus::InProgress,
+                        Some(STATUS_ACTOR),
+                        Some("Merge conflict"),
+                    )
+                    .await?;
 
                     info!(
                         task_id = %task.id,
@@ -382,6 +1253,7 @@ impl ReviewAutomationService {
 
                     self.notification_service
                         .notify(
+                            task.project_id,
                             "Review Automation",
                             &format!(
                                 "Merge conflict #{} for '{}'. Task moved back to InProgress for conflict resolution. ({} attempts remaining)",
@@ -392,6 +1264,24 @@ impl ReviewAutomationService {
 
                     return Ok(ReviewAction::MergeConflict);
                 }
+                Err(ReviewAutomationError::TargetNotAllowed(repos)) => {
+                    // Whatever was allowed has merged; a human has to merge or retarget the rest.
+                    // The flag takes the task out of the review queue until it is cleared.
+                    let reason = format!(
+                        "Not merged, target branch not in the auto-merge allowlist: {repos}"
+                    );
+                    Task::set_needs_attention(&self.db.pool, task.id, Some(&reason)).await?;
+
+                    self.notification_service
+                        .notify(
+                            task.project_id,
+                            "Review Automation",
+                            &format!("Task '{}' needs a manual merge: {}", task.title, reason),
+                        )
+                        .await;
+
+                    return Ok(ReviewAction::Skipped);
+                }
                 Err(e) => {
                     ReviewAutomationLog::create(
                         &self.db.pool,
@@ -421,10 +1311,8 @@ impl ReviewAutomationService {
         Ok(ReviewAction::Skipped)
     }
 
-    /// Detect the project stack from files in the workspace
-    fn detect_stack(&self, workspace_path: &str) -> ProjectStack {
-        let path = Path::new(workspace_path);
-
+    /// Detect the project stack from files in a directory
+    fn detect_stack(&self, path: &Path) -> ProjectStack {
         // Check for Node.js (package.json)
         if path.join("package.json").exists() {
             return ProjectStack::NodeJs;
@@ -451,60 +1339,330 @@ impl ReviewAutomationService {
         ProjectStack::Unknown
     }
 
-    /// Run tests for a workspace
+    /// Run tests for a workspace. A task whose type has a test profile runs only the profile's
+    /// commands. Otherwise, when the project's test targets match files the branch changed,
+    /// only those commands run; failing both, each repo runs its detected stack's full suite in
+    /// its own directory, and the review fails if any repo's tests fail.
     async fn run_tests(
         &self,
+        task: &Task,
         workspace: &Workspace,
         workspace_path: &str,
+        settings: &ProjectReviewSettings,
+        runner: &dyn TestRunner,
     ) -> Result<String, ReviewAutomationError> {
-        // Detect the stack
-        let stack = self.detect_stack(workspace_path);
-
-        let (cmd, args) = match stack.test_command() {
-            Some(c) => c,
-            None => {
+        let mut selected = self
+            .profile_test_commands(task, workspace, workspace_path, settings)
+            .await?;
+        if selected.is_empty() {
+            selected = self
+                .targeted_test_commands(workspace, workspace_path, settings)
+                .await?;
+        }
+        if !selected.is_empty() {
+            let mut combined_output = String::new();
+            let (shell, shell_arg) = runner.shell();
+            for (worktree_path, command) in &selected {
                 info!(
                     workspace_id = %workspace.id,
-                    "Review automation: unknown stack, skipping tests"
+                    command = %command,
+                    path = %worktree_path.display(),
+                    "Review automation: running selected tests"
                 );
-                return Ok("Unknown stack, tests skipped".to_string());
+                // Through the shell, so quoting, pipes and env assignments work as written
+                let (success, output) =
+                    run_test_command(runner, &shell, &[shell_arg, command], worktree_path).await?;
+                combined_output.push_str(&format!("$ {command}\n{output}\n\n"));
+                if !success {
+                    return Err(ReviewAutomationError::TestFailed(combined_output));
+                }
             }
+            return Ok(combined_output);
+        }
+
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&self.db.pool, workspace.id)
+                .await?;
+        let repo_paths: Vec<(&str, PathBuf)> = repos
+            .iter()
+            .map(|r| {
+                (
+                    r.repo.name.as_str(),
+                    Path::new(workspace_path).join(&r.repo.name),
+                )
+            })
+            .filter(|(_, path)| path.exists())
+            .collect();
+
+        // No repo worktrees on disk: treat the workspace itself as the project
+        if repo_paths.is_empty() {
+            return match self
+                .run_stack_tests(workspace, Path::new(workspace_path), runner)
+                .await?
+            {
+                Some((true, output)) => Ok(output),
+                Some((false, output)) => Err(ReviewAutomationError::TestFailed(output)),
+                None => Ok("Unknown stack, tests skipped".to_string()),
+            };
+        }
+
+        let mut combined_output = String::new();
+        let mut any_failed = false;
+        for (repo_name, repo_path) in &repo_paths {
+            let result = match self.run_stack_tests(workspace, repo_path, runner).await? {
+                Some((true, output)) => format!("PASSED\n{output}"),
+                Some((false, output)) => {
+                    any_failed = true;
+                    format!("FAILED\n{output}")
+                }
+                None => "Unknown stack, tests skipped".to_string(),
+            };
+            combined_output.push_str(&format!("== {repo_name} ==\n{result}\n\n"));
+        }
+
+        if any_failed {
+            Err(ReviewAutomationError::TestFailed(combined_output))
+        } else {
+            Ok(combined_output)
+        }
+    }
+
+    /// Run the project's pre-merge command through the runner's shell in the workspace
+    /// directory, returning whether it exited 0 and its output
+    async fn run_pre_merge_command(
+        &self,
+        workspace: &Workspace,
+        workspace_path: &str,
+        command: &str,
+        runner: &dyn TestRunner,
+    ) -> Result<(bool, String), ReviewAutomationError> {
+        info!(
+            workspace_id = %workspace.id,
+            command = %command,
+            "Review automation: running pre-merge command"
+        );
+        let (shell, shell_arg) = runner.shell();
+        run_test_command(
+            runner,
+            &shell,
+            &[shell_arg, command],
+            Path::new(workspace_path),
+        )
+        .await
+    }
+
+    /// Run the full test suite for the stack detected in `dir`, returning whether it passed and
+    /// its output. None when the stack is unknown and there is nothing to run.
+    async fn run_stack_tests(
+        &self,
+        workspace: &Workspace,
+        dir: &Path,
+        runner: &dyn TestRunner,
+    ) -> Result<Option<(bool, String)>, ReviewAutomationError> {
+        let stack = self.detect_stack(dir);
+        let Some((cmd, args)) = stack.test_command() else {
+            info!(
+                workspace_id = %workspace.id,
+                path = %dir.display(),
+                "Review automation: unknown stack, skipping tests"
+            );
+            return Ok(None);
         };
 
         info!(
             workspace_id = %workspace.id,
             stack = ?stack,
             command = cmd,
+            path = %dir.display(),
             "Review automation: running tests"
         );
 
-        let output = Command::new(cmd)
-            .args(args)
-            .current_dir(workspace_path)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-            .map_err(|e| ReviewAutomationError::CommandFailed(e.to_string()))?;
+        run_test_command(runner, cmd, args, dir).await.map(Some)
+    }
+
+    /// The test profile commands for the task's type, each paired with every repo worktree on
+    /// disk (or the workspace itself when there are none). Empty when the type has no profile
+    /// or the profiles can't be parsed, so the usual test selection applies.
+    async fn profile_test_commands(
+        &self,
+        task: &Task,
+        workspace: &Workspace,
+        workspace_path: &str,
+        settings: &ProjectReviewSettings,
+    ) -> Result<Vec<(PathBuf, String)>, ReviewAutomationError> {
+        let Some(rules) = settings.test_profiles.as_deref() else {
+            return Ok(Vec::new());
+        };
+        let profiles = match parse_test_profiles(rules) {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                warn!(
+                    workspace_id = %workspace.id,
+                    error = %e,
+                    "Review automation: invalid test profiles, using the usual test selection"
+                );
+                return Ok(Vec::new());
+            }
+        };
+        let task_type = task.task_type.clone().unwrap_or_default();
+        let commands = profile_commands(&profiles, &task_type);
+        if commands.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let combined_output = format!("STDOUT:\n{}\n\nSTDERR:\n{}", stdout, stderr);
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&self.db.pool, workspace.id)
+                .await?;
+        let mut dirs: Vec<PathBuf> = repos
+            .iter()
+            .map(|r| Path::new(workspace_path).join(&r.repo.name))
+            .filter(|path| path.exists())
+            .collect();
+        if dirs.is_empty() {
+            dirs.push(PathBuf::from(workspace_path));
+        }
 
-        if output.status.success() {
-            Ok(combined_output)
-        } else {
-            Err(ReviewAutomationError::TestFailed(combined_output))
+        info!(
+            workspace_id = %workspace.id,
+            task_type = %task_type,
+            "Review automation: using the task type's test profile"
+        );
+        Ok(dirs
+            .iter()
+            .flat_map(|dir| {
+                commands
+                    .iter()
+                    .map(|command| (dir.clone(), command.to_string()))
+            })
+            .collect())
+    }
+
+    /// The targeted test commands for the files the workspace branch changed, each paired with
+    /// the repo worktree to run it in. Empty when no rules are configured, the changed files
+    /// can't be determined, or any changed file matches no rule, so the full suite runs instead.
+    async fn targeted_test_commands(
+        &self,
+        workspace: &Workspace,
+        workspace_path: &str,
+        settings: &ProjectReviewSettings,
+    ) -> Result<Vec<(PathBuf, String)>, ReviewAutomationError> {
+        let Some(rules) = settings.test_targets.as_deref() else {
+            return Ok(Vec::new());
+        };
+        let targets = match parse_test_targets(rules) {
+            Ok(targets) => targets,
+            Err(e) => {
+                warn!(
+                    workspace_id = %workspace.id,
+                    error = %e,
+                    "Review automation: invalid test targets, running the full suite"
+                );
+                return Ok(Vec::new());
+            }
+        };
+        if targets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&self.db.pool, workspace.id)
+                .await?;
+        let mut commands = Vec::new();
+        for repo_with_branch in &repos {
+            let task_worktree_path = Path::new(workspace_path).join(&repo_with_branch.repo.name);
+            if !task_worktree_path.exists() {
+                continue;
+            }
+            let changed_files = match self
+                .git_service
+                .changed_files(&task_worktree_path, &repo_with_branch.target_branch)
+            {
+                Ok(files) => files,
+                Err(e) => {
+                    warn!(
+                        workspace_id = %workspace.id,
+                        repo_id = %repo_with_branch.repo.id,
+                        error = %e,
+                        "Review automation: could not list changed files, running the full suite"
+                    );
+                    return Ok(Vec::new());
+                }
+            };
+            let Some(matched) = matching_commands(&targets, &changed_files) else {
+                info!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo_with_branch.repo.id,
+                    "Review automation: changes outside the test targets, running the full suite"
+                );
+                return Ok(Vec::new());
+            };
+            commands.extend(
+                matched
+                    .into_iter()
+                    .map(|command| (task_worktree_path.clone(), command.to_string())),
+            );
+        }
+        Ok(commands)
+    }
+
+    /// Write full test output to the workspace's artifacts directory when enabled.
+    /// Returns the output to store in the log (a preview when written to disk) and the artifact path.
+    fn store_test_output(
+        &self,
+        settings: &ProjectReviewSettings,
+        workspace: &Workspace,
+        output: String,
+    ) -> (String, Option<String>) {
+        if !settings.store_test_artifacts {
+            return (output, None);
+        }
+
+        let dir = review_artifacts_dir().join(workspace.id.to_string());
+        let path = dir.join(format!(
+            "tests-{}.log",
+            chrono::Utc::now().format("%Y%m%d%H%M%S%3f")
+        ));
+
+        if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, &output)) {
+            warn!(
+                workspace_id = %workspace.id,
+                path = %path.display(),
+                error = %e,
+                "Review automation: failed to write test artifact, storing full output"
+            );
+            return (output, None);
         }
+
+        let skipped = output.chars().count().saturating_sub(TEST_OUTPUT_PREVIEW_CHARS);
+        let preview = if skipped > 0 {
+            let tail: String = output.chars().skip(skipped).collect();
+            format!(
+                "... ({} characters truncated, full output in {})\n{}",
+                skipped,
+                path.display(),
+                tail
+            )
+        } else {
+            output
+        };
+
+        (preview, Some(path.to_string_lossy().to_string()))
     }
 
     /// Attempt to auto-merge the workspace branch into target branches
-    /// If the base branch has moved ahead, automatically rebase and retry
+    /// If the base branch has moved ahead, automatically rebase and retry.
+    /// Repos merged by an earlier partial attempt are skipped. When a repo fails to merge, the
+    /// repos merged cleanly in this attempt are rolled back, unless `allow_partial_merge` is set:
+    /// then they are kept, the remaining repos are logged as [`ReviewAction::PartiallyMerged`],
+    /// and the conflict is still returned so the task goes back for resolution.
+    /// Repos whose target branch isn't in `auto_merge_target_allowlist` are skipped and logged;
+    /// once the others have merged they are reported as [`ReviewAutomationError::TargetNotAllowed`].
     async fn attempt_auto_merge(
         &self,
         task: &Task,
         workspace: &Workspace,
         workspace_path: &str,
+        settings: &ProjectReviewSettings,
     ) -> Result<(), ReviewAutomationError> {
         // Get workspace repos with their target branches
         let workspace_repos =
@@ -519,7 +1677,39 @@ impl ReviewAutomationService {
             return Ok(());
         }
 
+        let target_allowlist = settings
+            .auto_merge_target_allowlist
+            .as_deref()
+            .map(parse_target_allowlist)
+            .transpose()
+            .map_err(|e| {
+                ReviewAutomationError::InvalidSettings(format!("auto_merge_target_allowlist: {e}"))
+            })?
+            .unwrap_or_default();
+
+        // The task's post-task notes go into the configured repo's .progress file, or the first
+        // repo's when none is configured
+        let progress_repo_id = match settings.progress_repo.as_deref() {
+            Some(name) => {
+                let found = workspace_repos.iter().find(|r| r.repo.name == name);
+                if found.is_none() && task.post_task_actions.is_some() {
+                    warn!(
+                        workspace_id = %workspace.id,
+                        progress_repo = %name,
+                        "Review automation: configured progress repo is not part of the workspace"
+                    );
+                }
+                found.map(|r| r.repo.id)
+            }
+            None => workspace_repos.first().map(|r| r.repo.id),
+        };
+
         // Merge each repo
+        let mut merged_before = HashSet::new();
+        let mut not_allowed = Vec::new();
+        let mut clean_merges = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut failure = None;
         for repo_with_branch in &workspace_repos {
             let repo = &repo_with_branch.repo;
             let target_branch = &repo_with_branch.target_branch;
@@ -539,312 +1729,3008 @@ impl ReviewAutomationService {
                 continue;
             }
 
-            info!(
-                workspace_id = %workspace.id,
-                repo_id = %repo.id,
-                branch = %workspace.branch,
-                target_branch = %target_branch,
-                "Review automation: attempting merge"
-            );
+            // Already merged by an earlier partial or interrupted attempt: merging again would
+            // duplicate the changes on the target
+            if Merge::find_direct(&self.db.pool, workspace.id, repo.id, target_branch)
+                .await?
+                .is_some()
+            {
+                debug!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    "Review automation: repo already merged, skipping"
+                );
+                merged_before.insert(repo.id);
+                continue;
+            }
 
-            // Perform the merge
-            let commit_message = format!("Merge {} into {}\n\nTask: {}", workspace.branch, target_branch, task.title);
+            // Guardrail: never merge into a branch the project hasn't opened to auto-merge
+            if !target_allowlist.is_empty()
+                && !target_allowlist
+                    .iter()
+                    .any(|allowed| allowed.is_match(target_branch))
+            {
+                warn!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    target_branch = %target_branch,
+                    "Review automation: target branch is not in the auto-merge allowlist, skipping"
+                );
+                ReviewAutomationLog::create(
+                    &self.db.pool,
+                    task.id,
+                    workspace.id,
+                    ReviewAction::Skipped,
+                    None,
+                    Some(format!(
+                        "Did not merge {} into '{}': the branch is not in the auto-merge allowlist",
+                        repo.name, target_branch
+                    )),
+                )
+                .await?;
+                not_allowed.push(format!("{} ({})", repo.name, target_branch));
+                continue;
+            }
 
-            let merge_result = self.git_service.merge_changes(
-                repo_path,
-                &task_worktree_path,
-                &workspace.branch,
-                target_branch,
-                &commit_message,
-            );
+            // Bring the local target branch up to date so divergence detection sees upstream commits
+            if settings.fetch_before_merge {
+                self.fetch_target_branch(workspace, repo.id, repo_path, target_branch);
+            }
 
-            match merge_result {
-                Ok(merge_commit) => {
-                    info!(
-                        workspace_id = %workspace.id,
-                        repo_id = %repo.id,
-                        merge_commit = %merge_commit,
-                        "Review automation: merge successful"
-                    );
+            // Commit the .progress update on the task branch so it lands with the merge,
+            // building on the freshly fetched target's copy of the file
+            if progress_repo_id == Some(repo.id)
+                && let Err(e) = self.append_progress_entry(
+                    task,
+                    &task_worktree_path,
+                    &workspace.branch,
+                    target_branch,
+                    settings.cherry_pick_single_commits && !settings.sign_commits,
+                )
+            {
+                warn!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    error = %e,
+                    "Review automation: failed to update .progress file"
+                );
+            }
 
-                    // Record the direct merge
-                    Merge::create_direct(
-                        &self.db.pool,
-                        workspace.id,
-                        repo.id,
-                        target_branch,
-                        &merge_commit,
-                    )
-                    .await?;
+            // Set aside anything the agent left uncommitted so it can't break the merge
+            let prepared = self
+                .git_service
+                .get_branch_oid(repo_path, target_branch)
+                .and_then(|previous_head| {
+                    let stashed = settings.stash_before_merge
+                        && self.git_service.stash(
+                            &task_worktree_path,
+                            &format!("Review automation: before merging task {}", task.id),
+                        )?;
+                    Ok((previous_head, stashed))
+                });
+            let (previous_head, stashed) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    failure = Some(e.into());
+                    break;
                 }
-                Err(super::git::GitServiceError::BranchesDiverged(_)) => {
-                    // Base branch has moved ahead - try to rebase and merge
-                    info!(
-                        workspace_id = %workspace.id,
-                        repo_id = %repo.id,
-                        branch = %workspace.branch,
-                        target_branch = %target_branch,
-                        "Review automation: base branch diverged, attempting rebase"
-                    );
+            };
 
-                    // Get the fork point (old base) for rebase
-                    let fork_point = match self.git_service.get_fork_point(
-                        &task_worktree_path,
-                        target_branch,
-                        &workspace.branch,
-                    ) {
-                        Ok(fp) => fp,
-                        Err(e) => {
-                            return Err(ReviewAutomationError::MergeConflict(format!(
-                                "Could not determine fork point for rebase: {}",
-                                e
-                            )));
-                        }
-                    };
-
-                    // Attempt rebase onto new base
-                    match self.git_service.rebase_branch(
-                        repo_path,
-                        &task_worktree_path,
-                        target_branch,
-                        &fork_point,
-                        &workspace.branch,
-                    ) {
-                        Ok(new_head) => {
-                            info!(
-                                workspace_id = %workspace.id,
-                                repo_id = %repo.id,
-                                new_head = %new_head,
-                                "Review automation: rebase successful, retrying merge"
-                            );
-
-                            // Retry the merge after successful rebase
-                            match self.git_service.merge_changes(
-                                repo_path,
-                                &task_worktree_path,
-                                &workspace.branch,
-                                target_branch,
-                                &commit_message,
-                            ) {
-                                Ok(merge_commit) => {
-                                    info!(
-                                        workspace_id = %workspace.id,
-                                        repo_id = %repo.id,
-                                        merge_commit = %merge_commit,
-                                        "Review automation: merge successful after rebase"
-                                    );
-
-                                    Merge::create_direct(
-                                        &self.db.pool,
-                                        workspace.id,
-                                        repo.id,
-                                        target_branch,
-                                        &merge_commit,
-                                    )
-                                    .await?;
-                                }
-                                Err(e) => {
-                                    return Err(ReviewAutomationError::MergeConflict(format!(
-                                        "Merge failed after rebase: {}",
-                                        e
-                                    )));
-                                }
-                            }
-                        }
-                        Err(super::git::GitServiceError::MergeConflicts(msg)) => {
-                            // Rebase had conflicts - abort and report
-                            let _ = self.git_service.abort_conflicts(&task_worktree_path);
-                            return Err(ReviewAutomationError::MergeConflict(format!(
-                                "Automatic rebase failed due to conflicts. Manual intervention required. {}",
-                                msg
-                            )));
-                        }
-                        Err(e) => {
-                            // Rebase failed for other reasons - abort and report
-                            let _ = self.git_service.abort_conflicts(&task_worktree_path);
-                            return Err(ReviewAutomationError::MergeConflict(format!(
-                                "Automatic rebase failed: {}",
-                                e
-                            )));
-                        }
-                    }
-                }
-                Err(super::git::GitServiceError::MergeConflicts(msg)) => {
-                    return Err(ReviewAutomationError::MergeConflict(msg));
+            let merged = self
+                .merge_repo(
+                    task,
+                    workspace,
+                    repo,
+                    target_branch,
+                    &task_worktree_path,
+                    settings,
+                )
+                .await;
+
+            if stashed {
+                let discard = merged.is_ok() && settings.drop_stash_after_merge;
+                self.restore_stash(task, workspace, &task_worktree_path, discard)
+                    .await?;
+            }
+            match merged {
+                Ok(merge_commit) => clean_merges.push(CleanMerge {
+                    repo,
+                    target_branch,
+                    previous_head,
+                    merge_commit,
+                }),
+                // Keep going: the other repos may still merge cleanly
+                Err(ReviewAutomationError::MergeConflict(msg)) if settings.allow_partial_merge => {
+                    conflicts.push(format!("{}: {msg}", repo.name));
                 }
                 Err(e) => {
-                    return Err(ReviewAutomationError::Git(e));
+                    failure = Some(e);
+                    break;
                 }
             }
         }
 
-        Ok(())
-    }
-
-    /// Get the current review automation status for a project
-    pub async fn get_status(
-        pool: &SqlitePool,
-        project_id: Uuid,
-    ) -> Result<ReviewAutomationStatus, ReviewAutomationError> {
-        let settings = ProjectReviewSettings::find_by_project_id(pool, project_id).await?;
-        let latest_log = ReviewAutomationLog::find_latest_by_project_id(pool, project_id).await?;
-
-        Ok(ReviewAutomationStatus {
-            enabled: settings.as_ref().is_some_and(|s| s.enabled),
-            auto_merge_enabled: settings.as_ref().is_some_and(|s| s.auto_merge_enabled),
-            run_tests_enabled: settings.as_ref().is_some_and(|s| s.run_tests_enabled),
-            last_action: latest_log.as_ref().map(|l| l.action.clone()),
-            last_task_id: latest_log.map(|l| l.task_id),
-        })
-    }
+        let failure = match failure {
+            Some(e) => e,
+            None if conflicts.is_empty() => {
+                self.record_clean_merges(workspace, &clean_merges).await?;
+                if !not_allowed.is_empty() {
+                    return Err(ReviewAutomationError::TargetNotAllowed(
+                        not_allowed.join(", "),
+                    ));
+                }
+                return Ok(());
+            }
+            None => ReviewAutomationError::MergeConflict(conflicts.join("\n")),
+        };
 
-    /// Enable review automation for a project
-    pub async fn enable(
-        pool: &SqlitePool,
-        project_id: Uuid,
-    ) -> Result<ProjectReviewSettings, ReviewAutomationError> {
-        Ok(ProjectReviewSettings::set_enabled(pool, project_id, true).await?)
-    }
+        if !settings.allow_partial_merge {
+            self.roll_back_clean_merges(workspace, &clean_merges)
+                .await?;
+            return Err(failure);
+        }
 
-    /// Disable review automation for a project
-    pub async fn disable(
-        pool: &SqlitePool,
-        project_id: Uuid,
-    ) -> Result<ProjectReviewSettings, ReviewAutomationError> {
-        Ok(ProjectReviewSettings::set_enabled(pool, project_id, false).await?)
+        self.record_clean_merges(workspace, &clean_merges).await?;
+        if !clean_merges.is_empty() {
+            let merged: Vec<&str> = clean_merges
+                .iter()
+                .map(|merge| merge.repo.name.as_str())
+                .collect();
+            let remaining: Vec<&str> = workspace_repos
+                .iter()
+                .filter(|r| {
+                    !merged_before.contains(&r.repo.id)
+                        && !clean_merges.iter().any(|merge| merge.repo.id == r.repo.id)
+                })
+                .map(|r| r.repo.name.as_str())
+                .collect();
+            info!(
+                task_id = %task.id,
+                workspace_id = %workspace.id,
+                merged = ?merged,
+                remaining = ?remaining,
+                "Review automation: partially merged task"
+            );
+            ReviewAutomationLog::create(
+                &self.db.pool,
+                task.id,
+                workspace.id,
+                ReviewAction::PartiallyMerged,
+                Some(format!(
+                    "Merged: {}\nStill to merge: {}",
+                    merged.join(", "),
+                    remaining.join(", ")
+                )),
+                None,
+            )
+            .await?;
+        }
+        Err(failure)
     }
 
-    /// Get review automation logs for a project
-    pub async fn get_logs(
-        pool: &SqlitePool,
-        project_id: Uuid,
-        limit: i32,
-    ) -> Result<Vec<ReviewAutomationLog>, ReviewAutomationError> {
-        Ok(ReviewAutomationLog::find_by_project_id(pool, project_id, limit).await?)
+    /// Record the direct merges an auto-merge attempt is keeping
+    async fn record_clean_merges(
+        &self,
+        workspace: &Workspace,
+        clean_merges: &[CleanMerge<'_>],
+    ) -> Result<(), ReviewAutomationError> {
+        for merge in clean_merges {
+            Merge::create_direct(
+                &self.db.pool,
+                workspace.id,
+                merge.repo.id,
+                merge.target_branch,
+                &merge.merge_commit,
+                Some(&merge.previous_head),
+            )
+            .await?;
+        }
+        Ok(())
     }
 
-    /// Get review automation logs for a specific task
-    pub async fn get_logs_by_task(
-        pool: &SqlitePool,
-        task_id: Uuid,
-    ) -> Result<Vec<ReviewAutomationLog>, ReviewAutomationError> {
-        Ok(ReviewAutomationLog::find_by_task_id(pool, task_id).await?)
+    /// Move each target branch back to where it was before this attempt merged into it. A branch
+    /// that can't be reset (it moved on, or its checkout has changes in the way) keeps the merge,
+    /// which is then recorded so the merge state stays truthful.
+    async fn roll_back_clean_merges(
+        &self,
+        workspace: &Workspace,
+        clean_merges: &[CleanMerge<'_>],
+    ) -> Result<(), ReviewAutomationError> {
+        for merge in clean_merges.iter().rev() {
+            match self.git_service.reset_branch(
+                &merge.repo.path,
+                merge.target_branch,
+                &merge.merge_commit,
+                &merge.previous_head,
+            ) {
+                Ok(()) => info!(
+                    workspace_id = %workspace.id,
+                    repo_id = %merge.repo.id,
+                    merge_commit = %merge.merge_commit,
+                    "Review automation: rolled back merge after another repo failed to merge"
+                ),
+                Err(e) => {
+                    warn!(
+                        workspace_id = %workspace.id,
+                        repo_id = %merge.repo.id,
+                        merge_commit = %merge.merge_commit,
+                        error = %e,
+                        "Review automation: could not roll back merge, keeping it"
+                    );
+                    self.record_clean_merges(workspace, std::slice::from_ref(merge))
+                        .await?;
+                }
+            }
+        }
+        Ok(())
     }
 
-    /// Break down a task that has failed to merge too many times into simpler subtasks
-    async fn breakdown_conflicting_task(
+    /// Merge one workspace repo's branch into its target branch, rebasing and retrying once if
+    /// the target has moved ahead. Returns the merge commit; recording it is left to the caller.
+    async fn merge_repo(
         &self,
         task: &Task,
-        conflict_details: &str,
-    ) -> Result<usize, ReviewAutomationError> {
-        let claude = ClaudeApiClient::from_env()
-            .map_err(|e: ClaudeApiError| ReviewAutomationError::CommandFailed(e.to_string()))?;
-
-        let prompt = format!(
-            r#"A software development task has failed to merge {max_attempts} times due to conflicts.
-The task needs to be broken down into smaller, simpler subtasks that are less likely to cause conflicts.
-
-## Original Task
-Title: {title}
-Description: {description}
-Layer: {layer}
-Type: {task_type}
-
-## Conflict Details
-{conflict_details}
-
-## Requirements
-1. Break this task into 2-4 smaller, independent subtasks
-2. Each subtask should be small enough to avoid merge conflicts
-3. Subtasks should be able to be completed and merged independently
-4. Focus on making atomic, isolated changes
-
-## Output Format (JSON only):
-{{
-  "subtasks": [
-    {{"title": "<subtask title>", "description": "<clear description of what to do>", "layer": "<data|backend|frontend|null>"}},
-    ...
-  ],
-  "reasoning": "<brief explanation of how you split the task>"
-}}"#,
-            max_attempts = MAX_MERGE_CONFLICT_ATTEMPTS,
-            title = task.title,
-            description = task.description.as_deref().unwrap_or("(no description)"),
-            layer = task.layer.as_ref().map(|l| l.to_string()).unwrap_or_else(|| "unspecified".to_string()),
-            task_type = task.task_type.as_ref().map(|t| t.to_string()).unwrap_or_else(|| "implementation".to_string()),
-            conflict_details = conflict_details,
-        );
-
-        let system = Some(
-            "You are a task breakdown assistant. Break complex tasks into smaller, independent pieces that can be merged without conflicts. Output valid JSON only.".to_string()
-        );
+        workspace: &Workspace,
+        repo: &Repo,
+        target_branch: &str,
+        task_worktree_path: &Path,
+        settings: &ProjectReviewSettings,
+    ) -> Result<String, ReviewAutomationError> {
+        let repo_path = &repo.path;
 
-        let response: ConflictBreakdownResponse = claude
-            .ask_json::<ConflictBreakdownResponse>(&prompt, system)
-            .await
-            .map_err(|e: ClaudeApiError| ReviewAutomationError::CommandFailed(e.to_string()))?;
+        info!(
+            workspace_id = %workspace.id,
+            repo_id = %repo.id,
+            branch = %workspace.branch,
+            target_branch = %target_branch,
+            "Review automation: attempting merge"
+        );
 
-        if response.subtasks.is_empty() || response.subtasks.len() < 2 {
-            return Err(ReviewAutomationError::CommandFailed(
-                "AI did not suggest enough subtasks for breakdown".to_string(),
-            ));
+        // Rebase up front when the target has moved, rather than learning it from a failed merge
+        if self.plan_merge(workspace, repo, target_branch, task_worktree_path)
+            != MergeApproach::Direct
+        {
+            let new_head = self.rebase_onto_target(
+                repo_path,
+                task_worktree_path,
+                &workspace.branch,
+                target_branch,
+            )?;
+            info!(
+                workspace_id = %workspace.id,
+                repo_id = %repo.id,
+                new_head = %new_head,
+                "Review automation: rebased onto target before merging"
+            );
         }
 
-        let mut created_count = 0;
-        let base_sequence = task.sequence.unwrap_or(1);
+        // Perform the merge
+        let commit_message = GitService::with_task_id_trailer(
+            &merge_commit_message(
+                task,
+                &workspace.branch,
+                target_branch,
+                settings.conventional_commits,
+            ),
+            task.id,
+        );
 
-        for (i, subtask) in response.subtasks.iter().enumerate() {
-            let layer: Option<TaskLayer> = subtask.layer.as_ref().and_then(|l: &String| {
-                match l.to_lowercase().as_str() {
-                    "data" => Some(TaskLayer::Data),
-                    "backend" => Some(TaskLayer::Backend),
-                    "frontend" => Some(TaskLayer::Frontend),
-                    "fullstack" => Some(TaskLayer::Fullstack),
-                    "devops" => Some(TaskLayer::Devops),
-                    "testing" => Some(TaskLayer::Testing),
-                    _ => task.layer.clone(),
-                }
-            }).or_else(|| task.layer.clone());
+        let merge_result = self.merge_workspace_branch(
+            repo_path,
+            task_worktree_path,
+            &workspace.branch,
+            target_branch,
+            &commit_message,
+            settings.fast_forward_merges,
+            settings.cherry_pick_single_commits,
+            settings.sign_commits,
+            task.id,
+        );
 
-            let create_task = CreateTask::subtask_of(
-                task.project_id,
-                subtask.title.clone(),
-                Some(subtask.description.clone()),
-                layer,
-                task.task_type.clone(),
-                base_sequence * 10 + (i as i32) + 1,
-                task.testing_criteria.clone(),
-                None,
-                task.id,
-            );
+        match merge_result {
+            Ok(merge_commit) => {
+                info!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    merge_commit = %merge_commit,
+                    "Review automation: merge successful"
+                );
+                Ok(merge_commit)
+            }
+            Err(GitServiceError::BranchesDiverged(_)) => {
+                // Base branch has moved ahead - try to rebase and merge
+                info!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    branch = %workspace.branch,
+                    target_branch = %target_branch,
+                    "Review automation: base branch diverged, attempting rebase"
+                );
 
-            match Task::create(&self.db.pool, &create_task, Uuid::new_v4()).await {
-                Ok(new_task) => {
-                    info!(
-                        parent_task_id = %task.id,
-                        subtask_id = %new_task.id,
-                        subtask_title = %new_task.title,
-                        "Review automation: created subtask from conflicting task"
-                    );
-                    created_count += 1;
-                }
-                Err(e) => {
-                    warn!(
-                        parent_task_id = %task.id,
-                        error = %e,
-                        "Failed to create subtask"
-                    );
+                let new_head = self.rebase_onto_target(
+                    repo_path,
+                    task_worktree_path,
+                    &workspace.branch,
+                    target_branch,
+                )?;
+
+                info!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    new_head = %new_head,
+                    "Review automation: rebase successful, retrying merge"
+                );
+
+                // Retry the merge after successful rebase
+                match self.merge_workspace_branch(
+                    repo_path,
+                    task_worktree_path,
+                    &workspace.branch,
+                    target_branch,
+                    &commit_message,
+                    settings.fast_forward_merges,
+                    settings.cherry_pick_single_commits,
+                    settings.sign_commits,
+                    task.id,
+                ) {
+                    Ok(merge_commit) => {
+                        info!(
+                            workspace_id = %workspace.id,
+                            repo_id = %repo.id,
+                            merge_commit = %merge_commit,
+                            "Review automation: merge successful after rebase"
+                        );
+                        Ok(merge_commit)
+                    }
+                    Err(e) => Err(ReviewAutomationError::MergeConflict(format!(
+                        "Merge failed after rebase: {}",
+                        e
+                    ))),
                 }
             }
+            Err(GitServiceError::MergeConflicts(msg)) => {
+                Err(ReviewAutomationError::MergeConflict(msg))
+            }
+            Err(e) => Err(ReviewAutomationError::Git(e)),
         }
+    }
+
+    /// Measure how far the task branch and its target have diverged and pick the merge approach,
+    /// logging the numbers for conflict debugging. Branches that can't be compared are merged
+    /// directly, leaving divergence to the rebase-and-retry fallback.
+    fn plan_merge(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        target_branch: &str,
+        task_worktree_path: &Path,
+    ) -> MergeApproach {
+        let (ahead, behind) = match self.git_service.ahead_behind(
+            task_worktree_path,
+            &workspace.branch,
+            target_branch,
+        ) {
+            Ok(counts) => counts,
+            Err(e) => {
+                warn!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    error = %e,
+                    "Review automation: could not compare branch with target"
+                );
+                return MergeApproach::Direct;
+            }
+        };
+
+        let shared_files = if behind == 0 {
+            Vec::new()
+        } else {
+            self.shared_changed_files(task_worktree_path, target_branch)
+        };
+        let approach = merge_approach(behind, shared_files.len());
 
         info!(
-            task_id = %task.id,
-            created_count = created_count,
-            reasoning = %response.reasoning,
-            "Review automation: task broken down after merge conflicts"
+            workspace_id = %workspace.id,
+            repo_id = %repo.id,
+            branch = %workspace.branch,
+            target_branch = %target_branch,
+            ahead,
+            behind,
+            shared_files = shared_files.len(),
+            approach = ?approach,
+            "Review automation: branch divergence"
         );
+        if approach == MergeApproach::Diverged {
+            warn!(
+                workspace_id = %workspace.id,
+                repo_id = %repo.id,
+                ahead,
+                behind,
+                files = %shared_files.join(", "),
+                "Review automation: branch has diverged heavily from its target, rebase may conflict"
+            );
+        }
+        approach
+    }
 
-        Ok(created_count)
+    /// Files changed both on the task branch and on its target since the branch forked. Errors
+    /// are treated as no overlap, since the rebase reports any real conflicts.
+    fn shared_changed_files(&self, task_worktree_path: &Path, target_branch: &str) -> Vec<String> {
+        let changed = |files: Result<Vec<String>, GitServiceError>| {
+            files.unwrap_or_else(|e| {
+                debug!(error = %e, "Review automation: could not list changed files");
+                Vec::new()
+            })
+        };
+        let on_branch: HashSet<String> = changed(
+            self.git_service
+                .changed_files(task_worktree_path, target_branch),
+        )
+        .into_iter()
+        .collect();
+        changed(
+            self.git_service
+                .changed_files_on_target(task_worktree_path, target_branch),
+        )
+        .into_iter()
+        .filter(|file| on_branch.contains(file))
+        .collect()
+    }
+
+    /// Rebase the task branch onto the target from their fork point, aborting on failure.
+    /// Returns the branch's new head.
+    fn rebase_onto_target(
+        &self,
+        repo_path: &Path,
+        task_worktree_path: &Path,
+        branch: &str,
+        target_branch: &str,
+    ) -> Result<String, ReviewAutomationError> {
+        // Get the fork point (old base) for rebase
+        let fork_point = self
+            .git_service
+            .get_fork_point(task_worktree_path, target_branch, branch)
+            .map_err(|e| {
+                ReviewAutomationError::MergeConflict(format!(
+                    "Could not determine fork point for rebase: {}",
+                    e
+                ))
+            })?;
+
+        // Attempt rebase onto new base
+        match self.git_service.rebase_branch(
+            repo_path,
+            task_worktree_path,
+            target_branch,
+            &fork_point,
+            branch,
+        ) {
+            Ok(new_head) => Ok(new_head),
+            Err(GitServiceError::MergeConflicts(msg)) => {
+                // Rebase had conflicts - abort and report
+                let _ = self.git_service.abort_conflicts(task_worktree_path);
+                Err(ReviewAutomationError::MergeConflict(format!(
+                    "Automatic rebase failed due to conflicts. Manual intervention required. {}",
+                    msg
+                )))
+            }
+            Err(e) => {
+                // Rebase failed for other reasons - abort and report
+                let _ = self.git_service.abort_conflicts(task_worktree_path);
+                Err(ReviewAutomationError::MergeConflict(format!(
+                    "Automatic rebase failed: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// Restore the changes stashed before a merge, or discard them when the merge superseded
+    /// them. A failed restore leaves the stash in place and is logged and notified, since the
+    /// changes would otherwise be stranded without anyone knowing.
+    async fn restore_stash(
+        &self,
+        task: &Task,
+        workspace: &Workspace,
+        task_worktree_path: &Path,
+        discard: bool,
+    ) -> Result<(), ReviewAutomationError> {
+        let result = if discard {
+            self.git_service.stash_drop(task_worktree_path)
+        } else {
+            self.git_service.stash_pop(task_worktree_path)
+        };
+        let Err(e) = result else {
+            return Ok(());
+        };
+
+        warn!(
+            workspace_id = %workspace.id,
+            path = %task_worktree_path.display(),
+            error = %e,
+            "Review automation: failed to restore changes stashed before merge"
+        );
+        ReviewAutomationLog::create(
+            &self.db.pool,
+            task.id,
+            workspace.id,
+            ReviewAction::Error,
+            None,
+            Some(format!(
+                "Uncommitted changes stashed before merging could not be {} in {}; they remain in the stash: {}",
+                if discard { "discarded" } else { "restored" },
+                task_worktree_path.display(),
+                e
+            )),
+        )
+        .await?;
+        self.notification_service
+            .notify(
+                task.project_id,
+                "Review Automation",
+                &format!(
+                    "Uncommitted changes for '{}' could not be restored after merging and remain in git stash",
+                    task.title
+                ),
+            )
+            .await;
+        Ok(())
+    }
+
+    /// Append the task's `post_task_actions` to the worktree's .progress file (creating it if
+    /// absent) and commit it. The entry is appended to the target branch's copy of the file so
+    /// that entries from tasks merged in the meantime don't conflict with it. Returns false when
+    /// there is nothing to add, including when an earlier attempt already recorded the entry.
+    /// When `cherry_pick` is set and the branch has a single commit, the entry is folded into
+    /// that commit so the branch still qualifies for the cherry-pick.
+    fn append_progress_entry(
+        &self,
+        task: &Task,
+        task_worktree_path: &Path,
+        branch: &str,
+        target_branch: &str,
+        cherry_pick: bool,
+    ) -> Result<bool, GitServiceError> {
+        let Some(actions) = task.post_task_actions.as_deref() else {
+            return Ok(false);
+        };
+
+        let progress_path = task_worktree_path.join(PROGRESS_FILE);
+        let on_branch = match std::fs::read_to_string(&progress_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        if append_progress(&on_branch, task.id, actions).is_none() {
+            return Ok(false);
+        }
+        let on_target = self
+            .git_service
+            .read_file_at_branch(task_worktree_path, target_branch, Path::new(PROGRESS_FILE))?
+            .unwrap_or_default();
+        let Some(updated) = append_progress(&on_target, task.id, actions) else {
+            return Ok(false);
+        };
+
+        let single_commit = cherry_pick
+            && self
+                .git_service
+                .commit_count_between(task_worktree_path, target_branch, branch)?
+                == 1;
+        std::fs::write(&progress_path, updated)?;
+        if single_commit {
+            self.git_service
+                .amend_paths(task_worktree_path, &[PROGRESS_FILE])?;
+        } else {
+            self.git_service.commit_paths(
+                task_worktree_path,
+                &[PROGRESS_FILE],
+                &format!("Update {PROGRESS_FILE} for task: {}", task.title),
+            )?;
+        }
+        Ok(true)
+    }
+
+    /// Merge the workspace branch into the target branch.
+    /// When fast-forwards are allowed and the branch has not diverged, the target is moved to the
+    /// branch head instead of receiving a squash commit; the returned sha is recorded either way.
+    /// Fast-forwarded heads carry no merge message, so they get a `Task-Id` note instead, and
+    /// keep the signatures of the branch commits rather than gaining a new one.
+    /// Otherwise, when cherry-picks are allowed and the branch has exactly one commit, that commit
+    /// is cherry-picked onto the target to keep history linear; it is noted the same way. Signed
+    /// projects always squash merge, since cherry-picked commits can't be signed.
+    #[allow(clippy::too_many_arguments)]
+    fn merge_workspace_branch(
+        &self,
+        repo_path: &Path,
+        task_worktree_path: &Path,
+        branch: &str,
+        target_branch: &str,
+        commit_message: &str,
+        allow_fast_forward: bool,
+        allow_cherry_pick: bool,
+        sign_commits: bool,
+        task_id: Uuid,
+    ) -> Result<String, GitServiceError> {
+        if allow_fast_forward
+            && self
+                .git_service
+                .can_fast_forward(repo_path, branch, target_branch)?
+        {
+            debug!(
+                branch = %branch,
+                target_branch = %target_branch,
+                "Review automation: fast-forwarding target branch"
+            );
+            let sha = self
+                .git_service
+                .fast_forward_merge(repo_path, branch, target_branch)?;
+            if let Err(e) = self.git_service.add_task_id_note(repo_path, &sha, task_id) {
+                warn!(
+                    task_id = %task_id,
+                    commit = %sha,
+                    error = %e,
+                    "Review automation: failed to note task id on fast-forwarded commit"
+                );
+            }
+            return Ok(sha);
+        }
+
+        if allow_cherry_pick
+            && !sign_commits
+            && self
+                .git_service
+                .commit_count_between(task_worktree_path, target_branch, branch)?
+                == 1
+        {
+            debug!(
+                branch = %branch,
+                target_branch = %target_branch,
+                "Review automation: cherry-picking single commit onto target branch"
+            );
+            let sha = self.git_service.cherry_pick(
+                repo_path,
+                task_worktree_path,
+                branch,
+                target_branch,
+            )?;
+            if let Err(e) = self.git_service.add_task_id_note(repo_path, &sha, task_id) {
+                warn!(
+                    task_id = %task_id,
+                    commit = %sha,
+                    error = %e,
+                    "Review automation: failed to note task id on cherry-picked commit"
+                );
+            }
+            return Ok(sha);
+        }
+
+        self.git_service.merge_changes_with_signing(
+            repo_path,
+            task_worktree_path,
+            branch,
+            target_branch,
+            commit_message,
+            sign_commits,
+        )
+    }
+
+    /// Whether any of the workspace's repos has commits ahead of its target branch.
+    /// Repos without a worktree on disk are treated as having changes so the merge step decides.
+    async fn workspace_has_commits(
+        &self,
+        workspace: &Workspace,
+        workspace_path: &str,
+    ) -> Result<bool, ReviewAutomationError> {
+        let workspace_repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&self.db.pool, workspace.id)
+                .await?;
+        if workspace_repos.is_empty() {
+            return Ok(true);
+        }
+
+        for repo_with_branch in &workspace_repos {
+            let task_worktree_path = Path::new(workspace_path).join(&repo_with_branch.repo.name);
+            if !task_worktree_path.exists()
+                || self.git_service.branch_has_commits_ahead(
+                    &task_worktree_path,
+                    &workspace.branch,
+                    &repo_with_branch.target_branch,
+                )?
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Ask the AI whether the workspace's changes and test output meet the task's testing criteria
+    async fn check_testing_criteria(
+        &self,
+        task: &Task,
+        workspace: &Workspace,
+        workspace_path: &str,
+        testing_criteria: &str,
+        test_output: Option<&str>,
+    ) -> Result<CriteriaCheckResponse, ReviewAutomationError> {
+        let claude = ClaudeApiClient::from_env()?;
+
+        let workspace_repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&self.db.pool, workspace.id)
+                .await?;
+        let mut changed_files = Vec::new();
+        for repo_with_branch in &workspace_repos {
+            let task_worktree_path = Path::new(workspace_path).join(&repo_with_branch.repo.name);
+            if !task_worktree_path.exists() {
+                continue;
+            }
+            let files = self
+                .git_service
+                .changed_files(&task_worktree_path, &repo_with_branch.target_branch)?;
+            changed_files.extend(
+                files
+                    .into_iter()
+                    .map(|file| format!("- {}/{file}", repo_with_branch.repo.name)),
+            );
+        }
+        let changed_files = if changed_files.is_empty() {
+            "(no changed files found)".to_string()
+        } else {
+            changed_files.join("\n")
+        };
+        let test_output = match test_output {
+            Some(output) => {
+                let skipped = output
+                    .chars()
+                    .count()
+                    .saturating_sub(TEST_OUTPUT_PREVIEW_CHARS);
+                output.chars().skip(skipped).collect()
+            }
+            None => "(no tests were run)".to_string(),
+        };
+
+        let template =
+            prompts::active(&self.db.pool, task.project_id, TemplateName::CriteriaCheck).await?;
+        let prompt = template.render(&[
+            ("title", &task.title),
+            (
+                "description",
+                task.description.as_deref().unwrap_or("(no description)"),
+            ),
+            ("testing_criteria", testing_criteria),
+            ("changed_files", &changed_files),
+            ("test_output", &test_output),
+        ]);
+        let system =
+            Some(prompts::system_prompt(&self.db.pool, task.project_id, template.system).await?);
+        info!(
+            task_id = %task.id,
+            prompt_version = %template.label(),
+            "Review automation: checking testing criteria"
+        );
+
+        Ok(claude
+            .ask_json::<CriteriaCheckResponse>(ModelRole::CriteriaCheck, &prompt, system)
+            .await?)
+    }
+
+    /// Once the last subtask of `parent_task_id` finishes, record the parent's completion and
+    /// move it (typically cancelled when it was broken down) to Done.
+    async fn rollup_parent_completion(
+        &self,
+        task: &Task,
+        parent_task_id: Uuid,
+    ) -> Result<(), ReviewAutomationError> {
+        let pool = &self.db.pool;
+        let Some(parent) = Task::find_by_id(pool, parent_task_id).await? else {
+            return Ok(());
+        };
+        if parent.status == TaskStatus::Done {
+            return Ok(());
+        }
+
+        let subtasks = Task::find_subtasks(pool, parent_task_id).await?;
+        if !subtasks_complete(subtasks.iter().map(|subtask| &subtask.status)) {
+            return Ok(());
+        }
+
+        // The parent was typically cancelled when it was broken down, so this deliberately
+        // bypasses transition validation
+        Task::update_status_force(
+            pool,
+            parent.id,
+            TaskStatus::Done,
+            Some(STATUS_ACTOR),
+            Some("All subtasks done"),
+        )
+        .await?;
+        AgentActivityLog::create(
+            pool,
+            parent.project_id,
+            Some(parent.id),
+            AgentAction::ParentCompleted,
+            Some(format!(
+                "All {} subtasks finished; last completed: {}",
+                subtasks.len(),
+                task.title
+            )),
+        )
+        .await?;
+
+        info!(
+            task_id = %parent.id,
+            subtask_count = subtasks.len(),
+            "Review automation: parent task completed by its subtasks"
+        );
+
+        self.notification_service
+            .notify(
+                parent.project_id,
+                "Review Automation",
+                &format!("Task completed via subtasks: {}", parent.title),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    /// Files that would conflict when merging each of the workspace's repos into its target
+    /// branch (best-effort). Paths are prefixed with the repo name in multi-repo workspaces.
+    async fn workspace_conflicted_files(
+        &self,
+        workspace: &Workspace,
+        workspace_path: &str,
+    ) -> Vec<String> {
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&self.db.pool, workspace.id)
+                .await
+                .unwrap_or_default();
+        let multi_repo = repos.len() > 1;
+
+        let mut files = Vec::new();
+        for repo_with_branch in &repos {
+            let repo = &repo_with_branch.repo;
+            let task_worktree_path = Path::new(workspace_path).join(&repo.name);
+            if !task_worktree_path.exists() {
+                continue;
+            }
+            match self.git_service.would_conflict(
+                &task_worktree_path,
+                &workspace.branch,
+                &repo_with_branch.target_branch,
+            ) {
+                Ok(Some(conflicted)) => files.extend(conflicted.into_iter().map(|file| {
+                    if multi_repo {
+                        format!("{}/{file}", repo.name)
+                    } else {
+                        file
+                    }
+                })),
+                Ok(None) => {}
+                Err(e) => debug!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    error = %e,
+                    "Review automation: could not determine conflicted files"
+                ),
+            }
+        }
+        files
+    }
+
+    /// Structured summary of a task's repeated conflicts for the breakdown prompt: the files
+    /// that conflicted across all logged attempts (most frequent first) and the diff hunks on
+    /// both sides for those files.
+    async fn conflict_summary(
+        &self,
+        task: &Task,
+        workspace: &Workspace,
+        workspace_path: &str,
+    ) -> Result<String, ReviewAutomationError> {
+        let logs = ReviewAutomationLog::find_by_task_id(&self.db.pool, task.id).await?;
+        let ranked = rank_conflicted_files(
+            logs.iter()
+                .filter(|log| log.action == ReviewAction::MergeConflict)
+                .filter_map(|log| log.output.as_deref()),
+        );
+        if ranked.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut summary = String::from("## Conflicting Files (times conflicted)\n");
+        for (file, count) in &ranked {
+            summary.push_str(&format!("- {file} ({count})\n"));
+        }
+
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&self.db.pool, workspace.id)
+                .await?;
+        let multi_repo = repos.len() > 1;
+        for repo_with_branch in &repos {
+            let repo = &repo_with_branch.repo;
+            let task_worktree_path = Path::new(workspace_path).join(&repo.name);
+            let prefix = format!("{}/", repo.name);
+            let paths: Vec<String> = ranked
+                .iter()
+                .filter_map(|(file, _)| {
+                    if multi_repo {
+                        file.strip_prefix(&prefix).map(str::to_string)
+                    } else {
+                        Some(file.clone())
+                    }
+                })
+                .collect();
+            if paths.is_empty() || !task_worktree_path.exists() {
+                continue;
+            }
+
+            match self.git_service.conflict_hunks(
+                &task_worktree_path,
+                &workspace.branch,
+                &repo_with_branch.target_branch,
+                &paths,
+                CONFLICT_HUNKS_MAX_BYTES,
+            ) {
+                Ok(hunks) => {
+                    summary.push_str(&format!("\n## Conflicting Hunks ({})\n{hunks}", repo.name))
+                }
+                Err(e) => debug!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    error = %e,
+                    "Review automation: could not collect conflicting hunks"
+                ),
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Remove the git worktrees of a workspace that will not be worked on again.
+    /// Failures are logged; the periodic archived-workspace sweep retries them.
+    async fn remove_workspace_worktrees(&self, workspace: &Workspace, workspace_path: &str) {
+        let repos = match WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await {
+            Ok(repos) => repos,
+            Err(e) => {
+                warn!(
+                    workspace_id = %workspace.id,
+                    error = %e,
+                    "Review automation: could not load repos to remove worktrees"
+                );
+                return;
+            }
+        };
+
+        for repo in repos {
+            let worktree_path = Path::new(workspace_path).join(&repo.name);
+            if let Err(e) = self
+                .git_service
+                .remove_worktree(&repo.path, &worktree_path, true)
+            {
+                warn!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo.id,
+                    error = %e,
+                    "Review automation: failed to remove worktree"
+                );
+            }
+        }
+    }
+
+    /// Fetch the target branch from the remote before merging.
+    /// Failures are logged and the merge proceeds against local state, so offline repos still merge.
+    fn fetch_target_branch(
+        &self,
+        workspace: &Workspace,
+        repo_id: Uuid,
+        repo_path: &Path,
+        target_branch: &str,
+    ) {
+        match self
+            .git_service
+            .fetch_and_fast_forward_branch(repo_path, target_branch)
+        {
+            Ok(true) => {
+                debug!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo_id,
+                    target_branch = %target_branch,
+                    "Review automation: fetched target branch before merge"
+                );
+            }
+            Ok(false) => {
+                info!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo_id,
+                    "Review automation: repo has no remote, skipping fetch before merge"
+                );
+            }
+            Err(e) => {
+                warn!(
+                    workspace_id = %workspace.id,
+                    repo_id = %repo_id,
+                    target_branch = %target_branch,
+                    error = %e,
+                    "Review automation: fetch before merge failed, using local target branch"
+                );
+            }
+        }
+    }
+
+    /// Get the current review automation status for a project
+    pub async fn get_status(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ReviewAutomationStatus, ReviewAutomationError> {
+        let settings = ProjectReviewSettings::find_by_project_id(pool, project_id).await?;
+        let latest_log = ReviewAutomationLog::find_latest_by_project_id(pool, project_id).await?;
+
+        Ok(ReviewAutomationStatus {
+            enabled: settings.as_ref().is_some_and(|s| s.enabled),
+            auto_merge_enabled: settings.as_ref().is_some_and(|s| s.auto_merge_enabled),
+            run_tests_enabled: settings.as_ref().is_some_and(|s| s.run_tests_enabled),
+            fetch_before_merge: settings.as_ref().is_some_and(|s| s.fetch_before_merge),
+            store_test_artifacts: settings.as_ref().is_some_and(|s| s.store_test_artifacts),
+            fast_forward_merges: settings.as_ref().is_some_and(|s| s.fast_forward_merges),
+            conventional_commits: settings.as_ref().is_some_and(|s| s.conventional_commits),
+            sign_commits: settings.as_ref().is_some_and(|s| s.sign_commits),
+            complete_parent_on_subtasks_done: settings
+                .as_ref()
+                .is_some_and(|s| s.complete_parent_on_subtasks_done),
+            require_successful_attempt: settings
+                .as_ref()
+                .is_some_and(|s| s.require_successful_attempt),
+            auto_archive_on_merge: settings.as_ref().is_some_and(|s| s.auto_archive_on_merge),
+            progress_repo: settings.as_ref().and_then(|s| s.progress_repo.clone()),
+            test_targets: settings.as_ref().and_then(|s| s.test_targets.clone()),
+            pre_merge_command: settings.as_ref().and_then(|s| s.pre_merge_command.clone()),
+            stash_before_merge: settings.as_ref().is_some_and(|s| s.stash_before_merge),
+            drop_stash_after_merge: settings.as_ref().is_some_and(|s| s.drop_stash_after_merge),
+            require_tests_before_merge: settings
+                .as_ref()
+                .is_some_and(|s| s.require_tests_before_merge),
+            cherry_pick_single_commits: settings
+                .as_ref()
+                .is_some_and(|s| s.cherry_pick_single_commits),
+            enforce_testing_criteria: settings
+                .as_ref()
+                .is_some_and(|s| s.enforce_testing_criteria),
+            allow_partial_merge: settings.as_ref().is_some_and(|s| s.allow_partial_merge),
+            auto_merge_target_allowlist: settings
+                .as_ref()
+                .and_then(|s| s.auto_merge_target_allowlist.clone()),
+            test_profiles: settings.as_ref().and_then(|s| s.test_profiles.clone()),
+            last_action: latest_log.as_ref().map(|l| l.action.clone()),
+            last_task_id: latest_log.map(|l| l.task_id),
+        })
+    }
+
+    /// Predict, without merging, which of a workspace's repos would conflict with their target branch
+    pub async fn merge_preview(
+        pool: &SqlitePool,
+        git_service: &GitService,
+        workspace: &Workspace,
+    ) -> Result<Vec<RepoMergePreview>, ReviewAutomationError> {
+        let container_ref = workspace
+            .container_ref
+            .as_ref()
+            .ok_or(ReviewAutomationError::NoWorkspaceContainer)?;
+        let workspace_path = WorkspaceLocation::parse(container_ref).path();
+        let workspace_repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+
+        let mut previews = Vec::new();
+        for repo_with_branch in workspace_repos {
+            let repo = repo_with_branch.repo;
+            let task_worktree_path = Path::new(workspace_path).join(&repo.name);
+            if !task_worktree_path.exists() {
+                continue;
+            }
+
+            let conflicted_files = git_service.would_conflict(
+                &task_worktree_path,
+                &workspace.branch,
+                &repo_with_branch.target_branch,
+            )?;
+            previews.push(RepoMergePreview {
+                repo_id: repo.id,
+                repo_name: repo.name,
+                target_branch: repo_with_branch.target_branch,
+                conflicted_files,
+            });
+        }
+
+        Ok(previews)
+    }
+
+    /// Enable review automation for a project
+    pub async fn enable(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ProjectReviewSettings, ReviewAutomationError> {
+        Ok(ProjectReviewSettings::set_enabled(pool, project_id, true).await?)
+    }
+
+    /// Update review automation toggles for a project. Test and pre-merge commands must pass
+    /// `command_policy`. Returns the saved settings and warnings about risky combinations.
+    pub async fn update_settings(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        update: &UpdateReviewAutomationSettings,
+        command_policy: &CommandPolicy,
+    ) -> Result<(ProjectReviewSettings, Vec<String>), ReviewAutomationError> {
+        if let Some(test_targets) = update.test_targets.as_deref() {
+            let targets = parse_test_targets(test_targets).map_err(|e| {
+                ReviewAutomationError::InvalidSettings(format!("test_targets {e}"))
+            })?;
+            for target in &targets {
+                command_policy.check(&target.command).map_err(|e| {
+                    ReviewAutomationError::InvalidSettings(format!("test_targets: {e}"))
+                })?;
+            }
+        }
+        if let Some(test_profiles) = update.test_profiles.as_deref() {
+            let profiles = parse_test_profiles(test_profiles).map_err(|e| {
+                ReviewAutomationError::InvalidSettings(format!("test_profiles {e}"))
+            })?;
+            for profile in &profiles {
+                command_policy.check(&profile.command).map_err(|e| {
+                    ReviewAutomationError::InvalidSettings(format!("test_profiles: {e}"))
+                })?;
+            }
+        }
+        if let Some(allowlist) = update.auto_merge_target_allowlist.as_deref() {
+            parse_target_allowlist(allowlist).map_err(|e| {
+                ReviewAutomationError::InvalidSettings(format!("auto_merge_target_allowlist: {e}"))
+            })?;
+        }
+        if let Some(pre_merge_command) = update.pre_merge_command.as_deref()
+            && !pre_merge_command.trim().is_empty()
+        {
+            command_policy.check(pre_merge_command).map_err(|e| {
+                ReviewAutomationError::InvalidSettings(format!("pre_merge_command: {e}"))
+            })?;
+        }
+
+        let require_tests_before_merge = match update.require_tests_before_merge {
+            Some(require) => require,
+            None => ProjectReviewSettings::find_by_project_id(pool, project_id)
+                .await?
+                .is_some_and(|s| s.require_tests_before_merge),
+        };
+        if require_tests_before_merge && !update.run_tests_enabled {
+            return Err(ReviewAutomationError::InvalidSettings(
+                "require_tests_before_merge needs run_tests_enabled".to_string(),
+            ));
+        }
+
+        let settings = ProjectReviewSettings::update(pool, project_id, update).await?;
+
+        let mut warnings = Vec::new();
+        if settings.auto_merge_enabled && !settings.run_tests_enabled {
+            warnings.push(
+                "Auto-merge is enabled without running tests; changes will be merged unverified"
+                    .to_string(),
+            );
+        }
+        if settings.enforce_testing_criteria && std::env::var("ANTHROPIC_API_KEY").is_err() {
+            warnings.push(
+                "Testing criteria are enforced but ANTHROPIC_API_KEY is not set; tasks with \
+                 testing criteria will not be auto-merged"
+                    .to_string(),
+            );
+        }
+
+        Ok((settings, warnings))
+    }
+
+    /// Disable review automation for a project
+    pub async fn disable(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ProjectReviewSettings, ReviewAutomationError> {
+        Ok(ProjectReviewSettings::set_enabled(pool, project_id, false).await?)
+    }
+
+    /// Get review automation logs for a project
+    pub async fn get_logs(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        limit: i32,
+    ) -> Result<Vec<ReviewAutomationLog>, ReviewAutomationError> {
+        Ok(ReviewAutomationLog::find_by_project_id(pool, project_id, limit).await?)
+    }
+
+    /// Get review automation logs for a specific task
+    pub async fn get_logs_by_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<ReviewAutomationLog>, ReviewAutomationError> {
+        Ok(ReviewAutomationLog::find_by_task_id(pool, task_id).await?)
+    }
+
+    /// Give a task a fresh merge conflict budget after its conflicts were resolved by hand
+    pub async fn reset_conflict_count(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<ReviewAutomationLog>, ReviewAutomationError> {
+        Ok(ReviewAutomationLog::reset_conflict_count(pool, task_id).await?)
+    }
+
+    /// Revert a task's direct merges on their target branches, newest first, logging
+    /// `MergeReverted` for each, and move the task back to Todo, unarchiving it if auto-archive
+    /// hid it after the merge. Merges already reverted are
+    /// skipped; it's an error if none are left. Fast-forwarded merges are reverted back to the
+    /// target head recorded before them; PR merges are left to the git host.
+    pub async fn revert_task_merges(
+        pool: &SqlitePool,
+        git_service: &GitService,
+        task_id: Uuid,
+    ) -> Result<Vec<ReviewAutomationLog>, ReviewAutomationError> {
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(TaskError::TaskNotFound)?;
+
+        let mut logs = Vec::new();
+        for merge in Merge::find_by_task_id(pool, task.id).await? {
+            let Merge::Direct(merge) = merge else {
+                continue;
+            };
+            let Some(repo) = Repo::find_by_id(pool, merge.repo_id).await? else {
+                warn!(
+                    task_id = %task.id,
+                    repo_id = %merge.repo_id,
+                    "Review automation: repo of merge to revert no longer exists, skipping"
+                );
+                continue;
+            };
+
+            let revert_commit = match git_service.revert_merge(
+                Path::new(&repo.path),
+                &merge.merge_commit,
+                merge.base_commit.as_deref(),
+                &merge.target_branch_name,
+            ) {
+                Ok(revert_commit) => revert_commit,
+                Err(GitServiceError::AlreadyReverted(_)) => {
+                    debug!(
+                        task_id = %task.id,
+                        merge_commit = %merge.merge_commit,
+                        "Review automation: merge already reverted, skipping"
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            info!(
+                task_id = %task.id,
+                repo_id = %repo.id,
+                merge_commit = %merge.merge_commit,
+                revert_commit = %revert_commit,
+                "Review automation: reverted merge"
+            );
+            logs.push(
+                ReviewAutomationLog::create(
+                    pool,
+                    task.id,
+                    merge.workspace_id,
+                    ReviewAction::MergeReverted,
+                    Some(format!(
+                        "Reverted {} on {} in {} with {}",
+                        merge.merge_commit, merge.target_branch_name, repo.name, revert_commit
+                    )),
+                    None,
+                )
+                .await?,
+            );
+        }
+
+        if logs.is_empty() {
+            return Err(ReviewAutomationError::NothingToRevert(format!(
+                "task {} has no direct merges left to revert",
+                task.id
+            )));
+        }
+
+        Task::update_status(
+            pool,
+            task.id,
+            TaskStatus::Todo,
+            Some("user"),
+            Some("Merge reverted"),
+        )
+        .await?;
+        // Archived tasks are off the board and never selected, so the rework would be lost
+        Task::set_archived(pool, task.id, false).await?;
+        Ok(logs)
+    }
+
+    /// Get the full output of a review log, reading it from its artifact file if it has one.
+    /// Falls back to the stored output when the log has no artifact.
+    pub async fn get_log_artifact(
+        pool: &SqlitePool,
+        log_id: Uuid,
+    ) -> Result<Option<String>, ReviewAutomationError> {
+        let log = ReviewAutomationLog::find_by_id(pool, log_id)
+            .await?
+            .ok_or_else(|| ReviewAutomationError::NotFound(format!("review log {log_id}")))?;
+        let Some(path) = log.artifact_path else {
+            return Ok(log.output);
+        };
+        match tokio::fs::read_to_string(&path).await {
+            Ok(output) => Ok(Some(output)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(
+                ReviewAutomationError::NotFound(format!("artifact file {path}")),
+            ),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Break down a task that has failed to merge too many times into simpler subtasks
+    async fn breakdown_conflicting_task(
+        &self,
+        task: &Task,
+        conflict_details: &str,
+        conflict_summary: &str,
+    ) -> Result<usize, ReviewAutomationError> {
+        let claude = ClaudeApiClient::from_env()?;
+
+        let template =
+            prompts::active(&self.db.pool, task.project_id, TemplateName::ConflictBreakdown)
+                .await?;
+        let layer = task
+            .layer
+            .as_ref()
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "unspecified".to_string());
+        let task_type = task
+            .task_type
+            .as_ref()
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "implementation".to_string());
+        let prompt = template.render(&[
+            ("max_attempts", &MAX_MERGE_CONFLICT_ATTEMPTS.to_string()),
+            ("title", &task.title),
+            (
+                "description",
+                task.description.as_deref().unwrap_or("(no description)"),
+            ),
+            ("layer", &layer),
+            ("task_type", &task_type),
+            ("conflict_details", conflict_details),
+            ("conflict_summary", conflict_summary),
+        ]);
+        let system =
+            Some(prompts::system_prompt(&self.db.pool, task.project_id, template.system).await?);
+        info!(
+            task_id = %task.id,
+            prompt_version = %template.label(),
+            "Review automation: breaking down conflicting task"
+        );
+
+        let response: ConflictBreakdownResponse = claude
+            .ask_json::<ConflictBreakdownResponse>(ModelRole::ConflictBreakdown, &prompt, system)
+            .await?;
+
+        if response.subtasks.is_empty() || response.subtasks.len() < 2 {
+            return Err(ReviewAutomationError::CommandFailed(
+                "AI did not suggest enough subtasks for breakdown".to_string(),
+            ));
+        }
+
+        let mut created_count = 0;
+        let base_sequence = task.sequence.unwrap_or(1);
+
+        for (i, subtask) in response.subtasks.iter().enumerate() {
+            let layer: Option<TaskLayer> = subtask.layer.as_ref().and_then(|l: &String| {
+                match l.to_lowercase().as_str() {
+                    "data" => Some(TaskLayer::Data),
+                    "backend" => Some(TaskLayer::Backend),
+                    "frontend" => Some(TaskLayer::Frontend),
+                    "fullstack" => Some(TaskLayer::Fullstack),
+                    "devops" => Some(TaskLayer::Devops),
+                    "testing" => Some(TaskLayer::Testing),
+                    _ => task.layer.clone(),
+                }
+            }).or_else(|| task.layer.clone());
+
+            let create_task = CreateTask::subtask_of(
+                task.project_id,
+                subtask.title.clone(),
+                Some(subtask.description.clone()),
+                layer,
+                task.task_type.clone(),
+                base_sequence * 10 + (i as i32) + 1,
+                task.testing_criteria.clone(),
+                None,
+                task.id,
+            );
+
+            match Task::create(&self.db.pool, &create_task, Uuid::new_v4()).await {
+                Ok(new_task) => {
+                    info!(
+                        parent_task_id = %task.id,
+                        subtask_id = %new_task.id,
+                        subtask_title = %new_task.title,
+                        "Review automation: created subtask from conflicting task"
+                    );
+                    created_count += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        parent_task_id = %task.id,
+                        error = %e,
+                        "Failed to create subtask"
+                    );
+                }
+            }
+        }
+
+        info!(
+            task_id = %task.id,
+            created_count = created_count,
+            reasoning = %response.reasoning,
+            "Review automation: task broken down after merge conflicts"
+        );
+
+        Ok(created_count)
+    }
+}
+
+/// Run a test command in `dir` with `runner`, returning whether it succeeded and its combined
+/// output
+async fn run_test_command(
+    runner: &dyn TestRunner,
+    program: &str,
+    args: &[&str],
+    dir: &Path,
+) -> Result<(bool, String), ReviewAutomationError> {
+    runner
+        .run(program, args, dir)
+        .await
+        .map_err(|e| ReviewAutomationError::CommandFailed(e.to_string()))
+}
+
+/// Append a task's progress notes to `.progress` content, marked with the task id so retries
+/// don't duplicate them. Returns None when there is nothing to append.
+fn append_progress(existing: &str, task_id: Uuid, actions: &str) -> Option<String> {
+    let actions = actions.trim();
+    let marker = format!("<!-- task: {task_id} -->");
+    if actions.is_empty() || existing.contains(&marker) {
+        return None;
+    }
+
+    let mut content = existing.trim_end().to_string();
+    if !content.is_empty() {
+        content.push_str("\n\n");
+    }
+    content.push_str(&format!("{marker}\n{actions}\n"));
+    Some(content)
+}
+
+/// Format a conflicted file list for a merge conflict log's output
+fn format_conflicted_files(files: &[String]) -> String {
+    let mut out = String::from(CONFLICTED_FILES_HEADER);
+    for file in files {
+        out.push_str("\n- ");
+        out.push_str(file);
+    }
+    out
+}
+
+/// Format the unmet criteria and the AI's reasoning for a criteria check log's output
+fn unmet_criteria_report(response: &CriteriaCheckResponse) -> String {
+    let mut out = String::from("Unmet testing criteria:");
+    for criterion in &response.unmet_criteria {
+        out.push_str("\n- ");
+        out.push_str(criterion);
+    }
+    out.push_str("\n\n");
+    out.push_str(&response.reasoning);
+    out
+}
+
+/// Count how often each file conflicted across merge conflict log outputs, most frequent first
+fn rank_conflicted_files<'a>(outputs: impl IntoIterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for output in outputs {
+        let Some(list) = output.strip_prefix(CONFLICTED_FILES_HEADER) else {
+            continue;
+        };
+        for file in list.lines().filter_map(|line| line.strip_prefix("- ")) {
+            match counts.iter_mut().find(|(existing, _)| existing == file) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((file.to_string(), 1)),
+            }
+        }
+    }
+    // Stable sort keeps first-seen order among equally frequent files
+    counts.sort_by(|a, b| b.1.cmp(&a.1));
+    counts
+}
+
+/// Whether a parent's subtasks are all finished: every one is Done or Cancelled, and at least
+/// one is Done (a parent whose subtasks were all cancelled has not had its work done).
+fn subtasks_complete<'a>(statuses: impl IntoIterator<Item = &'a TaskStatus>) -> bool {
+    let mut any_done = false;
+    for status in statuses {
+        match status {
+            TaskStatus::Done => any_done = true,
+            TaskStatus::Cancelled => {}
+            _ => return false,
+        }
+    }
+    any_done
+}
+
+/// Build the auto-merge commit message. With `conventional` set, the subject follows
+/// Conventional Commits (`feat(backend): <title> (#<task>)`), with type and scope derived
+/// from the task's type and layer; otherwise the plain "Merge X into Y" format is used.
+fn merge_commit_message(
+    task: &Task,
+    branch: &str,
+    target_branch: &str,
+    conventional: bool,
+) -> String {
+    if !conventional {
+        return format!("Merge {} into {}\n\nTask: {}", branch, target_branch, task.title);
+    }
+
+    let commit_type = match (&task.layer, &task.task_type) {
+        (Some(TaskLayer::Testing), _) | (_, Some(TaskType::Testing)) => "test",
+        (Some(TaskLayer::Devops), _) => "ci",
+        (_, Some(TaskType::Architecture)) => "chore",
+        _ => "feat",
+    };
+    let scope = task
+        .layer
+        .as_ref()
+        .filter(|layer| **layer != TaskLayer::Fullstack)
+        .map(|layer| format!("({})", layer.to_string().to_lowercase()))
+        .unwrap_or_default();
+    let title = task.title.lines().next().unwrap_or_default().trim();
+    let short_id = task.id.to_string();
+    let short_id = short_id.split('-').next().unwrap_or_default();
+
+    format!(
+        "{commit_type}{scope}: {title} (#{short_id})\n\nMerge {branch} into {target_branch}"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
+    use db::models::{
+        execution_process::{
+            CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
+        },
+        repo::Repo,
+        session::{CreateSession, Session},
+        workspace::CreateWorkspace,
+        workspace_repo::CreateWorkspaceRepo,
+    };
+    use executors::actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    };
+    use tempfile::TempDir;
+    use tokio::sync::RwLock;
+
+    use super::*;
+    use crate::services::{config::Config, test_support};
+
+    /// Scripted result of one `merge_changes_with_signing` call
+    enum MergeOutcome {
+        Merged,
+        Diverged,
+        Conflict,
+    }
+
+    /// Git double that replays scripted merge outcomes and records the operations performed
+    #[derive(Default)]
+    struct MockGit {
+        merges: Mutex<VecDeque<MergeOutcome>>,
+        calls: Mutex<Vec<&'static str>>,
+        fail_stash_pop: bool,
+        commit_count: usize,
+        // Commits added to the branch by `commit_paths`, on top of `commit_count`
+        added_commits: Mutex<usize>,
+        behind: usize,
+        branch_changed: Vec<String>,
+        target_changed: Vec<String>,
+    }
+
+    impl MockGit {
+        fn with_merges(merges: impl IntoIterator<Item = MergeOutcome>) -> Self {
+            Self {
+                merges: Mutex::new(merges.into_iter().collect()),
+                ..Default::default()
+            }
+        }
+
+        fn record(&self, call: &'static str) {
+            self.calls.lock().unwrap().push(call);
+        }
+
+        fn calls(&self) -> Vec<&'static str> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl GitOps for MockGit {
+        fn merge_changes_with_signing(
+            &self,
+            _base_worktree_path: &Path,
+            _task_worktree_path: &Path,
+            _task_branch_name: &str,
+            _base_branch_name: &str,
+            _commit_message: &str,
+            _sign_commits: bool,
+        ) -> Result<String, GitServiceError> {
+            self.record("merge");
+            match self.merges.lock().unwrap().pop_front() {
+                Some(MergeOutcome::Merged) => Ok("merged-sha".to_string()),
+                Some(MergeOutcome::Diverged) => Err(GitServiceError::BranchesDiverged(
+                    "target branch moved ahead".to_string(),
+                )),
+                Some(MergeOutcome::Conflict) | None => Err(GitServiceError::MergeConflicts(
+                    "conflict in src/lib.rs".to_string(),
+                )),
+            }
+        }
+
+        fn can_fast_forward(
+            &self,
+            _worktree_path: &Path,
+            _branch_name: &str,
+            _target_branch: &str,
+        ) -> Result<bool, GitServiceError> {
+            Ok(false)
+        }
+
+        fn fast_forward_merge(
+            &self,
+            _base_worktree_path: &Path,
+            _task_branch_name: &str,
+            _base_branch_name: &str,
+        ) -> Result<String, GitServiceError> {
+            self.record("fast_forward");
+            Ok("fast-forward-sha".to_string())
+        }
+
+        fn commit_count_between(
+            &self,
+            _worktree_path: &Path,
+            _target_branch: &str,
+            _branch_name: &str,
+        ) -> Result<usize, GitServiceError> {
+            Ok(self.commit_count + *self.added_commits.lock().unwrap())
+        }
+
+        fn cherry_pick(
+            &self,
+            _repo_path: &Path,
+            _worktree_path: &Path,
+            _commit: &str,
+            _target_branch: &str,
+        ) -> Result<String, GitServiceError> {
+            self.record("cherry_pick");
+            Ok("cherry-picked-sha".to_string())
+        }
+
+        fn add_task_id_note(
+            &self,
+            _repo_path: &Path,
+            _commit_sha: &str,
+            _task_id: Uuid,
+        ) -> Result<(), GitServiceError> {
+            Ok(())
+        }
+
+        fn get_fork_point(
+            &self,
+            _worktree_path: &Path,
+            _target_branch: &str,
+            _task_branch: &str,
+        ) -> Result<String, GitServiceError> {
+            self.record("fork_point");
+            Ok("fork-point-sha".to_string())
+        }
+
+        fn rebase_branch(
+            &self,
+            _repo_path: &Path,
+            _worktree_path: &Path,
+            _new_base_branch: &str,
+            _old_base_branch: &str,
+            _task_branch: &str,
+        ) -> Result<String, GitServiceError> {
+            self.record("rebase");
+            Ok("rebased-sha".to_string())
+        }
+
+        fn abort_conflicts(&self, _worktree_path: &Path) -> Result<(), GitServiceError> {
+            self.record("abort_conflicts");
+            Ok(())
+        }
+
+        fn branch_has_commits_ahead(
+            &self,
+            _worktree_path: &Path,
+            _branch_name: &str,
+            _target_branch: &str,
+        ) -> Result<bool, GitServiceError> {
+            Ok(true)
+        }
+
+        fn ahead_behind(
+            &self,
+            _worktree_path: &Path,
+            _branch_name: &str,
+            _target_branch: &str,
+        ) -> Result<(usize, usize), GitServiceError> {
+            Ok((1, self.behind))
+        }
+
+        fn would_conflict(
+            &self,
+            _worktree_path: &Path,
+            _branch_name: &str,
+            _target_branch: &str,
+        ) -> Result<Option<Vec<String>>, GitServiceError> {
+            Ok(Some(vec!["src/lib.rs".to_string()]))
+        }
+
+        fn conflict_hunks(
+            &self,
+            _worktree_path: &Path,
+            _branch_name: &str,
+            _target_branch: &str,
+            _paths: &[String],
+            _max_bytes: usize,
+        ) -> Result<String, GitServiceError> {
+            Ok(String::new())
+        }
+
+        fn read_file_at_branch(
+            &self,
+            _repo_path: &Path,
+            _branch_name: &str,
+            _file_path: &Path,
+        ) -> Result<Option<String>, GitServiceError> {
+            Ok(None)
+        }
+
+        fn changed_files(
+            &self,
+            _worktree_path: &Path,
+            _target_branch: &str,
+        ) -> Result<Vec<String>, GitServiceError> {
+            Ok(self.branch_changed.clone())
+        }
+
+        fn changed_files_on_target(
+            &self,
+            _worktree_path: &Path,
+            _target_branch: &str,
+        ) -> Result<Vec<String>, GitServiceError> {
+            Ok(self.target_changed.clone())
+        }
+
+        fn commit_paths(
+            &self,
+            _path: &Path,
+            _paths: &[&str],
+            _message: &str,
+        ) -> Result<(), GitServiceError> {
+            self.record("commit_paths");
+            *self.added_commits.lock().unwrap() += 1;
+            Ok(())
+        }
+
+        fn amend_paths(&self, _path: &Path, _paths: &[&str]) -> Result<(), GitServiceError> {
+            self.record("amend_paths");
+            Ok(())
+        }
+
+        fn fetch_and_fast_forward_branch(
+            &self,
+            _repo_path: &Path,
+            _branch_name: &str,
+        ) -> Result<bool, GitServiceError> {
+            Ok(false)
+        }
+
+        fn remove_worktree(
+            &self,
+            _repo_path: &Path,
+            _worktree_path: &Path,
+            _force: bool,
+        ) -> Result<(), GitServiceError> {
+            Ok(())
+        }
+
+        fn stash(&self, _worktree_path: &Path, _message: &str) -> Result<bool, GitServiceError> {
+            self.record("stash");
+            Ok(true)
+        }
+
+        fn stash_pop(&self, _worktree_path: &Path) -> Result<(), GitServiceError> {
+            self.record("stash_pop");
+            if self.fail_stash_pop {
+                return Err(GitServiceError::InvalidRepository(
+                    "git stash pop failed: conflict".to_string(),
+                ));
+            }
+            Ok(())
+        }
+
+        fn stash_drop(&self, _worktree_path: &Path) -> Result<(), GitServiceError> {
+            self.record("stash_drop");
+            Ok(())
+        }
+
+        fn get_branch_oid(
+            &self,
+            _repo_path: &Path,
+            _branch_name: &str,
+        ) -> Result<String, GitServiceError> {
+            Ok("target-sha".to_string())
+        }
+
+        fn reset_branch(
+            &self,
+            _repo_path: &Path,
+            _branch: &str,
+            _merged_head: &str,
+            _previous_head: &str,
+        ) -> Result<(), GitServiceError> {
+            self.record("reset_branch");
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        service: ReviewAutomationService,
+        git: Arc<MockGit>,
+        task: Task,
+        workspace: Workspace,
+        settings: ProjectReviewSettings,
+        _dir: TempDir,
+    }
+
+    /// An in-review task with a single-repo workspace on disk and auto-merge enabled
+    async fn fixture(git: MockGit) -> Fixture {
+        let (pool, project) = test_support::project_pool().await;
+
+        let dir = TempDir::new().unwrap();
+        let workspace_path = dir.path().join("workspace");
+        std::fs::create_dir_all(workspace_path.join("app")).unwrap();
+
+        let repo = Repo::find_or_create(&pool, &dir.path().join("app"), "App")
+            .await
+            .unwrap();
+
+        let mut create_task =
+            CreateTask::from_title_description(project.id, "Add login".to_string(), None);
+        create_task.status = Some(TaskStatus::InReview);
+        let task = Task::create(&pool, &create_task, Uuid::new_v4())
+            .await
+            .unwrap();
+
+        let mut workspace = Workspace::create(
+            &pool,
+            &CreateWorkspace {
+                branch: "task/add-login".to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        workspace.container_ref = Some(workspace_path.to_string_lossy().to_string());
+        WorkspaceRepo::create_many(
+            &pool,
+            workspace.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: "main".to_string(),
+            }],
+        )
+        .await
+        .unwrap();
+
+        let settings =
+            ProjectReviewSettings::create_or_update(&pool, project.id, true, true, false)
+                .await
+                .unwrap();
+
+        let mut config = Config::default();
+        config.notifications.sound_enabled = false;
+        config.notifications.push_enabled = false;
+        let notification_service =
+            NotificationService::new(Arc::new(RwLock::new(config)), pool.clone());
+
+        let git = Arc::new(git);
+        let service = ReviewAutomationService {
+            db: DBService { pool },
+            git_service: git.clone(),
+            notification_service,
+            poll_interval: Duration::from_secs(10),
+            project_concurrency: DEFAULT_PROJECT_CONCURRENCY,
+        };
+
+        Fixture {
+            service,
+            git,
+            task,
+            workspace,
+            settings,
+            _dir: dir,
+        }
+    }
+
+    /// Add a second repo, "web", to the fixture's workspace; it sorts after "app"
+    async fn add_web_repo(fixture: &Fixture) {
+        let pool = &fixture.service.db.pool;
+        let workspace_path = PathBuf::from(fixture.workspace.container_ref.clone().unwrap());
+        std::fs::create_dir_all(workspace_path.join("web")).unwrap();
+        let repo = Repo::find_or_create(pool, &fixture._dir.path().join("web"), "Web")
+            .await
+            .unwrap();
+        WorkspaceRepo::create_many(
+            pool,
+            fixture.workspace.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: "main".to_string(),
+            }],
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn review(fixture: &Fixture) -> (ReviewAction, TaskStatus) {
+        let action = fixture
+            .service
+            .process_task_review(&fixture.task, &fixture.workspace, &fixture.settings)
+            .await
+            .unwrap();
+        let status = Task::find_by_id(&fixture.service.db.pool, fixture.task.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .status;
+        (action, status)
+    }
+
+    /// Test runner double that records the program and directory of every run, failing the
+    /// runs in `failing_dir`
+    #[derive(Default)]
+    struct RecordingRunner {
+        runs: Mutex<Vec<(String, PathBuf)>>,
+        failing_dir: Option<PathBuf>,
+    }
+
+    #[async_trait::async_trait]
+    impl TestRunner for RecordingRunner {
+        async fn run(
+            &self,
+            program: &str,
+            args: &[&str],
+            dir: &Path,
+        ) -> std::io::Result<(bool, String)> {
+            self.runs
+                .lock()
+                .unwrap()
+                .push((program.to_string(), dir.to_path_buf()));
+            let success = self.failing_dir.as_deref() != Some(dir);
+            Ok((success, format!("{program} {}", args.join(" "))))
+        }
+
+        fn shell(&self) -> (String, &'static str) {
+            ("sh".to_string(), "-c")
+        }
+    }
+
+    #[tokio::test]
+    async fn full_suite_runs_in_each_repo_and_fails_if_any_repo_fails() {
+        let fixture = fixture(MockGit::default()).await;
+        add_web_repo(&fixture).await;
+        let workspace_path = fixture.workspace.container_ref.clone().unwrap();
+        let app = Path::new(&workspace_path).join("app");
+        let web = Path::new(&workspace_path).join("web");
+        std::fs::write(app.join("Cargo.toml"), "").unwrap();
+        std::fs::write(web.join("package.json"), "{}").unwrap();
+
+        let runner = RecordingRunner::default();
+        let output = fixture
+            .service
+            .run_tests(
+                &fixture.task,
+                &fixture.workspace,
+                &workspace_path,
+                &fixture.settings,
+                &runner,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            *runner.runs.lock().unwrap(),
+            vec![
+                ("cargo".to_string(), app.clone()),
+                ("npm".to_string(), web.clone())
+            ]
+        );
+        assert_eq!(
+            output,
+            "== app ==\nPASSED\ncargo test\n\n== web ==\nPASSED\nnpm test\n\n"
+        );
+
+        let runner = RecordingRunner {
+            failing_dir: Some(web),
+            ..Default::default()
+        };
+        let result = fixture
+            .service
+            .run_tests(
+                &fixture.task,
+                &fixture.workspace,
+                &workspace_path,
+                &fixture.settings,
+                &runner,
+            )
+            .await;
+        let Err(ReviewAutomationError::TestFailed(output)) = result else {
+            panic!("expected a test failure, got {result:?}");
+        };
+        assert_eq!(runner.runs.lock().unwrap().len(), 2);
+        assert_eq!(
+            output,
+            "== app ==\nPASSED\ncargo test\n\n== web ==\nFAILED\nnpm test\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn targeted_tests_run_only_when_every_change_is_covered() {
+        let run = |branch_changed: &[&str]| {
+            let branch_changed = branch_changed.iter().map(|f| f.to_string()).collect();
+            async move {
+                let mut fixture = fixture(MockGit {
+                    branch_changed,
+                    ..Default::default()
+                })
+                .await;
+                fixture.settings.test_targets = Some("src/** => cargo test -p 'app'".to_string());
+                let workspace_path = fixture.workspace.container_ref.clone().unwrap();
+                let app = Path::new(&workspace_path).join("app");
+                std::fs::write(app.join("Cargo.toml"), "").unwrap();
+
+                let runner = RecordingRunner::default();
+                fixture
+                    .service
+                    .run_tests(
+                        &fixture.task,
+                        &fixture.workspace,
+                        &workspace_path,
+                        &fixture.settings,
+                        &runner,
+                    )
+                    .await
+                    .unwrap();
+                let runs = runner.runs.lock().unwrap();
+                assert!(runs.iter().all(|(_, dir)| dir == &app));
+                runs.iter()
+                    .map(|(program, _)| program.clone())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(run(&["src/login.rs"]).await, vec!["sh"]);
+        assert_eq!(run(&["src/login.rs", "README.md"]).await, vec!["cargo"]);
+    }
+
+    /// Record a coding agent run on the fixture's workspace that ended with `status`
+    async fn finish_agent_run(fixture: &Fixture, status: ExecutionProcessStatus) {
+        let pool = &fixture.service.db.pool;
+        let session = Session::create(
+            pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            fixture.workspace.id,
+        )
+        .await
+        .unwrap();
+        let process = ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                session_id: session.id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::ScriptRequest(ScriptRequest {
+                        script: "true".to_string(),
+                        language: ScriptRequestLanguage::Bash,
+                        context: ScriptContext::SetupScript,
+                        working_dir: None,
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            Uuid::new_v4(),
+            &[],
+        )
+        .await
+        .unwrap();
+        ExecutionProcess::update_completion(pool, process.id, status, None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn killed_run_is_ready_for_review_only_without_require_success() {
+        let fixture = fixture(MockGit::default()).await;
+        let pool = &fixture.service.db.pool;
+        let project_id = fixture.task.project_id;
+        let ready = |require_success| {
+            Task::find_in_review_with_completed_attempts(pool, project_id, require_success)
+        };
+
+        finish_agent_run(&fixture, ExecutionProcessStatus::Killed).await;
+        assert_eq!(ready(false).await.unwrap()[0].0.id, fixture.task.id);
+        assert!(ready(true).await.unwrap().is_empty());
+
+        finish_agent_run(&fixture, ExecutionProcessStatus::Completed).await;
+        assert_eq!(ready(true).await.unwrap()[0].0.id, fixture.task.id);
+    }
+
+    #[tokio::test]
+    async fn log_artifact_is_read_from_disk_or_falls_back_to_output() {
+        let fixture = fixture(MockGit::default()).await;
+        let pool = &fixture.service.db.pool;
+        let create_log = |output: &str, artifact_path: Option<String>| {
+            ReviewAutomationLog::create_with_artifact(
+                pool,
+                fixture.task.id,
+                fixture.workspace.id,
+                ReviewAction::TestFailed,
+                Some(output.to_string()),
+                None,
+                artifact_path,
+            )
+        };
+
+        let artifact = fixture._dir.path().join("tests.log");
+        std::fs::write(&artifact, "full test output").unwrap();
+        let stored = create_log("preview", Some(artifact.to_string_lossy().to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            ReviewAutomationService::get_log_artifact(pool, stored.id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("full test output")
+        );
+
+        let inline = create_log("inline output", None).await.unwrap();
+        assert_eq!(
+            ReviewAutomationService::get_log_artifact(pool, inline.id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("inline output")
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_log_or_artifact_file_is_not_found() {
+        let fixture = fixture(MockGit::default()).await;
+        let pool = &fixture.service.db.pool;
+
+        let missing_log = ReviewAutomationService::get_log_artifact(pool, Uuid::new_v4()).await;
+        assert!(matches!(
+            missing_log,
+            Err(ReviewAutomationError::NotFound(_))
+        ));
+
+        let deleted = fixture._dir.path().join("deleted.log");
+        let log = ReviewAutomationLog::create_with_artifact(
+            pool,
+            fixture.task.id,
+            fixture.workspace.id,
+            ReviewAction::TestFailed,
+            Some("preview".to_string()),
+            None,
+            Some(deleted.to_string_lossy().to_string()),
+        )
+        .await
+        .unwrap();
+        let missing_file = ReviewAutomationService::get_log_artifact(pool, log.id).await;
+        assert!(matches!(
+            missing_file,
+            Err(ReviewAutomationError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn clean_merge_completes_task() {
+        let fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        assert_eq!(fixture.git.calls(), vec!["merge"]);
+        let workspace = Workspace::find_by_id(&fixture.service.db.pool, fixture.workspace.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(workspace.archived);
+    }
+
+    #[tokio::test]
+    async fn required_tests_run_for_tasks_without_testing_criteria() {
+        let mut fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        fixture.settings.run_tests_enabled = true;
+        fixture.settings.require_tests_before_merge = true;
+        assert!(fixture.task.testing_criteria.is_none());
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        let logs = ReviewAutomationLog::find_by_task_id(&fixture.service.db.pool, fixture.task.id)
+            .await
+            .unwrap();
+        assert!(
+            logs.iter()
+                .any(|log| log.action == ReviewAction::TestPassed)
+        );
+    }
+
+    #[tokio::test]
+    async fn merged_task_is_archived_when_enabled() {
+        let mut fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        fixture.settings.auto_archive_on_merge = true;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        let task = Task::find_by_id(&fixture.service.db.pool, fixture.task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(task.archived);
+    }
+
+    #[tokio::test]
+    async fn needs_attention_flag_queues_task_until_cleared() {
+        let fixture = fixture(MockGit::with_merges([])).await;
+        let pool = &fixture.service.db.pool;
+        let project_id = fixture.task.project_id;
+
+        Task::set_needs_attention(pool, fixture.task.id, Some("Breakdown failed"))
+            .await
+            .unwrap();
+        let flagged = Task::find_needing_attention(pool, project_id)
+            .await
+            .unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(
+            flagged[0].needs_attention_reason.as_deref(),
+            Some("Breakdown failed")
+        );
+
+        Task::set_needs_attention(pool, fixture.task.id, None)
+            .await
+            .unwrap();
+        assert!(
+            Task::find_needing_attention(pool, project_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn flagged_task_leaves_the_review_queue_until_cleared() {
+        let fixture = fixture(MockGit::default()).await;
+        let pool = &fixture.service.db.pool;
+        let project_id = fixture.task.project_id;
+        finish_agent_run(&fixture, ExecutionProcessStatus::Completed).await;
+
+        Task::set_needs_attention(pool, fixture.task.id, Some("Not merged"))
+            .await
+            .unwrap();
+        assert!(
+            Task::find_in_review_with_completed_attempts(pool, project_id, true)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        Task::set_needs_attention(pool, fixture.task.id, None)
+            .await
+            .unwrap();
+        let ready = Task::find_in_review_with_completed_attempts(pool, project_id, true)
+            .await
+            .unwrap();
+        assert_eq!(ready[0].0.id, fixture.task.id);
+    }
+
+    #[tokio::test]
+    async fn dirty_worktree_is_stashed_around_the_merge() {
+        let mut restored = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        restored.settings.stash_before_merge = true;
+
+        let (action, _) = review(&restored).await;
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(restored.git.calls(), vec!["stash", "merge", "stash_pop"]);
+
+        let mut discarded = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        discarded.settings.stash_before_merge = true;
+        discarded.settings.drop_stash_after_merge = true;
+
+        review(&discarded).await;
+        assert_eq!(discarded.git.calls(), vec!["stash", "merge", "stash_drop"]);
+    }
+
+    #[tokio::test]
+    async fn failed_stash_restore_is_logged() {
+        let git = MockGit {
+            fail_stash_pop: true,
+            ..MockGit::with_merges([MergeOutcome::Merged])
+        };
+        let mut fixture = fixture(git).await;
+        fixture.settings.stash_before_merge = true;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        let logs = ReviewAutomationLog::find_by_task_id(&fixture.service.db.pool, fixture.task.id)
+            .await
+            .unwrap();
+        assert!(logs.iter().any(|log| {
+            log.action == ReviewAction::Error
+                && log
+                    .error_message
+                    .as_deref()
+                    .is_some_and(|msg| msg.contains("remain in the stash"))
+        }));
+    }
+
+    #[tokio::test]
+    async fn untested_merge_is_blocked_when_tests_are_required() {
+        let mut fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        finish_agent_run(&fixture, ExecutionProcessStatus::Completed).await;
+        // Settings saved before the gate needed tests enabled, so no tests run
+        fixture.settings.run_tests_enabled = false;
+        fixture.settings.require_tests_before_merge = true;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::Skipped);
+        assert_eq!(status, TaskStatus::InReview);
+        assert!(fixture.git.calls().is_empty());
+        // Flagged, so it no longer holds up the project's review queue
+        assert!(
+            Task::find_in_review_with_completed_attempts(
+                &fixture.service.db.pool,
+                fixture.task.project_id,
+                true
+            )
+            .await
+            .unwrap()
+            .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn enforced_criteria_skip_tasks_without_criteria() {
+        let mut fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        fixture.settings.enforce_testing_criteria = true;
+
+        // No testing criteria means nothing for the AI to check, so the merge goes ahead
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+    }
+
+    #[test]
+    fn unmet_criteria_report_lists_each_criterion() {
+        let report = unmet_criteria_report(&CriteriaCheckResponse {
+            met: false,
+            unmet_criteria: vec!["Login rejects bad passwords".to_string()],
+            reasoning: "No test covers the failure path".to_string(),
+        });
+        assert_eq!(
+            report,
+            "Unmet testing criteria:\n- Login rejects bad passwords\n\nNo test covers the failure path"
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_pre_merge_command_blocks_merge() {
+        let mut fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        fixture.settings.pre_merge_command = Some("echo license scan failed && exit 3".to_string());
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::Error);
+        assert_eq!(status, TaskStatus::InProgress);
+        assert!(fixture.git.calls().is_empty());
+        let logs = ReviewAutomationLog::find_by_task_id(&fixture.service.db.pool, fixture.task.id)
+            .await
+            .unwrap();
+        assert!(
+            logs[0]
+                .output
+                .as_deref()
+                .is_some_and(|output| output.contains("license scan failed"))
+        );
+    }
+
+    #[tokio::test]
+    async fn passing_pre_merge_command_allows_merge() {
+        let mut fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        fixture.settings.pre_merge_command = Some("exit 0".to_string());
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+    }
+
+    #[tokio::test]
+    async fn single_commit_branch_is_cherry_picked_when_enabled() {
+        let mut git = MockGit::with_merges([MergeOutcome::Merged]);
+        git.commit_count = 1;
+        let mut fixture = fixture(git).await;
+        fixture.settings.cherry_pick_single_commits = true;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        assert_eq!(fixture.git.calls(), vec!["cherry_pick"]);
+    }
+
+    #[tokio::test]
+    async fn progress_entry_is_folded_into_a_cherry_picked_commit() {
+        let mut git = MockGit::with_merges([MergeOutcome::Merged]);
+        git.commit_count = 1;
+        let mut fixture = fixture(git).await;
+        fixture.settings.cherry_pick_single_commits = true;
+        fixture.task.post_task_actions = Some("Rotate the API keys".to_string());
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        assert_eq!(fixture.git.calls(), vec!["amend_paths", "cherry_pick"]);
+    }
+
+    #[tokio::test]
+    async fn multi_commit_branch_is_merged_even_with_cherry_picks_enabled() {
+        let mut git = MockGit::with_merges([MergeOutcome::Merged]);
+        git.commit_count = 2;
+        let mut fixture = fixture(git).await;
+        fixture.settings.cherry_pick_single_commits = true;
+
+        let (action, _) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(fixture.git.calls(), vec!["merge"]);
+    }
+
+    #[tokio::test]
+    async fn diverged_branch_is_rebased_then_merged() {
+        let fixture = fixture(MockGit::with_merges([
+            MergeOutcome::Diverged,
+            MergeOutcome::Merged,
+        ]))
+        .await;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        assert_eq!(
+            fixture.git.calls(),
+            vec!["merge", "fork_point", "rebase", "merge"]
+        );
+    }
+
+    #[tokio::test]
+    async fn branch_behind_target_is_rebased_before_merging() {
+        let git = MockGit {
+            behind: 3,
+            branch_changed: vec!["src/login.rs".to_string()],
+            target_changed: vec!["README.md".to_string()],
+            ..MockGit::with_merges([MergeOutcome::Merged])
+        };
+        let fixture = fixture(git).await;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        assert_eq!(fixture.git.calls(), vec!["fork_point", "rebase", "merge"]);
+    }
+
+    #[test]
+    fn merge_approach_follows_divergence() {
+        assert_eq!(merge_approach(0, 4), MergeApproach::Direct);
+        assert_eq!(merge_approach(3, 0), MergeApproach::Rebase);
+        assert_eq!(merge_approach(3, 1), MergeApproach::Diverged);
+        assert_eq!(
+            merge_approach(HEAVY_DIVERGENCE_BEHIND + 1, 0),
+            MergeApproach::Diverged
+        );
+    }
+
+    #[tokio::test]
+    async fn merge_conflict_sends_task_back_to_in_progress() {
+        let fixture = fixture(MockGit::with_merges([MergeOutcome::Conflict])).await;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeConflict);
+        assert_eq!(status, TaskStatus::InProgress);
+        let logs = ReviewAutomationLog::find_by_task_id(&fixture.service.db.pool, fixture.task.id)
+            .await
+            .unwrap();
+        let conflict = logs
+            .iter()
+            .find(|log| log.action == ReviewAction::MergeConflict)
+            .unwrap();
+        assert_eq!(
+            conflict.output.as_deref(),
+            Some(format_conflicted_files(&["src/lib.rs".to_string()]).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn conflicting_repo_rolls_back_clean_merges_by_default() {
+        let fixture = fixture(MockGit::with_merges([
+            MergeOutcome::Merged,
+            MergeOutcome::Conflict,
+        ]))
+        .await;
+        add_web_repo(&fixture).await;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeConflict);
+        assert_eq!(status, TaskStatus::InProgress);
+        assert!(fixture.git.calls().contains(&"reset_branch"));
+        assert!(
+            Merge::find_by_workspace_id(&fixture.service.db.pool, fixture.workspace.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn partial_merge_keeps_clean_repos_and_merges_the_rest_later() {
+        let mut fixture = fixture(MockGit::with_merges([
+            MergeOutcome::Merged,
+            MergeOutcome::Conflict,
+            MergeOutcome::Merged,
+        ]))
+        .await;
+        fixture.settings.allow_partial_merge = true;
+        add_web_repo(&fixture).await;
+        let pool = &fixture.service.db.pool;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeConflict);
+        assert_eq!(status, TaskStatus::InProgress);
+        assert_eq!(
+            Merge::find_by_workspace_id(pool, fixture.workspace.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let logs = ReviewAutomationLog::find_by_task_id(pool, fixture.task.id)
+            .await
+            .unwrap();
+        let partial = logs
+            .iter()
+            .find(|log| log.action == ReviewAction::PartiallyMerged)
+            .unwrap();
+        assert_eq!(
+            partial.output.as_deref(),
+            Some("Merged: app\nStill to merge: web")
+        );
+
+        // Only the repo that conflicted is merged on the next review
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        assert_eq!(
+            fixture
+                .git
+                .calls()
+                .iter()
+                .filter(|call| **call == "merge")
+                .count(),
+            3
+        );
+        assert!(!fixture.git.calls().contains(&"reset_branch"));
+        assert_eq!(
+            Merge::find_by_workspace_id(pool, fixture.workspace.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_merge_is_neither_repeated_nor_duplicated() {
+        let fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        let pool = &fixture.service.db.pool;
+        let repo_id =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, fixture.workspace.id)
+                .await
+                .unwrap()[0]
+                .repo
+                .id;
+        // The merge recorded twice, as when a run is retried after recording it
+        for sha in ["first-sha", "retry-sha"] {
+            Merge::create_direct(pool, fixture.workspace.id, repo_id, "main", sha, None)
+                .await
+                .unwrap();
+        }
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::MergeCompleted);
+        assert_eq!(status, TaskStatus::Done);
+        assert!(!fixture.git.calls().contains(&"merge"));
+        let merges = Merge::find_by_workspace_id(pool, fixture.workspace.id)
+            .await
+            .unwrap();
+        assert_eq!(merges.len(), 1);
+        assert_eq!(merges[0].merge_commit().as_deref(), Some("retry-sha"));
+    }
+
+    #[tokio::test]
+    async fn reverting_an_auto_archived_merge_restores_the_task() {
+        let fixture = fixture(MockGit::default()).await;
+        let pool = &fixture.service.db.pool;
+        let git = GitService::new();
+        let repo_path = fixture._dir.path().join("app");
+        git.initialize_repo_with_main_branch(&repo_path).unwrap();
+        let base = git.get_branch_oid(&repo_path, "main").unwrap();
+        // The task's commit plus its .progress commit, fast-forwarded onto main
+        std::fs::write(repo_path.join("login.rs"), "fn login() {}\n").unwrap();
+        git.commit_paths(&repo_path, &["login.rs"], "Add login")
+            .unwrap();
+        std::fs::write(repo_path.join(PROGRESS_FILE), "## Done\n").unwrap();
+        git.commit_paths(&repo_path, &[PROGRESS_FILE], "Update .progress")
+            .unwrap();
+        let head = git.get_branch_oid(&repo_path, "main").unwrap();
+
+        let repo_id =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, fixture.workspace.id)
+                .await
+                .unwrap()[0]
+                .repo
+                .id;
+        Merge::create_direct(
+            pool,
+            fixture.workspace.id,
+            repo_id,
+            "main",
+            &head,
+            Some(&base),
+        )
+        .await
+        .unwrap();
+        Task::update_status(pool, fixture.task.id, TaskStatus::Done, None, None)
+            .await
+            .unwrap();
+        Task::set_archived(pool, fixture.task.id, true)
+            .await
+            .unwrap();
+
+        let logs = ReviewAutomationService::revert_task_merges(pool, &git, fixture.task.id)
+            .await
+            .unwrap();
+
+        assert_eq!(logs.len(), 1);
+        assert!(!repo_path.join("login.rs").exists());
+        let task = Task::find_by_id(pool, fixture.task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Todo);
+        assert!(!task.archived);
+    }
+
+    #[test]
+    fn target_allowlist_matches_names_and_globs() {
+        let allowlist = parse_target_allowlist("develop,\n release/* ,").unwrap();
+        let allowed = |branch: &str| allowlist.iter().any(|glob| glob.is_match(branch));
+        assert!(allowed("develop"));
+        assert!(allowed("release/1.2"));
+        assert!(!allowed("main"));
+        assert!(parse_target_allowlist(" \n").unwrap().is_empty());
+        assert!(parse_target_allowlist("release/[").is_err());
+    }
+
+    #[tokio::test]
+    async fn branch_outside_the_target_allowlist_is_not_merged() {
+        let mut fixture = fixture(MockGit::with_merges([MergeOutcome::Merged])).await;
+        fixture.settings.auto_merge_target_allowlist = Some("develop".to_string());
+        let pool = &fixture.service.db.pool;
+
+        let (action, status) = review(&fixture).await;
+
+        assert_eq!(action, ReviewAction::Skipped);
+        assert_eq!(status, TaskStatus::InReview);
+        assert!(!fixture.git.calls().contains(&"merge"));
+        let task = Task::find_by_id(pool, fixture.task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            task.needs_attention_reason.as_deref(),
+            Some("Not merged, target branch not in the auto-merge allowlist: app (main)")
+        );
+    }
+
+    #[tokio::test]
+    async fn resolved_conflicts_stop_counting() {
+        let fixture = fixture(MockGit::with_merges([
+            MergeOutcome::Conflict,
+            MergeOutcome::Conflict,
+        ]))
+        .await;
+        let pool = &fixture.service.db.pool;
+        assert!(
+            ReviewAutomationService::reset_conflict_count(pool, fixture.task.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        review(&fixture).await;
+        assert_eq!(
+            ReviewAutomationLog::count_merge_conflicts(pool, fixture.task.id)
+                .await
+                .unwrap(),
+            1
+        );
+
+        let reset = ReviewAutomationService::reset_conflict_count(pool, fixture.task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reset.action, ReviewAction::ConflictResolved);
+        assert_eq!(reset.workspace_id, fixture.workspace.id);
+        assert_eq!(
+            ReviewAutomationLog::count_merge_conflicts(pool, fixture.task.id)
+                .await
+                .unwrap(),
+            0
+        );
+
+        review(&fixture).await;
+        assert_eq!(
+            ReviewAutomationLog::count_merge_conflicts(pool, fixture.task.id)
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn criteria_check_failures_count_since_the_last_verdict() {
+        let fixture = fixture(MockGit::default()).await;
+        let pool = &fixture.service.db.pool;
+        let log = |action: ReviewAction, error: &str| {
+            ReviewAutomationLog::create(
+                pool,
+                fixture.task.id,
+                fixture.workspace.id,
+                action,
+                None,
+                Some(error.to_string()),
+            )
+        };
+        let check_failed = || log(ReviewAction::Error, "Testing criteria check failed: 529");
+        let failures = || ReviewAutomationLog::count_criteria_check_failures(pool, fixture.task.id);
+
+        check_failed().await.unwrap();
+        log(ReviewAction::Error, "Pre-merge command failed: make lint")
+            .await
+            .unwrap();
+        assert_eq!(failures().await.unwrap(), 1);
+
+        log(ReviewAction::CriteriaMet, "").await.unwrap();
+        assert_eq!(failures().await.unwrap(), 0);
+        check_failed().await.unwrap();
+        assert_eq!(failures().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn settings_update_keeps_unset_fields_and_clears_empty_text() {
+        let (pool, project) = test_support::project_pool().await;
+        let policy = CommandPolicy::default();
+        let update = |value: serde_json::Value| {
+            serde_json::from_value::<UpdateReviewAutomationSettings>(value).unwrap()
+        };
+
+        let (settings, _) = ReviewAutomationService::update_settings(
+            &pool,
+            project.id,
+            &update(serde_json::json!({
+                "enabled": true,
+                "auto_merge_enabled": false,
+                "run_tests_enabled": true,
+                "sign_commits": true,
+                "progress_repo": " app ",
+            })),
+            &policy,
+        )
+        .await
+        .unwrap();
+        assert!(settings.enabled && settings.sign_commits);
+        assert_eq!(settings.progress_repo.as_deref(), Some("app"));
+
+        let (settings, _) = ReviewAutomationService::update_settings(
+            &pool,
+            project.id,
+            &update(serde_json::json!({
+                "enabled": false,
+                "auto_merge_enabled": false,
+                "run_tests_enabled": true,
+                "progress_repo": "",
+            })),
+            &policy,
+        )
+        .await
+        .unwrap();
+        assert!(!settings.enabled);
+        assert!(settings.sign_commits);
+        assert_eq!(settings.progress_repo, None);
+    }
+
+    #[test]
+    fn progress_entries_append_once_per_task() {
+        let task_id = Uuid::new_v4();
+        let created = append_progress("", task_id, "## Done\n- Added API\n").unwrap();
+        assert_eq!(
+            created,
+            format!("<!-- task: {task_id} -->\n## Done\n- Added API\n")
+        );
+        assert!(append_progress(&created, task_id, "## Done").is_none());
+
+        let other = Uuid::new_v4();
+        let appended = append_progress(&created, other, "## Next").unwrap();
+        assert!(appended.starts_with(&created));
+        assert!(appended.ends_with(&format!("\n\n<!-- task: {other} -->\n## Next\n")));
+        assert!(append_progress(&appended, Uuid::new_v4(), "  ").is_none());
+    }
+
+    #[test]
+    fn conflicted_files_are_ranked_across_attempts() {
+        let first = format_conflicted_files(&["src/a.rs".to_string(), "src/b.rs".to_string()]);
+        let second = format_conflicted_files(&["src/b.rs".to_string()]);
+        let ranked = rank_conflicted_files([first.as_str(), "Other output", second.as_str()]);
+        assert_eq!(
+            ranked,
+            vec![("src/b.rs".to_string(), 2), ("src/a.rs".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn parent_completes_only_after_last_subtask_out_of_order() {
+        let mut statuses = [
+            TaskStatus::Todo,
+            TaskStatus::InProgress,
+            TaskStatus::InReview,
+        ];
+
+        statuses[2] = TaskStatus::Done;
+        assert!(!subtasks_complete(&statuses));
+
+        statuses[0] = TaskStatus::Done;
+        assert!(!subtasks_complete(&statuses));
+
+        statuses[1] = TaskStatus::Done;
+        assert!(subtasks_complete(&statuses));
+    }
+
+    #[test]
+    fn cancelled_subtasks_count_as_finished_but_not_alone() {
+        let cancelled = TaskStatus::Cancelled;
+        assert!(subtasks_complete([&cancelled, &TaskStatus::Done]));
+        assert!(!subtasks_complete([&cancelled, &cancelled]));
+        assert!(!subtasks_complete(&[]));
+    }
+
+    fn merge_task(title: &str, layer: Option<TaskLayer>, task_type: Option<TaskType>) -> Task {
+        Task {
+            id: Uuid::parse_str("1a2b3c4d-0000-0000-0000-000000000000").unwrap(),
+            layer,
+            task_type,
+            ..test_support::task(title)
+        }
+    }
+
+    #[test]
+    fn plain_merge_message_names_branches_and_task() {
+        let task = merge_task("Add login", Some(TaskLayer::Backend), None);
+        assert_eq!(
+            merge_commit_message(&task, "vk/1a2b-add-login", "main", false),
+            "Merge vk/1a2b-add-login into main\n\nTask: Add login"
+        );
+    }
+
+    #[test]
+    fn conventional_subject_uses_layer_scope_and_short_id_trailer() {
+        let task = merge_task("Add login", Some(TaskLayer::Backend), None);
+        assert_eq!(
+            merge_commit_message(&task, "vk/1a2b-add-login", "main", true),
+            "feat(backend): Add login (#1a2b3c4d)\n\nMerge vk/1a2b-add-login into main"
+        );
+    }
+
+    #[test]
+    fn conventional_type_follows_layer_and_task_type() {
+        let subject = |layer: Option<TaskLayer>, task_type: Option<TaskType>| {
+            let task = merge_task("Do it", layer, task_type);
+            merge_commit_message(&task, "b", "main", true)
+                .lines()
+                .next()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            subject(Some(TaskLayer::Testing), None),
+            "test(testing): Do it (#1a2b3c4d)"
+        );
+        assert_eq!(
+            subject(Some(TaskLayer::Frontend), Some(TaskType::Testing)),
+            "test(frontend): Do it (#1a2b3c4d)"
+        );
+        assert_eq!(
+            subject(Some(TaskLayer::Devops), None),
+            "ci(devops): Do it (#1a2b3c4d)"
+        );
+        assert_eq!(
+            subject(Some(TaskLayer::Data), Some(TaskType::Architecture)),
+            "chore(data): Do it (#1a2b3c4d)"
+        );
+        assert_eq!(
+            subject(Some(TaskLayer::Fullstack), None),
+            "feat: Do it (#1a2b3c4d)"
+        );
+        assert_eq!(subject(None, None), "feat: Do it (#1a2b3c4d)");
+    }
+
+    #[test]
+    fn conventional_subject_keeps_only_the_first_title_line() {
+        let task = merge_task("  Add login  \nwith OAuth providers", None, None);
+        assert_eq!(
+            merge_commit_message(&task, "b", "main", true),
+            "feat: Add login (#1a2b3c4d)\n\nMerge b into main"
+        );
     }
 }
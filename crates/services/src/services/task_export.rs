@@ -0,0 +1,419 @@
+//! Export of AI-generated task plans as Markdown or JSON documents, and import of JSON plans.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use db::models::task::{CreateTask, Task, TaskLayer, TaskType};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Output format for a task plan export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+/// A single task in an exported plan. The JSON export is an array of these, which is also
+/// the schema accepted by task import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ExportedTask {
+    pub title: String,
+    pub description: Option<String>,
+    pub layer: Option<TaskLayer>,
+    pub task_type: Option<TaskType>,
+    pub sequence: Option<i32>,
+    pub testing_criteria: Option<String>,
+    pub post_task_actions: Option<String>,
+    pub source_feature: Option<String>,
+}
+
+impl From<&Task> for ExportedTask {
+    fn from(task: &Task) -> Self {
+        Self {
+            title: task.title.clone(),
+            description: task.description.clone(),
+            layer: task.layer.clone(),
+            task_type: task.task_type.clone(),
+            sequence: task.sequence,
+            testing_criteria: task.testing_criteria.clone(),
+            post_task_actions: task.post_task_actions.clone(),
+            source_feature: task.source_feature.clone(),
+        }
+    }
+}
+
+impl ExportedTask {
+    fn into_create_task(self, project_id: Uuid) -> CreateTask {
+        let mut create_task = CreateTask::ai_generated(
+            project_id,
+            self.title,
+            self.description,
+            self.layer,
+            self.task_type,
+            self.sequence.unwrap_or(0),
+            self.testing_criteria,
+            self.post_task_actions,
+        );
+        create_task.source_feature = self.source_feature;
+        create_task
+    }
+}
+
+/// Outcome of importing a single row of a task plan
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskImportRowResult {
+    pub index: usize,
+    pub task_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+/// Outcome of a task plan import
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskImportResult {
+    pub imported: usize,
+    pub failed: usize,
+    pub rows: Vec<TaskImportRowResult>,
+}
+
+/// Loosely-typed import row, so that one bad field is reported against its row instead of
+/// rejecting the whole request body
+#[derive(Debug, Deserialize)]
+struct ImportRow {
+    title: String,
+    description: Option<String>,
+    layer: Option<String>,
+    task_type: Option<String>,
+    sequence: Option<i32>,
+    testing_criteria: Option<String>,
+    post_task_actions: Option<String>,
+    source_feature: Option<String>,
+}
+
+/// Validate one row of an import against the export schema
+pub fn parse_import_row(value: serde_json::Value) -> Result<ExportedTask, String> {
+    let row: ImportRow = serde_json::from_value(value).map_err(|e| e.to_string())?;
+
+    let title = row.title.trim().to_string();
+    if title.is_empty() {
+        return Err("title must not be empty".to_string());
+    }
+    if row.sequence.is_some_and(|s| s < 0) {
+        return Err("sequence must not be negative".to_string());
+    }
+
+    Ok(ExportedTask {
+        title,
+        description: row.description,
+        layer: parse_enum_field::<TaskLayer>("layer", row.layer)?,
+        task_type: parse_enum_field::<TaskType>("task_type", row.task_type)?,
+        sequence: row.sequence,
+        testing_criteria: row.testing_criteria,
+        post_task_actions: row.post_task_actions,
+        source_feature: row.source_feature,
+    })
+}
+
+fn parse_enum_field<T: FromStr>(field: &str, value: Option<String>) -> Result<Option<T>, String> {
+    match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(v) => T::from_str(&v.to_lowercase())
+            .map(Some)
+            .map_err(|_| format!("invalid {field} '{v}'")),
+    }
+}
+
+/// Create tasks from the rows of an exported plan in a single transaction. Invalid rows are
+/// reported individually; when `atomic` is set, any failure rolls back the whole batch.
+pub async fn import_tasks(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    rows: Vec<serde_json::Value>,
+    atomic: bool,
+) -> Result<TaskImportResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut results = Vec::with_capacity(rows.len());
+
+    for (index, value) in rows.into_iter().enumerate() {
+        let outcome = match parse_import_row(value) {
+            Ok(task) => {
+                let create_task = task.into_create_task(project_id);
+                Task::create(&mut *tx, &create_task, Uuid::new_v4())
+                    .await
+                    .map(|created| created.id)
+                    .map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e),
+        };
+        results.push(match outcome {
+            Ok(task_id) => TaskImportRowResult {
+                index,
+                task_id: Some(task_id),
+                error: None,
+            },
+            Err(error) => TaskImportRowResult {
+                index,
+                task_id: None,
+                error: Some(error),
+            },
+        });
+    }
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    if atomic && failed > 0 {
+        tx.rollback().await?;
+        for row in results.iter_mut().filter(|r| r.error.is_none()) {
+            row.task_id = None;
+            row.error = Some("not imported: another row failed".to_string());
+        }
+        return Ok(TaskImportResult {
+            imported: 0,
+            failed,
+            rows: results,
+        });
+    }
+
+    tx.commit().await?;
+    Ok(TaskImportResult {
+        imported: results.len() - failed,
+        failed,
+        rows: results,
+    })
+}
+
+/// Render tasks in the requested format
+pub fn render(
+    format: ExportFormat,
+    project_name: &str,
+    tasks: &[Task],
+) -> Result<String, serde_json::Error> {
+    match format {
+        ExportFormat::Markdown => Ok(to_markdown(project_name, tasks)),
+        ExportFormat::Json => to_json(tasks),
+    }
+}
+
+/// Pretty-printed JSON array of exported tasks
+pub fn to_json(tasks: &[Task]) -> Result<String, serde_json::Error> {
+    let exported: Vec<ExportedTask> = tasks.iter().map(ExportedTask::from).collect();
+    serde_json::to_string_pretty(&exported)
+}
+
+/// Human-readable plan with a section per task type and a subsection per layer
+pub fn to_markdown(project_name: &str, tasks: &[Task]) -> String {
+    let mut groups: BTreeMap<(u8, u8), Vec<&Task>> = BTreeMap::new();
+    for task in tasks {
+        groups
+            .entry((task_type_rank(task.task_type.as_ref()), layer_rank(task.layer.as_ref())))
+            .or_default()
+            .push(task);
+    }
+
+    let mut out = format!("# Task Plan: {}\n", project_name);
+    if tasks.is_empty() {
+        out.push_str("\nNo AI-generated tasks.\n");
+        return out;
+    }
+
+    let mut current_type = None;
+    for ((type_rank, _), group) in &groups {
+        let first = group[0];
+        if current_type != Some(*type_rank) {
+            current_type = Some(*type_rank);
+            out.push_str(&format!("\n## {}\n", task_type_heading(first.task_type.as_ref())));
+        }
+        out.push_str(&format!("\n### {}\n", layer_heading(first.layer.as_ref())));
+
+        for task in group {
+            out.push_str(&format!("\n#### {}\n", task.title));
+            if let Some(sequence) = task.sequence {
+                out.push_str(&format!("\n- Sequence: {}\n", sequence));
+            }
+            if let Some(feature) = &task.source_feature {
+                out.push_str(&format!("- Feature: {}\n", feature));
+            }
+            if let Some(description) = task.description.as_deref().filter(|d| !d.trim().is_empty())
+            {
+                out.push_str(&format!("\n{}\n", description.trim()));
+            }
+            if let Some(criteria) = &task.testing_criteria {
+                out.push_str(&format!("\n**Testing criteria**\n\n{}\n", criteria.trim()));
+            }
+            if let Some(actions) = &task.post_task_actions {
+                out.push_str(&format!("\n**Post-task actions**\n\n{}\n", actions.trim()));
+            }
+        }
+    }
+
+    out
+}
+
+fn task_type_rank(task_type: Option<&TaskType>) -> u8 {
+    match task_type {
+        Some(TaskType::Architecture) => 0,
+        Some(TaskType::Implementation) => 1,
+        Some(TaskType::Testing) => 2,
+        Some(TaskType::Integration) => 3,
+        None => 4,
+    }
+}
+
+fn task_type_heading(task_type: Option<&TaskType>) -> &'static str {
+    match task_type {
+        Some(TaskType::Architecture) => "Architecture",
+        Some(TaskType::Implementation) => "Implementation",
+        Some(TaskType::Testing) => "Testing",
+        Some(TaskType::Integration) => "Integration",
+        None => "Other",
+    }
+}
+
+fn layer_rank(layer: Option<&TaskLayer>) -> u8 {
+    match layer {
+        Some(TaskLayer::Data) => 0,
+        Some(TaskLayer::Backend) => 1,
+        Some(TaskLayer::Frontend) => 2,
+        Some(TaskLayer::Fullstack) => 3,
+        Some(TaskLayer::Devops) => 4,
+        Some(TaskLayer::Testing) => 5,
+        None => 6,
+    }
+}
+
+fn layer_heading(layer: Option<&TaskLayer>) -> &'static str {
+    match layer {
+        Some(TaskLayer::Data) => "Data",
+        Some(TaskLayer::Backend) => "Backend",
+        Some(TaskLayer::Frontend) => "Frontend",
+        Some(TaskLayer::Fullstack) => "Fullstack",
+        Some(TaskLayer::Devops) => "DevOps",
+        Some(TaskLayer::Testing) => "Testing",
+        None => "Unassigned",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::task::TaskSource;
+
+    use super::*;
+    use crate::services::test_support;
+
+    fn task(
+        title: &str,
+        task_type: Option<TaskType>,
+        layer: Option<TaskLayer>,
+        sequence: i32,
+    ) -> Task {
+        Task {
+            description: Some(format!("Do {title}")),
+            source: TaskSource::AiGenerated,
+            layer,
+            task_type,
+            sequence: Some(sequence),
+            testing_criteria: Some("cargo test".to_string()),
+            post_task_actions: Some("Update .progress".to_string()),
+            source_feature: Some("Login".to_string()),
+            ..test_support::task(title)
+        }
+    }
+
+    #[test]
+    fn markdown_groups_by_type_then_layer() {
+        let tasks = vec![
+            task("Wire UI", Some(TaskType::Integration), None, 3),
+            task("Login form", Some(TaskType::Implementation), Some(TaskLayer::Frontend), 2),
+            task("User schema", Some(TaskType::Architecture), Some(TaskLayer::Data), 0),
+            task("Login API", Some(TaskType::Implementation), Some(TaskLayer::Backend), 1),
+        ];
+
+        let md = to_markdown("Demo", &tasks);
+
+        let positions: Vec<usize> = [
+            "## Architecture",
+            "### Data",
+            "#### User schema",
+            "## Implementation",
+            "### Backend",
+            "#### Login API",
+            "### Frontend",
+            "#### Login form",
+            "## Integration",
+            "### Unassigned",
+            "#### Wire UI",
+        ]
+        .iter()
+        .map(|heading| md.find(heading).unwrap_or_else(|| panic!("missing {heading}")))
+        .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(md.matches("## Implementation").count(), 1);
+        assert!(md.contains("**Testing criteria**\n\ncargo test"));
+        assert!(md.contains("**Post-task actions**\n\nUpdate .progress"));
+    }
+
+    #[test]
+    fn markdown_handles_empty_plan() {
+        assert!(to_markdown("Demo", &[]).contains("No AI-generated tasks."));
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let tasks = vec![
+            task("User schema", Some(TaskType::Architecture), Some(TaskLayer::Data), 0),
+            task("Wire UI", None, None, 1),
+        ];
+
+        let json = to_json(&tasks).unwrap();
+        let parsed: Vec<ExportedTask> = serde_json::from_str(&json).unwrap();
+
+        let expected: Vec<ExportedTask> = tasks.iter().map(ExportedTask::from).collect();
+        assert_eq!(parsed, expected);
+        assert!(json.contains("\"layer\": \"data\""));
+        assert!(json.contains("\"task_type\": \"architecture\""));
+    }
+
+    #[test]
+    fn import_row_parses_exported_json() {
+        let exported = ExportedTask::from(&task(
+            "User schema",
+            Some(TaskType::Architecture),
+            Some(TaskLayer::Data),
+            0,
+        ));
+        let value = serde_json::to_value(&exported).unwrap();
+        assert_eq!(parse_import_row(value).unwrap(), exported);
+    }
+
+    #[test]
+    fn import_row_accepts_case_insensitive_enums() {
+        let row = parse_import_row(serde_json::json!({
+            "title": "Login API",
+            "layer": "Backend",
+            "task_type": "IMPLEMENTATION",
+        }))
+        .unwrap();
+        assert_eq!(row.layer, Some(TaskLayer::Backend));
+        assert_eq!(row.task_type, Some(TaskType::Implementation));
+    }
+
+    #[test]
+    fn import_row_rejects_invalid_fields() {
+        let err = parse_import_row(serde_json::json!({"title": "X", "layer": "mobile"}));
+        assert_eq!(err.unwrap_err(), "invalid layer 'mobile'");
+        assert!(parse_import_row(serde_json::json!({"title": "  "})).is_err());
+        assert!(parse_import_row(serde_json::json!({"title": "X", "sequence": -1})).is_err());
+        assert!(parse_import_row(serde_json::json!({"description": "no title"})).is_err());
+    }
+}
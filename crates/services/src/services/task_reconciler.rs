@@ -0,0 +1,170 @@
+//! Background job keeping task relationships consistent.
+//!
+//! Deleting a workspace through the normal path nullifies the `parent_workspace_id` of tasks it
+//! created, but workspaces removed any other way (or archived) leave those tasks pointing at a
+//! workspace that can't be resolved. The reconciler periodically finds such tasks and clears the
+//! dangling reference.
+
+use std::{collections::HashSet, time::Duration};
+
+use db::{DBService, models::task::Task};
+use tokio::time::interval;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+/// How often dangling parent workspace references are checked for
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Background service nullifying task references to missing or archived workspaces
+pub struct TaskReconcilerService {
+    db: DBService,
+    poll_interval: Duration,
+}
+
+impl TaskReconcilerService {
+    pub async fn spawn(db: DBService, poll_interval: Duration) -> tokio::task::JoinHandle<()> {
+        let service = Self { db, poll_interval };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task reconciler service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.reconcile().await {
+                error!("Error reconciling task parent workspaces: {}", e);
+            }
+        }
+    }
+
+    /// Nullify every dangling parent workspace reference, returning how many tasks were fixed
+    pub async fn reconcile(&self) -> Result<u64, sqlx::Error> {
+        let dangling = Task::find_with_dangling_parent_workspace(&self.db.pool).await?;
+        if dangling.is_empty() {
+            debug!("Task reconciler: no dangling parent workspace references");
+            return Ok(0);
+        }
+
+        let workspace_ids: HashSet<Uuid> = dangling
+            .iter()
+            .filter_map(|task| task.parent_workspace_id)
+            .collect();
+        let mut fixed = 0;
+        for workspace_id in workspace_ids {
+            fixed += Task::nullify_children_by_workspace_id(&self.db.pool, workspace_id).await?;
+        }
+
+        info!(
+            "Task reconciler: cleared {} dangling parent workspace references across {} tasks",
+            fixed,
+            dangling.len()
+        );
+        Ok(fixed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::{
+        task::CreateTask,
+        workspace::{CreateWorkspace, Workspace},
+    };
+
+    use super::*;
+    use crate::services::test_support;
+
+    #[tokio::test]
+    async fn clears_references_to_missing_and_archived_workspaces() {
+        let (pool, project) = test_support::project_pool().await;
+        let owner = Task::create(
+            &pool,
+            &CreateTask::from_title_description(project.id, "Owner".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let create_workspace = |branch: &str| CreateWorkspace {
+            branch: branch.to_string(),
+            agent_working_dir: None,
+        };
+        let live = Workspace::create(&pool, &create_workspace("live"), Uuid::new_v4(), owner.id)
+            .await
+            .unwrap();
+        let archived = Workspace::create(
+            &pool,
+            &create_workspace("archived"),
+            Uuid::new_v4(),
+            owner.id,
+        )
+        .await
+        .unwrap();
+        Workspace::set_archived(&pool, archived.id, true)
+            .await
+            .unwrap();
+        let deleted = Uuid::new_v4();
+
+        let create_child = |title: &str, parent: Uuid| {
+            let mut create =
+                CreateTask::from_title_description(project.id, title.to_string(), None);
+            create.parent_workspace_id = Some(parent);
+            create
+        };
+        let mut children = Vec::new();
+        // A workspace deleted outside the normal path can't be referenced with FKs enforced
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (title, parent) in [
+            ("Live child", live.id),
+            ("Archived child", archived.id),
+            ("Orphan", deleted),
+        ] {
+            let task = Task::create(&pool, &create_child(title, parent), Uuid::new_v4())
+                .await
+                .unwrap();
+            children.push(task.id);
+        }
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let dangling = Task::find_with_dangling_parent_workspace(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            dangling.iter().map(|t| t.id).collect::<HashSet<_>>(),
+            children[1..].iter().copied().collect()
+        );
+
+        let reconciler = TaskReconcilerService {
+            db: DBService { pool: pool.clone() },
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        };
+        assert_eq!(reconciler.reconcile().await.unwrap(), 2);
+        assert_eq!(reconciler.reconcile().await.unwrap(), 0);
+
+        let parent = |id| {
+            let pool = pool.clone();
+            async move {
+                Task::find_by_id(&pool, id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .parent_workspace_id
+            }
+        };
+        assert_eq!(parent(children[0]).await, Some(live.id));
+        assert_eq!(parent(children[1]).await, None);
+        assert_eq!(parent(children[2]).await, None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Similarity between tasks, for spotting near-duplicates before a new task is added.
+//!
+//! Texts are compared by word overlap: lowercase alphanumeric tokens, skipping very short and
+//! common words, scored with the Jaccard index of the two token sets. A task's score is the
+//! better of its title-to-title score and its title-plus-description score.
+
+use std::collections::HashSet;
+
+use db::models::task::Task;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Scores below this are too weak to call a task similar
+pub const MIN_SIMILARITY: f64 = 0.2;
+
+const STOP_WORDS: [&str; 16] = [
+    "the", "and", "for", "with", "from", "into", "that", "this", "when", "should", "add", "use",
+    "make", "new", "task", "all",
+];
+
+/// An existing task and how similar it is to the text searched for (0.0 to 1.0)
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SimilarTask {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub task: Task,
+    pub score: f64,
+}
+
+/// The distinct meaningful words in `text`
+fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() > 2 && !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Jaccard index of two token sets; 0.0 when either is empty
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+/// Rank `tasks` by similarity to `title` and `description`, most similar first, keeping at
+/// most `limit` that score at least [`MIN_SIMILARITY`]
+pub fn rank_similar(
+    title: &str,
+    description: Option<&str>,
+    tasks: Vec<Task>,
+    limit: usize,
+) -> Vec<SimilarTask> {
+    let title_tokens = tokens(title);
+    let mut all_tokens = title_tokens.clone();
+    all_tokens.extend(tokens(description.unwrap_or_default()));
+
+    let mut similar: Vec<SimilarTask> = tasks
+        .into_iter()
+        .filter_map(|task| {
+            let task_title_tokens = tokens(&task.title);
+            let mut task_tokens = task_title_tokens.clone();
+            task_tokens.extend(tokens(task.description.as_deref().unwrap_or_default()));
+
+            let score =
+                jaccard(&title_tokens, &task_title_tokens).max(jaccard(&all_tokens, &task_tokens));
+            (score >= MIN_SIMILARITY).then_some(SimilarTask { task, score })
+        })
+        .collect();
+    similar.sort_by(|a, b| b.score.total_cmp(&a.score));
+    similar.truncate(limit);
+    similar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support;
+
+    fn task(title: &str, description: Option<&str>) -> Task {
+        Task {
+            description: description.map(str::to_string),
+            ..test_support::task(title)
+        }
+    }
+
+    #[test]
+    fn tokens_skip_case_punctuation_and_stop_words() {
+        let tokens = tokens("Add the Login-form, for OAuth2 users!");
+        let mut tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+        tokens.sort();
+        assert_eq!(tokens, vec!["form", "login", "oauth2", "users"]);
+    }
+
+    #[test]
+    fn ranks_near_duplicates_first_and_drops_unrelated() {
+        let tasks = vec![
+            task("Set up CI pipeline", None),
+            task("Login form validation", Some("Validate the email field")),
+            task("Build login form", Some("Email and password fields")),
+        ];
+
+        let similar = rank_similar(
+            "Create login form",
+            Some("Form with email and password"),
+            tasks,
+            10,
+        );
+
+        let titles: Vec<&str> = similar.iter().map(|s| s.task.title.as_str()).collect();
+        assert_eq!(titles, vec!["Build login form", "Login form validation"]);
+        assert!(similar[0].score > similar[1].score);
+    }
+
+    #[test]
+    fn limit_caps_results() {
+        let tasks = vec![task("Login form", None), task("Login form styling", None)];
+        assert_eq!(rank_similar("Login form", None, tasks, 1).len(), 1);
+        assert!(rank_similar("", None, vec![task("Login form", None)], 5).is_empty());
+    }
+}
@@ -1,13 +1,14 @@
 //! Service for detecting and handling stalled tasks that have exceeded timeout thresholds.
 
-use std::time::Duration;
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
+use chrono::Utc;
 use db::{
     DBService,
     models::{
         agent_activity::{AgentAction, AgentActivityLog},
         execution_process::{ExecutionProcess, ExecutionProcessStatus},
-        task::{Task, TaskStatus},
+        task::{Task, TaskError, TaskStatus},
     },
 };
 use thiserror::Error;
@@ -15,35 +16,46 @@ use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use super::notification::NotificationService;
+use super::{agent_activity::WorkspaceStarter, notification::NotificationService};
+
+/// How often the service checks for stalled tasks when the config doesn't say
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Error)]
 pub enum TaskTimeoutError {
     #[error("database error: {0}")]
     Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Task(#[from] TaskError),
 }
 
 /// Background service for detecting and handling stalled tasks
 pub struct TaskTimeoutService {
     db: DBService,
     notification_service: NotificationService,
+    workspace_starter: Option<Arc<dyn WorkspaceStarter>>,
     poll_interval: Duration,
     in_progress_timeout_minutes: i64,
     in_review_timeout_minutes: i64,
+    unstarted_grace_minutes: i64,
 }
 
 impl TaskTimeoutService {
-    /// Spawn the background task timeout service
+    /// Spawn the background task timeout service, checking every `poll_interval`
     pub async fn spawn(
         db: DBService,
         notification_service: NotificationService,
+        workspace_starter: Option<Arc<dyn WorkspaceStarter>>,
+        poll_interval: Duration,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
             notification_service,
-            poll_interval: Duration::from_secs(10), // Check every 10 seconds
-            in_progress_timeout_minutes: 20,        // 20 minute timeout for in-progress
-            in_review_timeout_minutes: 20,          // 20 minute timeout for in-review
+            workspace_starter,
+            poll_interval,
+            in_progress_timeout_minutes: 20, // 20 minute timeout for in-progress
+            in_review_timeout_minutes: 20,   // 20 minute timeout for in-review
+            unstarted_grace_minutes: 2,      // 2 minute grace for in-progress without an attempt
         };
         tokio::spawn(async move {
             service.start().await;
@@ -108,6 +120,15 @@ impl TaskTimeoutService {
                     "Error processing stalled in-review tasks"
                 );
             }
+
+            // Check in-progress tasks that never got an attempt
+            if let Err(e) = self.process_tasks_without_attempt(project_id).await {
+                warn!(
+                    project_id = %project_id,
+                    error = %e,
+                    "Error processing in-progress tasks without an attempt"
+                );
+            }
         }
 
         Ok(())
@@ -128,6 +149,11 @@ impl TaskTimeoutService {
     }
 
     /// Process stalled tasks for a specific project and status
+    #[tracing::instrument(
+        name = "task_timeout_cycle",
+        skip_all,
+        fields(project_id = %project_id, status = %status, timeout_minutes)
+    )]
     async fn process_stalled_tasks(
         &self,
         project_id: Uuid,
@@ -157,7 +183,14 @@ impl TaskTimeoutService {
             }
 
             // Cancel the task
-            Task::update_status(&self.db.pool, task.id, TaskStatus::Cancelled).await?;
+            Task::update_status(
+                &self.db.pool,
+                task.id,
+                TaskStatus::Cancelled,
+                Some("task_timeout"),
+                Some("Timed out"),
+            )
+            .await?;
 
             // Log the timeout action
             AgentActivityLog::create(
@@ -175,6 +208,7 @@ impl TaskTimeoutService {
             // Send notification
             self.notification_service
                 .notify(
+                    project_id,
                     "Task Timeout",
                     &format!(
                         "Task '{}' cancelled due to timeout ({}+ minutes in {} status)",
@@ -187,11 +221,70 @@ impl TaskTimeoutService {
         Ok(())
     }
 
-    /// Mark all running execution processes associated with a task as killed
+    /// Move InProgress tasks that have had no attempt for longer than the grace period back to
+    /// Todo, so the agent can pick them up again instead of them sitting "in progress" forever
+    async fn process_tasks_without_attempt(
+        &self,
+        project_id: Uuid,
+    ) -> Result<(), TaskTimeoutError> {
+        let grace = chrono::Duration::minutes(self.unstarted_grace_minutes);
+        let tasks = Task::find_inprogress_without_attempt(&self.db.pool, project_id).await?;
+
+        for task in tasks {
+            let since = task.stage_started_at.unwrap_or(task.updated_at);
+            if Utc::now() - since < grace {
+                continue;
+            }
+
+            info!(
+                task_id = %task.id,
+                project_id = %project_id,
+                since = %since,
+                "Task timeout: in-progress task has no attempt, moving back to Todo"
+            );
+
+            Task::update_status(
+                &self.db.pool,
+                task.id,
+                TaskStatus::Todo,
+                Some("task_timeout"),
+                Some("In progress without an attempt"),
+            )
+            .await?;
+
+            AgentActivityLog::create(
+                &self.db.pool,
+                project_id,
+                Some(task.id),
+                AgentAction::Error,
+                Some(format!(
+                    "Task was in progress for {}+ minutes without an attempt, moved back to Todo",
+                    self.unstarted_grace_minutes
+                )),
+            )
+            .await?;
+
+            self.notification_service
+                .notify(
+                    project_id,
+                    "Task Timeout",
+                    &format!(
+                        "Task '{}' had no running attempt and was moved back to Todo",
+                        task.title
+                    ),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Mark all running execution processes associated with a task as killed,
+    /// then stop their workspaces so the processes are actually terminated
     async fn mark_task_processes_killed(&self, task_id: Uuid) -> Result<(), TaskTimeoutError> {
         // Find all running processes for workspaces belonging to this task
-        let running_process_ids: Vec<(Uuid,)> = sqlx::query_as(
-            r#"SELECT ep.id
+        let running_processes: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            r#"SELECT ep.id, w.id
             FROM execution_processes ep
             JOIN sessions s ON ep.session_id = s.id
             JOIN workspaces w ON s.workspace_id = w.id
@@ -202,14 +295,16 @@ impl TaskTimeoutService {
         .fetch_all(&self.db.pool)
         .await?;
 
-        for (process_id,) in running_process_ids {
+        let mut workspace_ids = HashSet::new();
+        for (process_id, workspace_id) in running_processes {
+            workspace_ids.insert(workspace_id);
             info!(
                 task_id = %task_id,
                 process_id = %process_id,
                 "Task timeout: marking process as killed"
             );
 
-            // Mark the process as killed (the container service will handle actual termination)
+            // Mark the process as killed; termination happens in stop_workspace below
             ExecutionProcess::update_completion(
                 &self.db.pool,
                 process_id,
@@ -219,6 +314,203 @@ impl TaskTimeoutService {
             .await?;
         }
 
+        if let Some(workspace_starter) = &self.workspace_starter {
+            for workspace_id in workspace_ids {
+                if let Err(e) = workspace_starter.stop_workspace(workspace_id).await {
+                    warn!(
+                        task_id = %task_id,
+                        workspace_id = %workspace_id,
+                        error = %e,
+                        "Task timeout: failed to stop workspace"
+                    );
+                }
+            }
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use db::models::{
+        task::CreateTask,
+        task_status_history::TaskStatusHistory,
+        workspace::{CreateWorkspace, Workspace},
+    };
+    use tokio::sync::RwLock;
+
+    use super::*;
+    use crate::services::{config::Config, test_support};
+
+    #[tokio::test]
+    async fn in_progress_tasks_without_an_attempt_go_back_to_todo() {
+        let (pool, project) = test_support::project_pool().await;
+        let create_task = |title: &str| {
+            let mut create =
+                CreateTask::from_title_description(project.id, title.to_string(), None);
+            create.status = Some(TaskStatus::InProgress);
+            create
+        };
+        let stuck = Task::create(&pool, &create_task("Stuck"), Uuid::new_v4())
+            .await
+            .unwrap();
+        let running = Task::create(&pool, &create_task("Running"), Uuid::new_v4())
+            .await
+            .unwrap();
+        // The workspace of a failed start: created, but nothing ever ran in it
+        let orphaned = Task::create(&pool, &create_task("Orphaned"), Uuid::new_v4())
+            .await
+            .unwrap();
+        let create_workspace = |task_id, branch: &str| {
+            let pool = pool.clone();
+            let create = CreateWorkspace {
+                branch: branch.to_string(),
+                agent_working_dir: None,
+            };
+            async move {
+                Workspace::create(&pool, &create, Uuid::new_v4(), task_id)
+                    .await
+                    .unwrap()
+            }
+        };
+        let workspace = create_workspace(running.id, "task/running").await;
+        test_support::start_process(&pool, workspace.id).await;
+        let orphan_workspace = create_workspace(orphaned.id, "task/orphaned").await;
+
+        let mut without_attempt: Vec<_> = Task::find_inprogress_without_attempt(&pool, project.id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        without_attempt.sort();
+        let mut expected = vec![stuck.id, orphaned.id];
+        expected.sort();
+        assert_eq!(without_attempt, expected);
+
+        assert_eq!(
+            Workspace::archive_unstarted_for_task(&pool, orphaned.id)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            Workspace::archive_unstarted_for_task(&pool, running.id)
+                .await
+                .unwrap(),
+            0
+        );
+        let archived = |id| {
+            let pool = pool.clone();
+            async move {
+                Workspace::find_by_id(&pool, id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .archived
+            }
+        };
+        assert!(archived(orphan_workspace.id).await);
+        assert!(!archived(workspace.id).await);
+
+        let mut config = Config::default();
+        config.notifications.sound_enabled = false;
+        config.notifications.push_enabled = false;
+        let service = TaskTimeoutService {
+            db: DBService { pool: pool.clone() },
+            notification_service: NotificationService::new(
+                Arc::new(RwLock::new(config)),
+                pool.clone(),
+            ),
+            workspace_starter: None,
+            poll_interval: Duration::from_secs(10),
+            in_progress_timeout_minutes: 20,
+            in_review_timeout_minutes: 20,
+            unstarted_grace_minutes: 0,
+        };
+        service
+            .process_tasks_without_attempt(project.id)
+            .await
+            .unwrap();
+
+        let status = |id| {
+            let pool = pool.clone();
+            async move { Task::find_by_id(&pool, id).await.unwrap().unwrap().status }
+        };
+        assert_eq!(status(stuck.id).await, TaskStatus::Todo);
+        assert_eq!(status(orphaned.id).await, TaskStatus::Todo);
+        assert_eq!(status(running.id).await, TaskStatus::InProgress);
+    }
+
+    #[tokio::test]
+    async fn blocked_tasks_leave_timeouts_until_unblocked() {
+        let (pool, project) = test_support::project_pool().await;
+        let mut create =
+            CreateTask::from_title_description(project.id, "Call payments API".to_string(), None);
+        create.status = Some(TaskStatus::InProgress);
+        let task = Task::create(&pool, &create, Uuid::new_v4()).await.unwrap();
+
+        Task::block(
+            &pool,
+            task.id,
+            "Waiting on a payments API key",
+            Some("user"),
+        )
+        .await
+        .unwrap();
+        let blocked = Task::find_by_id(&pool, task.id).await.unwrap().unwrap();
+        assert_eq!(blocked.status, TaskStatus::Blocked);
+        assert_eq!(
+            blocked.blocked_reason.as_deref(),
+            Some("Waiting on a payments API key")
+        );
+        assert!(blocked.stage_started_at.is_none());
+        assert!(
+            Task::find_inprogress_without_attempt(&pool, project.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(!TaskStatus::Blocked.can_transition_to(&TaskStatus::Done));
+
+        Task::unblock(&pool, task.id, Some("user")).await.unwrap();
+        let unblocked = Task::find_by_id(&pool, task.id).await.unwrap().unwrap();
+        assert_eq!(unblocked.status, TaskStatus::Todo);
+        assert!(unblocked.blocked_reason.is_none());
+        assert!(matches!(
+            Task::unblock(&pool, task.id, Some("user")).await,
+            Err(TaskError::InvalidStatusTransition { .. })
+        ));
+
+        let history = TaskStatusHistory::find_by_task_id(&pool, task.id)
+            .await
+            .unwrap();
+        let transitions: Vec<_> = history
+            .iter()
+            .map(|entry| {
+                (
+                    entry.from_status.clone(),
+                    entry.to_status.clone(),
+                    entry.reason.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            transitions,
+            [
+                (
+                    TaskStatus::InProgress,
+                    TaskStatus::Blocked,
+                    Some("Waiting on a payments API key")
+                ),
+                (TaskStatus::Blocked, TaskStatus::Todo, Some("Unblocked")),
+            ]
+        );
+        assert!(
+            history
+                .iter()
+                .all(|entry| entry.actor.as_deref() == Some("user"))
+        );
+    }
+}
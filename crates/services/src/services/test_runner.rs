@@ -0,0 +1,172 @@
+//! Where review automation runs test commands: directly on the host, or inside the workspace's
+//! container so the container's toolchain and dependencies are used.
+//!
+//! A workspace is containerized when its `container_ref` has the form
+//! `docker://<container>/<path>`. The workspace directory is expected to be bind-mounted at the
+//! same `<path>` on the host and in the container, so git operations keep working on the host
+//! while commands run inside the container. Any other `container_ref` is a host path.
+
+use std::{path::Path, process::Stdio};
+
+use async_trait::async_trait;
+use tokio::process::Command;
+use utils::shell::get_shell_command;
+
+/// `container_ref` prefix naming a Docker container
+pub const DOCKER_REF_PREFIX: &str = "docker://";
+
+/// Where a workspace's files live, parsed from its `container_ref`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceLocation<'a> {
+    Host { path: &'a str },
+    Container { container: &'a str, path: &'a str },
+}
+
+impl<'a> WorkspaceLocation<'a> {
+    pub fn parse(container_ref: &'a str) -> Self {
+        let Some(rest) = container_ref.strip_prefix(DOCKER_REF_PREFIX) else {
+            return Self::Host {
+                path: container_ref,
+            };
+        };
+        match rest.find('/') {
+            Some(idx) if idx > 0 => Self::Container {
+                container: &rest[..idx],
+                path: &rest[idx..],
+            },
+            // No path after the container name: treat the ref as a plain host path
+            _ => Self::Host {
+                path: container_ref,
+            },
+        }
+    }
+
+    /// The workspace directory, which is the same on the host and in the container
+    pub fn path(&self) -> &'a str {
+        match self {
+            Self::Host { path } | Self::Container { path, .. } => path,
+        }
+    }
+}
+
+/// Runs test and gate commands for a workspace
+#[async_trait]
+pub trait TestRunner: Send + Sync {
+    /// Run `program` with `args` in `dir`, returning whether it exited 0 and its combined output
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&str],
+        dir: &Path,
+    ) -> std::io::Result<(bool, String)>;
+
+    /// The shell and its "run this string" flag for free-form commands
+    fn shell(&self) -> (String, &'static str);
+}
+
+/// Runs commands directly on the host
+pub struct HostTestRunner;
+
+#[async_trait]
+impl TestRunner for HostTestRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&str],
+        dir: &Path,
+    ) -> std::io::Result<(bool, String)> {
+        let mut command = Command::new(program);
+        command.args(args).current_dir(dir);
+        run_command(command).await
+    }
+
+    fn shell(&self) -> (String, &'static str) {
+        get_shell_command()
+    }
+}
+
+/// Runs commands inside a Docker container with `docker exec`
+pub struct ContainerTestRunner {
+    container: String,
+}
+
+impl ContainerTestRunner {
+    pub fn new(container: impl Into<String>) -> Self {
+        Self {
+            container: container.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl TestRunner for ContainerTestRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&str],
+        dir: &Path,
+    ) -> std::io::Result<(bool, String)> {
+        let mut command = Command::new("docker");
+        command
+            .arg("exec")
+            .arg("--workdir")
+            .arg(dir)
+            .arg(&self.container)
+            .arg(program)
+            .args(args);
+        run_command(command).await
+    }
+
+    fn shell(&self) -> (String, &'static str) {
+        ("sh".to_string(), "-c")
+    }
+}
+
+/// The runner for a workspace at `location`
+pub fn test_runner_for(location: &WorkspaceLocation<'_>) -> Box<dyn TestRunner> {
+    match location {
+        WorkspaceLocation::Host { .. } => Box::new(HostTestRunner),
+        WorkspaceLocation::Container { container, .. } => {
+            Box::new(ContainerTestRunner::new(*container))
+        }
+    }
+}
+
+async fn run_command(mut command: Command) -> std::io::Result<(bool, String)> {
+    let output = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined_output = format!("STDOUT:\n{}\n\nSTDERR:\n{}", stdout, stderr);
+    Ok((output.status.success(), combined_output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_host_and_container_refs() {
+        assert_eq!(
+            WorkspaceLocation::parse("/tmp/worktrees/abcd"),
+            WorkspaceLocation::Host {
+                path: "/tmp/worktrees/abcd"
+            }
+        );
+        assert_eq!(
+            WorkspaceLocation::parse("docker://vk-abcd/tmp/worktrees/abcd"),
+            WorkspaceLocation::Container {
+                container: "vk-abcd",
+                path: "/tmp/worktrees/abcd"
+            }
+        );
+        assert_eq!(
+            WorkspaceLocation::parse("docker://vk-abcd").path(),
+            "docker://vk-abcd"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Fixtures shared by the service unit tests.
+
+use axum::{Json, Router, routing::post};
+use chrono::Utc;
+use db::models::{
+    execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason},
+    project::{CreateProject, Project},
+    session::{CreateSession, Session},
+    task::{Task, TaskStatus},
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use uuid::Uuid;
+
+use crate::services::claude_api::ClaudeApiClient;
+
+/// A migrated in-memory database holding a single project with no repositories
+pub async fn project_pool() -> (SqlitePool, Project) {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+
+    let project = Project::create(
+        &pool,
+        &CreateProject {
+            name: "Project".to_string(),
+            repositories: Vec::new(),
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap();
+    (pool, project)
+}
+
+/// A Claude client served by a local stub that answers every request with `reply` as the
+/// response text, so tests never reach the real API
+pub async fn claude_stub(reply: &str) -> ClaudeApiClient {
+    let response = serde_json::json!({
+        "id": "msg_stub",
+        "content": [{ "type": "text", "text": reply }],
+        "model": "stub",
+        "stop_reason": "end_turn",
+        "usage": { "input_tokens": 0, "output_tokens": 0 },
+    });
+    let app = Router::new().route(
+        "/v1/messages",
+        post(move || {
+            let response = response.clone();
+            async move { Json(response) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    ClaudeApiClient::new("test-key".to_string(), None)
+        .unwrap()
+        .with_api_url(format!("http://{addr}/v1/messages"))
+}
+
+/// A running coding agent process in a new session of `workspace_id`
+pub async fn start_process(pool: &SqlitePool, workspace_id: Uuid) -> ExecutionProcess {
+    let session = Session::create(
+        pool,
+        &CreateSession { executor: None },
+        Uuid::new_v4(),
+        workspace_id,
+    )
+    .await
+    .unwrap();
+    ExecutionProcess::create(
+        pool,
+        &CreateExecutionProcess {
+            session_id: session.id,
+            executor_action: ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: "true".to_string(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::SetupScript,
+                    working_dir: None,
+                }),
+                None,
+            ),
+            run_reason: ExecutionProcessRunReason::CodingAgent,
+        },
+        Uuid::new_v4(),
+        &[],
+    )
+    .await
+    .unwrap()
+}
+
+/// A Todo task with `title` and every optional field unset. Tests override what they need
+/// with struct update syntax, e.g. `Task { layer, ..task("Add login") }`.
+pub fn task(title: &str) -> Task {
+    let now = Utc::now();
+    Task {
+        id: Uuid::new_v4(),
+        project_id: Uuid::nil(),
+        title: title.to_string(),
+        description: None,
+        status: TaskStatus::Todo,
+        parent_workspace_id: None,
+        source: Default::default(),
+        layer: None,
+        task_type: None,
+        sequence: None,
+        testing_criteria: None,
+        stage_started_at: None,
+        complexity_score: None,
+        parent_task_id: None,
+        prevent_breakdown: false,
+        post_task_actions: None,
+        source_feature: None,
+        archived: false,
+        needs_attention_reason: None,
+        commit_count: None,
+        requirements_id: None,
+        blocked_reason: None,
+        edited_at: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
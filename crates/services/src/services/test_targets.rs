@@ -0,0 +1,256 @@
+//! Rules mapping changed files to targeted test commands, so review automation can run only
+//! the tests affected by a branch instead of the full suite.
+//!
+//! Rules are written one per line as `<glob> => <command>`, e.g.
+//! `crates/db/**/*.rs => cargo test -p db`. Globs are matched against paths relative to the
+//! repo root. Blank lines and lines starting with `#` are ignored.
+//!
+//! Test profiles pick validation by task type instead, in the same format with a task type in
+//! place of the glob, e.g. `architecture => cargo check --workspace`. A task whose type has a
+//! profile runs only that profile's commands.
+
+use std::str::FromStr;
+
+use db::models::task::TaskType;
+use globset::{Glob, GlobMatcher};
+
+/// Separator between a rule's glob and its command
+const RULE_SEPARATOR: &str = "=>";
+
+/// A parsed `glob => command` rule
+#[derive(Debug, Clone)]
+pub struct TestTarget {
+    matcher: GlobMatcher,
+    pub command: String,
+}
+
+impl TestTarget {
+    pub fn glob(&self) -> &str {
+        self.matcher.glob().glob()
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.matcher.is_match(path)
+    }
+}
+
+/// A parsed `task type => command` rule
+#[derive(Debug, Clone)]
+pub struct TestProfile {
+    pub task_type: TaskType,
+    pub command: String,
+}
+
+/// Split rules into `(line number, key, command)`, where the key is named `key` in errors
+fn parse_rules<'a>(rules: &'a str, key: &str) -> Result<Vec<(usize, &'a str, &'a str)>, String> {
+    let mut parsed = Vec::new();
+    for (index, line) in rules.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line_number = index + 1;
+        let Some((lhs, command)) = line.split_once(RULE_SEPARATOR) else {
+            return Err(format!(
+                "line {line_number}: expected '<{key}> {RULE_SEPARATOR} <command>'"
+            ));
+        };
+        let (lhs, command) = (lhs.trim(), command.trim());
+        if lhs.is_empty() || command.is_empty() {
+            return Err(format!(
+                "line {line_number}: both a {key} and a command are required"
+            ));
+        }
+        parsed.push((line_number, lhs, command));
+    }
+    Ok(parsed)
+}
+
+/// Parse the configured rules. Errors name the offending line.
+pub fn parse_test_targets(rules: &str) -> Result<Vec<TestTarget>, String> {
+    parse_rules(rules, "glob")?
+        .into_iter()
+        .map(|(line_number, glob, command)| {
+            let matcher = Glob::new(glob)
+                .map_err(|e| format!("line {line_number}: invalid glob '{glob}': {e}"))?
+                .compile_matcher();
+            Ok(TestTarget {
+                matcher,
+                command: command.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse the configured test profiles. Errors name the offending line.
+pub fn parse_test_profiles(rules: &str) -> Result<Vec<TestProfile>, String> {
+    parse_rules(rules, "task type")?
+        .into_iter()
+        .map(|(line_number, task_type, command)| {
+            let task_type = TaskType::from_str(&task_type.to_lowercase()).map_err(|_| {
+                format!(
+                    "line {line_number}: unknown task type '{task_type}', expected \
+                     architecture, implementation, testing or integration"
+                )
+            })?;
+            Ok(TestProfile {
+                task_type,
+                command: command.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The commands of the profile for `task_type`, in rule order. Empty when the type has no
+/// profile, meaning the usual test selection applies.
+pub fn profile_commands<'a>(profiles: &'a [TestProfile], task_type: &TaskType) -> Vec<&'a str> {
+    profiles
+        .iter()
+        .filter(|profile| &profile.task_type == task_type)
+        .map(|profile| profile.command.as_str())
+        .collect()
+}
+
+/// The commands whose globs match any of the changed files, in rule order and without
+/// duplicates. None when some changed file matches no rule, meaning the full suite should run.
+pub fn matching_commands<'a>(
+    targets: &'a [TestTarget],
+    changed_files: &[String],
+) -> Option<Vec<&'a str>> {
+    if !changed_files
+        .iter()
+        .all(|file| targets.iter().any(|target| target.matches(file)))
+    {
+        return None;
+    }
+
+    let mut commands: Vec<&str> = Vec::new();
+    for target in targets {
+        if !commands.contains(&target.command.as_str())
+            && changed_files.iter().any(|file| target.matches(file))
+        {
+            commands.push(&target.command);
+        }
+    }
+    Some(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = "
+        # Backend crates
+        crates/db/**/*.rs => cargo test -p db
+        crates/server/** => cargo test -p server
+        *.sql => cargo test -p db
+
+        frontend/src/**/*.{ts,tsx} => pnpm test
+    ";
+
+    fn files(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_rules_and_skips_comments() {
+        let targets = parse_test_targets(RULES).unwrap();
+        let globs: Vec<&str> = targets.iter().map(TestTarget::glob).collect();
+        assert_eq!(
+            globs,
+            vec![
+                "crates/db/**/*.rs",
+                "crates/server/**",
+                "*.sql",
+                "frontend/src/**/*.{ts,tsx}",
+            ]
+        );
+        assert_eq!(targets[0].command, "cargo test -p db");
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        assert!(
+            parse_test_targets("crates/db/**")
+                .unwrap_err()
+                .starts_with("line 1:")
+        );
+        assert!(
+            parse_test_targets("\n => cargo test")
+                .unwrap_err()
+                .starts_with("line 2:")
+        );
+        assert!(parse_test_targets("[ => cargo test").is_err());
+        assert!(parse_test_targets("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn selects_matching_commands_once() {
+        let targets = parse_test_targets(RULES).unwrap();
+
+        let changed = files(&[
+            "crates/db/src/models/task.rs",
+            "crates/db/migrations/20260101000000_add.sql",
+        ]);
+        assert_eq!(
+            matching_commands(&targets, &changed),
+            Some(vec!["cargo test -p db"])
+        );
+
+        let changed = files(&["crates/server/src/main.rs", "frontend/src/App.tsx"]);
+        assert_eq!(
+            matching_commands(&targets, &changed),
+            Some(vec!["cargo test -p server", "pnpm test"])
+        );
+    }
+
+    #[test]
+    fn unmatched_changes_select_nothing() {
+        let targets = parse_test_targets(RULES).unwrap();
+        let changed = files(&["README.md", "crates/db/Cargo.toml"]);
+        assert_eq!(matching_commands(&targets, &changed), None);
+    }
+
+    #[test]
+    fn any_uncovered_change_falls_back_to_the_full_suite() {
+        let targets = parse_test_targets(RULES).unwrap();
+        let changed = files(&["crates/db/src/models/task.rs", "crates/utils/src/lib.rs"]);
+        assert_eq!(matching_commands(&targets, &changed), None);
+        assert_eq!(matching_commands(&targets, &[]), Some(Vec::new()));
+    }
+
+    #[test]
+    fn profiles_select_commands_by_task_type() {
+        let profiles = parse_test_profiles(
+            "
+            # Schema and contract changes only need to compile
+            Architecture => cargo check --workspace
+            architecture => pnpm run check
+            integration => pnpm run test:e2e
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            profile_commands(&profiles, &TaskType::Architecture),
+            vec!["cargo check --workspace", "pnpm run check"]
+        );
+        assert_eq!(
+            profile_commands(&profiles, &TaskType::Integration),
+            vec!["pnpm run test:e2e"]
+        );
+        assert!(profile_commands(&profiles, &TaskType::Implementation).is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_task_types() {
+        let err = parse_test_profiles("design => cargo check").unwrap_err();
+        assert!(err.starts_with("line 1: unknown task type 'design'"));
+        assert!(
+            parse_test_profiles("architecture")
+                .unwrap_err()
+                .contains("<task type>")
+        );
+    }
+}
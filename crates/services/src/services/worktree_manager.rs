@@ -60,7 +60,8 @@ impl WorktreeManager {
         let _ = WORKSPACE_DIR_OVERRIDE.set(path);
     }
 
-    /// Create a worktree with a new branch
+    /// Create a worktree, branching it off `base_branch` first when `create_branch` is set. An
+    /// existing branch of that name is reused unless it's checked out elsewhere.
     pub async fn create_worktree(
         repo_path: &Path,
         branch_name: &str,
@@ -72,17 +73,15 @@ impl WorktreeManager {
             let repo_path_owned = repo_path.to_path_buf();
             let branch_name_owned = branch_name.to_string();
             let base_branch_owned = base_branch.to_string();
+            let worktree_path_owned = worktree_path.to_path_buf();
 
             tokio::task::spawn_blocking(move || {
-                let repo = Repository::open(&repo_path_owned)?;
-                let base_branch_ref =
-                    GitService::find_branch(&repo, &base_branch_owned)?.into_reference();
-                repo.branch(
+                GitService::new().create_branch_for_worktree(
+                    &repo_path_owned,
                     &branch_name_owned,
-                    &base_branch_ref.peel_to_commit()?,
-                    false,
-                )?;
-                Ok::<(), GitServiceError>(())
+                    &base_branch_owned,
+                    &worktree_path_owned,
+                )
             })
             .await
             .map_err(|e| WorktreeError::TaskJoin(format!("Task join error: {e}")))??;
@@ -5,7 +5,7 @@ use std::{
 };
 
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
-use services::services::git::{GitCli, GitCliError, GitService};
+use services::services::git::{GitCli, GitCliError, GitService, GitServiceError};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
 
@@ -598,6 +598,223 @@ fn merge_preserves_unstaged_changes_on_base() {
     assert_eq!(m, "merged content\n");
 }
 
+#[test]
+fn signed_merge_falls_back_to_unsigned_without_key() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+
+    let sha = s
+        .merge_changes_with_signing(&repo_path, &worktree_path, "feature", "main", "squash", true)
+        .unwrap();
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let oid = git2::Oid::from_str(&sha).unwrap();
+    assert!(repo.extract_signature(&oid, None).is_err());
+    assert_eq!(sha, s.get_branch_oid(&repo_path, "main").unwrap());
+}
+
+#[test]
+fn signed_merge_uses_configured_ssh_key() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let key_path = td.path().join("signing_key");
+    let keygen = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&key_path)
+        .status();
+    if !keygen.is_ok_and(|status| status.success()) {
+        eprintln!("ssh-keygen unavailable; skipping");
+        return;
+    }
+    let repo = Repository::open(&repo_path).unwrap();
+    let mut cfg = repo.config().unwrap();
+    cfg.set_str("gpg.format", "ssh").unwrap();
+    cfg.set_str(
+        "user.signingkey",
+        key_path.with_extension("pub").to_str().unwrap(),
+    )
+    .unwrap();
+
+    let s = GitService::new();
+    let sha = s
+        .merge_changes_with_signing(&repo_path, &worktree_path, "feature", "main", "squash", true)
+        .unwrap();
+
+    let oid = git2::Oid::from_str(&sha).unwrap();
+    let (signature, _) = repo.extract_signature(&oid, None).unwrap();
+    assert!(signature.as_str().unwrap().contains("BEGIN SSH SIGNATURE"));
+    assert_eq!(sha, s.get_branch_oid(&repo_path, "main").unwrap());
+    let merged = repo.find_commit(oid).unwrap();
+    assert!(merged.tree().unwrap().get_name("feat.txt").is_some());
+}
+
+#[test]
+fn fast_forward_merge_moves_base_to_task_head() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+
+    write_file(&worktree_path, "ff.txt", "fast forward\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "feature ff");
+
+    assert!(s.can_fast_forward(&repo_path, "feature", "main").unwrap());
+    let sha = s.fast_forward_merge(&repo_path, "feature", "main").unwrap();
+
+    // main now points at the feature head, with no extra merge commit
+    assert_eq!(sha, s.get_branch_oid(&repo_path, "feature").unwrap());
+    assert_eq!(sha, s.get_branch_oid(&repo_path, "main").unwrap());
+    let m = std::fs::read_to_string(repo_path.join("ff.txt")).unwrap();
+    assert_eq!(m, "fast forward\n");
+    assert!(!s.can_fast_forward(&repo_path, "feature", "main").unwrap());
+}
+
+#[test]
+fn can_fast_forward_false_when_diverged() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+
+    write_file(&worktree_path, "feat.txt", "feat\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "feature change");
+    write_file(&repo_path, "base.txt", "base\n");
+    commit_all(&repo, "base change");
+
+    assert!(!s.can_fast_forward(&repo_path, "feature", "main").unwrap());
+    assert!(s.fast_forward_merge(&repo_path, "feature", "main").is_err());
+}
+
+#[test]
+fn branch_has_commits_ahead_detects_empty_branches() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_no_unique_feature_repo(&td);
+    let s = GitService::new();
+
+    assert!(!s
+        .branch_has_commits_ahead(&worktree_path, "feature", "old-base")
+        .unwrap());
+
+    write_file(&worktree_path, "feat.txt", "feat\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "feature change");
+
+    assert!(s
+        .branch_has_commits_ahead(&repo_path, "feature", "old-base")
+        .unwrap());
+}
+
+#[test]
+fn remove_worktree_tolerates_missing_directory() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+
+    std::fs::remove_dir_all(&worktree_path).unwrap();
+    s.remove_worktree(&repo_path, &worktree_path, true).unwrap();
+
+    // The stale registration is pruned, so the path can be reused
+    s.add_worktree(&repo_path, &worktree_path, "feature", false)
+        .unwrap();
+    assert!(worktree_path.join("feat.txt").exists());
+}
+
+#[test]
+fn would_conflict_reports_files_and_restores_worktree() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let s = GitService::new();
+    let before = s.get_head_info(&worktree_path).unwrap().oid;
+
+    let conflicts = s
+        .would_conflict(&worktree_path, "feature", "new-base")
+        .unwrap();
+    assert_eq!(conflicts, Some(vec!["conflict.txt".to_string()]));
+
+    // No merge left behind and the worktree content is untouched
+    assert!(s.detect_conflict_op(&worktree_path).unwrap().is_none());
+    assert_eq!(s.get_head_info(&worktree_path).unwrap().oid, before);
+    assert!(s.is_worktree_clean(&worktree_path).unwrap());
+    let content = std::fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "feature version\n");
+}
+
+#[test]
+fn would_conflict_leaves_uncommitted_changes_alone() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    write_file(&worktree_path, "conflict.txt", "work in progress\n");
+    let s = GitService::new();
+
+    let conflicts = s
+        .would_conflict(&worktree_path, "feature", "new-base")
+        .unwrap();
+    assert_eq!(conflicts, Some(vec!["conflict.txt".to_string()]));
+
+    let content = std::fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "work in progress\n");
+    assert!(s.detect_conflict_op(&worktree_path).unwrap().is_none());
+}
+
+#[test]
+fn would_conflict_none_for_clean_merge() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "main.txt", "main only\n");
+    commit_all(&repo, "main change");
+
+    assert_eq!(
+        s.would_conflict(&worktree_path, "feature", "main").unwrap(),
+        None
+    );
+    assert!(!worktree_path.join("main.txt").exists());
+    assert!(s.detect_conflict_op(&worktree_path).unwrap().is_none());
+}
+
+#[test]
+fn find_commit_by_task_id_matches_trailer_and_note() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+
+    // Squash merge carries the trailer in its message
+    let merged_task = uuid::Uuid::new_v4();
+    let message = GitService::with_task_id_trailer("squash", merged_task);
+    let sha = s
+        .merge_changes(&repo_path, &worktree_path, "feature", "main", &message)
+        .unwrap();
+    assert_eq!(
+        s.find_commit_by_task_id(&repo_path, "main", merged_task)
+            .unwrap(),
+        Some(sha.clone())
+    );
+
+    // A commit without a trailer can be tagged with a note instead
+    let noted_task = uuid::Uuid::new_v4();
+    s.add_task_id_note(&repo_path, &sha, noted_task).unwrap();
+    assert_eq!(
+        s.find_commit_by_task_id(&repo_path, "main", noted_task)
+            .unwrap(),
+        Some(sha)
+    );
+
+    assert_eq!(
+        s.find_commit_by_task_id(&repo_path, "main", uuid::Uuid::new_v4())
+            .unwrap(),
+        None
+    );
+}
+
 #[test]
 fn update_ref_does_not_destroy_feature_worktree_dirty_state() {
     let td = TempDir::new().unwrap();
@@ -1406,3 +1623,278 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn default_branch_prefers_origin_head_then_existing_branches() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let repo = Repository::init(&repo_path).unwrap();
+    configure_user(&repo);
+    // Override any global init.defaultBranch so detection falls through to local branches
+    repo.config().unwrap().set_str("init.defaultBranch", "").unwrap();
+    write_file(&repo_path, "README.md", "hello\n");
+    commit_all(&repo, "init");
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    if repo.find_branch("master", git2::BranchType::Local).is_err() {
+        repo.branch("master", &head, false).unwrap();
+    }
+    repo.set_head("refs/heads/master").unwrap();
+    if let Ok(mut main) = repo.find_branch("main", git2::BranchType::Local) {
+        main.delete().unwrap();
+    }
+
+    let s = GitService::new();
+    assert_eq!(s.default_branch(&repo_path).unwrap(), "master");
+
+    let other_path = td.path().join("other");
+    let other = Repository::init(&other_path).unwrap();
+    configure_user(&other);
+    write_file(&other_path, "README.md", "hello\n");
+    commit_all(&other, "init");
+    let head = other.head().unwrap().peel_to_commit().unwrap();
+    other.reference("refs/remotes/origin/trunk", head.id(), true, "test").unwrap();
+    other
+        .reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/trunk",
+            true,
+            "test",
+        )
+        .unwrap();
+    assert_eq!(s.default_branch(&other_path).unwrap(), "trunk");
+}
+
+#[test]
+fn default_branch_prefers_existing_branch_over_missing_configured_one() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let repo = Repository::init(&repo_path).unwrap();
+    configure_user(&repo);
+    write_file(&repo_path, "README.md", "hello\n");
+    commit_all(&repo, "init");
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    if repo.find_branch("master", git2::BranchType::Local).is_err() {
+        repo.branch("master", &head, false).unwrap();
+    }
+    repo.set_head("refs/heads/master").unwrap();
+    if let Ok(mut main) = repo.find_branch("main", git2::BranchType::Local) {
+        main.delete().unwrap();
+    }
+    repo.config()
+        .unwrap()
+        .set_str("init.defaultBranch", "develop")
+        .unwrap();
+
+    let s = GitService::new();
+    assert_eq!(s.default_branch(&repo_path).unwrap(), "master");
+
+    // With no branches at all, the configured name is still used
+    let other_path = td.path().join("other");
+    let other = Repository::init(&other_path).unwrap();
+    configure_user(&other);
+    other
+        .config()
+        .unwrap()
+        .set_str("init.defaultBranch", "develop")
+        .unwrap();
+    assert_eq!(s.default_branch(&other_path).unwrap(), "develop");
+}
+
+#[test]
+fn changed_files_lists_only_branch_changes_since_fork() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+
+    // The target branch moves on after the fork; its changes are not the branch's
+    let repo = Repository::open(&repo_path).unwrap();
+    write_file(&repo_path, "target_only.txt", "target change\n");
+    commit_all(&repo, "old-base advances");
+
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    write_file(&worktree_path, "src/lib.rs", "pub fn feature() {}\n");
+    commit_all(&wt_repo, "feature adds lib");
+
+    let s = GitService::new();
+    let mut changed = s.changed_files(&worktree_path, "old-base").unwrap();
+    changed.sort();
+    assert_eq!(
+        changed,
+        vec!["feat.txt".to_string(), "src/lib.rs".to_string()]
+    );
+}
+
+#[test]
+fn create_worktree_branches_off_target_and_reuses_existing_branch() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, feature_worktree) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+
+    let new_worktree = td.path().join("worktrees").join("wt-task");
+    let path = s
+        .create_worktree(&repo_path, "task", "new-base", &new_worktree)
+        .unwrap();
+    assert_eq!(path, new_worktree);
+    assert_eq!(
+        fs::read_to_string(new_worktree.join("base.txt")).unwrap(),
+        "from new-base\n"
+    );
+
+    // Once the worktree is gone, the branch (and its commits) is picked up again
+    let wt_repo = Repository::open(&new_worktree).unwrap();
+    write_file(&new_worktree, "task.txt", "task change\n");
+    commit_all(&wt_repo, "task commit");
+    s.remove_worktree(&repo_path, &new_worktree, true).unwrap();
+    s.create_worktree(&repo_path, "task", "new-base", &new_worktree)
+        .unwrap();
+    assert!(new_worktree.join("task.txt").exists());
+
+    // A branch checked out in another worktree can't be used
+    let other_worktree = td.path().join("wt-other");
+    let err = s
+        .create_worktree(&repo_path, "feature", "old-base", &other_worktree)
+        .unwrap_err();
+    match err {
+        GitServiceError::BranchCheckedOut(branch, path) => {
+            assert_eq!(branch, "feature");
+            assert!(path.ends_with("wt-feature"), "{path}");
+        }
+        other => panic!("expected BranchCheckedOut, got {other}"),
+    }
+    assert!(feature_worktree.exists());
+}
+
+fn branch_has_file(repo: &Repository, branch: &str, path: &str) -> bool {
+    repo.find_branch(branch, git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_tree()
+        .unwrap()
+        .get_path(Path::new(path))
+        .is_ok()
+}
+
+#[test]
+fn revert_merge_undoes_squash_merge_once() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    // Keep the target branch out of any working tree so both run in memory
+    checkout_branch(&repo, "main");
+
+    let s = GitService::new();
+    let merge_sha = s
+        .merge_changes(&repo_path, &worktree_path, "feature", "old-base", "squash")
+        .unwrap();
+    assert!(branch_has_file(&repo, "old-base", "feat.txt"));
+
+    assert!(matches!(
+        s.revert_merge(&repo_path, &merge_sha, None, "new-base"),
+        Err(GitServiceError::InvalidRepository(_))
+    ));
+
+    let revert_sha = s
+        .revert_merge(&repo_path, &merge_sha, None, "old-base")
+        .unwrap();
+    assert!(!branch_has_file(&repo, "old-base", "feat.txt"));
+    assert!(branch_has_file(&repo, "old-base", "base.txt"));
+    let revert = repo
+        .find_commit(git2::Oid::from_str(&revert_sha).unwrap())
+        .unwrap();
+    assert!(
+        revert
+            .message()
+            .unwrap()
+            .contains(&format!("This reverts commit {merge_sha}"))
+    );
+
+    assert!(matches!(
+        s.revert_merge(&repo_path, &merge_sha, None, "old-base"),
+        Err(GitServiceError::AlreadyReverted(sha)) if sha == merge_sha
+    ));
+}
+
+#[test]
+fn revert_merge_on_checked_out_target_updates_working_tree() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+
+    // old-base is checked out in the main repo, so the merge and revert go through the CLI
+    let s = GitService::new();
+    let merge_sha = s
+        .merge_changes(&repo_path, &worktree_path, "feature", "old-base", "squash")
+        .unwrap();
+    assert!(repo_path.join("feat.txt").exists());
+
+    s.revert_merge(&repo_path, &merge_sha, None, "old-base")
+        .unwrap();
+    assert!(!repo_path.join("feat.txt").exists());
+    assert!(!branch_has_file(&repo, "old-base", "feat.txt"));
+    assert!(matches!(
+        s.revert_merge(&repo_path, &merge_sha, None, "old-base"),
+        Err(GitServiceError::AlreadyReverted(_))
+    ));
+}
+
+#[test]
+fn revert_merge_undoes_every_fast_forwarded_commit() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+
+    // A task commit followed by a .progress commit, fast-forwarded onto old-base
+    let s = GitService::new();
+    let base_sha = s.get_branch_oid(&repo_path, "old-base").unwrap();
+    write_file(&worktree_path, ".progress", "notes\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "progress commit");
+    let head_sha = s
+        .fast_forward_merge(&repo_path, "feature", "old-base")
+        .unwrap();
+    assert!(branch_has_file(&repo, "old-base", "feat.txt"));
+
+    let revert_sha = s
+        .revert_merge(&repo_path, &head_sha, Some(&base_sha), "old-base")
+        .unwrap();
+    assert!(!branch_has_file(&repo, "old-base", "feat.txt"));
+    assert!(!branch_has_file(&repo, "old-base", ".progress"));
+    assert!(branch_has_file(&repo, "old-base", "base.txt"));
+    let revert = repo
+        .find_commit(git2::Oid::from_str(&revert_sha).unwrap())
+        .unwrap();
+    assert_eq!(revert.parent_id(0).unwrap().to_string(), head_sha);
+
+    assert!(matches!(
+        s.revert_merge(&repo_path, &head_sha, Some(&base_sha), "old-base"),
+        Err(GitServiceError::AlreadyReverted(sha)) if sha == head_sha
+    ));
+}
+
+#[test]
+fn reset_branch_undoes_merge_only_while_branch_is_unmoved() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+
+    // old-base is checked out in the main repo, so the reset goes through the CLI
+    let s = GitService::new();
+    let previous_head = s.get_branch_oid(&repo_path, "old-base").unwrap();
+    let merge_sha = s
+        .merge_changes(&repo_path, &worktree_path, "feature", "old-base", "squash")
+        .unwrap();
+
+    assert!(matches!(
+        s.reset_branch(&repo_path, "old-base", &previous_head, &previous_head),
+        Err(GitServiceError::InvalidRepository(_))
+    ));
+    assert!(branch_has_file(&repo, "old-base", "feat.txt"));
+
+    s.reset_branch(&repo_path, "old-base", &merge_sha, &previous_head)
+        .unwrap();
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "old-base").unwrap(),
+        previous_head
+    );
+    assert!(!repo_path.join("feat.txt").exists());
+}
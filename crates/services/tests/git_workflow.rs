@@ -308,6 +308,97 @@ fn branch_status_ahead_and_behind() {
     assert_eq!((ahead2, behind2), (2, 1));
 }
 
+#[test]
+fn commit_count_between_ignores_target_only_commits() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+
+    write_file(&repo_path, "base.txt", "base\n");
+    let _ = s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    let count = |target, branch| s.commit_count_between(&repo_path, target, branch).unwrap();
+    assert_eq!(count("main", "feature"), 0);
+
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "f1.txt", "f1\n");
+    let _ = s.commit(&repo_path, "f1").unwrap();
+    write_file(&repo_path, "f2.txt", "f2\n");
+    let _ = s.commit(&repo_path, "f2").unwrap();
+
+    checkout_branch(&repo_path, "main");
+    write_file(&repo_path, "main.txt", "m1\n");
+    let _ = s.commit(&repo_path, "m1").unwrap();
+
+    assert_eq!(count("main", "feature"), 2);
+    assert_eq!(count("feature", "main"), 1);
+}
+
+#[test]
+fn cherry_pick_applies_single_commit_onto_moved_target() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+
+    write_file(&repo_path, "base.txt", "base\n");
+    let _ = s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "main.txt", "m1\n");
+    let _ = s.commit(&repo_path, "m1").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "f1.txt", "f1\n");
+    let _ = s.commit(&repo_path, "f1").unwrap();
+
+    // main is not checked out, so the cherry-pick happens in memory
+    let sha = s
+        .cherry_pick(&repo_path, &repo_path, "feature", "main")
+        .unwrap();
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let main_head = repo
+        .find_branch("main", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(main_head.id().to_string(), sha);
+    assert_eq!(main_head.message(), Some("f1"));
+    assert_eq!(main_head.parent_count(), 1);
+    let tree = main_head.tree().unwrap();
+    assert!(tree.get_name("f1.txt").is_some());
+    assert!(tree.get_name("main.txt").is_some());
+}
+
+#[test]
+fn cherry_pick_reports_conflicts_and_leaves_checkout_clean() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+
+    write_file(&repo_path, "shared.txt", "base\n");
+    let _ = s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "shared.txt", "main\n");
+    let _ = s.commit(&repo_path, "main change").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "shared.txt", "feature\n");
+    let _ = s.commit(&repo_path, "feature change").unwrap();
+    checkout_branch(&repo_path, "main");
+
+    // main is checked out, so the cherry-pick runs through the CLI in its working tree
+    let res = s.cherry_pick(&repo_path, &repo_path, "feature", "main");
+    assert!(res.is_err());
+    assert!(
+        !GitCli::new()
+            .is_cherry_pick_in_progress(&repo_path)
+            .unwrap()
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("shared.txt")).unwrap(),
+        "main\n"
+    );
+}
+
 #[test]
 fn get_all_branches_lists_current_and_others() {
     let td = TempDir::new().unwrap();
@@ -39,6 +39,16 @@ impl<T, E> ApiResponse<T, E> {
         }
     }
 
+    /// Creates an error response, with both a `message` and structured `error_data`.
+    pub fn error_with_message_and_data(message: &str, data: E) -> Self {
+        ApiResponse {
+            success: false,
+            data: None,
+            message: Some(message.to_string()),
+            error_data: Some(data),
+        }
+    }
+
     /// Returns true if the response was successful.
     pub fn is_success(&self) -> bool {
         self.success
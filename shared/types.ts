@@ -4,11 +4,24 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, 
+/**
+ * Description, tech stack and conventions given to the AI when generating work
+ */
+context: string | null, 
+/**
+ * Org-specific guidance prepended to the system prompt of every AI call for the project
+ */
+system_prompt_prefix: string | null, 
+/**
+ * Provider for pull/merge requests ("github", "gitlab" or "azure_devops"); None detects it
+ * from the remote URL
+ */
+git_host_provider: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, };
+export type UpdateProject = { name: string | null, context: string | null, system_prompt_prefix: string | null, git_host_provider: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -38,55 +51,133 @@ export type CreateTag = { tag_name: string, content: string, };
 
 export type UpdateTag = { tag_name: string | null, content: string | null, };
 
-export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
+export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled" | "blocked";
 
 export type TaskSource = "manual" | "ai_generated";
 
 export type TaskLayer = "data" | "backend" | "frontend" | "fullstack" | "devops" | "testing";
 
-export type TaskType = "architecture" | "implementation" | "integration";
+export type TaskType = "architecture" | "implementation" | "testing" | "integration";
+
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, source: TaskSource, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, stage_started_at: string | null, complexity_score: number | null, parent_task_id: string | null, prevent_breakdown: boolean, post_task_actions: string | null, source_feature: string | null, archived: boolean, needs_attention_reason: string | null, commit_count: number | null, requirements_id: string | null, blocked_reason: string | null, edited_at: string | null, created_at: string, updated_at: string, };
+
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, source: TaskSource, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, stage_started_at: string | null, complexity_score: number | null, parent_task_id: string | null, prevent_breakdown: boolean, post_task_actions: string | null, source_feature: string | null, archived: boolean, needs_attention_reason: string | null, commit_count: number | null, requirements_id: string | null, blocked_reason: string | null, edited_at: string | null, created_at: string, updated_at: string, };
+
+export type TaskStatsGroup = { status: TaskStatus, layer: TaskLayer | null, task_type: TaskType | null, source: TaskSource, count: bigint, };
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, source: TaskSource, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, stage_started_at: string | null, complexity_score: number | null, parent_task_id: string | null, prevent_breakdown: boolean, post_task_actions: string | null, created_at: string, updated_at: string, };
+export type ProjectTaskStats = { total: bigint, by_status: { [key in string]?: bigint }, by_layer: { [key in string]?: bigint }, by_task_type: { [key in string]?: bigint }, by_source: { [key in string]?: bigint }, needs_grooming: bigint, groups: Array<TaskStatsGroup>, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, source: TaskSource, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, stage_started_at: string | null, complexity_score: number | null, parent_task_id: string | null, prevent_breakdown: boolean, post_task_actions: string | null, created_at: string, updated_at: string, };
+export type TaskWithImages = { image_ids: Array<string>, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, source: TaskSource, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, stage_started_at: string | null, complexity_score: number | null, parent_task_id: string | null, prevent_breakdown: boolean, post_task_actions: string | null, source_feature: string | null, archived: boolean, needs_attention_reason: string | null, commit_count: number | null, requirements_id: string | null, blocked_reason: string | null, edited_at: string | null, created_at: string, updated_at: string, };
+
+export type TaskDetails = { image_ids: Array<string>, parent_task: Task | null, subtasks: Array<Task>, dependencies: Array<Task>, has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, source: TaskSource, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, stage_started_at: string | null, complexity_score: number | null, parent_task_id: string | null, prevent_breakdown: boolean, post_task_actions: string | null, source_feature: string | null, archived: boolean, needs_attention_reason: string | null, commit_count: number | null, requirements_id: string | null, blocked_reason: string | null, edited_at: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, source: TaskSource | null, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, parent_task_id: string | null, prevent_breakdown: boolean | null, post_task_actions: string | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, source: TaskSource | null, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, parent_task_id: string | null, prevent_breakdown: boolean | null, post_task_actions: string | null, source_feature: string | null, requirements_id: string | null, };
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
 export type GenerationStatus = "pending" | "analyzing" | "generating" | "completed" | "failed";
 
-export type ExtractedFeature = { name: string, description: string, layer: string | null, priority: number | null, };
+export type FeatureGranularity = "coarse" | "normal" | "fine";
+
+export type ExtractedFeature = { 
+/**
+ * Stable id used to link generated tasks back to this feature
+ */
+id: string, name: string, description: string, layer: string | null, priority: number | null, };
+
+export type AnalysisResult = { features: Array<ExtractedFeature>, summary: string | null, 
+/**
+ * Feature extraction prompt that produced this result, e.g. 'feature_extraction@v1'
+ */
+prompt_version: string | null, };
+
+export type ProjectRequirements = { id: string, project_id: string, raw_requirements: string, prd_content: string | null, analysis_result: string | null, generation_status: GenerationStatus, error_message: string | null, granularity: FeatureGranularity, created_at: string, updated_at: string, };
+
+export type CreateProjectRequirements = { raw_requirements: string, prd_content: string | null, granularity: FeatureGranularity | null, };
 
-export type AnalysisResult = { features: Array<ExtractedFeature>, summary: string | null, };
+export type ProjectRequirementsStatus = { id: string, project_id: string, generation_status: GenerationStatus, analysis_result: AnalysisResult | null, tasks_generated: number | null, error_message: string | null, raw_requirements: string | null, prd_content: string | null, granularity: FeatureGranularity, created_at: string, updated_at: string, };
 
-export type ProjectRequirements = { id: string, project_id: string, raw_requirements: string, prd_content: string | null, analysis_result: string | null, generation_status: GenerationStatus, error_message: string | null, created_at: string, updated_at: string, };
+export type ProjectRequirementsVersion = { id: string, requirements_id: string, raw_requirements: string, prd_content: string | null, analysis_result: string | null, created_at: string, };
 
-export type CreateProjectRequirements = { raw_requirements: string, prd_content: string | null, };
+export type AgentAction = "selected" | "skipped" | "error" | "replaced" | "timeout" | "parentcompleted";
 
-export type ProjectRequirementsStatus = { id: string, project_id: string, generation_status: GenerationStatus, analysis_result: AnalysisResult | null, tasks_generated: number | null, error_message: string | null, created_at: string, updated_at: string, };
+export type WorkingDirStrategy = "repo_name" | "workspace_root" | "explicit";
 
-export type AgentAction = "selected" | "skipped" | "error" | "replaced" | "timeout";
+export type ProjectAgentSettings = { id: string, project_id: string, enabled: boolean, interval_seconds: number, max_breakdown_depth: number, digest_window_minutes: number | null, max_concurrent_layers: number, working_dir_strategy: WorkingDirStrategy, working_dir_path: string | null, auto_breakdown_fullstack: boolean, min_complexity_for_auto_attempt: number, max_in_progress_tasks: number, max_prompt_description_chars: number, max_breakdown_subtasks: number, auto_attempt_retries: number, include_recent_comments: boolean, integration_blocks_all: boolean, quiet_hours_start: string | null, quiet_hours_end: string | null, quiet_hours_utc_offset_minutes: number, default_layer: TaskLayer | null, created_at: string, updated_at: string, };
 
-export type ProjectAgentSettings = { id: string, project_id: string, enabled: boolean, interval_seconds: number, max_breakdown_depth: number, created_at: string, updated_at: string, };
+export type SelectionFactors = { priority: string | null, unblocking: string | null, layer_readiness: string | null, };
 
-export type AgentActivityLog = { id: string, project_id: string, task_id: string | null, action: AgentAction, reasoning: string | null, created_at: string, };
+export type SelectionReasoning = { summary: string, runner_up_task_id: string | null, factors: SelectionFactors, };
 
-export type AgentActivityStatus = { enabled: boolean, interval_seconds: number, last_run: string | null, last_selected_task_id: string | null, last_reasoning: string | null, };
+export type AgentActivityLog = { id: string, project_id: string, task_id: string | null, action: AgentAction, reasoning: string | null, reasoning_details: SelectionReasoning | null, prompt_version: string | null, created_at: string, };
+
+export type AgentActivityStatus = { enabled: boolean, interval_seconds: number, digest_window_minutes: number | null, last_run: string | null, last_selected_task_id: string | null, last_reasoning: string | null, };
+
+export type UpdateAgentActivitySettings = { interval_seconds: number, max_concurrent_layers: number, digest_window_minutes?: number | null, working_dir_strategy: WorkingDirStrategy | null, working_dir_path: string | null, auto_breakdown_fullstack: boolean | null, min_complexity_for_auto_attempt: number | null, max_in_progress_tasks: number | null, max_prompt_description_chars: number | null, max_breakdown_subtasks: number | null, auto_attempt_retries: number | null, include_recent_comments: boolean | null, integration_blocks_all: boolean | null, quiet_hours_start: string | null, quiet_hours_end: string | null, quiet_hours_utc_offset_minutes: number | null, default_layer: string | null, };
 
 export type AgentTriggerResponse = { action: AgentAction, task_id: string | null, reasoning: string | null, };
 
-export type ReviewAction = "test_passed" | "test_failed" | "merge_completed" | "merge_conflict" | "skipped" | "error";
+export type ClaudeHealthStatus = "ok" | "missing_key" | "invalid_key" | "unreachable";
+
+export type CallQueueStatus = { max_concurrent: number, in_flight: number, queued: number, };
+
+export type ClaudeHealth = { status: ClaudeHealthStatus, error: string | null, queue: CallQueueStatus, };
+
+export type SelectionPromptPreview = { prompt: string | null, system_prompt: string | null, candidate_task_ids: Array<string>, blocked_reason: string | null, prompt_version: string | null, };
+
+export type ComplexityAnalysisResponse = { complexity_score: number, can_be_broken_down: boolean, reasoning: string, subtasks: Array<SubtaskSuggestion> | null, };
+
+export type SubtaskSuggestion = { title: string, description: string, layer: string | null, };
+
+export type NotificationChannelType = "desktop" | "webhook" | "slack";
+
+export type ProjectNotificationChannel = { id: string, project_id: string, channel_type: NotificationChannelType, config: JsonValue, enabled: boolean, created_at: string, updated_at: string, };
+
+export type CreateProjectNotificationChannel = { channel_type: NotificationChannelType, config: JsonValue | null, enabled: boolean | null, };
+
+export type UpdateProjectNotificationChannel = { config: JsonValue | null, enabled: boolean | null, };
+
+export type ProjectPromptVersion = { project_id: string, template_name: string, version: bigint, created_at: string, updated_at: string, };
+
+export type SetProjectPromptVersion = { version: bigint | null, };
+
+export type TaskComment = { id: string, task_id: string, author: string | null, content: string, created_at: string, };
+
+export type CreateTaskComment = { author: string | null, content: string, };
+
+export type TaskStatusHistory = { id: string, task_id: string, from_status: TaskStatus, to_status: TaskStatus, 
+/**
+ * `user`, or the service that made the change, e.g. `review_automation`
+ */
+actor: string | null, reason: string | null, created_at: string, };
+
+export type PromptTemplateVersions = { name: string, versions: Array<bigint>, active_version: bigint, pinned: boolean, };
+
+export type ReviewAction = "test_passed" | "test_failed" | "merge_completed" | "merge_conflict" | "skipped" | "error" | "no_changes" | "conflict_resolved" | "criteria_met" | "criteria_unmet" | "merge_reverted" | "partially_merged";
+
+export type ProjectReviewSettings = { id: string, project_id: string, enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, fetch_before_merge: boolean, store_test_artifacts: boolean, fast_forward_merges: boolean, conventional_commits: boolean, sign_commits: boolean, complete_parent_on_subtasks_done: boolean, require_successful_attempt: boolean, auto_archive_on_merge: boolean, stash_before_merge: boolean, drop_stash_after_merge: boolean, require_tests_before_merge: boolean, cherry_pick_single_commits: boolean, enforce_testing_criteria: boolean, allow_partial_merge: boolean, progress_repo: string | null, test_targets: string | null, pre_merge_command: string | null, auto_merge_target_allowlist: string | null, test_profiles: string | null, created_at: string, updated_at: string, };
+
+export type ReviewAutomationLog = { id: string, task_id: string, workspace_id: string, action: ReviewAction, output: string | null, error_message: string | null, artifact_path: string | null, created_at: string, };
+
+export type ReviewAutomationStatus = { enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, fetch_before_merge: boolean, store_test_artifacts: boolean, fast_forward_merges: boolean, conventional_commits: boolean, sign_commits: boolean, complete_parent_on_subtasks_done: boolean, require_successful_attempt: boolean, auto_archive_on_merge: boolean, stash_before_merge: boolean, drop_stash_after_merge: boolean, require_tests_before_merge: boolean, cherry_pick_single_commits: boolean, enforce_testing_criteria: boolean, allow_partial_merge: boolean, progress_repo: string | null, test_targets: string | null, pre_merge_command: string | null, auto_merge_target_allowlist: string | null, test_profiles: string | null, last_action: ReviewAction | null, last_task_id: string | null, };
+
+export type ReviewAutomationSettingsResponse = { enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, fetch_before_merge: boolean, store_test_artifacts: boolean, fast_forward_merges: boolean, conventional_commits: boolean, sign_commits: boolean, complete_parent_on_subtasks_done: boolean, require_successful_attempt: boolean, auto_archive_on_merge: boolean, stash_before_merge: boolean, drop_stash_after_merge: boolean, require_tests_before_merge: boolean, cherry_pick_single_commits: boolean, enforce_testing_criteria: boolean, allow_partial_merge: boolean, progress_repo: string | null, test_targets: string | null, pre_merge_command: string | null, auto_merge_target_allowlist: string | null, test_profiles: string | null, };
+
+export type UpdateReviewAutomationSettings = { enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, fetch_before_merge: boolean | null, store_test_artifacts: boolean | null, fast_forward_merges: boolean | null, conventional_commits: boolean | null, sign_commits: boolean | null, complete_parent_on_subtasks_done: boolean | null, require_successful_attempt: boolean | null, auto_archive_on_merge: boolean | null, stash_before_merge: boolean | null, drop_stash_after_merge: boolean | null, require_tests_before_merge: boolean | null, cherry_pick_single_commits: boolean | null, enforce_testing_criteria: boolean | null, allow_partial_merge: boolean | null, progress_repo: string | null, test_targets: string | null, pre_merge_command: string | null, auto_merge_target_allowlist: string | null, test_profiles: string | null, };
+
+export type ReviewAutomationSettingsUpdateResponse = { warnings: Array<string>, enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, fetch_before_merge: boolean, store_test_artifacts: boolean, fast_forward_merges: boolean, conventional_commits: boolean, sign_commits: boolean, complete_parent_on_subtasks_done: boolean, require_successful_attempt: boolean, auto_archive_on_merge: boolean, stash_before_merge: boolean, drop_stash_after_merge: boolean, require_tests_before_merge: boolean, cherry_pick_single_commits: boolean, enforce_testing_criteria: boolean, allow_partial_merge: boolean, progress_repo: string | null, test_targets: string | null, pre_merge_command: string | null, auto_merge_target_allowlist: string | null, test_profiles: string | null, };
+
+export type RepoMergePreview = { repo_id: string, repo_name: string, target_branch: string, conflicted_files: Array<string> | null, };
 
-export type ProjectReviewSettings = { id: string, project_id: string, enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, created_at: string, updated_at: string, };
+export type ExportFormat = "markdown" | "json";
 
-export type ReviewAutomationLog = { id: string, task_id: string, workspace_id: string, action: ReviewAction, output: string | null, error_message: string | null, created_at: string, };
+export type ExportedTask = { title: string, description: string | null, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, post_task_actions: string | null, source_feature: string | null, };
 
-export type ReviewAutomationStatus = { enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, last_action: ReviewAction | null, last_task_id: string | null, };
+export type TaskImportRowResult = { index: number, task_id: string | null, error: string | null, };
 
-export type ReviewAutomationSettingsResponse = { enabled: boolean, auto_merge_enabled: boolean, run_tests_enabled: boolean, };
+export type TaskImportResult = { imported: number, failed: number, rows: Array<TaskImportRowResult>, };
 
 export type DraftFollowUpData = { message: string, variant: string | null, };
 
@@ -134,7 +225,7 @@ export type ExecutionProcessRepoState = { id: string, execution_process_id: stri
 
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
-export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
+export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, base_commit: string | null, target_branch_name: string, created_at: string, };
 
 export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
 
@@ -218,6 +309,14 @@ export type ListProjectsResponse = { projects: Array<RemoteProject>, };
 
 export type RemoteProjectMembersResponse = { organization_id: string, members: Array<OrganizationMemberWithProfile>, };
 
+export type RetryableErrorData = { retryable: boolean, retry_after_secs: number, };
+
+export type ProjectAutomationOverview = { project_id: string, project_name: string, agent_settings: ProjectAgentSettings | null, review_settings: ReviewAutomationSettingsResponse | null, 
+/**
+ * Most recent agent activity or review automation log entry
+ */
+last_activity_at: string | null, };
+
 export type CreateRemoteProjectRequest = { organization_id: string, name: string, };
 
 export type LinkToExistingRequest = { remote_project_id: string, };
@@ -276,6 +375,24 @@ export type OpenEditorResponse = { url: string | null, };
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
 
+export type SetTaskArchivedRequest = { archived: boolean, };
+
+export type SubmitForReviewRequest = { 
+/**
+ * Run review automation now instead of waiting for its next poll
+ */
+run_review_now: boolean, };
+
+export type BlockTaskRequest = { reason: string, };
+
+export type FindSimilarTasksRequest = { title: string, description: string | null, 
+/**
+ * Maximum number of tasks to return (default 5)
+ */
+limit: number | null, };
+
+export type SimilarTask = { score: number, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, source: TaskSource, layer: TaskLayer | null, task_type: TaskType | null, sequence: number | null, testing_criteria: string | null, stage_started_at: string | null, complexity_score: number | null, parent_task_id: string | null, prevent_breakdown: boolean, post_task_actions: string | null, source_feature: string | null, archived: boolean, needs_attention_reason: string | null, commit_count: number | null, requirements_id: string | null, blocked_reason: string | null, edited_at: string | null, created_at: string, updated_at: string, };
+
 export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
@@ -330,7 +447,7 @@ export type GetPrCommentsQuery = { repo_id: string, };
 
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ProviderKind = "git_hub" | "git_lab" | "azure_dev_ops" | "unknown";
 
 export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -402,7 +519,38 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, 
+/**
+ * Host-wide cap on attempts auto-started by agent activity across all projects (None: no cap)
+ */
+max_concurrent_attempts: number | null, 
+/**
+ * Template for attempt branch names, e.g. `feat/{layer}/{short_id}-{slug}` (None: use
+ * `git_branch_prefix`)
+ */
+branch_name_template: string | null, 
+/**
+ * Programs project review settings may use for test and pre-merge commands (None: the
+ * built-in allowlist)
+ */
+test_command_allowlist: Array<string> | null, 
+/**
+ * Let project test and pre-merge commands use shell metacharacters such as `&&` and `|`
+ */
+allow_shell_in_test_commands: boolean, 
+/**
+ * Seconds between review automation polls (None: 10)
+ */
+review_poll_interval_secs: bigint | null, 
+/**
+ * Seconds between task timeout checks (None: 10)
+ */
+task_timeout_poll_interval_secs: bigint | null, 
+/**
+ * Projects the agent activity and review automation services each process at once
+ * (None: 4)
+ */
+background_project_concurrency: number | null, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 